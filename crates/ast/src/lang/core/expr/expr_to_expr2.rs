@@ -651,6 +651,10 @@ pub fn expr_to_expr2<'a>(
             //            (RuntimeError(MalformedClosure(region)), Output::default())
             todo!()
         }
+        Malformed(_) => {
+            // The syntax error was already reported when we parsed the module
+            (Expr2::RuntimeError(), Output::default())
+        }
         MalformedIdent(_name, _problem) => {
             //            use roc_problem::can::RuntimeError::*;
             //
@@ -16,6 +16,7 @@ pub fn load_module(
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Check,
+        typed_holes: false,
     };
 
     let arena = Bump::new();
@@ -0,0 +1,223 @@
+//! `roc annotate`, which writes the inferred type annotation above each top-level def
+//! that doesn't have one.
+use bumpalo::Bump;
+use roc_build::program::report_problems_typechecked;
+use roc_can::expr::{DeclarationTag, Declarations};
+use roc_fmt::def::fmt_value_def;
+use roc_fmt::{Buf, DEFAULT_LINE_WIDTH};
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_module::symbol::Symbol;
+use roc_packaging::cache::RocCacheDir;
+use roc_parse::ast::{Pattern, ValueDef};
+use roc_parse::parser::Parser;
+use roc_parse::state::State;
+use roc_parse::type_annotation;
+use roc_problem::{Levels, Severity};
+use roc_region::all::Loc;
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+
+/// Which of the unannotated top-level defs to annotate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnnotateMode {
+    All,
+    ExposedOnly,
+}
+
+/// Insert the inferred type annotation above every top-level def in `roc_file_path`
+/// that doesn't have one yet. The module has to type-check first, or the inferred
+/// types could be wrong.
+pub fn annotate(
+    roc_file_path: &Path,
+    threading: Threading,
+    roc_cache_dir: RocCacheDir<'_>,
+    mode: AnnotateMode,
+) -> io::Result<i32> {
+    let start_time = Instant::now();
+    let arena = Bump::new();
+    let load_config = LoadConfig {
+        // only used for generating errors, so hardcoding it is fine
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Check,
+        typed_holes: false,
+    };
+
+    let mut loaded = match roc_load::load_and_typecheck(
+        &arena,
+        roc_file_path.to_path_buf(),
+        roc_cache_dir,
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(LoadingProblem::FormattedReport(report)) => {
+            print!("{}", report);
+
+            return Ok(1);
+        }
+        Err(other) => {
+            panic!("annotate failed with error:\n{:?}", other);
+        }
+    };
+
+    if has_errors(&loaded) {
+        let problems = report_problems_typechecked(&mut loaded, &Levels::default());
+
+        problems.print_to_stdout(start_time.elapsed());
+        println!(".");
+
+        return Ok(problems.exit_code());
+    }
+
+    let home = loaded.module_id;
+    let (path, src) = loaded.sources[&home].clone();
+    let exposed: Vec<Symbol> = loaded
+        .exposed_values
+        .iter()
+        .chain(loaded.exposed_to_host.keys())
+        .copied()
+        .collect();
+    let decls = &loaded.declarations_by_id[&home];
+    let mut insertions = Vec::new();
+
+    for index in unannotated_defs(decls) {
+        let symbol = decls.symbols[index].value;
+        let name = symbol.as_str(&loaded.interns);
+
+        // Generated defs (like the ones in effect modules) have names that can't be written
+        // in source, so there's nowhere to put an annotation for them.
+        let is_generated = !name.starts_with(|c: char| c.is_ascii_lowercase());
+
+        if is_generated || (mode == AnnotateMode::ExposedOnly && !exposed.contains(&symbol)) {
+            continue;
+        }
+
+        let printed = name_and_print_var(
+            decls.variables[index],
+            loaded.solved.inner_mut(),
+            home,
+            &loaded.interns,
+            DebugPrint::NOTHING,
+        );
+
+        match format_annotation(name, &printed) {
+            Some(annotation) => {
+                let offset = decls.symbols[index].region.start().offset as usize;
+
+                insertions.push((line_start(&src, offset), annotation));
+            }
+            None => {
+                eprintln!(
+                    "Skipped `{}`, because its inferred type `{}` can't be written as an annotation.",
+                    name, printed
+                );
+            }
+        }
+    }
+
+    let count = insertions.len();
+
+    if count > 0 {
+        fs::write(&path, insert_annotations(&src, insertions))?;
+    }
+
+    println!(
+        "Added {} {} to {}.",
+        count,
+        if count == 1 {
+            "annotation"
+        } else {
+            "annotations"
+        },
+        path.display()
+    );
+
+    Ok(0)
+}
+
+fn has_errors(loaded: &LoadedModule) -> bool {
+    let can_errors = loaded
+        .can_problems
+        .values()
+        .flatten()
+        .any(|problem| problem.severity() != Severity::Warning);
+    let type_errors = loaded
+        .type_problems
+        .values()
+        .flatten()
+        .any(|problem| problem.severity() != Severity::Warning);
+
+    can_errors || type_errors
+}
+
+/// The indices of the top-level value and function defs that don't have an annotation.
+/// Destructures and expects can't be annotated this way, so they're never included.
+fn unannotated_defs(decls: &Declarations) -> impl Iterator<Item = usize> + '_ {
+    decls
+        .declarations
+        .iter()
+        .enumerate()
+        .filter_map(move |(index, tag)| match tag {
+            DeclarationTag::Value
+            | DeclarationTag::Function(_)
+            | DeclarationTag::Recursive(_)
+            | DeclarationTag::TailRecursive(_)
+                if decls.annotations[index].is_none() =>
+            {
+                Some(index)
+            }
+            _ => None,
+        })
+}
+
+/// Turn `name` and its printed type into a formatted annotation line, like `name : Str -> Str`.
+/// Returns `None` if the printed type doesn't parse as an annotation, which can happen for types
+/// that only the compiler can name.
+fn format_annotation(name: &str, printed_type: &str) -> Option<String> {
+    let arena = Bump::new();
+    let (_, ann, state) = type_annotation::located(false)
+        .parse(&arena, State::new(printed_type.as_bytes()), 0)
+        .ok()?;
+
+    if state.pos().offset as usize != printed_type.trim_end().len() {
+        return None;
+    }
+
+    // Formatting it as a def breaks it up the same way `roc format` would.
+    let def = ValueDef::Annotation(Loc::at_zero(Pattern::Identifier(name)), ann);
+    let mut buf = Buf::with_max_width(&arena, DEFAULT_LINE_WIDTH);
+
+    fmt_value_def(&mut buf, &def, 0);
+
+    Some(format!("{}\n", buf.into_bump_str()))
+}
+
+fn line_start(src: &str, offset: usize) -> usize {
+    src[..offset].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+/// Insert each annotation at the start of the line its def is on. The annotations are
+/// all at different lines, since each def starts on a line of its own.
+fn insert_annotations(src: &str, mut insertions: Vec<(usize, String)>) -> String {
+    insertions.sort_by_key(|(offset, _)| *offset);
+
+    let mut annotated = String::with_capacity(src.len());
+    let mut copied_up_to = 0;
+
+    for (offset, annotation) in insertions {
+        annotated.push_str(&src[copied_up_to..offset]);
+        annotated.push_str(&annotation);
+        copied_up_to = offset;
+    }
+
+    annotated.push_str(&src[copied_up_to..]);
+
+    annotated
+}
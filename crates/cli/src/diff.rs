@@ -0,0 +1,167 @@
+//! Line-based unified diffs, for showing what `roc format --check` would change.
+use std::fmt::Write;
+use std::path::Path;
+
+/// How many unchanged lines to show around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Above this many lines (old lines times new lines, after trimming the common prefix and suffix)
+/// we don't look for the smallest diff, and just replace all of the lines in between.
+const MAX_DIFF_CELLS: usize = 16_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DiffLine {
+    /// Indices of a line that's in both the old and the new text
+    Same(usize, usize),
+    /// Index of a line only in the old text
+    Removed(usize),
+    /// Index of a line only in the new text
+    Added(usize),
+}
+
+impl DiffLine {
+    fn is_old(&self) -> bool {
+        !matches!(self, DiffLine::Added(_))
+    }
+
+    fn is_new(&self) -> bool {
+        !matches!(self, DiffLine::Removed(_))
+    }
+}
+
+/// A unified diff between the `old` and `new` contents of `path`,
+/// or the empty string if they have the same lines.
+pub fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let lines = diff_lines(&old_lines, &new_lines);
+
+    let changes: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Same(..)))
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut buf = String::new();
+
+    if changes.is_empty() {
+        return buf;
+    }
+
+    let _ = writeln!(buf, "--- {}", path.display());
+    let _ = writeln!(buf, "+++ {}", path.display());
+
+    let mut index = 0;
+
+    while index < changes.len() {
+        let first_change = changes[index];
+
+        // Changes that are close enough to share context go in the same hunk
+        while index + 1 < changes.len()
+            && changes[index + 1] - changes[index] <= 2 * CONTEXT_LINES + 1
+        {
+            index += 1;
+        }
+
+        let start = first_change.saturating_sub(CONTEXT_LINES);
+        let end = (changes[index] + CONTEXT_LINES + 1).min(lines.len());
+        let hunk = &lines[start..end];
+
+        let old_before = lines[..start].iter().filter(|line| line.is_old()).count();
+        let new_before = lines[..start].iter().filter(|line| line.is_new()).count();
+        let old_count = hunk.iter().filter(|line| line.is_old()).count();
+        let new_count = hunk.iter().filter(|line| line.is_new()).count();
+
+        let _ = writeln!(
+            buf,
+            "@@ -{} +{} @@",
+            hunk_range(old_before, old_count),
+            hunk_range(new_before, new_count)
+        );
+
+        for line in hunk {
+            let _ = match line {
+                DiffLine::Same(old_index, _) => writeln!(buf, " {}", old_lines[*old_index]),
+                DiffLine::Removed(old_index) => writeln!(buf, "-{}", old_lines[*old_index]),
+                DiffLine::Added(new_index) => writeln!(buf, "+{}", new_lines[*new_index]),
+            };
+        }
+
+        index += 1;
+    }
+
+    buf
+}
+
+/// The `start,count` part of a hunk header. Lines are numbered from 1, except that an empty
+/// range is numbered after the line it comes after.
+fn hunk_range(lines_before: usize, count: usize) -> String {
+    let start = if count == 0 {
+        lines_before
+    } else {
+        lines_before + 1
+    };
+
+    format!("{start},{count}")
+}
+
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut lines: Vec<DiffLine> = (0..prefix).map(|i| DiffLine::Same(i, i)).collect();
+
+    if old_middle.len().saturating_mul(new_middle.len()) <= MAX_DIFF_CELLS {
+        // lcs[i * width + j] is the length of the longest common subsequence
+        // of old_middle[i..] and new_middle[j..]
+        let width = new_middle.len() + 1;
+        let mut lcs = vec![0u32; (old_middle.len() + 1) * width];
+
+        for i in (0..old_middle.len()).rev() {
+            for j in (0..new_middle.len()).rev() {
+                lcs[i * width + j] = if old_middle[i] == new_middle[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+
+        while i < old_middle.len() || j < new_middle.len() {
+            if i < old_middle.len() && j < new_middle.len() && old_middle[i] == new_middle[j] {
+                lines.push(DiffLine::Same(prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if j == new_middle.len()
+                || (i < old_middle.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+            {
+                lines.push(DiffLine::Removed(prefix + i));
+                i += 1;
+            } else {
+                lines.push(DiffLine::Added(prefix + j));
+                j += 1;
+            }
+        }
+    } else {
+        lines.extend((0..old_middle.len()).map(|i| DiffLine::Removed(prefix + i)));
+        lines.extend((0..new_middle.len()).map(|j| DiffLine::Added(prefix + j)));
+    }
+
+    let old_suffix_start = old.len() - suffix;
+    let new_suffix_start = new.len() - suffix;
+
+    lines.extend((0..suffix).map(|k| DiffLine::Same(old_suffix_start + k, new_suffix_start + k)));
+
+    lines
+}
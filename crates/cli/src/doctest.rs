@@ -0,0 +1,273 @@
+//! Running the examples in doc comments, for `roc test --doc`. The checked examples become
+//! top-level `expect`s (see [roc_docs::doctest]), so they run like any other test, and the
+//! `repl` examples get evaluated in a REPL.
+use bumpalo::Bump;
+use roc_build::program::{handle_error_module, handle_loading_problem};
+use roc_docs::doctest::{
+    doc_examples, doc_test_src, line_region, normalize_repl_output, repl_inputs, DocExample,
+    ExampleKind, ReplInput,
+};
+use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading, ToplevelExpects};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::ir::OptLevel;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::ast::{Header, Module};
+use roc_parse::module::parse_header;
+use roc_parse::state::State;
+use roc_problem::{Levels, Severity};
+use roc_region::all::{LineInfo, Region};
+use roc_repl_cli::repl_state::ReplState;
+use roc_reporting::report::{
+    RenderTarget, Report, RocDocAllocator, RocDocBuilder, DEFAULT_PALETTE,
+};
+use roc_target::TargetInfo;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+use target_lexicon::Triple;
+use tempfile::TempDir;
+use ven_pretty::DocAllocator;
+
+/// Run the examples in the docs of each of the `modules` (their paths and sources), which are
+/// in `root_dir`. Each module gets loaded again with its checked examples appended to it, so
+/// that they build and run in its scope; only the `expect`s that came from the examples run,
+/// since the module's own tests already ran. An example that doesn't compile counts as a failure.
+///
+/// Returns how many examples failed and passed.
+pub fn run_doc_examples<W: io::Write>(
+    writer: &mut W,
+    target: &Triple,
+    opt_level: OptLevel,
+    threading: Threading,
+    root_dir: &Path,
+    modules: &[(&Path, &str)],
+) -> io::Result<(usize, usize)> {
+    let mut failed = 0;
+    let mut passed = 0;
+
+    for &(path, src) in modules {
+        let examples = doc_examples(src);
+
+        if examples.is_empty() {
+            continue;
+        }
+
+        let start_time = Instant::now();
+        let arena = Bump::new();
+        let doc_test_src = doc_test_src(src, &examples);
+        let load_config = LoadConfig {
+            target_info: TargetInfo::from(target),
+            render: RenderTarget::ColorTerminal,
+            palette: DEFAULT_PALETTE,
+            threading,
+            exec_mode: ExecutionMode::Test,
+            typed_holes: false,
+        };
+
+        let load_result = roc_load::load_and_monomorphize_from_str(
+            &arena,
+            path.to_path_buf(),
+            arena.alloc_str(&doc_test_src),
+            root_dir.to_path_buf(),
+            RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+            load_config,
+        );
+
+        let mut loaded = match load_result {
+            Ok(loaded) => loaded,
+            Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+                handle_loading_problem(problem)?;
+                failed += 1;
+
+                continue;
+            }
+            Err(LoadMonomorphizedError::ErrorModule(mut module)) => {
+                // Examples often define things only to show how they're written,
+                // so only their errors are worth reporting.
+                for problems in module.can_problems.values_mut() {
+                    problems.retain(|problem| problem.severity() != Severity::Warning);
+                }
+
+                for problems in module.type_problems.values_mut() {
+                    problems.retain(|problem| problem.severity() != Severity::Warning);
+                }
+
+                handle_error_module(
+                    module,
+                    start_time.elapsed(),
+                    path.as_os_str(),
+                    false,
+                    &Levels::default(),
+                )?;
+                failed += 1;
+
+                continue;
+            }
+        };
+
+        let home = loaded.module_id;
+        let ToplevelExpects { pure, fx } = std::mem::take(&mut loaded.toplevel_expects);
+        // The examples were appended to the end of the module, after all of its own code.
+        let from_examples = |(symbol, region): &(Symbol, Region)| {
+            symbol.module_id() == home && region.start().offset as usize >= src.len()
+        };
+
+        loaded.toplevel_expects = ToplevelExpects {
+            pure: pure.into_iter().filter(from_examples).collect(),
+            fx: fx.into_iter().filter(from_examples).collect(),
+        };
+
+        let mut expectations = std::mem::take(&mut loaded.expectations);
+        let interns = loaded.interns.clone();
+
+        // Failed expects get reported by reading the module's file, which doesn't have the
+        // examples in it, so point them at a copy that does.
+        let doc_test_dir = TempDir::new()?;
+        let doc_test_path = doc_test_dir
+            .path()
+            .join(path.file_name().unwrap_or_default());
+
+        std::fs::write(&doc_test_path, &doc_test_src)?;
+
+        if let Some(module_expectations) = expectations.get_mut(&home) {
+            module_expectations.path = doc_test_path;
+        }
+
+        let (lib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
+            &arena,
+            target.clone(),
+            loaded,
+            opt_level,
+            LlvmBackendMode::CliTest,
+        )
+        .unwrap();
+
+        let expect_arena = Bump::new();
+        let interns = expect_arena.alloc(interns);
+
+        let (expects_failed, expects_passed) = roc_repl_expect::run::run_toplevel_expects(
+            writer,
+            RenderTarget::ColorTerminal,
+            &expect_arena,
+            interns,
+            &layout_interner.into_global(),
+            &lib,
+            &mut expectations,
+            expects,
+        )?;
+
+        let (repl_failed, repl_passed) =
+            run_repl_examples(writer, path, src, &examples, home, interns)?;
+
+        failed += expects_failed + repl_failed;
+        passed += expects_passed + repl_passed;
+    }
+
+    Ok((failed, passed))
+}
+
+/// Evaluate the inputs of each `repl` example in the docs of the module at `path` (whose source
+/// is `src`), and report the ones for which the REPL prints something other than what the docs
+/// show. Each example gets a REPL of its own, with the module loaded if it's an interface.
+///
+/// Returns how many inputs failed and passed.
+fn run_repl_examples<W: io::Write>(
+    writer: &mut W,
+    path: &Path,
+    src: &str,
+    examples: &[DocExample],
+    home: ModuleId,
+    interns: &Interns,
+) -> io::Result<(usize, usize)> {
+    let src_lines: Vec<&str> = src.split('\n').collect();
+    let lines = LineInfo::new(src);
+    let alloc = RocDocAllocator::new(&src_lines, home, interns);
+    let is_interface = is_interface(src);
+    let mut failed = 0;
+    let mut passed = 0;
+
+    for example in examples {
+        if example.kind != ExampleKind::Repl {
+            continue;
+        }
+
+        let mut repl = ReplState::new();
+
+        if is_interface {
+            // The module already type-checked, so this can't fail.
+            let _ = repl.step(&format!(":load {}", path.display()), None);
+        }
+
+        for input in repl_inputs(example) {
+            let actual = match repl.step(&input.input, None) {
+                Ok(output) => normalize_repl_output(&output),
+                // The input was `:exit` or similar, which ends the example.
+                Err(_) => break,
+            };
+
+            if actual == input.expected {
+                passed += 1;
+            } else {
+                let report = repl_example_report(&alloc, &lines, path, src, &input, &actual);
+                let mut buf = String::new();
+
+                report.render_color_terminal(&mut buf, &alloc, &DEFAULT_PALETTE);
+                writeln!(writer, "\n{}\n", buf)?;
+
+                failed += 1;
+            }
+        }
+    }
+
+    Ok((failed, passed))
+}
+
+fn repl_example_report<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    lines: &LineInfo,
+    path: &Path,
+    src: &str,
+    input: &ReplInput,
+    actual: &str,
+) -> Report<'b> {
+    let doc = alloc.stack([
+        alloc.reflow("The REPL printed something different for this example in the docs:"),
+        alloc.region(lines.convert_region(line_region(src, input.line))),
+        alloc.reflow("The docs show:"),
+        repl_output(alloc, &input.expected),
+        alloc.reflow("But the REPL printed:"),
+        repl_output(alloc, actual),
+    ]);
+
+    Report {
+        title: "REPL EXAMPLE FAILED".to_string(),
+        filename: path.to_path_buf(),
+        doc,
+        severity: Severity::RuntimeError,
+    }
+}
+
+fn repl_output<'b>(alloc: &'b RocDocAllocator<'b>, output: &str) -> RocDocBuilder<'b> {
+    if output.is_empty() {
+        alloc.type_block(alloc.reflow("(nothing)"))
+    } else {
+        alloc.type_block(alloc.vcat(output.lines().map(|line| alloc.text(line.to_string()))))
+    }
+}
+
+/// Only interface modules can be loaded into the REPL.
+fn is_interface(src: &str) -> bool {
+    let arena = Bump::new();
+
+    matches!(
+        parse_header(&arena, State::new(src.as_bytes())),
+        Ok((
+            Module {
+                header: Header::Interface(_),
+                ..
+            },
+            _
+        ))
+    )
+}
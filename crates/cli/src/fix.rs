@@ -0,0 +1,108 @@
+//! `roc fix`, which applies the fixes the compiler knows for its problems,
+//! like removing unused imports and adding missing `when` branches.
+use bumpalo::Bump;
+use roc_load::{ExecutionMode, LoadConfig, LoadingProblem, Threading};
+use roc_packaging::cache::RocCacheDir;
+use roc_reporting::cli::collect_fixes;
+use roc_reporting::fix::apply_fixes;
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Fixing one problem can make another one fixable (e.g. removing an unused def can leave an
+/// import unused), so we check again after applying fixes, up to this many times.
+const MAX_FIX_PASSES: usize = 3;
+
+/// Apply every fix we can to the modules of the app or package at `roc_file_path`.
+/// Only modules in the same directory (or below it) get changed.
+pub fn fix(
+    roc_file_path: &Path,
+    threading: Threading,
+    roc_cache_dir: RocCacheDir<'_>,
+) -> io::Result<i32> {
+    let root_dir = roc_file_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut fixes_applied = 0;
+    let mut files_fixed = BTreeSet::new();
+
+    for _ in 0..MAX_FIX_PASSES {
+        let arena = Bump::new();
+        let load_config = LoadConfig {
+            // only used for generating errors, so hardcoding it is fine
+            target_info: TargetInfo::default_x86_64(),
+            render: RenderTarget::ColorTerminal,
+            palette: DEFAULT_PALETTE,
+            threading,
+            exec_mode: ExecutionMode::Check,
+            typed_holes: false,
+        };
+
+        let mut loaded = match roc_load::load_and_typecheck(
+            &arena,
+            roc_file_path.to_path_buf(),
+            roc_cache_dir,
+            load_config,
+        ) {
+            Ok(loaded) => loaded,
+            Err(LoadingProblem::FormattedReport(report)) => {
+                print!("{}", report);
+
+                return Ok(1);
+            }
+            Err(other) => {
+                panic!("fix failed with error:\n{:?}", other);
+            }
+        };
+
+        let fixes = collect_fixes(
+            &loaded.sources,
+            &loaded.interns,
+            &mut loaded.can_problems,
+            &mut loaded.type_problems,
+        );
+
+        let mut applied_this_pass = 0;
+
+        for (home, path, fixes) in fixes {
+            if home.is_builtin() || !path.starts_with(root_dir) {
+                continue;
+            }
+
+            let (_, src) = &loaded.sources[&home];
+            let (fixed, applied) = apply_fixes(src, fixes);
+
+            if applied > 0 {
+                fs::write(&path, fixed)?;
+
+                applied_this_pass += applied;
+                files_fixed.insert(path);
+            }
+        }
+
+        fixes_applied += applied_this_pass;
+
+        if applied_this_pass == 0 {
+            break;
+        }
+    }
+
+    for path in files_fixed.iter() {
+        println!("Fixed {}", path.display());
+    }
+
+    println!(
+        "Applied {} {} in {} {}.",
+        fixes_applied,
+        if fixes_applied == 1 { "fix" } else { "fixes" },
+        files_fixed.len(),
+        if files_fixed.len() == 1 {
+            "file"
+        } else {
+            "files"
+        },
+    );
+
+    Ok(0)
+}
@@ -1,18 +1,33 @@
 use std::ffi::OsStr;
+use std::io::Read;
+use std::ops::{Range, RangeInclusive};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+use crate::diff::unified_diff;
 use crate::FormatMode;
 use bumpalo::Bump;
-use roc_error_macros::{internal_error, user_error};
+use roc_error_macros::internal_error;
 use roc_fmt::def::fmt_defs;
+use roc_fmt::imports::organize_imports;
 use roc_fmt::module::fmt_module;
 use roc_fmt::spaces::RemoveSpaces;
 use roc_fmt::{Ast, Buf};
+use roc_load::{ExecutionMode, LoadConfig, Threading};
+use roc_packaging::cache::{self, RocCacheDir};
 use roc_parse::{
+    ast::Defs,
     module::{self, module_defs},
     parser::{Parser, SyntaxError},
     state::State,
 };
+use roc_problem::can::Problem;
+use roc_region::all::{LineInfo, Region};
+use roc_reporting::report::{to_parse_problem_report_string, RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+
+/// The file name used in reports about source code read from stdin.
+const STDIN_FILENAME: &str = "stdin.roc";
 
 fn flatten_directories(files: std::vec::Vec<PathBuf>) -> std::vec::Vec<PathBuf> {
     let mut to_flatten = files;
@@ -58,99 +73,470 @@ fn is_roc_file(path: &Path) -> bool {
     matches!(path.extension().and_then(OsStr::to_str), Some("roc"))
 }
 
-pub fn format(files: std::vec::Vec<PathBuf>, mode: FormatMode) -> Result<(), String> {
+/// What formatting did to one file.
+enum FileOutcome {
+    AlreadyFormatted,
+    Reformatted,
+    /// In check mode, the file would change; this is the diff.
+    NeedsFormatting(String),
+    /// The file didn't parse; this is the report saying why.
+    ParseFailed(String),
+}
+
+/// Formats the given files and directories, or just checks that they're formatted.
+/// Returns the exit code.
+///
+/// When checking, every file gets visited and we print a diff for each one that would change.
+/// The exit code is then the number of files that need formatting (or didn't parse), up to 255.
+///
+/// With `organize_imports`, each file's imports are also sorted and merged, and the ones the
+/// compiler reports as unused are removed. That takes loading the module, so it's opt-in.
+///
+/// Lines that would be wider than `line_width` get broken up where possible.
+pub fn format(
+    files: std::vec::Vec<PathBuf>,
+    mode: FormatMode,
+    opt_lines: Option<RangeInclusive<u32>>,
+    organize_imports: bool,
+    line_width: usize,
+) -> i32 {
     let files = flatten_directories(files);
 
-    for file in files {
-        let arena = Bump::new();
-
-        let src = std::fs::read_to_string(&file).unwrap();
-
-        let ast = arena.alloc(parse_all(&arena, &src).unwrap_or_else(|e| {
-            user_error!("Unexpected parse failure when parsing this formatting:\n\n{:?}\n\nParse error was:\n\n{:?}\n\n", src, e)
-        }));
-        let mut buf = Buf::new_in(&arena);
-        fmt_all(&mut buf, ast);
-
-        let reparsed_ast = arena.alloc(parse_all(&arena, buf.as_str()).unwrap_or_else(|e| {
-            let mut fail_file = file.clone();
-            fail_file.set_extension("roc-format-failed");
-            std::fs::write(&fail_file, buf.as_str()).unwrap();
-            internal_error!(
-                "Formatting bug; formatted code isn't valid\n\n\
-                I wrote the incorrect result to this file for debugging purposes:\n{}\n\n\
-                Parse error was: {:?}\n\n",
-                fail_file.display(),
-                e
-            );
-        }));
-
-        let ast_normalized = ast.remove_spaces(&arena);
-        let reparsed_ast_normalized = reparsed_ast.remove_spaces(&arena);
-
-        // HACK!
-        // We compare the debug format strings of the ASTs, because I'm finding in practice that _somewhere_ deep inside the ast,
-        // the PartialEq implementation is returning `false` even when the Debug-formatted impl is exactly the same.
-        // I don't have the patience to debug this right now, so let's leave it for another day...
-        // TODO: fix PartialEq impl on ast types
-        if format!("{:?}", ast_normalized) != format!("{:?}", reparsed_ast_normalized) {
-            let mut fail_file = file.clone();
-            fail_file.set_extension("roc-format-failed");
-            std::fs::write(&fail_file, buf.as_str()).unwrap();
-
-            let mut before_file = file.clone();
-            before_file.set_extension("roc-format-failed-ast-before");
-            std::fs::write(&before_file, format!("{:#?}\n", ast_normalized)).unwrap();
-
-            let mut after_file = file.clone();
-            after_file.set_extension("roc-format-failed-ast-after");
-            std::fs::write(&after_file, format!("{:#?}\n", reparsed_ast_normalized)).unwrap();
-
-            internal_error!(
-                "Formatting bug; formatting didn't reparse as the same tree\n\n\
-                I wrote the incorrect result to this file for debugging purposes:\n{}\n\n\
-                I wrote the tree before and after formatting to these files for debugging purposes:\n{}\n{}\n\n",
-                fail_file.display(),
-                before_file.display(),
-                after_file.display());
+    let mut needs_formatting = 0;
+    let mut parse_failures = 0;
+
+    let outcomes = format_files_in_parallel(
+        &files,
+        mode,
+        opt_lines.as_ref(),
+        organize_imports,
+        line_width,
+    );
+
+    for outcome in outcomes {
+        match outcome {
+            FileOutcome::AlreadyFormatted | FileOutcome::Reformatted => {}
+            FileOutcome::NeedsFormatting(diff) => {
+                print!("{diff}");
+                needs_formatting += 1;
+            }
+            FileOutcome::ParseFailed(report) => {
+                eprintln!("{report}");
+                parse_failures += 1;
+            }
         }
+    }
+
+    match mode {
+        FormatMode::CheckOnly => {
+            match needs_formatting {
+                0 => {}
+                1 => eprintln!("1 file needs to be reformatted."),
+                _ => eprintln!("{needs_formatting} files need to be reformatted."),
+            }
 
-        // Now verify that the resultant formatting is _stable_ - i.e. that it doesn't change again if re-formatted
-        let mut reformatted_buf = Buf::new_in(&arena);
-        fmt_all(&mut reformatted_buf, reparsed_ast);
-        if buf.as_str() != reformatted_buf.as_str() {
-            let mut unstable_1_file = file.clone();
-            unstable_1_file.set_extension("roc-format-unstable-1");
-            std::fs::write(&unstable_1_file, buf.as_str()).unwrap();
-
-            let mut unstable_2_file = file.clone();
-            unstable_2_file.set_extension("roc-format-unstable-2");
-            std::fs::write(&unstable_2_file, reformatted_buf.as_str()).unwrap();
-
-            internal_error!(
-                "Formatting bug; formatting is not stable. Reformatting the formatted file changed it again.\n\n\
-                I wrote the result of formatting to this file for debugging purposes:\n{}\n\n\
-                I wrote the result of double-formatting here:\n{}\n\n",
-                unstable_1_file.display(),
-                unstable_2_file.display());
+            // exit codes only go up to 255, and 256 would look like success
+            (needs_formatting + parse_failures).min(255)
         }
+        FormatMode::Format => (parse_failures > 0) as i32,
+    }
+}
 
-        match mode {
-            FormatMode::CheckOnly => {
-                // If we notice that this file needs to be formatted, return early
-                if buf.as_str() != src {
-                    return Err("One or more files need to be reformatted.".to_string());
-                }
+/// Formats each file with its own arena, on as many threads as there are cores.
+/// The outcomes are in the same order as the files.
+fn format_files_in_parallel(
+    files: &[PathBuf],
+    mode: FormatMode,
+    opt_lines: Option<&RangeInclusive<u32>>,
+    organize_imports: bool,
+    line_width: usize,
+) -> std::vec::Vec<FileOutcome> {
+    let num_threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(files.len());
+    let next_file = &AtomicUsize::new(0);
+
+    let mut outcomes: std::vec::Vec<(usize, FileOutcome)> = std::thread::scope(|scope| {
+        let workers: std::vec::Vec<_> = (0..num_threads)
+            .map(|_| {
+                scope.spawn(move || {
+                    let mut outcomes = std::vec::Vec::new();
+
+                    loop {
+                        let index = next_file.fetch_add(1, Ordering::Relaxed);
+
+                        match files.get(index) {
+                            Some(file) => outcomes.push((
+                                index,
+                                format_file(file, mode, opt_lines, organize_imports, line_width),
+                            )),
+                            None => break outcomes,
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+            })
+            .collect()
+    });
+
+    outcomes.sort_by_key(|(index, _)| *index);
+
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+fn format_file(
+    file: &Path,
+    mode: FormatMode,
+    opt_lines: Option<&RangeInclusive<u32>>,
+    organize_imports: bool,
+    line_width: usize,
+) -> FileOutcome {
+    let arena = Bump::new();
+
+    let src = std::fs::read_to_string(file).unwrap();
+    let unused_imports = organize_imports.then(|| unused_import_regions(file));
+
+    let unused_imports = unused_imports.as_deref();
+
+    let formatted = match format_src(&arena, file, &src, opt_lines, unused_imports, line_width) {
+        Ok(formatted) => formatted,
+        Err(report) => return FileOutcome::ParseFailed(report),
+    };
+
+    if formatted == src {
+        return FileOutcome::AlreadyFormatted;
+    }
+
+    match mode {
+        FormatMode::CheckOnly => FileOutcome::NeedsFormatting(diff(file, &src, &formatted)),
+        FormatMode::Format => {
+            // If all the checks passed, actually write out the new file.
+            std::fs::write(file, formatted).unwrap();
+
+            FileOutcome::Reformatted
+        }
+    }
+}
+
+/// Formats the source code on stdin, and writes the result to stdout.
+/// Returns the exit code.
+///
+/// Source code from stdin can't be loaded, so `organize_imports` only sorts and merges the
+/// imports here; unused ones stay.
+pub fn format_stdin(
+    mode: FormatMode,
+    opt_lines: Option<RangeInclusive<u32>>,
+    organize_imports: bool,
+    line_width: usize,
+) -> i32 {
+    let mut src = String::new();
+
+    if let Err(err) = std::io::stdin().read_to_string(&mut src) {
+        eprintln!("Failed to read from stdin: {err}");
+
+        return 1;
+    }
+
+    let arena = Bump::new();
+    let file = Path::new(STDIN_FILENAME);
+
+    let unused_imports: Option<&[Region]> = organize_imports.then_some(&[]);
+
+    let opt_lines = opt_lines.as_ref();
+
+    let formatted = match format_src(&arena, file, &src, opt_lines, unused_imports, line_width) {
+        Ok(formatted) => formatted,
+        Err(report) => {
+            eprintln!("{report}");
+
+            return 1;
+        }
+    };
+
+    match mode {
+        FormatMode::CheckOnly => {
+            if formatted == src {
+                0
+            } else {
+                print!("{}", diff(file, &src, &formatted));
+                eprintln!("The input needs to be reformatted.");
+
+                1
+            }
+        }
+
+        FormatMode::Format => {
+            print!("{formatted}");
+
+            0
+        }
+    }
+}
+
+/// The regions of the imports in `file` that the compiler reports as unused. If the module
+/// doesn't load (say, because it's part of a package we can't resolve from here), we can't
+/// tell which imports are unused, so this is empty and the imports only get sorted and merged.
+fn unused_import_regions(file: &Path) -> std::vec::Vec<Region> {
+    let arena = Bump::new();
+    let load_config = LoadConfig {
+        // only used for generating errors, which we don't report, so hardcoding it is fine
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        // files are already being formatted in parallel
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Check,
+        typed_holes: false,
+    };
+
+    let loaded = match roc_load::load_and_typecheck(
+        &arena,
+        file.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(_) => return std::vec::Vec::new(),
+    };
+
+    loaded
+        .can_problems
+        .get(&loaded.module_id)
+        .into_iter()
+        .flatten()
+        .filter_map(|problem| match problem {
+            Problem::UnusedImport(_, region) | Problem::UnusedModuleImport(_, region) => {
+                Some(*region)
             }
+            _ => None,
+        })
+        .collect()
+}
+
+/// What formatting would change about `file`, for `roc format --check`.
+fn diff(file: &Path, src: &str, formatted: &str) -> String {
+    let diff = unified_diff(file, src, formatted);
+
+    if diff.is_empty() {
+        // The lines are all the same, so it's just line endings that differ.
+        format!(
+            "{}: the line endings need to be reformatted\n",
+            file.display()
+        )
+    } else {
+        diff
+    }
+}
+
+/// Parses a line range like `12:20` (1-based and inclusive) for `roc format --lines`.
+pub fn parse_line_range(range: &str) -> Result<RangeInclusive<u32>, String> {
+    let invalid = || format!("`{range}` is not a line range like `12:20`");
+
+    let (first, last) = range.split_once(':').ok_or_else(invalid)?;
+    let first: u32 = first.trim().parse().map_err(|_| invalid())?;
+    let last: u32 = last.trim().parse().map_err(|_| invalid())?;
+
+    if first == 0 || last < first {
+        return Err(invalid());
+    }
 
-            FormatMode::Format => {
-                // If all the checks above passed, actually write out the new file.
-                std::fs::write(&file, buf.as_str()).unwrap();
+    Ok(first..=last)
+}
+
+/// Formats `src`, the contents of `file`. If there are `opt_lines`, only the top-level defs
+/// overlapping those lines get formatted; everything else is left exactly as it was.
+///
+/// If there are `opt_unused_imports`, the imports get organized too, dropping the ones at
+/// those regions. That only happens when formatting the whole file, since `opt_lines`
+/// never covers the header.
+///
+/// If `src` doesn't parse, the error is a report saying why.
+fn format_src(
+    arena: &Bump,
+    file: &Path,
+    src: &str,
+    opt_lines: Option<&RangeInclusive<u32>>,
+    opt_unused_imports: Option<&[Region]>,
+    line_width: usize,
+) -> Result<String, String> {
+    let mut ast = parse_all(arena, src).map_err(|problem| {
+        to_parse_problem_report_string(
+            file,
+            src,
+            problem,
+            RenderTarget::ColorTerminal,
+            &DEFAULT_PALETTE,
+        )
+    })?;
+
+    match opt_lines {
+        None => {
+            if let Some(unused) = opt_unused_imports {
+                // Organize before formatting, so the formatter's checks compare what it
+                // printed against the organized tree rather than the original one.
+                ast.module = organize_imports(arena, &ast.module, unused);
             }
+
+            let formatted = fmt_checked(
+                arena,
+                file,
+                arena.alloc(ast),
+                line_width,
+                parse_all,
+                fmt_all_in,
+            );
+
+            Ok(formatted.to_string())
         }
+        Some(lines) => {
+            let span = match defs_span(&ast.defs, &LineInfo::new(src), lines) {
+                Some(span) => span,
+                // There are no defs in range; the header is always left alone.
+                None => return Ok(src.to_string()),
+            };
+
+            // Top-level defs start in the first column, so the defs in range can be parsed
+            // and formatted on their own, without the rest of the module.
+            let defs_src = &src[span.clone()];
+            let defs = parse_defs(arena, defs_src).unwrap_or_else(|e| {
+                internal_error!(
+                    "The top-level defs in lines {}-{} don't parse on their own:\n\n{:?}\n\nParse error was: {:?}\n\n",
+                    lines.start(),
+                    lines.end(),
+                    defs_src,
+                    e
+                )
+            });
+            let formatted = fmt_checked(
+                arena,
+                file,
+                arena.alloc(defs),
+                line_width,
+                parse_defs,
+                fmt_defs_in,
+            );
+
+            Ok(format!(
+                "{}{}{}",
+                &src[..span.start],
+                formatted.trim_end(),
+                &src[span.end..]
+            ))
+        }
+    }
+}
+
+/// The byte range from the start of the first top-level def overlapping `lines`
+/// to the end of the last one, if there are any.
+fn defs_span(
+    defs: &Defs,
+    line_info: &LineInfo,
+    lines: &RangeInclusive<u32>,
+) -> Option<Range<usize>> {
+    // LineInfo counts lines from 0
+    let first_line = lines.start() - 1;
+    let last_line = lines.end() - 1;
+
+    let mut in_range = defs.regions.iter().filter(|region| {
+        let region = line_info.convert_region(**region);
+
+        region.start.line <= last_line && region.end.line >= first_line
+    });
+
+    let first = in_range.next()?;
+    let last = in_range.last().unwrap_or(first);
+
+    Some(first.start().offset as usize..last.end().offset as usize)
+}
+
+/// Formats what `parse` parsed out of `src`, then checks that the result parses to the
+/// same tree and that formatting it again doesn't change it. Either failing is a bug
+/// in the formatter, for which we write out some files to help debug it.
+fn fmt_checked<'a, T>(
+    arena: &'a Bump,
+    file: &Path,
+    ast: &'a T,
+    line_width: usize,
+    parse: fn(&'a Bump, &'a str) -> Result<T, SyntaxError<'a>>,
+    fmt: fn(&'a Bump, &'a T, usize) -> &'a str,
+) -> &'a str
+where
+    T: RemoveSpaces<'a> + std::fmt::Debug,
+{
+    let formatted = fmt(arena, ast, line_width);
+
+    let reparsed_ast = arena.alloc(parse(arena, formatted).unwrap_or_else(|e| {
+        let mut fail_file = file.to_path_buf();
+        fail_file.set_extension("roc-format-failed");
+        std::fs::write(&fail_file, formatted).unwrap();
+        internal_error!(
+            "Formatting bug; formatted code isn't valid\n\n\
+            I wrote the incorrect result to this file for debugging purposes:\n{}\n\n\
+            Parse error was: {:?}\n\n",
+            fail_file.display(),
+            e
+        );
+    }));
+
+    let ast_normalized = ast.remove_spaces(arena);
+    let reparsed_ast_normalized = reparsed_ast.remove_spaces(arena);
+
+    // HACK!
+    // We compare the debug format strings of the ASTs, because I'm finding in practice that _somewhere_ deep inside the ast,
+    // the PartialEq implementation is returning `false` even when the Debug-formatted impl is exactly the same.
+    // I don't have the patience to debug this right now, so let's leave it for another day...
+    // TODO: fix PartialEq impl on ast types
+    if format!("{:?}", ast_normalized) != format!("{:?}", reparsed_ast_normalized) {
+        let mut fail_file = file.to_path_buf();
+        fail_file.set_extension("roc-format-failed");
+        std::fs::write(&fail_file, formatted).unwrap();
+
+        let mut before_file = file.to_path_buf();
+        before_file.set_extension("roc-format-failed-ast-before");
+        std::fs::write(&before_file, format!("{:#?}\n", ast_normalized)).unwrap();
+
+        let mut after_file = file.to_path_buf();
+        after_file.set_extension("roc-format-failed-ast-after");
+        std::fs::write(&after_file, format!("{:#?}\n", reparsed_ast_normalized)).unwrap();
+
+        internal_error!(
+            "Formatting bug; formatting didn't reparse as the same tree\n\n\
+            I wrote the incorrect result to this file for debugging purposes:\n{}\n\n\
+            I wrote the tree before and after formatting to these files for debugging purposes:\n{}\n{}\n\n",
+            fail_file.display(),
+            before_file.display(),
+            after_file.display());
+    }
+
+    // Now verify that the resultant formatting is _stable_ - i.e. that it doesn't change again if re-formatted
+    let reformatted = fmt(arena, reparsed_ast, line_width);
+    if formatted != reformatted {
+        let mut unstable_1_file = file.to_path_buf();
+        unstable_1_file.set_extension("roc-format-unstable-1");
+        std::fs::write(&unstable_1_file, formatted).unwrap();
+
+        let mut unstable_2_file = file.to_path_buf();
+        unstable_2_file.set_extension("roc-format-unstable-2");
+        std::fs::write(&unstable_2_file, reformatted).unwrap();
+
+        internal_error!(
+            "Formatting bug; formatting is not stable. Reformatting the formatted file changed it again.\n\n\
+            I wrote the result of formatting to this file for debugging purposes:\n{}\n\n\
+            I wrote the result of double-formatting here:\n{}\n\n",
+            unstable_1_file.display(),
+            unstable_2_file.display());
     }
 
-    Ok(())
+    formatted
 }
 
 fn parse_all<'a>(arena: &'a Bump, src: &'a str) -> Result<Ast<'a>, SyntaxError<'a>> {
@@ -162,6 +548,31 @@ fn parse_all<'a>(arena: &'a Bump, src: &'a str) -> Result<Ast<'a>, SyntaxError<'
     Ok(Ast { module, defs })
 }
 
+fn parse_defs<'a>(arena: &'a Bump, src: &'a str) -> Result<Defs<'a>, SyntaxError<'a>> {
+    let (_, defs, _) = module_defs()
+        .parse(arena, State::new(src.as_bytes()), 0)
+        .map_err(|(_, e)| e)?;
+
+    Ok(defs)
+}
+
+/// Formats the whole module, breaking lines that are wider than `line_width`.
+fn fmt_all_in<'a>(arena: &'a Bump, ast: &'a Ast, line_width: usize) -> &'a str {
+    let mut buf = Buf::with_max_width(arena, line_width);
+    fmt_all(&mut buf, ast);
+
+    buf.into_bump_str()
+}
+
+/// Formats just some top-level defs, breaking lines that are wider than `line_width`.
+fn fmt_defs_in<'a>(arena: &'a Bump, defs: &'a Defs, line_width: usize) -> &'a str {
+    let mut buf = Buf::with_max_width(arena, line_width);
+    fmt_defs(&mut buf, defs, 0);
+    buf.fmt_end_of_file();
+
+    buf.into_bump_str()
+}
+
 fn fmt_all<'a>(buf: &mut Buf<'a>, ast: &'a Ast) {
     fmt_module(buf, &ast.module);
 
@@ -16,10 +16,12 @@ use roc_build::program::{
 use roc_error_macros::{internal_error, user_error};
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_lint::{LintConfig, LintKind};
 use roc_load::{ExpectMetadata, Threading};
 use roc_mono::ir::OptLevel;
 use roc_packaging::cache::RocCacheDir;
 use roc_packaging::tarball::Compression;
+use roc_problem::{Level, Levels};
 use std::env;
 use std::ffi::{CString, OsStr, OsString};
 use std::io;
@@ -36,8 +38,18 @@ use target_lexicon::{
 #[cfg(not(target_os = "linux"))]
 use tempfile::TempDir;
 
+mod annotate;
+mod diff;
+#[cfg(not(windows))]
+mod doctest;
+mod fix;
 mod format;
-pub use format::format;
+mod lint;
+pub mod warnings;
+pub use annotate::{annotate, AnnotateMode};
+pub use fix::fix;
+pub use format::{format, format_stdin, parse_line_range};
+pub use lint::lint;
 
 pub const CMD_BUILD: &str = "build";
 pub const CMD_RUN: &str = "run";
@@ -46,6 +58,10 @@ pub const CMD_REPL: &str = "repl";
 pub const CMD_EDIT: &str = "edit";
 pub const CMD_DOCS: &str = "docs";
 pub const CMD_CHECK: &str = "check";
+pub const CMD_FIX: &str = "fix";
+pub const CMD_LINT: &str = "lint";
+pub const CMD_ANNOTATE: &str = "annotate";
+pub const CMD_API_DIFF: &str = "api-diff";
 pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
@@ -65,9 +81,22 @@ pub const FLAG_TIME: &str = "time";
 pub const FLAG_LINKER: &str = "linker";
 pub const FLAG_PREBUILT: &str = "prebuilt-platform";
 pub const FLAG_CHECK: &str = "check";
+pub const FLAG_STDIN: &str = "stdin";
+pub const FLAG_LINES: &str = "lines";
+pub const FLAG_ORGANIZE_IMPORTS: &str = "organize-imports";
+pub const FLAG_LINE_WIDTH: &str = "line-width";
+pub const FLAG_DOC: &str = "doc";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
+pub const FLAG_DENY: &str = "deny";
+pub const FLAG_ALLOW: &str = "allow";
+pub const FLAG_EXPOSED_ONLY: &str = "exposed-only";
+pub const FLAG_OUTPUT: &str = "output";
+pub const FLAG_DOCS_FORMAT: &str = "format";
+pub const FLAG_TYPED_HOLES: &str = "typed-holes";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
+pub const OLD_ROC_FILE: &str = "OLD_ROC_FILE";
+pub const NEW_ROC_FILE: &str = "NEW_ROC_FILE";
 pub const GLUE_DIR: &str = "GLUE_DIR";
 pub const GLUE_SPEC: &str = "GLUE_SPEC";
 pub const DIRECTORY_OR_FILES: &str = "DIRECTORY_OR_FILES";
@@ -130,6 +159,20 @@ pub fn build_app() -> Command {
         .value_parser(value_parser!(u32))
         .required(false);
 
+    let flag_deny = Arg::new(FLAG_DENY)
+        .long(FLAG_DENY)
+        .help("Report this kind of warning or lint as an error, e.g. --deny unused-import\n(Can be given more than once. Overrides the levels in roc-warnings.json and roc-lint.json.)")
+        .value_name("NAME")
+        .action(ArgAction::Append)
+        .required(false);
+
+    let flag_allow = Arg::new(FLAG_ALLOW)
+        .long(FLAG_ALLOW)
+        .help("Don't report this kind of warning or lint, e.g. --allow unused-def\n(Can be given more than once. Overrides the levels in roc-warnings.json and roc-lint.json.)")
+        .value_name("NAME")
+        .action(ArgAction::Append)
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .value_parser(value_parser!(PathBuf))
@@ -158,6 +201,8 @@ pub fn build_app() -> Command {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_allow.clone())
             .arg(flag_wasm_stack_size_kb)
             .arg(
                 Arg::new(FLAG_TARGET)
@@ -207,6 +252,15 @@ pub fn build_app() -> Command {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_allow.clone())
+            .arg(
+                Arg::new(FLAG_DOC)
+                    .long(FLAG_DOC)
+                    .help("Also run the code examples in the main module's ## doc comments\n(Examples tagged `unchecked` are skipped; `repl` examples have to print what the docs show.)")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file for the main module")
@@ -229,6 +283,8 @@ pub fn build_app() -> Command {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_allow.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -242,6 +298,8 @@ pub fn build_app() -> Command {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_allow.clone())
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone().last(true))
         )
@@ -260,6 +318,36 @@ pub fn build_app() -> Command {
                     .action(ArgAction::SetTrue)
                     .required(false),
             )
+            .arg(
+                Arg::new(FLAG_STDIN)
+                    .long(FLAG_STDIN)
+                    .help("Format code from stdin; output to stdout")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with(DIRECTORY_OR_FILES)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_LINES)
+                    .long(FLAG_LINES)
+                    .help("Only format the top-level definitions overlapping these lines, e.g. 12:20\n(Requires --stdin or a single file.)")
+                    .value_parser(parse_line_range)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_ORGANIZE_IMPORTS)
+                    .long(FLAG_ORGANIZE_IMPORTS)
+                    .help("Also sort the imports and exposed names, merge imports of the same module, and remove unused imports\n(Unused imports are only found in files that type-check on their own.)")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_LINE_WIDTH)
+                    .long(FLAG_LINE_WIDTH)
+                    .help("Break up lines that would be wider than this many columns")
+                    .value_parser(value_parser!(usize))
+                    .default_value(concatcp!(roc_fmt::DEFAULT_LINE_WIDTH))
+                    .required(false),
+            )
         )
         .subcommand(Command::new(CMD_VERSION)
             .about(concatcp!("Print the Roc compiler’s version, which is currently ", VERSION)))
@@ -267,6 +355,15 @@ pub fn build_app() -> Command {
             .about("Check the code for problems, but don’t build or run it")
             .arg(flag_time.clone())
             .arg(flag_max_threads.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_allow.clone())
+            .arg(
+                Arg::new(FLAG_TYPED_HOLES)
+                    .long(FLAG_TYPED_HOLES)
+                    .help("Treat a `_` where a value should be as a hole, and report the type it needs to have")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
                     .help("The .roc file of an app to check")
@@ -275,6 +372,63 @@ pub fn build_app() -> Command {
                     .default_value(DEFAULT_ROC_FILENAME),
             )
             )
+        .subcommand(Command::new(CMD_FIX)
+            .about("Automatically fix the problems that have a clear fix, like unused imports")
+            .arg(flag_max_threads.clone())
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file of an app or package to fix")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
+        .subcommand(Command::new(CMD_ANNOTATE)
+            .about("Add the inferred type annotation to each top-level def that doesn't have one")
+            .arg(flag_max_threads.clone())
+            .arg(
+                Arg::new(FLAG_EXPOSED_ONLY)
+                    .long(FLAG_EXPOSED_ONLY)
+                    .help("Only annotate the values the module exposes")
+                    .action(ArgAction::SetTrue)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file to annotate")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
+        .subcommand(Command::new(CMD_LINT)
+            .about(concatcp!("Check the code for things that could be clearer, using the lints configured in ", roc_lint::config::CONFIG_FILENAME))
+            .arg(flag_max_threads.clone())
+            .arg(flag_deny.clone())
+            .arg(flag_allow.clone())
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file of an app or package to lint")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
+        .subcommand(Command::new(CMD_API_DIFF)
+            .about("Compare what two versions of a package expose, and report which changes would break its users\n(Exits with code 1 if any do.)")
+            .arg(
+                Arg::new(OLD_ROC_FILE)
+                    .help("The main .roc file of the old version of the package")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(true),
+            )
+            .arg(
+                Arg::new(NEW_ROC_FILE)
+                    .help("The main .roc file of the new version of the package")
+                    .value_parser(value_parser!(PathBuf))
+                    .required(true),
+            )
+        )
         .subcommand(
             Command::new(CMD_DOCS)
                 .about("Generate documentation for a Roc package")
@@ -284,6 +438,22 @@ pub fn build_app() -> Command {
                     .required(false)
                     .default_value(DEFAULT_ROC_FILENAME),
                 )
+                .arg(
+                    Arg::new(FLAG_OUTPUT)
+                        .long(FLAG_OUTPUT)
+                        .help("The directory to write the docs to")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(false)
+                        .default_value(roc_docs::DEFAULT_OUTPUT_DIR),
+                )
+                .arg(
+                    Arg::new(FLAG_DOCS_FORMAT)
+                        .long(FLAG_DOCS_FORMAT)
+                        .help("What to generate: a website, one JSON file for tools to consume, or a Markdown file per module")
+                        .value_parser(["html", "json", "markdown"])
+                        .required(false)
+                        .default_value("html"),
+                )
         )
         .subcommand(Command::new(CMD_GLUE)
             .about("Generate glue code between a platform's Roc API and its host language")
@@ -333,6 +503,8 @@ pub fn build_app() -> Command {
         .arg(flag_time)
         .arg(flag_linker)
         .arg(flag_prebuilt)
+        .arg(flag_deny)
+        .arg(flag_allow)
         .arg(roc_file_to_run)
         .arg(args_for_app.trailing_var_arg(true));
 
@@ -360,6 +532,7 @@ pub enum BuildConfig {
     BuildAndRunIfNoErrors,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FormatMode {
     Format,
     CheckOnly,
@@ -379,6 +552,70 @@ fn opt_level_from_flags(matches: &ArgMatches) -> OptLevel {
     }
 }
 
+/// The names given to `--allow` and `--deny`, with the level each one sets.
+/// Each name has to be the name of a compiler warning or of a lint.
+fn level_flags(matches: &ArgMatches) -> Vec<(&str, Level)> {
+    let mut names_and_levels = Vec::new();
+
+    for (flag, level) in [(FLAG_ALLOW, Level::Allow), (FLAG_DENY, Level::Deny)] {
+        for name in matches.get_many::<String>(flag).unwrap_or_default() {
+            if !warnings::is_warning_name(name) && LintKind::from_name(name).is_none() {
+                let mut names = warnings::warning_names();
+
+                names.extend(LintKind::ALL.iter().map(|kind| kind.name()));
+                names.sort_unstable();
+
+                user_error!(
+                    "--{} {} is not the name of a warning or lint. These are the names: {}",
+                    flag,
+                    name,
+                    names.join(", ")
+                );
+            }
+
+            names_and_levels.push((name.as_str(), level));
+        }
+    }
+
+    names_and_levels
+}
+
+/// The levels for compiler warnings from the project's `roc-warnings.json` next to
+/// `roc_file_path` (if it has one), with the levels from any `--deny` and `--allow` flags on top.
+pub fn warning_levels_from_flags(matches: &ArgMatches, roc_file_path: &Path) -> Levels {
+    let root_dir = roc_file_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut levels = match warnings::load_levels(root_dir) {
+        Ok(levels) => levels,
+        Err(err) => user_error!("{}", err),
+    };
+
+    for (name, level) in level_flags(matches) {
+        if warnings::is_warning_name(name) {
+            levels.set(name, level);
+        }
+    }
+
+    levels
+}
+
+/// The project's `roc-lint.json` next to `roc_file_path` (if it has one),
+/// with the levels from any `--deny` and `--allow` flags on top.
+pub fn lint_config_from_flags(matches: &ArgMatches, roc_file_path: &Path) -> LintConfig {
+    let root_dir = roc_file_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut config = match LintConfig::load(root_dir) {
+        Ok(config) => config,
+        Err(err) => user_error!("{}", err),
+    };
+
+    for (name, level) in level_flags(matches) {
+        if let Some(kind) = LintKind::from_name(name) {
+            config.set_level(kind, level);
+        }
+    }
+
+    config
+}
+
 #[cfg(windows)]
 pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
     todo!("running tests does not work on windows right now")
@@ -387,6 +624,7 @@ pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
 #[cfg(not(windows))]
 pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     use roc_build::program::report_problems_monomorphized;
+    use roc_docs::doctest::modules_in;
     use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError};
     use roc_packaging::cache;
     use roc_target::TargetInfo;
@@ -431,6 +669,7 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     let target = &triple;
     let opt_level = opt_level;
     let target_info = TargetInfo::from(target);
+    let levels = &warning_levels_from_flags(matches, path);
 
     // Step 1: compile the app and generate the .o file
     let load_config = LoadConfig {
@@ -440,6 +679,7 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Test,
+        typed_holes: false,
     };
     let load_result = roc_load::load_and_monomorphize(
         arena,
@@ -454,13 +694,37 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
             return handle_loading_problem(problem);
         }
         Err(LoadMonomorphizedError::ErrorModule(module)) => {
-            return handle_error_module(module, start_time.elapsed(), path.as_os_str(), false);
+            return handle_error_module(
+                module,
+                start_time.elapsed(),
+                path.as_os_str(),
+                false,
+                levels,
+            );
         }
     };
-    let problems = report_problems_monomorphized(&mut loaded);
+    let problems = report_problems_monomorphized(&mut loaded, levels);
+
+    if problems.errors > 0 {
+        // Loading only stops for errors, so these must be warnings that were denied.
+        problems.print_to_stdout(start_time.elapsed());
+        println!(".");
+
+        return Ok(problems.exit_code());
+    }
 
     let mut expectations = std::mem::take(&mut loaded.expectations);
 
+    // With --doc, the examples in the docs of every module in the root module's directory
+    // (or below it) run after the modules' own tests.
+    let root_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let sources = std::mem::take(&mut loaded.sources);
+    let doc_modules = if matches.get_flag(FLAG_DOC) {
+        modules_in(&sources, &root_dir)
+    } else {
+        Vec::new()
+    };
+
     let interns = loaded.interns.clone();
 
     let (lib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
@@ -502,6 +766,21 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     )
     .unwrap();
 
+    let (failed, passed) = if doc_modules.is_empty() {
+        (failed, passed)
+    } else {
+        let (doc_failed, doc_passed) = doctest::run_doc_examples(
+            &mut writer,
+            target,
+            opt_level,
+            threading,
+            &root_dir,
+            &doc_modules,
+        )?;
+
+        (failed + doc_failed, passed + doc_passed)
+    };
+
     let total_time = start_time.elapsed();
 
     if failed == 0 && passed == 0 {
@@ -724,6 +1003,7 @@ pub fn build(
     };
 
     let load_config = standard_load_config(&triple, build_ordering, threading);
+    let levels = warning_levels_from_flags(matches, path);
 
     let res_binary_path = build_file(
         &arena,
@@ -737,6 +1017,7 @@ pub fn build(
         wasm_dev_stack_bytes,
         roc_cache_dir,
         load_config,
+        &levels,
     );
 
     match res_binary_path {
@@ -806,10 +1087,14 @@ pub fn build(
                         // Return a nonzero exit code due to fatal problem
                         return Ok(problems.exit_code());
                     }
-                    debug_assert_eq!(
-                        problems.errors, 0,
-                        "if there are non-fatal errors, they should have been returned as an error variant"
-                    );
+                    if problems.errors > 0 {
+                        // Other errors would have been returned as an error variant,
+                        // so these must be warnings that were denied.
+                        problems.print_to_stdout(total_time);
+                        println!(".");
+
+                        return Ok(problems.exit_code());
+                    }
 
                     if problems.warnings > 0 {
                         problems.print_to_stdout(total_time);
@@ -833,7 +1118,7 @@ pub fn build(
             }
         }
         Err(BuildFileError::ErrorModule { module, total_time }) => {
-            handle_error_module(module, total_time, path.as_os_str(), true)
+            handle_error_module(module, total_time, path.as_os_str(), true, &levels)
         }
         Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
     }
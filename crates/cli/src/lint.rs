@@ -0,0 +1,120 @@
+//! `roc lint`, which runs the checks in `roc_lint` over the modules of an app or package.
+use bumpalo::Bump;
+use roc_build::program::report_problems_typechecked;
+use roc_lint::report::lint_report;
+use roc_lint::{lint_module, LintConfig};
+use roc_load::{ExecutionMode, LoadConfig, LoadingProblem, Threading};
+use roc_packaging::cache::RocCacheDir;
+use roc_problem::{Levels, Severity};
+use roc_region::all::LineInfo;
+use roc_reporting::report::{RenderTarget, Report, RocDocAllocator, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+
+/// Lint the modules of the app or package at `roc_file_path`.
+/// Only modules in the same directory (or below it) get linted.
+pub fn lint(
+    roc_file_path: &Path,
+    threading: Threading,
+    roc_cache_dir: RocCacheDir<'_>,
+    config: LintConfig,
+    warning_levels: &Levels,
+) -> io::Result<i32> {
+    let start_time = Instant::now();
+    let root_dir = roc_file_path.parent().unwrap_or_else(|| Path::new(""));
+    let arena = Bump::new();
+    let load_config = LoadConfig {
+        // only used for generating errors, so hardcoding it is fine
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Check,
+        typed_holes: false,
+    };
+
+    let mut loaded = match roc_load::load_and_typecheck(
+        &arena,
+        roc_file_path.to_path_buf(),
+        roc_cache_dir,
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(LoadingProblem::FormattedReport(report)) => {
+            print!("{}", report);
+
+            return Ok(1);
+        }
+        Err(other) => {
+            panic!("lint failed with error:\n{:?}", other);
+        }
+    };
+
+    // The lints assume the code compiles, so there's no point running them if it doesn't.
+    let mut problems = report_problems_typechecked(&mut loaded, warning_levels);
+
+    if problems.errors > 0 {
+        problems.print_to_stdout(start_time.elapsed());
+        println!(".");
+
+        return Ok(problems.exit_code());
+    }
+
+    let mut modules: Vec<_> = loaded
+        .sources
+        .iter()
+        .filter(|(home, (path, _))| !home.is_builtin() && path.starts_with(root_dir))
+        .collect();
+
+    modules.sort_by(|(_, (a, _)), (_, (b, _))| a.cmp(b));
+
+    let mut lints_reported = 0;
+
+    for (home, (path, src)) in modules {
+        let decls = match loaded.declarations_by_id.get(home) {
+            Some(decls) => decls,
+            None => continue,
+        };
+
+        let lints = lint_module(src, decls, &config);
+
+        if lints.is_empty() {
+            continue;
+        }
+
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let alloc = RocDocAllocator::new(&src_lines, *home, &loaded.interns);
+
+        for lint in lints {
+            let severity = match config.level(lint.kind()).severity() {
+                Some(severity) => severity,
+                None => continue,
+            };
+
+            let report = lint_report(&alloc, &lines, path.clone(), src, lint, severity);
+            let mut buf = String::new();
+
+            report.render_color_terminal(&mut buf, &alloc, &DEFAULT_PALETTE);
+            println!("\n{}\n", buf);
+
+            match severity {
+                Severity::Warning => problems.warnings += 1,
+                Severity::RuntimeError | Severity::Fatal => problems.errors += 1,
+            }
+
+            lints_reported += 1;
+        }
+    }
+
+    if lints_reported > 0 {
+        println!("{}\u{001B}[0m\n", Report::horizontal_rule(&DEFAULT_PALETTE));
+    }
+
+    problems.print_to_stdout(start_time.elapsed());
+    println!(".");
+
+    Ok(problems.exit_code())
+}
@@ -1,13 +1,19 @@
 //! The `roc` binary that brings together all functionality in the Roc toolset.
+use clap::ArgMatches;
 use roc_build::link::LinkType;
 use roc_build::program::{check_file, CodeGenBackend};
 use roc_cli::{
-    build_app, format, test, BuildConfig, FormatMode, Target, CMD_BUILD, CMD_CHECK, CMD_DEV,
-    CMD_DOCS, CMD_EDIT, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_REPL, CMD_RUN, CMD_TEST,
-    CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_LIB, FLAG_NO_LINK, FLAG_TARGET,
-    FLAG_TIME, GLUE_DIR, GLUE_SPEC, ROC_FILE,
+    annotate, build_app, fix, format, format_stdin, lint, lint_config_from_flags, test,
+    warning_levels_from_flags, AnnotateMode, BuildConfig, FormatMode, Target, CMD_ANNOTATE,
+    CMD_API_DIFF, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_DOCS, CMD_EDIT, CMD_FIX, CMD_FORMAT,
+    CMD_GEN_STUB_LIB, CMD_GLUE, CMD_LINT, CMD_REPL, CMD_RUN, CMD_TEST, CMD_VERSION,
+    DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_DOCS_FORMAT, FLAG_EXPOSED_ONLY, FLAG_LIB,
+    FLAG_LINES, FLAG_LINE_WIDTH, FLAG_NO_LINK, FLAG_ORGANIZE_IMPORTS, FLAG_OUTPUT, FLAG_STDIN,
+    FLAG_TARGET, FLAG_TIME, FLAG_TYPED_HOLES, GLUE_DIR, GLUE_SPEC, NEW_ROC_FILE, OLD_ROC_FILE,
+    ROC_FILE,
 };
-use roc_docs::generate_docs_html;
+use roc_docs::api_diff::api_diff;
+use roc_docs::{generate_docs, DocsFormat};
 use roc_error_macros::user_error;
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
@@ -15,6 +21,7 @@ use roc_load::{LoadingProblem, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
 use std::fs::{self, FileType};
 use std::io;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use target_lexicon::Triple;
@@ -169,6 +176,8 @@ fn main() -> io::Result<()> {
                 emit_timings,
                 RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
                 threading,
+                &warning_levels_from_flags(matches, roc_file_path),
+                matches.get_flag(FLAG_TYPED_HOLES),
             ) {
                 Ok((problems, total_time)) => {
                     println!(
@@ -228,54 +237,109 @@ fn main() -> io::Result<()> {
             // Exit 0 if the editor exited normally
             Ok(0)
         }
+        Some((CMD_FIX, matches)) => {
+            let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+            let threading = match matches.get_one::<usize>(roc_cli::FLAG_MAX_THREADS) {
+                None => Threading::AllAvailable,
+                Some(0) => user_error!("cannot build with at most 0 threads"),
+                Some(1) => Threading::Single,
+                Some(n) => Threading::AtMost(*n),
+            };
+
+            fix(
+                roc_file_path,
+                threading,
+                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+            )
+        }
+        Some((CMD_ANNOTATE, matches)) => {
+            let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+            let threading = match matches.get_one::<usize>(roc_cli::FLAG_MAX_THREADS) {
+                None => Threading::AllAvailable,
+                Some(0) => user_error!("cannot build with at most 0 threads"),
+                Some(1) => Threading::Single,
+                Some(n) => Threading::AtMost(*n),
+            };
+            let mode = if matches.get_flag(FLAG_EXPOSED_ONLY) {
+                AnnotateMode::ExposedOnly
+            } else {
+                AnnotateMode::All
+            };
+
+            annotate(
+                roc_file_path,
+                threading,
+                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                mode,
+            )
+        }
+        Some((CMD_LINT, matches)) => {
+            let roc_file_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+            let threading = match matches.get_one::<usize>(roc_cli::FLAG_MAX_THREADS) {
+                None => Threading::AllAvailable,
+                Some(0) => user_error!("cannot build with at most 0 threads"),
+                Some(1) => Threading::Single,
+                Some(n) => Threading::AtMost(*n),
+            };
+
+            lint(
+                roc_file_path,
+                threading,
+                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+                lint_config_from_flags(matches, roc_file_path),
+                &warning_levels_from_flags(matches, roc_file_path),
+            )
+        }
+        Some((CMD_API_DIFF, matches)) => {
+            let old_root_path = matches.get_one::<PathBuf>(OLD_ROC_FILE).unwrap();
+            let new_root_path = matches.get_one::<PathBuf>(NEW_ROC_FILE).unwrap();
+
+            Ok(api_diff(old_root_path.to_owned(), new_root_path.to_owned()))
+        }
         Some((CMD_DOCS, matches)) => {
             let root_path = matches.get_one::<PathBuf>(ROC_FILE).unwrap();
+            let out_dir = matches.get_one::<PathBuf>(FLAG_OUTPUT).unwrap();
+            let docs_format = matches
+                .get_one::<String>(FLAG_DOCS_FORMAT)
+                .unwrap()
+                .parse::<DocsFormat>()
+                // clap only lets through the formats we know about
+                .unwrap();
 
-            generate_docs_html(root_path.to_owned());
+            generate_docs(root_path.to_owned(), out_dir, docs_format);
 
             Ok(0)
         }
         Some((CMD_FORMAT, matches)) => {
-            let maybe_values = matches.get_many::<OsString>(DIRECTORY_OR_FILES);
-
-            let mut values: Vec<OsString> = Vec::new();
-
-            match maybe_values {
-                None => {
-                    let mut os_string_values: Vec<OsString> = Vec::new();
-                    read_all_roc_files(
-                        &std::env::current_dir()?.as_os_str().to_os_string(),
-                        &mut os_string_values,
-                    )?;
-                    for os_string in os_string_values {
-                        values.push(os_string);
-                    }
-                }
-                Some(os_values) => {
-                    for os_string in os_values {
-                        values.push(os_string.to_owned());
-                    }
-                }
-            }
-
-            let mut roc_files = Vec::new();
-
-            // Populate roc_files
-            for os_str in values {
-                let metadata = fs::metadata(os_str.clone())?;
-                roc_files_recursive(os_str.as_os_str(), metadata.file_type(), &mut roc_files)?;
-            }
-
             let format_mode = match matches.get_flag(FLAG_CHECK) {
                 true => FormatMode::CheckOnly,
                 false => FormatMode::Format,
             };
 
-            let format_exit_code = match format(roc_files, format_mode) {
-                Ok(_) => 0,
-                Err(message) => {
-                    eprintln!("{}", message);
+            let opt_lines = matches.get_one::<RangeInclusive<u32>>(FLAG_LINES).cloned();
+            let organize_imports = matches.get_flag(FLAG_ORGANIZE_IMPORTS);
+            let line_width = *matches.get_one::<usize>(FLAG_LINE_WIDTH).unwrap();
+
+            let format_exit_code = if matches.get_flag(FLAG_STDIN) {
+                format_stdin(format_mode, opt_lines, organize_imports, line_width)
+            } else {
+                let roc_files = roc_files_to_format(matches)?;
+
+                if opt_lines.is_some() && roc_files.len() != 1 {
+                    eprintln!(
+                        "--{} needs --{} or exactly one file to format.",
+                        FLAG_LINES, FLAG_STDIN
+                    );
+
                     1
+                } else {
+                    format(
+                        roc_files,
+                        format_mode,
+                        opt_lines,
+                        organize_imports,
+                        line_width,
+                    )
                 }
             };
 
@@ -295,6 +359,42 @@ fn main() -> io::Result<()> {
     std::process::exit(exit_code);
 }
 
+/// The .roc files named on the command line (searching directories recursively),
+/// or all the ones in the current directory if none were named.
+fn roc_files_to_format(matches: &ArgMatches) -> io::Result<Vec<PathBuf>> {
+    let maybe_values = matches.get_many::<OsString>(DIRECTORY_OR_FILES);
+
+    let mut values: Vec<OsString> = Vec::new();
+
+    match maybe_values {
+        None => {
+            let mut os_string_values: Vec<OsString> = Vec::new();
+            read_all_roc_files(
+                &std::env::current_dir()?.as_os_str().to_os_string(),
+                &mut os_string_values,
+            )?;
+            for os_string in os_string_values {
+                values.push(os_string);
+            }
+        }
+        Some(os_values) => {
+            for os_string in os_values {
+                values.push(os_string.to_owned());
+            }
+        }
+    }
+
+    let mut roc_files = Vec::new();
+
+    // Populate roc_files
+    for os_str in values {
+        let metadata = fs::metadata(os_str.clone())?;
+        roc_files_recursive(os_str.as_os_str(), metadata.file_type(), &mut roc_files)?;
+    }
+
+    Ok(roc_files)
+}
+
 fn read_all_roc_files(
     dir: &OsString,
     roc_file_paths: &mut Vec<OsString>,
@@ -0,0 +1,71 @@
+//! Per-project levels for compiler warnings, read from a `roc-warnings.json` next to the app or
+//! package's main file. It maps the names of warnings to their levels:
+//!
+//! ```json
+//! { "unused-import": "deny", "unused-def": "allow" }
+//! ```
+//!
+//! The levels for lints are in `roc-lint.json` instead (see [roc_lint::config]).
+use roc_problem::can::Problem;
+use roc_problem::{Level, Levels};
+use roc_solve_problem::TypeError;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+pub const CONFIG_FILENAME: &str = "roc-warnings.json";
+
+/// Whether `name` is the name of a kind of compiler warning, which are the only problems
+/// whose level can be changed.
+pub fn is_warning_name(name: &str) -> bool {
+    Problem::WARNING_NAMES.contains(&name) || TypeError::WARNING_NAMES.contains(&name)
+}
+
+/// The names of all the kinds of compiler warnings, for error messages.
+pub fn warning_names() -> Vec<&'static str> {
+    let mut names: Vec<_> = Problem::WARNING_NAMES.to_vec();
+
+    names.extend(TypeError::WARNING_NAMES);
+    names.sort_unstable();
+
+    names
+}
+
+/// Read the levels in `dir`, or use the usual levels if there's no config there.
+pub fn load_levels(dir: &Path) -> Result<Levels, String> {
+    let path = dir.join(CONFIG_FILENAME);
+
+    match std::fs::read_to_string(&path) {
+        Ok(json) => levels_from_json(&json).map_err(|err| format!("{}: {}", path.display(), err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Levels::default()),
+        Err(err) => Err(format!("Could not read {}: {}", path.display(), err)),
+    }
+}
+
+pub fn levels_from_json(json: &str) -> Result<Levels, String> {
+    let raw: BTreeMap<String, String> =
+        serde_json::from_str(json).map_err(|err| format!("not a valid config: {}", err))?;
+    let mut levels = Levels::default();
+
+    for (name, level) in raw {
+        if !is_warning_name(&name) {
+            return Err(format!(
+                "there is no warning called `{}`. These are the warnings: {}",
+                name,
+                warning_names().join(", ")
+            ));
+        }
+
+        match Level::from_name(&level) {
+            Some(level) => levels.set(&name, level),
+            None => {
+                return Err(format!(
+                    "`{}` is not a level. The levels are allow, warn, and deny.",
+                    level
+                ))
+            }
+        }
+    }
+
+    Ok(levels)
+}
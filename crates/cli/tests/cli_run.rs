@@ -16,7 +16,10 @@ mod cli_run {
     };
     use const_format::concatcp;
     use indoc::indoc;
-    use roc_cli::{CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_FORMAT, CMD_RUN, CMD_TEST};
+    use roc_cli::{
+        CMD_ANNOTATE, CMD_API_DIFF, CMD_BUILD, CMD_CHECK, CMD_DEV, CMD_DOCS, CMD_FORMAT, CMD_RUN,
+        CMD_TEST,
+    };
     use roc_test_utils::assert_multiline_str_eq;
     use serial_test::serial;
     use std::iter;
@@ -52,6 +55,14 @@ mod cli_run {
     const OPTIMIZE_FLAG: &str = concatcp!("--", roc_cli::FLAG_OPTIMIZE);
     const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER);
     const CHECK_FLAG: &str = concatcp!("--", roc_cli::FLAG_CHECK);
+    const STDIN_FLAG: &str = concatcp!("--", roc_cli::FLAG_STDIN);
+    const LINES_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINES);
+    const ORGANIZE_IMPORTS_FLAG: &str = concatcp!("--", roc_cli::FLAG_ORGANIZE_IMPORTS);
+    const LINE_WIDTH_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINE_WIDTH);
+    const EXPOSED_ONLY_FLAG: &str = concatcp!("--", roc_cli::FLAG_EXPOSED_ONLY);
+    const DOC_FLAG: &str = concatcp!("--", roc_cli::FLAG_DOC);
+    const OUTPUT_FLAG: &str = concatcp!("--", roc_cli::FLAG_OUTPUT);
+    const DOCS_FORMAT_FLAG: &str = concatcp!("--", roc_cli::FLAG_DOCS_FORMAT);
     const PREBUILT_PLATFORM: &str = concatcp!("--", roc_cli::FLAG_PREBUILT);
     #[allow(dead_code)]
     const TARGET_FLAG: &str = concatcp!("--", roc_cli::FLAG_TARGET);
@@ -1503,6 +1514,699 @@ mod cli_run {
         // This doesn't fail, since only "Formatted.roc" and non-roc files are present in this folder
         check_format_check_as_expected(&fixtures_dir("format/formatted_directory"), true);
     }
+
+    #[test]
+    fn format_check_prints_diff() {
+        let file = fixture_file("format", "NotFormatted.roc");
+        let out = run_roc([CMD_FORMAT, file.to_str().unwrap(), CHECK_FLAG], &[], &[]);
+
+        // the exit code is the number of files that need formatting
+        assert_eq!(out.status.code(), Some(1));
+        assert!(
+            out.stdout.contains(indoc!(
+                r#"
+            @@ -1,6 +1,6 @@
+             app "formatted"
+            -    packages { pf: "platform/main.roc" }
+            -  provides [main] to pf
+            +    packages { pf: "platform/main.roc" } imports []
+            +    provides [main] to pf
+            "#
+            )),
+            "{}",
+            out.stdout
+        );
+        assert!(out.stderr.contains("1 file needs to be reformatted."));
+    }
+
+    #[test]
+    fn format_stdin() {
+        let not_formatted =
+            std::fs::read_to_string(fixture_file("format", "NotFormatted.roc")).unwrap();
+        let formatted = std::fs::read_to_string(fixture_file("format", "Formatted.roc")).unwrap();
+
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG], &[&not_formatted], &[]);
+
+        assert!(out.status.success());
+        assert_multiline_str_eq!(formatted.as_str(), out.stdout.as_str());
+    }
+
+    #[test]
+    fn format_stdin_only_the_defs_in_range() {
+        let src = indoc!(
+            r#"
+            app "formatted"
+                packages { pf: "platform/main.roc" } imports []
+                provides [main] to pf
+
+            a = [1,2]
+
+            b = [1,2]
+            "#
+        );
+
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG, LINES_FLAG, "7:7"], &[src], &[]);
+
+        assert!(out.status.success());
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                app "formatted"
+                    packages { pf: "platform/main.roc" } imports []
+                    provides [main] to pf
+
+                a = [1,2]
+
+                b = [1, 2]
+                "#
+            ),
+            out.stdout.as_str()
+        );
+    }
+
+    #[test]
+    fn format_stdin_organize_imports() {
+        let src = indoc!(
+            r#"
+            interface Test
+                exposes [zeta, alpha]
+                imports [Set.{ Set }, Dict.{ insert, Dict }, Dict.{ empty }]
+
+            alpha = 1
+
+            zeta = 2
+            "#
+        );
+
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG, ORGANIZE_IMPORTS_FLAG], &[src], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                interface Test
+                    exposes [alpha, zeta]
+                    imports [Dict.{ Dict, empty, insert }, Set.{ Set }]
+
+                alpha = 1
+
+                zeta = 2
+                "#
+            ),
+            out.stdout.as_str()
+        );
+    }
+
+    #[test]
+    fn format_organize_imports_removes_unused() {
+        let dir = roc_test_utils::TmpDir::new("tmp/format_organize_imports_removes_unused");
+        let file = dir.path().join("Main.roc");
+
+        std::fs::write(
+            dir.path().join("Util.roc"),
+            "interface Util exposes [double, triple] imports []\n\ndouble = \\n -> n * 2\n\ntriple = \\n -> n * 3\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Other.roc"),
+            "interface Other exposes [one] imports []\n\none = 1\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &file,
+            indoc!(
+                r#"
+                interface Main
+                    exposes [quad]
+                    imports [Util.{ triple }, Other, Util.{ double }]
+
+                quad = \n -> double (double n)
+                "#
+            ),
+        )
+        .unwrap();
+
+        let out = run_roc(
+            [CMD_FORMAT, ORGANIZE_IMPORTS_FLAG, file.to_str().unwrap()],
+            &[],
+            &[],
+        );
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                interface Main
+                    exposes [quad]
+                    imports [Util.{ double }]
+
+                quad = \n -> double (double n)
+                "#
+            ),
+            std::fs::read_to_string(&file).unwrap().as_str()
+        );
+    }
+
+    #[test]
+    fn format_stdin_line_width() {
+        let src = indoc!(
+            r#"
+            interface Test
+                exposes [x]
+                imports []
+
+            x = List.concat items [first, second]
+            "#
+        );
+
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG], &[src], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_multiline_str_eq!(src, out.stdout.as_str());
+
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG, LINE_WIDTH_FLAG, "30"], &[src], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                interface Test
+                    exposes [x]
+                    imports []
+
+                x = List.concat items [
+                    first,
+                    second,
+                ]
+                "#
+            ),
+            out.stdout.as_str()
+        );
+    }
+
+    #[test]
+    fn format_stdin_parse_error() {
+        let out = run_roc([CMD_FORMAT, STDIN_FLAG], &["main = \\"], &[]);
+
+        assert!(!out.status.success());
+        assert!(out.stdout.is_empty());
+        assert!(out.stderr.contains("stdin.roc"), "{}", out.stderr);
+    }
+
+    const UNANNOTATED: &str = indoc!(
+        r#"
+        interface Annotate
+            exposes [double, greet]
+            imports []
+
+        double = \n -> n * 2
+
+        ## Says hello
+        greet = \name -> Str.concat prefix name
+
+        prefix = "Hello, "
+        "#
+    );
+
+    /// Runs `roc annotate` with `flags` on a copy of [UNANNOTATED], and returns the result.
+    fn annotate(test_name: &str, flags: &[&str]) -> String {
+        let dir = roc_test_utils::TmpDir::new(&format!("tmp/{}", test_name));
+        let file = dir.path().join("Annotate.roc");
+
+        std::fs::write(&file, UNANNOTATED).unwrap();
+
+        let args = iter::once(CMD_ANNOTATE)
+            .chain(flags.iter().copied())
+            .chain(iter::once(file.to_str().unwrap()));
+        let out = run_roc(args, &[], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+
+        std::fs::read_to_string(&file).unwrap()
+    }
+
+    #[test]
+    fn annotate_all() {
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                interface Annotate
+                    exposes [double, greet]
+                    imports []
+
+                double : Num a -> Num a
+                double = \n -> n * 2
+
+                ## Says hello
+                greet : Str -> Str
+                greet = \name -> Str.concat prefix name
+
+                prefix : Str
+                prefix = "Hello, "
+                "#
+            ),
+            annotate("annotate_all", &[]).as_str()
+        );
+    }
+
+    #[test]
+    fn annotate_exposed_only() {
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                interface Annotate
+                    exposes [double, greet]
+                    imports []
+
+                double : Num a -> Num a
+                double = \n -> n * 2
+
+                ## Says hello
+                greet : Str -> Str
+                greet = \name -> Str.concat prefix name
+
+                prefix = "Hello, "
+                "#
+            ),
+            annotate("annotate_exposed_only", &[EXPOSED_ONLY_FLAG]).as_str()
+        );
+    }
+
+    fn test_doc_examples(test_name: &str, docs: &str) -> Out {
+        let dir = roc_test_utils::TmpDir::new(&format!("tmp/{}", test_name));
+        let file = dir.path().join("Doubling.roc");
+        let src = format!(
+            "interface Doubling\n    exposes [double]\n    imports []\n\n{}double = \\n -> n * 2\n",
+            docs
+        );
+
+        std::fs::write(&file, src).unwrap();
+
+        run_roc([CMD_TEST, DOC_FLAG, file.to_str().unwrap()], &[], &[])
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn doc_examples_pass() {
+        let out = test_doc_examples(
+            "doc_examples_pass",
+            indoc!(
+                r#"
+                ## Doubles a number.
+                ## ```
+                ## expect double 2 == 4
+                ## ```
+                ## In the REPL:
+                ## ```repl
+                ## » double 21
+                ## 42 : Num *
+                ## ```
+                ## Other languages aren't checked:
+                ## ```sh
+                ## roc test --doc Doubling.roc
+                ## ```
+                "#
+            ),
+        );
+
+        assert!(out.status.success(), "{}", out.stdout);
+        assert!(
+            strip_colors(&out.stdout).contains("0 failed and 2 passed"),
+            "{}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn doc_examples_fail() {
+        let out = test_doc_examples(
+            "doc_examples_fail",
+            indoc!(
+                r#"
+                ## ```
+                ## expect double 2 == 5
+                ## ```
+                ## ```repl
+                ## » double 21
+                ## 43 : Num *
+                ## ```
+                ## ```unchecked
+                ## double "not a number"
+                ## ```
+                "#
+            ),
+        );
+        let stdout = strip_colors(&out.stdout);
+
+        assert!(!out.status.success());
+        assert!(stdout.contains("REPL EXAMPLE FAILED"), "{}", stdout);
+        assert!(stdout.contains("2 failed and 0 passed"), "{}", stdout);
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn doc_examples_in_imported_modules() {
+        let dir = roc_test_utils::TmpDir::new("tmp/doc_examples_in_imported_modules");
+        let main = dir.path().join("Main.roc");
+
+        std::fs::write(
+            &main,
+            indoc!(
+                r#"
+                interface Main
+                    exposes [quadruple]
+                    imports [Doubling]
+
+                ## ```
+                ## expect quadruple 1 == 4
+                ## ```
+                quadruple = \n -> Doubling.double (Doubling.double n)
+
+                expect quadruple 2 == 8
+                "#
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Doubling.roc"),
+            indoc!(
+                r#"
+                interface Doubling
+                    exposes [double]
+                    imports []
+
+                ## ```
+                ## expect double 2 == 5
+                ## ```
+                double = \n -> n * 2
+                "#
+            ),
+        )
+        .unwrap();
+
+        let out = run_roc([CMD_TEST, DOC_FLAG, main.to_str().unwrap()], &[], &[]);
+        let stdout = strip_colors(&out.stdout);
+
+        assert!(!out.status.success());
+        assert!(stdout.contains("1 failed and 2 passed"), "{}", stdout);
+    }
+
+    /// Generate the docs of a package whose modules are exposed out of alphabetical order,
+    /// in the given format, and return the contents of the given generated files.
+    fn generated_docs(test_name: &str, format: &str, files: &[&str]) -> Vec<String> {
+        let dir = roc_test_utils::TmpDir::new(&format!("tmp/{}", test_name));
+        let main = dir.path().join("main.roc");
+        let out_dir = dir.path().join("docs");
+
+        std::fs::write(
+            &main,
+            "package \"numbers\"\n    exposes [Zebra, Apple]\n    packages {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Zebra.roc"),
+            indoc!(
+                r#"
+                interface Zebra exposes [stripes] imports []
+
+                ## How many stripes a zebra has
+                stripes : U64
+                stripes = 40
+                "#
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Apple.roc"),
+            indoc!(
+                r#"
+                interface Apple exposes [double] imports []
+
+                ## Doubles a number.
+                double : Num a -> Num a
+                double = \n -> n * 2
+                "#
+            ),
+        )
+        .unwrap();
+
+        let out = run_roc(
+            [
+                CMD_DOCS,
+                main.to_str().unwrap(),
+                DOCS_FORMAT_FLAG,
+                format,
+                OUTPUT_FLAG,
+                out_dir.to_str().unwrap(),
+            ],
+            &[],
+            &[],
+        );
+
+        assert!(out.status.success(), "{}{}", out.stdout, out.stderr);
+
+        files
+            .iter()
+            .map(|file| std::fs::read_to_string(out_dir.join(file)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn docs_json() {
+        let json = generated_docs("docs_json", "json", &["docs.json"]).remove(0);
+        let apple = json.find(r#""name": "Apple""#).expect(&json);
+        let zebra = json.find(r#""name": "Zebra""#).expect(&json);
+
+        // Modules are sorted by name, rather than by when they finished loading.
+        assert!(apple < zebra, "{}", json);
+        assert!(
+            json.contains(r#""signature": "double : Num a -> Num a""#),
+            "{}",
+            json
+        );
+        assert!(json.contains("Doubles a number."), "{}", json);
+        assert!(json.contains(r#""signature": "stripes : U64""#), "{}", json);
+    }
+
+    #[test]
+    fn docs_markdown() {
+        let files = generated_docs(
+            "docs_markdown",
+            "markdown",
+            &["index.md", "Apple.md", "Zebra.md"],
+        );
+
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                # numbers
+
+                ## Exposed Modules
+
+                - [Apple](Apple.md)
+                - [Zebra](Zebra.md)
+                "#
+            ),
+            files[0].as_str()
+        );
+        assert_multiline_str_eq!(
+            indoc!(
+                r#"
+                # Apple
+
+                ## double
+
+                ```roc
+                double : Num a -> Num a
+                ```
+
+                Doubles a number.
+                "#
+            ),
+            files[1].as_str()
+        );
+        assert!(files[2].contains("stripes : U64"), "{}", files[2]);
+    }
+
+    /// Write a package named `name` to `dir`, with a `Shapes` module whose source is `shapes`,
+    /// and return the path to its main.roc.
+    fn write_package(dir: &Path, name: &str, shapes: &str) -> std::path::PathBuf {
+        let package_dir = dir.join(name);
+        let main = package_dir.join("main.roc");
+
+        std::fs::create_dir_all(&package_dir).unwrap();
+        std::fs::write(
+            &main,
+            "package \"shapes\"\n    exposes [Shapes]\n    packages {}\n",
+        )
+        .unwrap();
+        std::fs::write(package_dir.join("Shapes.roc"), shapes).unwrap();
+
+        main
+    }
+
+    #[test]
+    fn api_diff_breaking() {
+        let dir = roc_test_utils::TmpDir::new("tmp/api_diff_breaking");
+        let old = write_package(
+            dir.path(),
+            "old",
+            indoc!(
+                r#"
+                interface Shapes exposes [area, unit] imports []
+
+                area : { width : F64, height : F64 } -> F64
+                area = \{ width, height } -> width * height
+
+                unit : F64
+                unit = 1
+                "#
+            ),
+        );
+        let new = write_package(
+            dir.path(),
+            "new",
+            indoc!(
+                r#"
+                interface Shapes exposes [area, volume] imports []
+
+                area : { width : F64, height : F64, depth : F64 } -> F64
+                area = \{ width, height } -> width * height
+
+                volume : { width : F64, height : F64, depth : F64 } -> F64
+                volume = \{ width, height, depth } -> width * height * depth
+                "#
+            ),
+        );
+
+        let out = run_roc(
+            [CMD_API_DIFF, old.to_str().unwrap(), new.to_str().unwrap()],
+            &[],
+            &[],
+        );
+
+        assert!(!out.status.success());
+        // Records with more than one field are shown on multiple lines, like in the HTML docs.
+        assert!(out.stdout.contains("~ area : "), "{}", out.stdout);
+        assert!(out.stdout.contains("became area : "), "{}", out.stdout);
+        assert!(
+            out.stdout.contains("- unit : F64 (breaking)"),
+            "{}",
+            out.stdout
+        );
+        assert!(out.stdout.contains("+ volume : "), "{}", out.stdout);
+        assert!(
+            out.stdout.contains("Found 2 breaking changes"),
+            "{}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    fn api_diff_compatible() {
+        let dir = roc_test_utils::TmpDir::new("tmp/api_diff_compatible");
+        let old = write_package(
+            dir.path(),
+            "old",
+            indoc!(
+                r#"
+                interface Shapes exposes [describe] imports []
+
+                describe : [Circle, Square] -> Str
+                describe = \shape ->
+                    when shape is
+                        Circle -> "circle"
+                        Square -> "square"
+                "#
+            ),
+        );
+        let new = write_package(
+            dir.path(),
+            "new",
+            indoc!(
+                r#"
+                interface Shapes exposes [describe] imports []
+
+                describe : [Circle, Square, Triangle] -> Str
+                describe = \shape ->
+                    when shape is
+                        Circle -> "circle"
+                        Square -> "square"
+                        Triangle -> "triangle"
+                "#
+            ),
+        );
+
+        let out = run_roc(
+            [CMD_API_DIFF, old.to_str().unwrap(), new.to_str().unwrap()],
+            &[],
+            &[],
+        );
+
+        assert!(out.status.success(), "{}", out.stdout);
+        assert!(
+            out.stdout
+                .contains("All of these changes are backwards-compatible"),
+            "{}",
+            out.stdout
+        );
+    }
+
+    #[test]
+    fn api_diff_renamed_type_variables() {
+        let dir = roc_test_utils::TmpDir::new("tmp/api_diff_renamed_type_variables");
+        let old = write_package(
+            dir.path(),
+            "old",
+            indoc!(
+                r#"
+                interface Shapes exposes [first, same] imports []
+
+                first : a, b -> a
+                first = \x, _ -> x
+
+                same : a -> a
+                same = \x -> x
+                "#
+            ),
+        );
+        let new = write_package(
+            dir.path(),
+            "new",
+            indoc!(
+                r#"
+                interface Shapes exposes [first, same] imports []
+
+                first : a, b -> b
+                first = \_, y -> y
+
+                same : shape -> shape
+                same = \x -> x
+                "#
+            ),
+        );
+
+        let out = run_roc(
+            [CMD_API_DIFF, old.to_str().unwrap(), new.to_str().unwrap()],
+            &[],
+            &[],
+        );
+
+        assert!(!out.status.success());
+        // Each variable on its own could have just been renamed, but not all of them together.
+        assert!(
+            out.stdout.contains("~ first : a, b -> a (breaking)"),
+            "{}",
+            out.stdout
+        );
+        assert!(!out.stdout.contains("same"), "{}", out.stdout);
+        assert!(
+            out.stdout.contains("Found 1 breaking change,"),
+            "{}",
+            out.stdout
+        );
+    }
 }
 
 #[cfg(feature = "wasm32-cli-run")]
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod warning_levels {
+    use roc_cli::warnings::levels_from_json;
+    use roc_problem::Severity;
+
+    #[test]
+    fn levels_in_config() {
+        let levels =
+            levels_from_json(r#"{ "unused-import": "deny", "unused-def": "allow" }"#).unwrap();
+
+        assert_eq!(
+            levels.severity("unused-import", Severity::Warning),
+            Some(Severity::RuntimeError)
+        );
+        assert_eq!(levels.severity("unused-def", Severity::Warning), None);
+        assert_eq!(
+            levels.severity("unused-argument", Severity::Warning),
+            Some(Severity::Warning)
+        );
+    }
+
+    #[test]
+    fn errors_cannot_be_allowed() {
+        let result = levels_from_json(r#"{ "type-mismatch": "allow" }"#);
+
+        assert!(matches!(result, Err(message) if message.contains("`type-mismatch`")));
+    }
+
+    #[test]
+    fn lints_are_not_warnings() {
+        let result = levels_from_json(r#"{ "large-tuple": "allow" }"#);
+
+        assert!(matches!(result, Err(message) if message.contains("`large-tuple`")));
+    }
+
+    #[test]
+    fn unknown_level() {
+        let result = levels_from_json(r#"{ "unused-def": "ignore" }"#);
+
+        assert!(matches!(result, Err(message) if message.contains("`ignore`")));
+    }
+}
@@ -0,0 +1,111 @@
+interface Compare
+    exposes [
+        Compare,
+        compare,
+        tieBreak,
+        list,
+        str,
+        num,
+        bool,
+    ]
+    imports [
+        Bool.{ Bool },
+        List,
+        Num.{ Num },
+        Str,
+    ]
+
+## A value that can be ordered.
+##
+## [Compare] can be derived for records, tuples, tag unions, lists, strings,
+## booleans and numbers:
+## - records are compared field by field, in alphabetical order of field names;
+## - tuples are compared element by element, from first to last;
+## - tags are compared by tag name in alphabetical order, then by their payloads;
+## - lists and strings are compared lexicographically, with a prefix sorting first.
+##
+## Tag unions are structural, so `[Low, High]` and `[High, Low]` are the same
+## type; there is no declaration order to follow.
+##
+## The ordering is total, except for `F32` and `F64` values that are NaN: they
+## are not equal to, or less than, any number, so [num] orders them as `GT`
+## against everything, including themselves.
+##
+## The result can be passed directly to [List.sortWith]:
+##
+## ```
+## expect
+##     List.sortWith [{ age: 30u8, name: "B" }, { age: 30u8, name: "A" }] Compare.compare
+##     == [{ age: 30u8, name: "A" }, { age: 30u8, name: "B" }]
+## ```
+Compare has
+    ## Orders two values.
+    compare : a, a -> [LT, EQ, GT] | a has Compare
+
+## Uses a second ordering to break a tie in the first.
+##
+## ```
+## expect Compare.tieBreak EQ LT == LT
+## expect Compare.tieBreak GT LT == GT
+## ```
+tieBreak : [LT, EQ, GT], [LT, EQ, GT] -> [LT, EQ, GT]
+tieBreak = \first, second ->
+    when first is
+        EQ -> second
+        _ -> first
+
+## Compares two lists lexicographically.
+list : List elem, List elem -> [LT, EQ, GT] | elem has Compare
+list = \xs, ys -> listWith xs ys compare
+
+## Compares two strings lexicographically by their UTF-8 bytes.
+str : Str, Str -> [LT, EQ, GT]
+str = \a, b -> listWith (Str.toUtf8 a) (Str.toUtf8 b) Num.compare
+
+## Compares two numbers.
+num : Num a, Num a -> [LT, EQ, GT]
+num = \a, b -> Num.compare a b
+
+## Compares two booleans, with [Bool.false] sorting before [Bool.true].
+bool : Bool, Bool -> [LT, EQ, GT]
+bool = \a, b ->
+    if a == b then
+        EQ
+    else if a then
+        GT
+    else
+        LT
+
+listWith : List elem, List elem, (elem, elem -> [LT, EQ, GT]) -> [LT, EQ, GT]
+listWith = \xs, ys, compareElem ->
+    listWithHelp xs ys compareElem 0
+
+# Compares the elements at each index in turn, stopping at the first one that
+# differs. If one list runs out first, the shorter list sorts first.
+listWithHelp : List elem, List elem, (elem, elem -> [LT, EQ, GT]), Nat -> [LT, EQ, GT]
+listWithHelp = \xs, ys, compareElem, index ->
+    when (List.get xs index, List.get ys index) is
+        (Ok x, Ok y) ->
+            when compareElem x y is
+                EQ -> listWithHelp xs ys compareElem (index + 1)
+                order -> order
+
+        _ -> Num.compare (List.len xs) (List.len ys)
+
+expect compare { a: 1u8, b: "z" } { a: 2u8, b: "a" } == LT
+
+expect compare { a: 1u8, b: "z" } { a: 1u8, b: "a" } == GT
+
+expect compare (1u8, Bool.false) (1u8, Bool.true) == LT
+
+expect compare (Ok 5u8) (Err "") == GT
+
+expect compare (Some 2u8) (Some 1u8) == GT
+
+expect compare [1u8, 2] [1u8, 2, 3] == LT
+
+expect compare [2u8] [1u8, 2, 3] == GT
+
+expect str "apple" "apples" == LT
+
+expect str "b" "apple" == GT
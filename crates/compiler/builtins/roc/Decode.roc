@@ -1,6 +1,7 @@
 interface Decode
     exposes [
         DecodeError,
+        DecodePath,
         DecodeResult,
         Decoder,
         Decoding,
@@ -24,11 +25,15 @@ interface Decode
         list,
         record,
         tuple,
+        tag,
         custom,
         decodeWith,
         fromBytesPartial,
         fromBytes,
         mapResult,
+        inField,
+        inElem,
+        pathToStr,
     ]
     imports [
         List,
@@ -50,10 +55,24 @@ interface Decode
             Dec,
         },
         Bool.{ Bool },
+        Str,
     ]
 
 ## Error types when decoding a `List U8` of utf-8 bytes using a [Decoder]
-DecodeError : [TooShort]
+##
+## - `TooShort` means the input ended early, or is not well-formed.
+## - `Expected shape` means the input held a different kind of value, like
+##   `Expected "U8"` for a string where a number should be.
+## - `At path err` means `err` happened inside the decoded value, at `path`.
+##   A record field that is missing from the input is reported as `TooShort` at
+##   that field, unless its type is `Result a [Missing]`; such a field is
+##   decoded as `Err Missing` instead.
+DecodeError : [TooShort, Expected Str, At DecodePath [TooShort, Expected Str]]
+
+## The location of a value inside a decoded value, from the outside in. For
+## example, `[Field "users", Elem 3, Field "email"]` is the `email` field of the
+## fourth element of the `users` field. See [pathToStr].
+DecodePath : List [Field Str, Elem Nat]
 
 ## Return type of a [Decoder].
 ##
@@ -96,6 +115,10 @@ DecoderFormatting has
     dec : Decoder Dec fmt | fmt has DecoderFormatting
     bool : Decoder Bool fmt | fmt has DecoderFormatting
     string : Decoder Str fmt | fmt has DecoderFormatting
+    ## `list elemDecoder` decodes a list element-by-element.
+    ##
+    ## An error decoding an element should be reported at that element, using
+    ## [inElem].
     list : Decoder elem fmt -> Decoder (List elem) fmt | fmt has DecoderFormatting
 
     ## `record state stepField finalizer` decodes a record field-by-field.
@@ -115,6 +138,13 @@ DecoderFormatting has
     ## `finalizer` should produce the tuple value from the decoded `state`.
     tuple : state, (state, Nat -> [Next (Decoder state fmt), TooLong]), (state -> Result val DecodeError) -> Decoder val fmt | fmt has DecoderFormatting
 
+    ## `tag stepTag` decodes a tag of a tag union.
+    ##
+    ## `stepTag` is given the name of the decoded tag, and returns a decoder for
+    ## the tag's value, or `Skip` if the tag is not a part of the decoded tag
+    ## union.
+    tag : (Str -> [Keep (Decoder val fmt), Skip]) -> Decoder val fmt | fmt has DecoderFormatting
+
 ## Build a custom [Decoder] function. For example the implementation of
 ## `decodeBool` could be defined as follows;
 ##
@@ -162,10 +192,51 @@ fromBytes = \bytes, fmt ->
             if List.isEmpty rest then
                 when result is
                     Ok val -> Ok val
-                    Err TooShort -> Err TooShort
+                    Err err -> Err err
             else
                 Err (Leftover rest)
 
 ## Transform the `val` of a [DecodeResult]
 mapResult : DecodeResult a, (a -> b) -> DecodeResult b
 mapResult = \{ result, rest }, mapper -> { result: Result.map result mapper, rest }
+
+## Reports a decoding error as happening inside the record field `name`.
+##
+## ```
+## expect Decode.inField (Expected "U8") "age" == At [Field "age"] (Expected "U8")
+## ```
+inField : DecodeError, Str -> DecodeError
+inField = \err, name -> prependStep err (Field name)
+
+## Reports a decoding error as happening inside the list or tuple element at
+## `index`.
+##
+## ```
+## expect Decode.inElem (At [Field "email"] TooShort) 3 == At [Elem 3, Field "email"] TooShort
+## ```
+inElem : DecodeError, Nat -> DecodeError
+inElem = \err, index -> prependStep err (Elem index)
+
+prependStep : DecodeError, [Field Str, Elem Nat] -> DecodeError
+prependStep = \err, step ->
+    when err is
+        TooShort -> At [step] TooShort
+        Expected shape -> At [step] (Expected shape)
+        At path inner -> At (List.prepend path step) inner
+
+## Renders a [DecodePath] the way the value would be accessed in code, like
+## `.users[3].email`.
+##
+## ```
+## expect Decode.pathToStr [Field "users", Elem 3, Field "email"] == ".users[3].email"
+## ```
+pathToStr : DecodePath -> Str
+pathToStr = \path ->
+    List.walk path "" \str, step ->
+        when step is
+            Field name -> Str.concat (Str.concat str ".") name
+            Elem index -> Str.concat (Str.concat (Str.concat str "[") (Num.toStr index)) "]"
+
+expect inField (inElem (Expected "Str") 0) "tags" == At [Field "tags", Elem 0] (Expected "Str")
+
+expect pathToStr [] == ""
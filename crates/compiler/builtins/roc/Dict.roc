@@ -596,7 +596,8 @@ swapAndUpdateDataIndex = \@Dict { metadata, dataIndices, data, size }, removedIn
 
         Err NotFound ->
             # This should be impossible.
-            crash "unreachable state in dict swapAndUpdateDataIndex hit. Definitely a standard library bug."
+            crash
+                "unreachable state in dict swapAndUpdateDataIndex hit. Definitely a standard library bug."
 
 insertNotFoundHelper : Dict k v, k, v, U64, I8 -> Dict k v
 insertNotFoundHelper = \@Dict { metadata, dataIndices, data, size }, key, value, h1Key, h2Key ->
@@ -1092,13 +1093,24 @@ addBytes = \@LowLevelHasher { originalSeed, state }, list ->
         else
             hashBytesHelper48 seed seed seed list 0 length
 
-    combineState (@LowLevelHasher { originalSeed, state }) { a: abs.a, b: abs.b, seed: abs.seed, length: Num.toU64 length }
+    combineState (@LowLevelHasher { originalSeed, state }) {
+        a: abs.a,
+        b: abs.b,
+        seed: abs.seed,
+        length: Num.toU64 length,
+    }
 
 hashBytesHelper48 : U64, U64, U64, List U8, Nat, Nat -> { a : U64, b : U64, seed : U64 }
 hashBytesHelper48 = \seed, see1, see2, list, index, remaining ->
-    newSeed = wymix (Num.bitwiseXor (wyr8 list index) wyp1) (Num.bitwiseXor (wyr8 list (Num.addWrap index 8)) seed)
-    newSee1 = wymix (Num.bitwiseXor (wyr8 list (Num.addWrap index 16)) wyp2) (Num.bitwiseXor (wyr8 list (Num.addWrap index 24)) see1)
-    newSee2 = wymix (Num.bitwiseXor (wyr8 list (Num.addWrap index 32)) wyp3) (Num.bitwiseXor (wyr8 list (Num.addWrap index 40)) see2)
+    newSeed = wymix
+        (Num.bitwiseXor (wyr8 list index) wyp1)
+        (Num.bitwiseXor (wyr8 list (Num.addWrap index 8)) seed)
+    newSee1 = wymix
+        (Num.bitwiseXor (wyr8 list (Num.addWrap index 16)) wyp2)
+        (Num.bitwiseXor (wyr8 list (Num.addWrap index 24)) see1)
+    newSee2 = wymix
+        (Num.bitwiseXor (wyr8 list (Num.addWrap index 32)) wyp3)
+        (Num.bitwiseXor (wyr8 list (Num.addWrap index 40)) see2)
     newRemaining = Num.subWrap remaining 48
     newIndex = Num.addWrap index 48
 
@@ -1111,16 +1123,26 @@ hashBytesHelper48 = \seed, see1, see2, list, index, remaining ->
     else
         finalSeed = Num.bitwiseXor newSee2 (Num.bitwiseXor newSee1 newSeed)
 
-        { a: wyr8 list (Num.subWrap newRemaining 16 |> Num.addWrap newIndex), b: wyr8 list (Num.subWrap newRemaining 8 |> Num.addWrap newIndex), seed: finalSeed }
+        {
+            a: wyr8 list (Num.subWrap newRemaining 16 |> Num.addWrap newIndex),
+            b: wyr8 list (Num.subWrap newRemaining 8 |> Num.addWrap newIndex),
+            seed: finalSeed,
+        }
 
 hashBytesHelper16 : U64, List U8, Nat, Nat -> { a : U64, b : U64, seed : U64 }
 hashBytesHelper16 = \seed, list, index, remaining ->
-    newSeed = wymix (Num.bitwiseXor (wyr8 list index) wyp1) (Num.bitwiseXor (wyr8 list (Num.addWrap index 8)) seed)
+    newSeed = wymix
+        (Num.bitwiseXor (wyr8 list index) wyp1)
+        (Num.bitwiseXor (wyr8 list (Num.addWrap index 8)) seed)
     newRemaining = Num.subWrap remaining 16
     newIndex = Num.addWrap index 16
 
     if newRemaining <= 16 then
-        { a: wyr8 list (Num.subWrap newRemaining 16 |> Num.addWrap newIndex), b: wyr8 list (Num.subWrap newRemaining 8 |> Num.addWrap newIndex), seed: newSeed }
+        {
+            a: wyr8 list (Num.subWrap newRemaining 16 |> Num.addWrap newIndex),
+            b: wyr8 list (Num.subWrap newRemaining 8 |> Num.addWrap newIndex),
+            seed: newSeed,
+        }
     else
         hashBytesHelper16 newSeed list newIndex newRemaining
 
@@ -1290,7 +1312,24 @@ expect
 expect
     hash =
         createLowLevelHasher testSeed
-        |> addBytes [0x5D, 0x66, 0xB1, 0x8F, 0x68, 0x44, 0xC7, 0x03, 0xE1, 0xDD, 0x23, 0x34, 0xBB, 0x9A, 0x42, 0xA7]
+        |> addBytes [
+            0x5D,
+            0x66,
+            0xB1,
+            0x8F,
+            0x68,
+            0x44,
+            0xC7,
+            0x03,
+            0xE1,
+            0xDD,
+            0x23,
+            0x34,
+            0xBB,
+            0x9A,
+            0x42,
+            0xA7,
+        ]
         |> complete
 
     hash == 0xA16F_DDAA_C167_74C7
@@ -1322,7 +1361,8 @@ expect
 expect
     hash =
         createLowLevelHasher testSeed
-        |> Hash.hashStrBytes "1234567890123456789012345678901234567890123456789012345678901234567890"
+        |> Hash.hashStrBytes
+            "1234567890123456789012345678901234567890123456789012345678901234567890"
         |> complete
 
     hash == 0xDB6B_7997_7A55_BA03
@@ -1390,5 +1430,7 @@ expect
     |> Dict.insert "Alice" 17
     |> Dict.insert "Bob" 18
     |> Dict.insert "Charlie" 19
-    |> Dict.walkUntil Bool.false (\_, _, age -> if age >= 18 then Break Bool.true else Continue Bool.false)
+    |> Dict.walkUntil
+        Bool.false
+        (\_, _, age -> if age >= 18 then Break Bool.true else Continue Bool.false)
     |> Bool.isEq Bool.true
@@ -0,0 +1,170 @@
+interface Inspect
+    exposes [
+        Inspect,
+        Inspector,
+        inspect,
+        toStr,
+        record,
+        tuple,
+        tag,
+        list,
+        dict,
+        set,
+        num,
+        str,
+        bool,
+        opaque,
+    ]
+    imports [
+        Bool.{ Bool, Eq },
+        Dict.{ Dict },
+        Hash.{ Hash },
+        List,
+        Num.{ Num },
+        Set.{ Set },
+        Str,
+    ]
+
+## A structured description of a value, suitable for debugging and logging.
+##
+## An [Inspector] is built with the functions in this module, like [record] and
+## [list], and is usually turned into text with [toStr].
+Inspector := [
+    Record (List { key : Str, value : Inspector }),
+    Tuple (List Inspector),
+    Tag Str (List Inspector),
+    List (List Inspector),
+    Dict (List { key : Inspector, value : Inspector }),
+    Set (List Inspector),
+    Num Str,
+    Str Str,
+    Bool Bool,
+    Opaque Str,
+]
+
+## A value that can be inspected.
+##
+## [Inspect] can be derived for records, tuples, tag unions, lists, dictionaries,
+## sets, strings, booleans and numbers. Opaque types must opt in, which lets them
+## decide how much of their contents to reveal:
+##
+## ```
+## Password := Str has [Inspect { inspect: inspectPassword }]
+##
+## inspectPassword = \@Password _ -> Inspect.opaque "Password"
+## ```
+Inspect has
+    ## Describes a value as an [Inspector].
+    inspect : val -> Inspector | val has Inspect
+
+## Renders a value as Roc-like text.
+##
+## ```
+## expect Inspect.toStr { name: "Ada", tags: [Admin] } == "{ name: \"Ada\", tags: [Admin] }"
+## ```
+toStr : val -> Str | val has Inspect
+toStr = \val -> render (inspect val)
+
+## Describes a record from the names and descriptions of its fields.
+record : List { key : Str, value : Inspector } -> Inspector
+record = \fields -> @Inspector (Record fields)
+
+## Describes a tuple from the descriptions of its elements.
+tuple : List Inspector -> Inspector
+tuple = \elems -> @Inspector (Tuple elems)
+
+## Describes a tag from its name and the descriptions of its payloads.
+tag : Str, List Inspector -> Inspector
+tag = \name, payloads -> @Inspector (Tag name payloads)
+
+## Describes a list by inspecting each of its elements.
+list : List elem -> Inspector | elem has Inspect
+list = \elems -> @Inspector (List (List.map elems inspect))
+
+## Describes a dictionary by inspecting each of its keys and values.
+dict : Dict k v -> Inspector | k has Inspect & Hash & Eq, v has Inspect
+dict = \entries ->
+    pairs = Dict.walk entries [] \state, k, v ->
+        List.append state { key: inspect k, value: inspect v }
+
+    @Inspector (Dict pairs)
+
+## Describes a set by inspecting each of its elements.
+set : Set elem -> Inspector | elem has Inspect & Hash & Eq
+set = \elems -> @Inspector (Set (List.map (Set.toList elems) inspect))
+
+## Describes a number.
+num : Num a -> Inspector
+num = \n -> @Inspector (Num (Num.toStr n))
+
+## Describes a string.
+str : Str -> Inspector
+str = \s -> @Inspector (Str s)
+
+## Describes a boolean.
+bool : Bool -> Inspector
+bool = \b -> @Inspector (Bool b)
+
+## Describes an opaque value by name alone, hiding its contents.
+opaque : Str -> Inspector
+opaque = \name -> @Inspector (Opaque name)
+
+render : Inspector -> Str
+render = \@Inspector inspector ->
+    when inspector is
+        Record [] -> "{}"
+        Record fields ->
+            inner =
+                fields
+                |> List.map (\{ key, value } -> Str.concat (Str.concat key ": ") (render value))
+                |> Str.joinWith ", "
+
+            Str.concat (Str.concat "{ " inner) " }"
+
+        Tuple elems -> surround "(" (renderAll elems) ")"
+        Tag name [] -> name
+        Tag name payloads ->
+            payloads
+            |> List.map renderPayload
+            |> List.prepend name
+            |> Str.joinWith " "
+
+        List elems -> surround "[" (renderAll elems) "]"
+        Dict pairs ->
+            renderPair = \{ key, value } ->
+                surround "(" (Str.joinWith [render key, render value] ", ") ")"
+
+            surround "Dict.fromList [" (Str.joinWith (List.map pairs renderPair) ", ") "]"
+
+        Set elems -> surround "Set.fromList [" (renderAll elems) "]"
+        Num n -> n
+        Str s -> surround "\"" s "\""
+        Bool b -> if b then "Bool.true" else "Bool.false"
+        Opaque name -> surround "<" name ">"
+
+renderAll : List Inspector -> Str
+renderAll = \elems -> Str.joinWith (List.map elems render) ", "
+
+# Tags with payloads must be parenthesized when they are themselves a payload.
+renderPayload : Inspector -> Str
+renderPayload = \@Inspector payload ->
+    when payload is
+        Tag _ [_, ..] -> surround "(" (render (@Inspector payload)) ")"
+        _ -> render (@Inspector payload)
+
+surround : Str, Str, Str -> Str
+surround = \before, inner, after -> Str.concat (Str.concat before inner) after
+
+expect toStr { a: 1u8, b: "hello" } == "{ a: 1, b: \"hello\" }"
+
+expect toStr {} == "{}"
+
+expect toStr (1u8, Bool.true) == "(1, Bool.true)"
+
+expect toStr [Ok (Some 1u8), Err Empty] == "[Ok (Some 1), Err Empty]"
+
+expect toStr (Dict.single "x" [1u8]) == "Dict.fromList [(\"x\", [1])]"
+
+expect toStr (Set.single 2u8) == "Set.fromList [2]"
+
+expect render (opaque "Password") == "<Password>"
@@ -40,6 +40,9 @@ interface Json
         Json,
         json,
         jsonWithOptions,
+        decodeLines,
+        LinesResult,
+        LineError,
     ]
     imports [
         List,
@@ -53,6 +56,8 @@ interface Json
         },
         Decode,
         Decode.{
+            Decoding,
+            DecodeError,
             DecoderFormatting,
             DecodeResult,
         },
@@ -120,6 +125,7 @@ Json := { fieldNameMapping : FieldNameMapping }
              list: decodeList,
              record: decodeRecord,
              tuple: decodeTuple,
+             tag: decodeTag,
          },
      ]
 
@@ -127,6 +133,21 @@ Json := { fieldNameMapping : FieldNameMapping }
 json = @Json { fieldNameMapping: Default }
 
 ## Returns a JSON `Encoder` and `Decoder` with configuration options
+##
+## `fieldNameMapping` converts between Roc record field names and JSON object
+## names, in both directions:
+##
+## ```
+## expect
+##     input = Str.toUtf8 "{\"owner_name\":\"Farmer Joe\"}"
+##     actual = Decode.fromBytes input (Json.jsonWithOptions { fieldNameMapping: SnakeCase })
+##
+##     actual == Ok { ownerName: "Farmer Joe" }
+## ```
+##
+## A field that is missing from the JSON object is an error, unless the field's
+## type is `Result a [Missing]`. Such a field is decoded as `Err Missing`, which
+## can be given a default with `Result.withDefault`.
 jsonWithOptions = \{ fieldNameMapping ? Default } ->
     @Json { fieldNameMapping }
 
@@ -140,6 +161,104 @@ FieldNameMapping : [
     Custom (Str -> Str), # provide a custom formatting
 ]
 
+## The result of [decodeLines].
+##
+## `values` has one entry per non-blank line, in order. `rest` holds the bytes
+## after the last newline, which may be a line that is not complete yet.
+LinesResult val : {
+    values : List (Result val LineError),
+    rest : List U8,
+}
+
+## Why a line could not be decoded. `offset` is the byte offset, from the start
+## of the input, where decoding stopped.
+LineError : { offset : Nat, error : [Leftover (List U8)]DecodeError }
+
+LineResults val : List (Result val LineError)
+
+## Decodes newline-delimited JSON: a sequence of JSON values, one per line.
+##
+## Only lines that end in a newline are decoded. This allows a large file to be
+## read in chunks: prepend the `rest` of one chunk to the next chunk, and add
+## the number of bytes consumed, `List.len input - List.len rest`, to the
+## offsets of later errors. A line that fails to decode does not stop the
+## following lines from being decoded.
+##
+## ```
+## expect
+##     input = Str.toUtf8 "{\"a\":1}\n{\"a\":2}\n{\"a\""
+##     actual : LinesResult { a : U8 }
+##     actual = Json.decodeLines input Json.json
+##
+##     actual == { values: [Ok { a: 1 }, Ok { a: 2 }], rest: Str.toUtf8 "{\"a\"" }
+## ```
+decodeLines : List U8, Json -> LinesResult val | val has Decoding
+decodeLines = \bytes, fmt ->
+    when List.splitLast bytes '\n' is
+        Ok { before, after } ->
+            { values: decodeLinesHelp before 0 fmt [], rest: after }
+
+        Err NotFound ->
+            { values: [], rest: bytes }
+
+decodeLinesHelp : List U8, Nat, Json, LineResults val -> LineResults val | val has Decoding
+decodeLinesHelp = \bytes, offset, fmt, values ->
+    when List.splitFirst bytes '\n' is
+        Ok { before, after } ->
+            decodeLinesHelp
+                after
+                (offset + List.len before + 1)
+                fmt
+                (decodeLine values before offset fmt)
+
+        Err NotFound ->
+            decodeLine values bytes offset fmt
+
+decodeLine : LineResults val, List U8, Nat, Json -> LineResults val | val has Decoding
+decodeLine = \values, line, offset, fmt ->
+    leading = List.walkUntil line 0 \count, b ->
+        if isWhitespace b then Continue (count + 1) else Break count
+
+    if leading == List.len line then
+        # Blank lines are allowed between values.
+        values
+    else
+        when Decode.fromBytesPartial (List.drop line leading) fmt is
+            { result, rest } ->
+                stoppedAt = offset + List.len line - List.len rest
+
+                when result is
+                    Ok val if List.all rest isWhitespace -> List.append values (Ok val)
+                    Ok _ -> List.append values (Err { offset: stoppedAt, error: Leftover rest })
+                    Err err -> List.append values (Err { offset: stoppedAt, error: err })
+
+expect
+    input = Str.toUtf8 "{\"a\":1}\n\n{\"a\":2}\r\n{\"a\":"
+    actual : LinesResult { a : U8 }
+    actual = decodeLines input json
+    expected = { values: [Ok { a: 1 }, Ok { a: 2 }], rest: Str.toUtf8 "{\"a\":" }
+
+    actual == expected
+
+# A bad line is reported with its offset, and decoding carries on
+expect
+    input = Str.toUtf8 "1\n2 3\n4\n"
+    actual : LinesResult U8
+    actual = decodeLines input json
+    expected = {
+        values: [Ok 1, Err { offset: 3, error: Leftover [' ', '3'] }, Ok 4],
+        rest: [],
+    }
+
+    actual == expected
+
+expect
+    input = Str.toUtf8 "{\"a\":1}"
+    actual : LinesResult { a : U8 }
+    actual = decodeLines input json
+
+    actual == { values: [], rest: input }
+
 # TODO encode as JSON numbers as base 10 decimal digits
 # e.g. the REPL `Num.toStr 12e42f64` gives
 # "12000000000000000000000000000000000000000000" : Str
@@ -359,7 +478,10 @@ encodeRecord = \fields ->
             { buffer: bufferWithSuffix, fieldsLeft: fieldsLeft - 1 }
 
         bytesHead = List.append bytes (Num.toU8 '{')
-        { buffer: bytesWithRecord } = List.walk fields { buffer: bytesHead, fieldsLeft: List.len fields } writeRecord
+        { buffer: bytesWithRecord } = List.walk
+            fields
+            { buffer: bytesHead, fieldsLeft: List.len fields }
+            writeRecord
 
         List.append bytesWithRecord (Num.toU8 '}')
 
@@ -377,7 +499,8 @@ expect
     input = { fruitFlavours: ["Apples", "Bananas", "Pears"], isFresh: Bool.true }
     encoder = jsonWithOptions { fieldNameMapping: KebabCase }
     actual = Encode.toBytes input encoder
-    expected = Str.toUtf8 "{\"fruit-flavours\":[\"Apples\",\"Bananas\",\"Pears\"],\"is-fresh\":true}"
+    expected = Str.toUtf8
+        "{\"fruit-flavours\":[\"Apples\",\"Bananas\",\"Pears\"],\"is-fresh\":true}"
 
     actual == expected
 
@@ -419,7 +542,10 @@ encodeTuple = \elems ->
             { buffer: bufferWithSuffix, elemsLeft: elemsLeft - 1 }
 
         bytesHead = List.append bytes (Num.toU8 '[')
-        { buffer: bytesWithRecord } = List.walk elems { buffer: bytesHead, elemsLeft: List.len elems } writeTuple
+        { buffer: bytesWithRecord } = List.walk
+            elems
+            { buffer: bytesHead, elemsLeft: List.len elems }
+            writeTuple
 
         List.append bytesWithRecord (Num.toU8 ']')
 
@@ -452,7 +578,10 @@ encodeTag = \name, payload ->
             |> List.append (Num.toU8 ':')
             |> List.append (Num.toU8 '[')
 
-        { buffer: bytesWithPayload } = List.walk payload { buffer: bytesHead, itemsLeft: List.len payload } writePayload
+        { buffer: bytesWithPayload } = List.walk
+            payload
+            { buffer: bytesHead, itemsLeft: List.len payload }
+            writePayload
 
         List.append bytesWithPayload (Num.toU8 ']')
         |> List.append (Num.toU8 '}')
@@ -473,7 +602,7 @@ decodeU8 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toU8
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "U8"
 
     { result, rest }
 
@@ -489,7 +618,7 @@ decodeU16 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toU16
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "U16"
 
     { result, rest }
 
@@ -505,7 +634,7 @@ decodeU32 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toU32
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "U32"
 
     { result, rest }
 
@@ -521,7 +650,7 @@ decodeU64 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toU64
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "U64"
 
     { result, rest }
 
@@ -537,7 +666,7 @@ decodeU128 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toU128
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "U128"
 
     { result, rest }
 
@@ -559,7 +688,7 @@ decodeI8 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toI8
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "I8"
 
     { result, rest }
 
@@ -575,7 +704,7 @@ decodeI16 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toI16
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "I16"
 
     { result, rest }
 
@@ -591,7 +720,7 @@ decodeI32 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toI32
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "I32"
 
     { result, rest }
 
@@ -607,7 +736,7 @@ decodeI64 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toI64
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "I64"
 
     { result, rest }
 
@@ -623,7 +752,7 @@ decodeI128 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toI128
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "I128"
 
     { result, rest }
 
@@ -639,7 +768,7 @@ decodeF32 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toF32
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "F32"
 
     { result, rest }
 
@@ -658,7 +787,7 @@ decodeF64 = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toF64
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "F64"
 
     { result, rest }
 
@@ -677,7 +806,7 @@ decodeDec = Decode.custom \bytes, @Json {} ->
         taken
         |> Str.fromUtf8
         |> Result.try Str.toDec
-        |> Result.mapErr \_ -> TooShort
+        |> Result.mapErr \_ -> Expected "Dec"
 
     { result, rest }
 
@@ -692,7 +821,7 @@ decodeBool = Decode.custom \bytes, @Json {} ->
     when bytes is
         ['f', 'a', 'l', 's', 'e', ..] -> { result: Ok Bool.false, rest: List.drop bytes 5 }
         ['t', 'r', 'u', 'e', ..] -> { result: Ok Bool.true, rest: List.drop bytes 4 }
-        _ -> { result: Err TooShort, rest: bytes }
+        _ -> { result: Err (Expected "Bool"), rest: bytes }
 
 # Test decode of Bool
 expect
@@ -728,7 +857,15 @@ decodeTuple = \initialState, stepElem, finalizer -> Decode.custom \initialBytes,
 
         { rest: afterBracketBytes } <- initialBytes |> openBracket |> tryDecode
 
-        { val: endStateResult, rest: beforeClosingBracketBytes } <- decodeElems stepElem initialState 0 afterBracketBytes |> tryDecode
+        { val: endStateResult, rest: beforeClosingBracketBytes } <-
+            (
+                # An empty tuple, like the payload of a tag without arguments
+                if List.first afterBracketBytes == Ok ']' then
+                    { result: Ok initialState, rest: afterBracketBytes }
+                else
+                    decodeElems stepElem initialState 0 afterBracketBytes
+            )
+            |> tryDecode
 
         { rest: afterTupleBytes } <- beforeClosingBracketBytes |> closingBracket |> tryDecode
 
@@ -743,6 +880,67 @@ expect
 
     actual.result == Ok ("The Answer is", 42)
 
+decodeTag = \stepTag -> Decode.custom \bytes, @Json { fieldNameMapping } ->
+        # Tags are encoded as `{"A": [v1, v2]}`, see `encodeTag`
+        { rest: afterBraceBytes } <- bytes |> openBrace |> tryDecode
+
+        { val: name, rest: afterNameBytes } <-
+            Decode.decodeWith afterBraceBytes decodeString json |> tryDecode
+
+        { rest: afterColonBytes } <- afterNameBytes |> colon |> tryDecode
+
+        { val: tag, rest: afterPayloadBytes } <-
+            (
+                when stepTag name is
+                    Keep payloadDecoder ->
+                        Decode.decodeWith
+                            afterColonBytes
+                            payloadDecoder
+                            (@Json { fieldNameMapping })
+
+                    Skip ->
+                        # Not a tag of the decoded tag union, so leave the name unconsumed
+                        { result: Err (Expected "tag"), rest: afterBraceBytes }
+            )
+            |> tryDecode
+
+        { rest: afterTagBytes } <- afterPayloadBytes |> closingBrace |> tryDecode
+
+        { result: Ok tag, rest: afterTagBytes }
+
+# Test decode of tag
+expect
+    input = Str.toUtf8 "{\"Suspended\":[\"Too many requests\"]}"
+    actual : DecodeResult [Active, Suspended Str]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok (Suspended "Too many requests")
+
+# Test decode of tag without a payload
+expect
+    input = Str.toUtf8 "{\"Active\":[]}"
+    actual : DecodeResult [Active, Suspended Str]
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok Active
+
+# Test decode of tag that isn't in the tag union
+expect
+    input = Str.toUtf8 "{\"Deleted\":[]}"
+    actual : DecodeResult [Active, Suspended Str]
+    actual = Decode.fromBytesPartial input json
+    expected = { result: Err (Expected "tag"), rest: Str.toUtf8 "\"Deleted\":[]}" }
+
+    actual == expected
+
+# Test encode and decode of tag
+expect
+    input : [Active, Suspended Str]
+    input = Suspended "Too many requests"
+    actual = input |> Encode.toBytes json |> Decode.fromBytes json
+
+    actual == Ok input
+
 parseExactChar : List U8, U8 -> DecodeResult {}
 parseExactChar = \bytes, char ->
     when List.get bytes 0 is
@@ -762,6 +960,15 @@ openBracket = \bytes -> parseExactChar bytes '['
 closingBracket : List U8 -> DecodeResult {}
 closingBracket = \bytes -> parseExactChar bytes ']'
 
+openBrace : List U8 -> DecodeResult {}
+openBrace = \bytes -> parseExactChar bytes '{'
+
+closingBrace : List U8 -> DecodeResult {}
+closingBrace = \bytes -> parseExactChar bytes '}'
+
+colon : List U8 -> DecodeResult {}
+colon = \bytes -> parseExactChar bytes ':'
+
 anything : List U8 -> DecodeResult {}
 anything = \bytes -> { result: Err TooShort, rest: bytes }
 
@@ -886,19 +1093,19 @@ expect
 expect
     actual : DecodeResult U16
     actual = "+1" |> Str.toUtf8 |> Decode.fromBytesPartial json
-    expected = { result: Err TooShort, rest: ['+', '1'] }
+    expected = { result: Err (Expected "U16"), rest: ['+', '1'] }
     actual == expected
 
 expect
     actual : DecodeResult U16
     actual = ".0" |> Str.toUtf8 |> Decode.fromBytesPartial json
-    expected = { result: Err TooShort, rest: ['.', '0'] }
+    expected = { result: Err (Expected "U16"), rest: ['.', '0'] }
     actual == expected
 
 expect
     actual : DecodeResult U64
     actual = "-.1" |> Str.toUtf8 |> Decode.fromBytesPartial json
-    actual.result == Err TooShort
+    actual.result == Err (Expected "U64")
 
 expect
     actual : DecodeResult Dec
@@ -939,43 +1146,43 @@ expect
 expect
     actual : DecodeResult U64
     actual = "-12." |> Str.toUtf8 |> Decode.fromBytesPartial json
-    expected = Err TooShort
+    expected = Err (Expected "U64")
     actual.result == expected
 
 expect
     actual : DecodeResult U64
     actual = "01.1" |> Str.toUtf8 |> Decode.fromBytesPartial json
-    expected = Err TooShort
+    expected = Err (Expected "U64")
     actual.result == expected
 
 expect
     actual : DecodeResult U64
     actual = ".0" |> Str.toUtf8 |> Decode.fromBytesPartial json
-    expected = Err TooShort
+    expected = Err (Expected "U64")
     actual.result == expected
 
 expect
     actual : DecodeResult U64
     actual = "1.e1" |> Str.toUtf8 |> Decode.fromBytesPartial json
-    expected = Err TooShort
+    expected = Err (Expected "U64")
     actual.result == expected
 
 expect
     actual : DecodeResult U64
     actual = "-1.2E" |> Str.toUtf8 |> Decode.fromBytesPartial json
-    expected = Err TooShort
+    expected = Err (Expected "U64")
     actual.result == expected
 
 expect
     actual : DecodeResult U64
     actual = "0.1e+" |> Str.toUtf8 |> Decode.fromBytesPartial json
-    expected = Err TooShort
+    expected = Err (Expected "U64")
     actual.result == expected
 
 expect
     actual : DecodeResult U64
     actual = "-03" |> Str.toUtf8 |> Decode.fromBytesPartial json
-    expected = Err TooShort
+    expected = Err (Expected "U64")
     actual.result == expected
 
 # JSON STRING PRIMITIVE --------------------------------------------------------
@@ -993,7 +1200,7 @@ decodeString = Decode.custom \bytes, @Json {} ->
             { taken: strBytes, rest } = takeJsonString bytes
 
             if List.isEmpty strBytes then
-                { result: Err TooShort, rest: bytes }
+                { result: Err (Expected "Str"), rest: bytes }
             else
                 # Remove starting and ending quotation marks, replace unicode
                 # escpapes with Roc equivalent, and try to parse RocStr from
@@ -1014,7 +1221,7 @@ decodeString = Decode.custom \bytes, @Json {} ->
                         { result: Ok str, rest }
 
                     Err _ ->
-                        { result: Err TooShort, rest: bytes }
+                        { result: Err (Expected "Str"), rest: bytes }
 
 takeJsonString : List U8 -> { taken : List U8, rest : List U8 }
 takeJsonString = \bytes ->
@@ -1149,7 +1356,8 @@ expect
 
 unicodeReplacement = hexToUtf8 'f' 'f' 'd' 'd'
 
-replaceEscapedChars : { inBytes : List U8, outBytes : List U8 } -> { inBytes : List U8, outBytes : List U8 }
+replaceEscapedChars :
+    { inBytes : List U8, outBytes : List U8 } -> { inBytes : List U8, outBytes : List U8 }
 replaceEscapedChars = \{ inBytes, outBytes } ->
 
     firstByte = List.get inBytes 0
@@ -1281,9 +1489,9 @@ arrayElemDecoder = \elemDecoder ->
                         # or the end of the list
                         decodeElems rest (List.append accum elem)
 
-                    Err _ ->
+                    Err err ->
                         # Unable to decode next element
-                        { result: Err TooShort, rest }
+                        { result: Err (Decode.inElem err (List.len accum)), rest }
 
             BeforeNextElemOrClosingBracket _ ->
                 if List.isEmpty accum then
@@ -1387,7 +1595,7 @@ decodeRecord = \initialState, stepField, finalizer -> Decode.custom \bytes, @Jso
             valueBytes = List.drop bytesAfterField countBytesBeforeValue
 
             when objectNameResult is
-                Err TooShort ->
+                Err _ ->
                     # Invalid object, unable to decode field name or find colon ':'
                     # after field and before the value
                     { result: Err TooShort, rest: bytes }
@@ -1410,7 +1618,10 @@ decodeRecord = \initialState, stepField, finalizer -> Decode.custom \bytes, @Jso
                                 Keep valueDecoder ->
                                     # Decode the value using the decoder from the recordState
                                     # Note we need to pass json config options recursively here
-                                    Decode.decodeWith valueBytes valueDecoder (@Json { fieldNameMapping })
+                                    Decode.decodeWith
+                                        valueBytes
+                                        valueDecoder
+                                        (@Json { fieldNameMapping })
                         )
                         |> tryDecode
 
@@ -1434,19 +1645,25 @@ decodeRecord = \initialState, stepField, finalizer -> Decode.custom \bytes, @Jso
                             # Invalid object
                             { result: Err TooShort, rest: bytesAfterValue }
 
-        countBytesBeforeFirstField =
-            when List.walkUntil bytes (BeforeOpeningBrace 0) objectHelp is
-                ObjectFieldNameStart n -> n
-                _ -> 0
+        when List.walkUntil bytes (BeforeOpeningBrace 0) objectHelp is
+            ObjectFieldNameStart n ->
+                bytesBeforeFirstField = List.drop bytes n
 
-        if countBytesBeforeFirstField == 0 then
-            # Invalid object, expected opening brace '{' followed by a field
-            { result: Err TooShort, rest: bytes }
-        else
-            bytesBeforeFirstField = List.drop bytes countBytesBeforeFirstField
+                # Begin decoding field:value pairs
+                decodeFields initialState bytesBeforeFirstField
+
+            AfterClosingBrace n ->
+                rest = List.drop bytes n
 
-            # Begin decoding field:value pairs
-            decodeFields initialState bytesBeforeFirstField
+                # Empty object, every field of the record is missing
+                when finalizer initialState is
+                    Ok val -> { result: Ok val, rest }
+                    Err e -> { result: Err e, rest }
+
+            _ ->
+                # Invalid object, expected opening brace '{' followed by a field
+                # or a closing brace '}'
+                { result: Err TooShort, rest: bytes }
 
 objectHelp : ObjectState, U8 -> [Break ObjectState, Continue ObjectState]
 objectHelp = \state, byte ->
@@ -1455,6 +1672,7 @@ objectHelp = \state, byte ->
         (BeforeOpeningBrace n, b) if b == '{' -> Continue (AfterOpeningBrace (n + 1))
         (AfterOpeningBrace n, b) if isWhitespace b -> Continue (AfterOpeningBrace (n + 1))
         (AfterOpeningBrace n, b) if b == '"' -> Break (ObjectFieldNameStart n)
+        (AfterOpeningBrace n, b) if b == '}' -> Continue (AfterClosingBrace (n + 1))
         (BeforeColon n, b) if isWhitespace b -> Continue (BeforeColon (n + 1))
         (BeforeColon n, b) if b == ':' -> Continue (AfterColon (n + 1))
         (AfterColon n, b) if isWhitespace b -> Continue (AfterColon (n + 1))
@@ -1491,7 +1709,8 @@ expect
 
 # Test decode of record with an array of strings and a boolean field
 expect
-    input = Str.toUtf8 "{\"fruit-flavours\": [\"Apples\",\"Bananas\",\"Pears\"], \"is-fresh\": true }"
+    input = Str.toUtf8
+        "{\"fruit-flavours\": [\"Apples\",\"Bananas\",\"Pears\"], \"is-fresh\": true }"
     decoder = jsonWithOptions { fieldNameMapping: KebabCase }
     actual = Decode.fromBytesPartial input decoder
     expected = Ok { fruitFlavours: ["Apples", "Bananas", "Pears"], isFresh: Bool.true }
@@ -1516,6 +1735,47 @@ expect
 
     actual.result == expected
 
+# Test decode errors report where in the value they happened
+expect
+    input = Str.toUtf8 "{\"users\":[{\"email\":\"a\"},{\"email\":2}]}"
+    actual : DecodeResult { users : List { email : Str } }
+    actual = Decode.fromBytesPartial input json
+    expected = Err (At [Field "users", Elem 1, Field "email"] (Expected "Str"))
+
+    actual.result == expected
+
+# Test decode error for a missing field
+expect
+    input = Str.toUtf8 "{\"name\":\"a\"}"
+    actual : DecodeResult { name : Str, age : U8 }
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Err (At [Field "age"] TooShort)
+
+# Test decode of an absent field of type `Result a [Missing]`
+expect
+    input = Str.toUtf8 "{\"name\":\"a\"}"
+    actual : DecodeResult { name : Str, age : Result U8 [Missing] }
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok { name: "a", age: Err Missing }
+
+# Test decode of a present field of type `Result a [Missing]`
+expect
+    input = Str.toUtf8 "{\"name\":\"a\",\"age\":3}"
+    actual : DecodeResult { name : Str, age : Result U8 [Missing] }
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok { name: "a", age: Ok 3 }
+
+# Test decode of an empty object
+expect
+    input = Str.toUtf8 "{ }"
+    actual : DecodeResult {}
+    actual = Decode.fromBytesPartial input json
+
+    actual.result == Ok {}
+
 fromYellingCase = \str ->
     Str.graphemes str
     |> List.map toLowercase
@@ -1524,7 +1784,8 @@ fromYellingCase = \str ->
 expect fromYellingCase "YELLING" == "yelling"
 
 # Complex example from IETF RFC 8259 (2017)
-complexExampleJson = Str.toUtf8 "{\"Image\":{\"Animated\":false,\"Height\":600,\"Ids\":[116,943,234,38793],\"Thumbnail\":{\"Height\":125,\"Url\":\"http:\\/\\/www.example.com\\/image\\/481989943\",\"Width\":100},\"Title\":\"View from 15th Floor\",\"Width\":800}}"
+complexExampleJson = Str.toUtf8
+    "{\"Image\":{\"Animated\":false,\"Height\":600,\"Ids\":[116,943,234,38793],\"Thumbnail\":{\"Height\":125,\"Url\":\"http:\\/\\/www.example.com\\/image\\/481989943\",\"Width\":100},\"Title\":\"View from 15th Floor\",\"Width\":800}}"
 complexExampleRecord = {
     image: {
         width: 800,
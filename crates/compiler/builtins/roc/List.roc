@@ -750,12 +750,17 @@ rangeLengthHelp = \accum, i, remaining, calcNext ->
     else
         when i is
             Ok val ->
-                rangeLengthHelp (List.appendUnsafe accum val) (calcNext val) (remaining - 1) calcNext
+                rangeLengthHelp
+                    (List.appendUnsafe accum val)
+                    (calcNext val)
+                    (remaining - 1)
+                    calcNext
 
             Err _ ->
                 # We went past the end of the numeric range and there is no next.
                 # The list is not the correct length yet, so we must crash.
-                crash "List.range: failed to generate enough elements to fill the range before overflowing the numeric type"
+                crash
+                    "List.range: failed to generate enough elements to fill the range before overflowing the numeric type"
 
 expect
     List.range { start: At 0, end: At 4 } == [0, 1, 2, 3, 4]
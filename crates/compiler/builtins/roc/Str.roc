@@ -398,7 +398,8 @@ expect (Str.fromUtf8 [255]) |> Result.isErr
 ## ```
 ## expect Str.fromUtf8Range [72, 105, 80, 103] { start : 0, count : 2 } == Ok "Hi"
 ## ```
-fromUtf8Range : List U8, { start : Nat, count : Nat } -> Result Str [BadUtf8 Utf8ByteProblem Nat, OutOfBounds]
+fromUtf8Range :
+    List U8, { start : Nat, count : Nat } -> Result Str [BadUtf8 Utf8ByteProblem Nat, OutOfBounds]
 fromUtf8Range = \bytes, config ->
     if config.start + config.count <= List.len bytes then
         result = fromUtf8RangeLowlevel bytes config.start config.count
@@ -34,6 +34,10 @@ pub struct Env<'a> {
 
     pub top_level_symbols: VecSet<Symbol>,
 
+    /// Whether a `_` in expression position is a typed hole, whose type gets reported,
+    /// rather than a malformed identifier
+    pub typed_holes: bool,
+
     pub arena: &'a Bump,
 }
 
@@ -55,6 +59,7 @@ impl<'a> Env<'a> {
             qualified_type_lookups: VecSet::default(),
             tailcallable_symbol: None,
             top_level_symbols: VecSet::default(),
+            typed_holes: false,
         }
     }
 
@@ -275,7 +275,8 @@ pub enum Expr {
         symbol: Symbol,
     },
 
-    /// Rendered as empty box in editor
+    /// A `_` (or `_name`) in expression position, whose type gets reported to the user.
+    /// Rendered as empty box in editor.
     TypedHole(Variable),
 
     /// Compiles, but will crash if reached
@@ -1017,6 +1018,11 @@ pub fn canonicalize_expr<'a>(
         ast::Expr::Var { module_name, ident } => {
             canonicalize_var_lookup(env, var_store, scope, module_name, ident, region)
         }
+        ast::Expr::Underscore(_) if env.typed_holes => {
+            // `_` (or `_name`) isn't a value, but a hole for the type checker to tell us
+            // the type of, so it gets reported after solving rather than here.
+            (TypedHole(var_store.fresh()), Output::default())
+        }
         ast::Expr::Underscore(name) => {
             // we parse underscores, but they are not valid expression syntax
             let problem = roc_problem::can::RuntimeError::MalformedIdentifier(
@@ -1351,6 +1357,11 @@ pub fn canonicalize_expr<'a>(
             use roc_problem::can::RuntimeError::*;
             (RuntimeError(MalformedClosure(region)), Output::default())
         }
+        ast::Expr::Malformed(_) => {
+            // The syntax error was already reported when we parsed the module
+            use roc_problem::can::RuntimeError::*;
+            (RuntimeError(MalformedDef(region)), Output::default())
+        }
         ast::Expr::MalformedIdent(name, bad_ident) => {
             use roc_problem::can::RuntimeError::*;
 
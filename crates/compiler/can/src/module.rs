@@ -284,11 +284,14 @@ pub fn canonicalize_module_defs<'a>(
     exposed_symbols: VecSet<Symbol>,
     symbols_from_requires: &[(Loc<Symbol>, Loc<TypeAnnotation<'a>>)],
     var_store: &mut VarStore,
+    typed_holes: bool,
 ) -> ModuleOutput {
     let mut can_exposed_imports = MutMap::default();
     let mut scope = Scope::new(home, exposed_ident_ids, imported_abilities_state);
     let mut env = Env::new(arena, home, dep_idents, module_ids);
 
+    env.typed_holes = typed_holes;
+
     for (name, alias) in aliases.into_iter() {
         scope.add_alias(
             name,
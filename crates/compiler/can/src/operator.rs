@@ -136,6 +136,7 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
         | Underscore { .. }
         | MalformedIdent(_, _)
         | MalformedClosure
+        | Malformed(_)
         | PrecedenceConflict { .. }
         | MultipleRecordBuilders { .. }
         | UnappliedRecordBuilder { .. }
@@ -164,7 +164,7 @@ pub fn walk_decls<V: Visitor>(visitor: &mut V, decls: &Declarations) {
     }
 }
 
-fn walk_decl<V: Visitor>(visitor: &mut V, decl: DeclarationInfo<'_>) {
+pub fn walk_decl<V: Visitor>(visitor: &mut V, decl: DeclarationInfo<'_>) {
     use DeclarationInfo::*;
 
     match decl {
@@ -1724,12 +1724,15 @@ pub fn constrain_expr(
         }
         TypedHole(var) => {
             // store the expected type for this position
-            constraints.equal_types_var(
+            let store_con = constraints.equal_types_var(
                 *var,
                 expected,
                 Category::Storage(std::file!(), std::line!()),
                 region,
-            )
+            );
+            let hole_con = constraints.typed_hole(*var, region);
+
+            constraints.and_constraint([store_con, hole_con])
         }
         RuntimeError(_) => {
             // Runtime Errors are always going to crash, so they don't introduce any new
@@ -156,6 +156,9 @@ flags! {
     /// Writes a `final.wasm` file to /tmp
     ROC_WRITE_FINAL_WASM
 
+    /// Writes a `final.wat` file to /tmp, in WebAssembly text format
+    ROC_WRITE_FINAL_WAT
+
     /// Prints Wasm interpreter debug log in test_gen
     ROC_LOG_WASM_INTERP
 
@@ -0,0 +1,547 @@
+//! Derivers for the `Compare` ability.
+
+use std::iter::once;
+
+use roc_can::expr::{AnnotatedMark, ClosureData, Expr, Recursive, WhenBranch, WhenBranchPattern};
+use roc_can::pattern::Pattern;
+use roc_derive_key::compare::FlatCompareKey;
+use roc_module::called_via::CalledVia;
+use roc_module::ident::{Lowercase, TagName};
+use roc_module::symbol::Symbol;
+use roc_region::all::{Loc, Region};
+use roc_types::subs::{
+    Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable, RecordFields,
+    RedundantMark, SubsSlice, TagExt, TupleElems, UnionLambdas, UnionTags, Variable,
+    VariableSubsSlice,
+};
+use roc_types::types::RecordField;
+
+use crate::util::{Env, ExtensionKind};
+use crate::{synth_var, DerivedBody};
+
+pub(crate) fn derive_compare(
+    env: &mut Env<'_>,
+    key: FlatCompareKey,
+    def_symbol: Symbol,
+) -> DerivedBody {
+    let (body_type, body) = match key {
+        FlatCompareKey::Record(fields) => compare_record(env, def_symbol, fields),
+        FlatCompareKey::Tuple(arity) => compare_tuple(env, def_symbol, arity),
+        FlatCompareKey::TagUnion(tags) => compare_tag_union(env, def_symbol, tags),
+    };
+
+    let specialization_lambda_sets =
+        env.get_specialization_lambda_sets(body_type, Symbol::COMPARE_COMPARE);
+
+    DerivedBody {
+        body,
+        body_type,
+        specialization_lambda_sets,
+    }
+}
+
+fn compare_record(env: &mut Env<'_>, fn_name: Symbol, fields: Vec<Lowercase>) -> (Variable, Expr) {
+    // Suppose rcd = { f1, ..., fn }.
+    // Build a generalized type t_rcd = { f1: t1, ..., fn: tn }, with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many records of the same fields.
+    let (record_var, record_fields) = {
+        let flex_fields = fields
+            .into_iter()
+            .map(|name| {
+                (
+                    name,
+                    RecordField::Required(env.subs.fresh_unnamed_flex_var()),
+                )
+            })
+            .collect::<Vec<(Lowercase, _)>>();
+        let fields = RecordFields::insert_into_subs(env.subs, flex_fields);
+        let record_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Record(fields, Variable::EMPTY_RECORD)),
+        );
+
+        (record_var, fields)
+    };
+
+    // Now, a comparator for this record is
+    //
+    // compare_rcd : { f1: t1, ..., fn: tn }, { f1: t1, ..., fn: tn } -> [LT, EQ, GT]
+    // compare_rcd = \rcd1, rcd2 ->
+    //   Compare.tieBreak (
+    //      Compare.tieBreak
+    //          ...
+    //          (Compare.compare rcd1.f1 rcd2.f1)
+    //          ...
+    //      (Compare.compare rcd1.f_n1 rcd2.f_n1))
+    //   (Compare.compare rcd1.fn rcd2.fn)
+    //
+    // Fields are visited in sorted order, so records are ordered lexicographically by field
+    // name.
+    let rcd1_sym = env.new_symbol("rcd1");
+    let rcd2_sym = env.new_symbol("rcd2");
+    let order_var = order_var(env);
+
+    let comparisons = record_fields
+        .iter_all()
+        .map(|(field_name, field_var, _)| {
+            let field_name = env.subs[field_name].clone();
+            let field_var = env.subs[field_var];
+
+            let mut field_access = |rcd_sym| Expr::RecordAccess {
+                record_var,
+                field_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(
+                    rcd_sym,
+                    env.subs.fresh_unnamed_flex_var(),
+                ))),
+                field: field_name.clone(),
+            };
+            let lhs = field_access(rcd1_sym);
+            let rhs = field_access(rcd2_sym);
+
+            call_compare(env, order_var, (field_var, lhs), (field_var, rhs))
+        })
+        .collect();
+
+    let body = tie_break_all(env, order_var, comparisons);
+
+    // Finally, build the closure
+    // \rcd1, rcd2 -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        record_var,
+        (Pattern::Identifier(rcd1_sym), Pattern::Identifier(rcd2_sym)),
+        (order_var, body),
+    )
+}
+
+fn compare_tuple(env: &mut Env<'_>, fn_name: Symbol, arity: u32) -> (Variable, Expr) {
+    // Suppose tup = (v1, ..., vn).
+    // Build a generalized type t_tup = (t1, ..., tn), with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many tuples of the same arity.
+    let (tuple_var, tuple_elems) = {
+        let flex_elems: Vec<_> = (0..arity)
+            .map(|i| (i as usize, env.subs.fresh_unnamed_flex_var()))
+            .collect();
+        let elems = TupleElems::insert_into_subs(env.subs, flex_elems);
+        let tuple_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Tuple(elems, Variable::EMPTY_TUPLE)),
+        );
+
+        (tuple_var, elems)
+    };
+
+    // Now, a comparator for this tuple is
+    //
+    // compare_tup : (t1, ..., tn), (t1, ..., tn) -> [LT, EQ, GT]
+    // compare_tup = \tup1, tup2 ->
+    //   Compare.tieBreak (
+    //      Compare.tieBreak
+    //          ...
+    //          (Compare.compare tup1.0 tup2.0)
+    //          ...
+    //      (Compare.compare tup1.n1 tup2.n1))
+    //   (Compare.compare tup1.n tup2.n)
+    let tup1_sym = env.new_symbol("tup1");
+    let tup2_sym = env.new_symbol("tup2");
+    let order_var = order_var(env);
+
+    let comparisons = tuple_elems
+        .iter_all()
+        .map(|(elem_idx, elem_var)| {
+            let index = env.subs[elem_idx];
+            let elem_var = env.subs[elem_var];
+
+            let mut elem_access = |tup_sym| Expr::TupleAccess {
+                tuple_var,
+                elem_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(
+                    tup_sym,
+                    env.subs.fresh_unnamed_flex_var(),
+                ))),
+                index,
+            };
+            let lhs = elem_access(tup1_sym);
+            let rhs = elem_access(tup2_sym);
+
+            call_compare(env, order_var, (elem_var, lhs), (elem_var, rhs))
+        })
+        .collect();
+
+    let body = tie_break_all(env, order_var, comparisons);
+
+    // Finally, build the closure
+    // \tup1, tup2 -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        tuple_var,
+        (Pattern::Identifier(tup1_sym), Pattern::Identifier(tup2_sym)),
+        (order_var, body),
+    )
+}
+
+fn compare_tag_union(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Variable, Expr) {
+    // Suppose tags = [ A p11 .. p1n, ..., Q pq1 .. pqm ]
+    // Build a generalized type t_tags = [ A t11 .. t1n, ..., Q tq1 .. tqm ],
+    // with fresh t1, ..., tqm, so that we can re-use the derived impl for many
+    // unions of the same tags and payloads.
+    let (union_var, union_tags) = {
+        let flex_tag_labels = tags
+            .into_iter()
+            .map(|(label, arity)| {
+                let variables_slice = VariableSubsSlice::reserve_into_subs(env.subs, arity.into());
+                for var_index in variables_slice {
+                    env.subs[var_index] = env.subs.fresh_unnamed_flex_var();
+                }
+                (label, variables_slice)
+            })
+            .collect::<Vec<_>>();
+        let union_tags = UnionTags::insert_slices_into_subs(env.subs, flex_tag_labels);
+        let tag_union_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::TagUnion(
+                union_tags,
+                TagExt::Any(Variable::EMPTY_TAG_UNION),
+            )),
+        );
+
+        (tag_union_var, union_tags)
+    };
+
+    // Now, a comparator for this tag union is
+    //
+    // compare_union : [ A t11 .. t1n, ..., Q tq1 .. tqm ], [ A t11 .. t1n, ..., Q tq1 .. tqm ]
+    //                 -> [LT, EQ, GT]
+    // compare_union = \union1, union2 ->
+    //   when union1 is
+    //      A x11 .. x1n ->
+    //          when union2 is
+    //              A y11 .. y1n ->
+    //                  Compare.tieBreak (... (Compare.compare x11 y11) ...) (compare x1n y1n)
+    //              B _ .. _ -> LT
+    //              ...
+    //              Q _ .. _ -> LT
+    //      ...
+    //
+    // Tags are in sorted order, so tags with different names are ordered by name, and tags
+    // with the same name are ordered lexicographically by their payloads.
+    let union1_sym = env.new_symbol("union1");
+    let union2_sym = env.new_symbol("union2");
+    let order_var = order_var(env);
+
+    let tags: Vec<(TagName, Vec<Variable>)> = union_tags
+        .iter_all()
+        .map(|(tag, payloads)| {
+            (
+                env.subs[tag].clone(),
+                env.subs.get_subs_slice(env.subs[payloads]).to_vec(),
+            )
+        })
+        .collect();
+
+    let branches = tags
+        .iter()
+        .enumerate()
+        .map(|(i, (tag_name, payload_vars))| {
+            // x11 .. x1n
+            let lhs_syms: Vec<_> = std::iter::repeat_with(|| env.unique_symbol())
+                .take(payload_vars.len())
+                .collect();
+
+            let inner_branches = tags
+                .iter()
+                .enumerate()
+                .map(|(j, (other_tag_name, other_payload_vars))| {
+                    let (arguments, value) = if i == j {
+                        // y11 .. y1n
+                        let rhs_syms: Vec<_> = std::iter::repeat_with(|| env.unique_symbol())
+                            .take(payload_vars.len())
+                            .collect();
+
+                        let comparisons = (payload_vars.iter())
+                            .zip(lhs_syms.iter().zip(rhs_syms.iter()))
+                            .map(|(var, (lhs, rhs))| {
+                                call_compare(
+                                    env,
+                                    order_var,
+                                    (*var, Expr::Var(*lhs, *var)),
+                                    (*var, Expr::Var(*rhs, *var)),
+                                )
+                            })
+                            .collect();
+
+                        (
+                            identifier_arguments(payload_vars, &rhs_syms),
+                            tie_break_all(env, order_var, comparisons),
+                        )
+                    } else {
+                        let order = if i < j { "LT" } else { "GT" };
+
+                        (
+                            (other_payload_vars.iter())
+                                .map(|var| (*var, Loc::at_zero(Pattern::Underscore)))
+                                .collect(),
+                            order_tag(env, order_var, order),
+                        )
+                    };
+
+                    // `B y11 .. y1n` or `B _ .. _` pattern
+                    let pattern = Pattern::AppliedTag {
+                        whole_var: union_var,
+                        tag_name: other_tag_name.clone(),
+                        ext_var: Variable::EMPTY_TAG_UNION,
+                        arguments,
+                    };
+
+                    WhenBranch {
+                        patterns: vec![WhenBranchPattern {
+                            pattern: Loc::at_zero(pattern),
+                            degenerate: false,
+                        }],
+                        value: Loc::at_zero(value),
+                        guard: None,
+                        redundant: RedundantMark::known_non_redundant(),
+                    }
+                })
+                .collect();
+
+            // when union2 is
+            //   ...
+            let inner_when = Expr::When {
+                loc_cond: Box::new(Loc::at_zero(Expr::Var(union2_sym, union_var))),
+                cond_var: union_var,
+                expr_var: order_var,
+                region: Region::zero(),
+                branches: inner_branches,
+                branches_cond_var: union_var,
+                exhaustive: ExhaustiveMark::known_exhaustive(),
+            };
+
+            // `A x11 .. x1n` pattern
+            let pattern = Pattern::AppliedTag {
+                whole_var: union_var,
+                tag_name: tag_name.clone(),
+                ext_var: Variable::EMPTY_TAG_UNION,
+                arguments: identifier_arguments(payload_vars, &lhs_syms),
+            };
+
+            WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(pattern),
+                    degenerate: false,
+                }],
+                value: Loc::at_zero(inner_when),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            }
+        })
+        .collect();
+
+    // when union1 is
+    //   ...
+    let when_expr = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(union1_sym, union_var))),
+        cond_var: union_var,
+        expr_var: order_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: union_var,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    // Finally, build the closure
+    // \union1, union2 -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        union_var,
+        (
+            Pattern::Identifier(union1_sym),
+            Pattern::Identifier(union2_sym),
+        ),
+        (order_var, when_expr),
+    )
+}
+
+/// Builds the `[LT, EQ, GT]` type that all comparisons produce.
+fn order_var(env: &mut Env<'_>) -> Variable {
+    let union_tags = UnionTags::insert_slices_into_subs(
+        env.subs,
+        [
+            ("EQ".into(), Default::default()),
+            ("GT".into(), Default::default()),
+            ("LT".into(), Default::default()),
+        ],
+    );
+
+    synth_var(
+        env.subs,
+        Content::Structure(FlatType::TagUnion(
+            union_tags,
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        )),
+    )
+}
+
+/// Builds one of `LT`, `EQ`, or `GT`.
+fn order_tag(env: &mut Env<'_>, order_var: Variable, order: &str) -> Expr {
+    Expr::Tag {
+        tag_union_var: order_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: order.into(),
+        arguments: Vec::new(),
+    }
+}
+
+fn identifier_arguments(vars: &[Variable], syms: &[Symbol]) -> Vec<(Variable, Loc<Pattern>)> {
+    (vars.iter())
+        .zip(syms.iter())
+        .map(|(var, sym)| (*var, Loc::at_zero(Pattern::Identifier(*sym))))
+        .collect()
+}
+
+/// Folds `Compare.tieBreak` over the comparisons, so that the first comparison that is not `EQ`
+/// decides the ordering. With no comparisons at all, the values are `EQ`.
+fn tie_break_all(env: &mut Env<'_>, order_var: Variable, comparisons: Vec<Expr>) -> Expr {
+    let mut comparisons = comparisons.into_iter();
+
+    match comparisons.next() {
+        None => order_tag(env, order_var, "EQ"),
+        Some(first) => comparisons.fold(first, |total, next| {
+            call_compare_builtin(
+                env,
+                Symbol::COMPARE_TIE_BREAK,
+                order_var,
+                (order_var, total),
+                (order_var, next),
+            )
+        }),
+    }
+}
+
+fn call_compare(
+    env: &mut Env<'_>,
+    order_var: Variable,
+    lhs: (Variable, Expr),
+    rhs: (Variable, Expr),
+) -> Expr {
+    call_compare_builtin(env, Symbol::COMPARE_COMPARE, order_var, lhs, rhs)
+}
+
+fn call_compare_builtin(
+    env: &mut Env<'_>,
+    builtin: Symbol,
+    order_var: Variable,
+    lhs: (Variable, Expr),
+    rhs: (Variable, Expr),
+) -> Expr {
+    let (lhs_var, lhs_expr) = lhs;
+    let (rhs_var, rhs_expr) = rhs;
+
+    // build `builtin ...` function type. `builtin` here is `Compare.compare` or
+    // `Compare.tieBreak`.
+    //
+    // a, a -[uls]-> [LT, EQ, GT] | a has Compare
+    let exposed_fn_var = env.import_builtin_symbol_var(builtin);
+
+    // (typeof lhs), (typeof rhs) -[clos]-> [LT, EQ, GT]
+    let this_arguments_slice = VariableSubsSlice::insert_into_subs(env.subs, [lhs_var, rhs_var]);
+    let this_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            this_arguments_slice,
+            this_clos_var,
+            order_var,
+        )),
+    );
+
+    //   a,            a            -[uls]->  [LT, EQ, GT] | a has Compare
+    // ~ (typeof lhs), (typeof rhs) -[clos]-> [LT, EQ, GT]
+    env.unify(exposed_fn_var, this_fn_var);
+
+    let fn_head = if builtin == Symbol::COMPARE_COMPARE {
+        Expr::AbilityMember(builtin, None, this_fn_var)
+    } else {
+        Expr::Var(builtin, this_fn_var)
+    };
+    let fn_data = Box::new((this_fn_var, Loc::at_zero(fn_head), this_clos_var, order_var));
+
+    let arguments = vec![
+        (lhs_var, Loc::at_zero(lhs_expr)),
+        (rhs_var, Loc::at_zero(rhs_expr)),
+    ];
+
+    Expr::Call(fn_data, arguments, CalledVia::Space)
+}
+
+fn build_outer_derived_closure(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    val_var: Variable,
+    patterns: (Pattern, Pattern),
+    body: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (lhs_pattern, rhs_pattern) = patterns;
+    let (body_var, body_expr) = body;
+
+    let (fn_var, fn_clos_var) = {
+        // Create fn_var for ambient capture; we fix it up below.
+        let fn_var = synth_var(env.subs, Content::Error);
+
+        // -[fn_name]->
+        let fn_captures = vec![];
+        let fn_name_labels = UnionLambdas::insert_into_subs(env.subs, once((fn_name, fn_captures)));
+        let fn_clos_var = synth_var(
+            env.subs,
+            Content::LambdaSet(LambdaSet {
+                solved: fn_name_labels,
+                recursion_var: OptVariable::NONE,
+                unspecialized: SubsSlice::default(),
+                ambient_function: fn_var,
+            }),
+        );
+
+        // val_var, val_var -[fn_name]-> body_var
+        let args_slice = SubsSlice::insert_into_subs(env.subs, [val_var, val_var]);
+        env.subs.set_content(
+            fn_var,
+            Content::Structure(FlatType::Func(args_slice, fn_clos_var, body_var)),
+        );
+
+        (fn_var, fn_clos_var)
+    };
+
+    let clos_expr = Expr::Closure(ClosureData {
+        function_type: fn_var,
+        closure_type: fn_clos_var,
+        return_type: body_var,
+        name: fn_name,
+        captured_symbols: vec![],
+        recursive: Recursive::NotRecursive,
+        arguments: vec![
+            (
+                val_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(lhs_pattern),
+            ),
+            (
+                val_var,
+                AnnotatedMark::known_exhaustive(),
+                Loc::at_zero(rhs_pattern),
+            ),
+        ],
+        loc_body: Box::new(Loc::at_zero(body_expr)),
+    });
+
+    (fn_var, clos_expr)
+}
@@ -16,6 +16,7 @@ use crate::{synth_var, DerivedBody};
 
 mod list;
 mod record;
+mod tag;
 mod tuple;
 
 pub(crate) fn derive_decoder(
@@ -27,6 +28,7 @@ pub(crate) fn derive_decoder(
         FlatDecodableKey::List() => list::decoder(env, def_symbol),
         FlatDecodableKey::Record(fields) => record::decoder(env, def_symbol, fields),
         FlatDecodableKey::Tuple(arity) => tuple::decoder(env, def_symbol, arity),
+        FlatDecodableKey::TagUnion(tags) => tag::decoder(env, def_symbol, tags),
     };
 
     let specialization_lambda_sets =
@@ -207,3 +209,49 @@ fn wrap_in_decode_custom_decode_with(
 
     (decode_custom_call, decoder_var)
 }
+
+// Reports a decoding error one step further down its path, like `Decode.inField err "first"` or
+// `Decode.inElem err 0`.
+//
+// `decode_err_var` is unified with `DecodeError`, which is both the argument and result here.
+fn call_prepend_path_step(
+    env: &mut Env,
+    prepend_step: Symbol,
+    (err, decode_err_var): (Expr, Variable),
+    (step, step_var): (Expr, Variable),
+) -> Expr {
+    use Expr::*;
+
+    // Decode.inField : DecodeError, Str -> DecodeError
+    let prepend_step_type = env.import_builtin_symbol_var(prepend_step);
+
+    // Decode.inField : decode_err_var, step_var -> decode_err_var
+    let this_args_slice = SubsSlice::insert_into_subs(env.subs, [decode_err_var, step_var]);
+    let this_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            this_args_slice,
+            this_clos_var,
+            decode_err_var,
+        )),
+    );
+
+    env.unify(prepend_step_type, this_fn_var);
+
+    let prepend_step_fn = Box::new((
+        this_fn_var,
+        Loc::at_zero(Var(prepend_step, this_fn_var)),
+        this_clos_var,
+        decode_err_var,
+    ));
+
+    Call(
+        prepend_step_fn,
+        vec![
+            (decode_err_var, Loc::at_zero(err)),
+            (step_var, Loc::at_zero(step)),
+        ],
+        CalledVia::Space,
+    )
+}
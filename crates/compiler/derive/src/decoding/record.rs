@@ -3,6 +3,7 @@ use roc_can::expr::{
 };
 use roc_can::pattern::Pattern;
 use roc_collections::SendMap;
+use roc_derive_key::decoding::DecodableField;
 use roc_module::called_via::CalledVia;
 use roc_module::ident::Lowercase;
 use roc_module::symbol::Symbol;
@@ -16,7 +17,7 @@ use roc_types::types::RecordField;
 use crate::synth_var;
 use crate::util::{Env, ExtensionKind};
 
-use super::wrap_in_decode_custom_decode_with;
+use super::{call_prepend_path_step, wrap_in_decode_custom_decode_with};
 
 /// Implements decoding of a record. For example, for
 ///
@@ -38,12 +39,12 @@ use super::wrap_in_decode_custom_decode_with;
 ///                 Keep (Decode.custom \bytes, fmt ->
 ///                     when Decode.decodeWith bytes Decode.decoder fmt is
 ///                         {result, rest} ->
-///                             {result: Result.map result \val -> {state & f0: Ok val}, rest})
+///                             {result: Result.mapErr (Result.map result \val -> {state & f0: Ok val}) \err -> Decode.inField err "first", rest})
 ///             "second" ->
 ///                 Keep (Decode.custom \bytes, fmt ->
 ///                     when Decode.decodeWith bytes Decode.decoder fmt is
 ///                         {result, rest} ->
-///                             {result: Result.map result \val -> {state & f1: Ok val}, rest})
+///                             {result: Result.mapErr (Result.map result \val -> {state & f1: Ok val}) \err -> Decode.inField err "second", rest})
 ///             _ -> Skip
 ///
 ///     finalizer = \{f0, f1} ->
@@ -51,30 +52,44 @@ use super::wrap_in_decode_custom_decode_with;
 ///             Ok first ->
 ///                 when f1 is
 ///                     Ok second -> Ok {first, second}
-///                     Err NoField -> Err TooShort
-///             Err NoField -> Err TooShort
+///                     Err NoField -> Err (Decode.inField TooShort "second")
+///             Err NoField -> Err (Decode.inField TooShort "first")
 ///
 ///     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.record initialState stepField finalizer) fmt
 /// ```
+///
+/// A field of type `Result a [Missing]` may be missing from the input. Its value is decoded as
+/// an `a` and wrapped in `Ok`, and it starts out as `Ok (Err Missing)` in the initial state, so
+/// the finalizer never finds it missing.
 pub(crate) fn decoder(
     env: &mut Env,
     _def_symbol: Symbol,
-    fields: Vec<Lowercase>,
+    fields: Vec<(Lowercase, DecodableField)>,
 ) -> (Expr, Variable) {
     // The decoded type of each field in the record, e.g. {first: a, second: b}.
     let mut field_vars = Vec::with_capacity(fields.len());
     // The type of each field in the decoding state, e.g. {first: Result a [NoField], second: Result b [NoField]}
     let mut result_field_vars = Vec::with_capacity(fields.len());
+    // The type decoded from the input for each field. This is the type of the field, except for
+    // fields of type `Result a [Missing]`, where it's `a`.
+    let mut decoded_vars = Vec::with_capacity(fields.len());
 
     // initialState = ...
-    let (initial_state_var, initial_state) =
-        initial_state(env, &fields, &mut field_vars, &mut result_field_vars);
+    let (initial_state_var, initial_state) = initial_state(
+        env,
+        &fields,
+        &mut field_vars,
+        &mut result_field_vars,
+        &mut decoded_vars,
+    );
+
+    let field_names: Vec<_> = fields.iter().map(|(name, _)| name.clone()).collect();
 
     // finalizer = ...
     let (finalizer, finalizer_var, decode_err_var) = finalizer(
         env,
         initial_state_var,
-        &fields,
+        &field_names,
         &field_vars,
         &result_field_vars,
     );
@@ -84,6 +99,7 @@ pub(crate) fn decoder(
         env,
         fields,
         &field_vars,
+        &decoded_vars,
         &result_field_vars,
         initial_state_var,
         decode_err_var,
@@ -157,7 +173,7 @@ pub(crate) fn decoder(
 //                             rest: rec.rest,
 //                             result: when rec.result is
 //                                 Ok val -> Ok {state & first: Ok val},
-//                                 Err err -> Err err
+//                                 Err err -> Err (Decode.inField err "first")
 //                         })
 //
 //         "second" ->
@@ -168,14 +184,15 @@ pub(crate) fn decoder(
 //                             rest: rec.rest,
 //                             result: when rec.result is
 //                                 Ok val -> Ok {state & second: Ok val},
-//                                 Err err -> Err err
+//                                 Err err -> Err (Decode.inField err "second")
 //                         })
 //
 //         _ -> Skip
 fn step_field(
     env: &mut Env,
-    fields: Vec<Lowercase>,
+    fields: Vec<(Lowercase, DecodableField)>,
     field_vars: &[Variable],
+    decoded_vars: &[Variable],
     result_field_vars: &[Variable],
     state_record_var: Variable,
     decode_err_var: Variable,
@@ -202,9 +219,10 @@ fn step_field(
         synth_var(env.subs, Content::Structure(flat_type))
     };
 
-    for ((field_name, &field_var), &result_field_var) in fields
+    for ((((field_name, field), &field_var), &decoded_var), &result_field_var) in fields
         .into_iter()
         .zip(field_vars.iter())
+        .zip(decoded_vars.iter())
         .zip(result_field_vars.iter())
     {
         // Example:
@@ -218,7 +236,7 @@ fn step_field(
         //                     rest: rec.rest,
         //                     result: when rec.result is
         //                         Ok val -> Ok {state & first: Ok val},
-        //                         Err err -> Err err
+        //                         Err err -> Err (Decode.inField err "first")
         //                 }
         //     )
 
@@ -232,17 +250,17 @@ fn step_field(
             //                 rest: rec.rest,
             //                 result: when rec.result is
             //                     Ok val -> Ok {state & first: Ok val},
-            //                     Err err -> Err err
+            //                     Err err -> Err (Decode.inField err "first")
             //             }
             let bytes_arg_symbol = env.new_symbol("bytes");
             let fmt_arg_symbol = env.new_symbol("fmt");
             let bytes_arg_var = env.subs.fresh_unnamed_flex_var();
             let fmt_arg_var = env.subs.fresh_unnamed_flex_var();
 
-            // rec.result : [Ok field_var, Err DecodeError]
+            // rec.result : [Ok decoded_var, Err DecodeError]
             let rec_dot_result = {
                 let tag_union = FlatType::TagUnion(
-                    UnionTags::for_result(env.subs, field_var, decode_err_var),
+                    UnionTags::for_result(env.subs, decoded_var, decode_err_var),
                     TagExt::Any(Variable::EMPTY_TAG_UNION),
                 );
 
@@ -319,19 +337,38 @@ fn step_field(
                 //             rest: rec.rest,
                 //             result: when rec.result is
                 //                 Ok val -> Ok {state & first: Ok val},
-                //                 Err err -> Err err
+                //                 Err err -> Err (Decode.inField err "first")
                 //         }
                 let branch_body = {
                     let result_val = {
                         // result: when rec.result is
                         //     Ok val -> Ok {state & first: Ok val},
-                        //     Err err -> Err err
+                        //     Err err -> Err (Decode.inField err "first")
                         let ok_val_symbol = env.new_symbol("val");
                         let err_val_symbol = env.new_symbol("err");
+                        // Decode.inField err "first"
+                        let field_err = call_prepend_path_step(
+                            env,
+                            Symbol::DECODE_IN_FIELD,
+                            (Expr::Var(err_val_symbol, decode_err_var), decode_err_var),
+                            (Expr::Str(field_name.as_str().into()), Variable::STR),
+                        );
                         let ok_branch_expr = {
                             // Ok {state & first: Ok val},
                             let mut updates = SendMap::default();
 
+                            let decoded_val = Expr::Var(ok_val_symbol, decoded_var);
+                            let field_val = match field {
+                                DecodableField::Required => decoded_val,
+                                // A field of type `Result a [Missing]` that was there: `Ok val`
+                                DecodableField::MaybeMissing => Expr::Tag {
+                                    tag_union_var: field_var,
+                                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                                    name: "Ok".into(),
+                                    arguments: vec![(decoded_var, Loc::at_zero(decoded_val))],
+                                },
+                            };
+
                             updates.insert(
                                 field_name.clone(),
                                 Field {
@@ -341,10 +378,7 @@ fn step_field(
                                         tag_union_var: result_field_var,
                                         ext_var: env.new_ext_var(ExtensionKind::TagUnion),
                                         name: "Ok".into(),
-                                        arguments: vec![(
-                                            field_var,
-                                            Loc::at_zero(Expr::Var(ok_val_symbol, field_var)),
-                                        )],
+                                        arguments: vec![(field_var, Loc::at_zero(field_val))],
                                     })),
                                 },
                             );
@@ -373,7 +407,7 @@ fn step_field(
                                         ext_var: Variable::EMPTY_TAG_UNION,
                                         tag_name: "Ok".into(),
                                         arguments: vec![(
-                                            field_var,
+                                            decoded_var,
                                             Loc::at_zero(Pattern::Identifier(ok_val_symbol)),
                                         )],
                                     }),
@@ -383,7 +417,7 @@ fn step_field(
                                 guard: None,
                                 redundant: RedundantMark::known_non_redundant(),
                             },
-                            // Err err -> Err err
+                            // Err err -> Err (Decode.inField err "first")
                             WhenBranch {
                                 patterns: vec![WhenBranchPattern {
                                     pattern: Loc::at_zero(Pattern::AppliedTag {
@@ -401,10 +435,7 @@ fn step_field(
                                     tag_union_var: when_expr_var,
                                     ext_var: env.new_ext_var(ExtensionKind::TagUnion),
                                     name: "Err".into(),
-                                    arguments: vec![(
-                                        decode_err_var,
-                                        Loc::at_zero(Expr::Var(err_val_symbol, decode_err_var)),
-                                    )],
+                                    arguments: vec![(decode_err_var, Loc::at_zero(field_err))],
                                 }),
                                 guard: None,
                                 redundant: RedundantMark::known_non_redundant(),
@@ -413,7 +444,7 @@ fn step_field(
 
                         // when rec.result is
                         //     Ok val -> Ok {state & first: Ok val},
-                        //     Err err -> Err err
+                        //     Err err -> Err (Decode.inField err "first")
                         Expr::When {
                             loc_cond: Box::new(Loc::at_zero(Expr::RecordAccess {
                                 record_var: rec_var,
@@ -435,7 +466,7 @@ fn step_field(
                     //     rest: rec.rest,
                     //     result: when rec.result is
                     //         Ok val -> Ok {state & first: Ok val},
-                    //         Err err -> Err err
+                    //         Err err -> Err (Decode.inField err "first")
                     // }
                     let mut fields_map = SendMap::default();
 
@@ -456,7 +487,7 @@ fn step_field(
 
                     // result: when rec.result is
                     //     Ok val -> Ok {state & first: Ok val},
-                    //     Err err -> Err err
+                    //     Err err -> Err (Decode.inField err "first")
                     fields_map.insert(
                         "result".into(),
                         Field {
@@ -611,7 +642,7 @@ fn step_field(
             //                 rest: rec.rest,
             //                 result: when rec.result is
             //                     Ok val -> Ok {state & first: Ok val},
-            //                     Err err -> Err err
+            //                     Err err -> Err (Decode.inField err "first")
             //             }
             // )
             Expr::Tag {
@@ -631,7 +662,7 @@ fn step_field(
             //                     rest: rec.rest,
             //                     result: when rec.result is
             //                         Ok val -> Ok {state & first: Ok val},
-            //                         Err err -> Err err
+            //                         Err err -> Err (Decode.inField err "first")
             //                 }
             //     )
             WhenBranch {
@@ -730,8 +761,8 @@ fn step_field(
 //         Ok first ->
 //             when rec.second is
 //                 Ok second -> Ok {first, second}
-//                 Err NoField -> Err TooShort
-//         Err NoField -> Err TooShort
+//                 Err NoField -> Err (Decode.inField TooShort "second")
+//         Err NoField -> Err (Decode.inField TooShort "first")
 fn finalizer(
     env: &mut Env,
     state_record_var: Variable,
@@ -742,14 +773,8 @@ fn finalizer(
     let state_arg_symbol = env.new_symbol("stateRecord");
     let mut fields_map = SendMap::default();
     let mut pattern_symbols = Vec::with_capacity(fields.len());
-    let decode_err_var = {
-        let flat_type = FlatType::TagUnion(
-            UnionTags::tag_without_arguments(env.subs, "TooShort".into()),
-            TagExt::Any(Variable::EMPTY_TAG_UNION),
-        );
-
-        synth_var(env.subs, Content::Structure(flat_type))
-    };
+    // Fixed to `DecodeError` by the calls to `Decode.inField` below.
+    let decode_err_var = env.subs.fresh_unnamed_flex_var();
 
     for (field_name, &field_var) in fields.iter().zip(field_vars.iter()) {
         let symbol = env.new_symbol(field_name.as_str());
@@ -806,7 +831,7 @@ fn finalizer(
     //
     // when rec.first is
     //     Ok first -> ...happy path...
-    //     Err NoField -> Err TooShort
+    //     Err NoField -> Err (Decode.inField TooShort "first")
     for (((symbol, field_name), &field_var), &result_field_var) in pattern_symbols
         .iter()
         .rev()
@@ -839,7 +864,21 @@ fn finalizer(
             redundant: RedundantMark::known_non_redundant(),
         };
 
-        // Example: `_ -> Err TooShort`
+        // Example: `_ -> Err (Decode.inField TooShort "first")`
+        let missing_field_err = call_prepend_path_step(
+            env,
+            Symbol::DECODE_IN_FIELD,
+            (
+                Expr::Tag {
+                    tag_union_var: decode_err_var,
+                    ext_var: Variable::EMPTY_TAG_UNION,
+                    name: "TooShort".into(),
+                    arguments: Vec::new(),
+                },
+                decode_err_var,
+            ),
+            (Expr::Str(field_name.as_str().into()), Variable::STR),
+        );
         let err_branch = WhenBranch {
             patterns: vec![WhenBranchPattern {
                 pattern: Loc::at_zero(Pattern::Underscore),
@@ -849,15 +888,7 @@ fn finalizer(
                 tag_union_var: return_type_var,
                 ext_var: env.new_ext_var(ExtensionKind::TagUnion),
                 name: "Err".into(),
-                arguments: vec![(
-                    decode_err_var,
-                    Loc::at_zero(Expr::Tag {
-                        tag_union_var: decode_err_var,
-                        ext_var: Variable::EMPTY_TAG_UNION,
-                        name: "TooShort".into(),
-                        arguments: Vec::new(),
-                    }),
-                )],
+                arguments: vec![(decode_err_var, Loc::at_zero(missing_field_err))],
             }),
             guard: None,
             redundant: RedundantMark::known_non_redundant(),
@@ -914,20 +945,71 @@ fn finalizer(
 // Example:
 // initialState : {first: Result a [NoField], second: Result b [NoField]}
 // initialState = {first: Err NoField, second: Err NoField}
+//
+// A field of type `Result a [Missing]` starts out as `Ok (Err Missing)` instead.
 fn initial_state(
     env: &mut Env<'_>,
-    field_names: &[Lowercase],
+    fields: &[(Lowercase, DecodableField)],
     field_vars: &mut Vec<Variable>,
     result_field_vars: &mut Vec<Variable>,
+    decoded_vars: &mut Vec<Variable>,
 ) -> (Variable, Expr) {
     let mut initial_state_fields = SendMap::default();
 
-    for field_name in field_names {
+    for (field_name, field) in fields {
         let subs = &mut env.subs;
-        let field_var = subs.fresh_unnamed_flex_var();
+        let decoded_var = subs.fresh_unnamed_flex_var();
+
+        decoded_vars.push(decoded_var);
+
+        // Err Missing
+        let missing = match field {
+            DecodableField::Required => None,
+            DecodableField::MaybeMissing => {
+                let missing_label = "Missing";
+                let union_tags = UnionTags::tag_without_arguments(subs, missing_label.into());
+                let missing_var = synth_var(
+                    subs,
+                    Content::Structure(FlatType::TagUnion(
+                        union_tags,
+                        TagExt::Any(Variable::EMPTY_TAG_UNION),
+                    )),
+                );
+                let union_tags = UnionTags::for_result(subs, decoded_var, missing_var);
+                let field_var = synth_var(
+                    subs,
+                    Content::Structure(FlatType::TagUnion(
+                        union_tags,
+                        TagExt::Any(Variable::EMPTY_TAG_UNION),
+                    )),
+                );
+                let missing = Expr::Tag {
+                    tag_union_var: missing_var,
+                    ext_var: Variable::EMPTY_TAG_UNION,
+                    name: missing_label.into(),
+                    arguments: Vec::new(),
+                };
+
+                Some((
+                    field_var,
+                    Expr::Tag {
+                        tag_union_var: field_var,
+                        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                        name: "Err".into(),
+                        arguments: vec![(missing_var, Loc::at_zero(missing))],
+                    },
+                ))
+            }
+        };
+
+        let field_var = match &missing {
+            Some((field_var, _)) => *field_var,
+            None => decoded_var,
+        };
 
         field_vars.push(field_var);
 
+        let subs = &mut env.subs;
         let no_field_label = "NoField";
         let union_tags = UnionTags::tag_without_arguments(subs, no_field_label.into());
         let no_field_var = synth_var(
@@ -937,13 +1019,6 @@ fn initial_state(
                 TagExt::Any(Variable::EMPTY_TAG_UNION),
             )),
         );
-        let no_field = Expr::Tag {
-            tag_union_var: no_field_var,
-            ext_var: Variable::EMPTY_TAG_UNION,
-            name: no_field_label.into(),
-            arguments: Vec::new(),
-        };
-        let err_label = "Err";
         let union_tags = UnionTags::for_result(subs, field_var, no_field_var);
         let result_var = synth_var(
             subs,
@@ -952,11 +1027,30 @@ fn initial_state(
                 TagExt::Any(Variable::EMPTY_TAG_UNION),
             )),
         );
-        let field_expr = Expr::Tag {
-            tag_union_var: result_var,
-            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
-            name: err_label.into(),
-            arguments: vec![(no_field_var, Loc::at_zero(no_field))],
+        let field_expr = match missing {
+            // Ok (Err Missing)
+            Some((field_var, err_missing)) => Expr::Tag {
+                tag_union_var: result_var,
+                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                name: "Ok".into(),
+                arguments: vec![(field_var, Loc::at_zero(err_missing))],
+            },
+            // Err NoField
+            None => {
+                let no_field = Expr::Tag {
+                    tag_union_var: no_field_var,
+                    ext_var: Variable::EMPTY_TAG_UNION,
+                    name: no_field_label.into(),
+                    arguments: Vec::new(),
+                };
+
+                Expr::Tag {
+                    tag_union_var: result_var,
+                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                    name: "Err".into(),
+                    arguments: vec![(no_field_var, Loc::at_zero(no_field))],
+                }
+            }
         };
         result_field_vars.push(result_var);
         let field = Field {
@@ -969,10 +1063,10 @@ fn initial_state(
     }
 
     let subs = &mut env.subs;
-    let record_field_iter = field_names
+    let record_field_iter = fields
         .iter()
         .zip(result_field_vars.iter())
-        .map(|(field_name, &var)| (field_name.clone(), RecordField::Required(var)));
+        .map(|((field_name, _), &var)| (field_name.clone(), RecordField::Required(var)));
     let flat_type = FlatType::Record(
         RecordFields::insert_into_subs(subs, record_field_iter),
         Variable::EMPTY_RECORD,
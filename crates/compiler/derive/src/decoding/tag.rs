@@ -0,0 +1,649 @@
+use roc_can::expr::{
+    AnnotatedMark, ClosureData, Expr, Field, Recursive, WhenBranch, WhenBranchPattern,
+};
+use roc_can::pattern::Pattern;
+use roc_collections::SendMap;
+use roc_module::called_via::CalledVia;
+use roc_module::ident::TagName;
+use roc_module::symbol::Symbol;
+use roc_region::all::{Loc, Region};
+use roc_types::subs::{
+    Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable, RecordFields,
+    RedundantMark, SubsSlice, TagExt, TupleElems, UnionLambdas, UnionTags, Variable,
+    VariableSubsSlice,
+};
+use roc_types::types::RecordField;
+
+use crate::synth_var;
+use crate::util::{Env, ExtensionKind};
+
+use super::wrap_in_decode_custom_decode_with;
+
+/// Implements decoding of a tag union. For example, for
+///
+/// ```text
+///   [Active, Suspended a]
+/// ```
+///
+/// we'd like to generate an impl like
+///
+/// ```roc
+/// decoder : Decoder [Active, Suspended a] fmt | a has Decoding, fmt has DecoderFormatting
+/// decoder =
+///     stepTag = \name ->
+///         when name is
+///             "Active" ->
+///                 Keep (Decode.custom \bytes, fmt ->
+///                     when Decode.decodeWith bytes Decode.decoder fmt is
+///                         rec ->
+///                             {
+///                                 rest: rec.rest,
+///                                 result: when rec.result is
+///                                     Ok args -> Ok Active
+///                                     Err err -> Err err
+///                             })
+///             "Suspended" ->
+///                 Keep (Decode.custom \bytes, fmt ->
+///                     when Decode.decodeWith bytes Decode.decoder fmt is
+///                         rec ->
+///                             {
+///                                 rest: rec.rest,
+///                                 result: when rec.result is
+///                                     Ok args -> Ok (Suspended args.0)
+///                                     Err err -> Err err
+///                             })
+///             _ -> Skip
+///
+///     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tag stepTag) fmt
+/// ```
+///
+/// The payload of each tag is decoded as a tuple of its arguments - `()` for tags without any -
+/// so that formats only have to know how to decode tuples to decode tags.
+pub(crate) fn decoder(
+    env: &mut Env,
+    _def_symbol: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Expr, Variable) {
+    // Generalized tag union var so we can reuse this impl between many unions:
+    // if tags = [ A arity=2, B arity=1 ], this is [ A t1 t2, B t3 ] for fresh t1, t2, t3
+    let flex_tag_labels = tags
+        .into_iter()
+        .map(|(label, arity)| {
+            let variables_slice = VariableSubsSlice::reserve_into_subs(env.subs, arity.into());
+            for var_index in variables_slice {
+                env.subs[var_index] = env.subs.fresh_unnamed_flex_var();
+            }
+            (label, variables_slice)
+        })
+        .collect::<Vec<_>>();
+    let union_tags = UnionTags::insert_slices_into_subs(env.subs, flex_tag_labels.clone());
+    let tag_union_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::TagUnion(
+            union_tags,
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        )),
+    );
+
+    // stepTag = ...
+    let (step_tag, step_var) = step_tag(env, tag_union_var, &flex_tag_labels);
+
+    // Build up the type of `Decode.tag` we expect
+    let tag_decoder_var = env.subs.fresh_unnamed_flex_var();
+    let decode_tag_lambda_set = env.subs.fresh_unnamed_flex_var();
+    let decode_tag_var = env.import_builtin_symbol_var(Symbol::DECODE_TAG);
+    let this_decode_tag_var = {
+        let flat_type = FlatType::Func(
+            SubsSlice::insert_into_subs(env.subs, [step_var]),
+            decode_tag_lambda_set,
+            tag_decoder_var,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    env.unify(decode_tag_var, this_decode_tag_var);
+
+    // Decode.tag stepTag
+    let call_decode_tag = Expr::Call(
+        Box::new((
+            this_decode_tag_var,
+            Loc::at_zero(Expr::AbilityMember(
+                Symbol::DECODE_TAG,
+                None,
+                this_decode_tag_var,
+            )),
+            decode_tag_lambda_set,
+            tag_decoder_var,
+        )),
+        vec![(step_var, Loc::at_zero(step_tag))],
+        CalledVia::Space,
+    );
+
+    let bytes_sym = env.new_symbol("bytes");
+    let fmt_sym = env.new_symbol("fmt");
+    let fmt_var = env.subs.fresh_unnamed_flex_var();
+
+    wrap_in_decode_custom_decode_with(
+        env,
+        bytes_sym,
+        (fmt_sym, fmt_var),
+        vec![],
+        (call_decode_tag, tag_decoder_var),
+    )
+}
+
+// Example:
+// stepTag = \name ->
+//     when name is
+//         "Suspended" ->
+//             Keep (Decode.custom \bytes, fmt ->
+//                 # Uses a single-branch `when` because `let` is more expensive to monomorphize
+//                 # due to checks for polymorphic expressions, and `rec` would be polymorphic.
+//                 when Decode.decodeWith bytes Decode.decoder fmt is
+//                     rec ->
+//                         {
+//                             rest: rec.rest,
+//                             result: when rec.result is
+//                                 Ok args -> Ok (Suspended args.0)
+//                                 Err err -> Err err
+//                         })
+//
+//         _ -> Skip
+fn step_tag(
+    env: &mut Env,
+    tag_union_var: Variable,
+    tags: &[(TagName, VariableSubsSlice)],
+) -> (Expr, Variable) {
+    let name_arg_symbol = env.new_symbol("name");
+
+    // Left generic; `Decode.decodeWith` fixes it to `DecodeError`.
+    let decode_err_var = env.subs.fresh_unnamed_flex_var();
+
+    // +1 because of the default branch.
+    let mut branches = Vec::with_capacity(tags.len() + 1);
+    let keep_payload_var = env.subs.fresh_unnamed_flex_var();
+    let keep_or_skip_var = {
+        let keep_payload_subs_slice = SubsSlice::insert_into_subs(env.subs, [keep_payload_var]);
+        let flat_type = FlatType::TagUnion(
+            UnionTags::insert_slices_into_subs(
+                env.subs,
+                [
+                    ("Keep".into(), keep_payload_subs_slice),
+                    ("Skip".into(), Default::default()),
+                ],
+            ),
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    for (tag_name, payload_vars) in tags {
+        let payload_vars: Vec<Variable> = env.subs.get_subs_slice(*payload_vars).to_vec();
+
+        let this_custom_callback_var;
+        let custom_callback_ret_var;
+        let custom_callback = {
+            // \bytes, fmt ->
+            //     when Decode.decodeWith bytes Decode.decoder fmt is
+            //         rec ->
+            //             {
+            //                 rest: rec.rest,
+            //                 result: when rec.result is
+            //                     Ok args -> Ok (Suspended args.0)
+            //                     Err err -> Err err
+            //             }
+            let bytes_arg_symbol = env.new_symbol("bytes");
+            let fmt_arg_symbol = env.new_symbol("fmt");
+            let bytes_arg_var = env.subs.fresh_unnamed_flex_var();
+            let fmt_arg_var = env.subs.fresh_unnamed_flex_var();
+
+            // The tag's arguments, decoded as a tuple, e.g. (a,) for `Suspended a`
+            let args_var = {
+                let elems = TupleElems::insert_into_subs(
+                    env.subs,
+                    payload_vars.iter().copied().enumerate(),
+                );
+
+                synth_var(
+                    env.subs,
+                    Content::Structure(FlatType::Tuple(elems, Variable::EMPTY_TUPLE)),
+                )
+            };
+
+            // rec.result : [Ok args_var, Err DecodeError]
+            let rec_dot_result = {
+                let tag_union = FlatType::TagUnion(
+                    UnionTags::for_result(env.subs, args_var, decode_err_var),
+                    TagExt::Any(Variable::EMPTY_TAG_UNION),
+                );
+
+                synth_var(env.subs, Content::Structure(tag_union))
+            };
+
+            // rec : { rest: List U8, result: (typeof rec.result) }
+            let rec_var = {
+                let fields = RecordFields::insert_into_subs(
+                    env.subs,
+                    [
+                        ("rest".into(), RecordField::Required(Variable::LIST_U8)),
+                        ("result".into(), RecordField::Required(rec_dot_result)),
+                    ],
+                );
+                let record = FlatType::Record(fields, Variable::EMPTY_RECORD);
+
+                synth_var(env.subs, Content::Structure(record))
+            };
+
+            // `Decode.decoder` for the tag's arguments
+            let decoder_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODER);
+            let decode_with_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODE_WITH);
+            let lambda_set_var = env.subs.fresh_unnamed_flex_var();
+            let this_decode_with_var = {
+                let subs_slice = SubsSlice::insert_into_subs(
+                    env.subs,
+                    [bytes_arg_var, decoder_var, fmt_arg_var],
+                );
+                let this_decode_with_var = synth_var(
+                    env.subs,
+                    Content::Structure(FlatType::Func(subs_slice, lambda_set_var, rec_var)),
+                );
+
+                env.unify(decode_with_var, this_decode_with_var);
+
+                this_decode_with_var
+            };
+
+            // The result of decoding the tag's arguments - either the tag, or a decoding error.
+            let when_expr_var = {
+                let flat_type = FlatType::TagUnion(
+                    UnionTags::for_result(env.subs, tag_union_var, decode_err_var),
+                    TagExt::Any(Variable::EMPTY_TAG_UNION),
+                );
+
+                synth_var(env.subs, Content::Structure(flat_type))
+            };
+
+            // What our decoder passed to `Decode.custom` returns - the result of decoding the
+            // tag, and the remaining bytes.
+            custom_callback_ret_var = {
+                let rest_field = RecordField::Required(Variable::LIST_U8);
+                let result_field = RecordField::Required(when_expr_var);
+                let flat_type = FlatType::Record(
+                    RecordFields::insert_into_subs(
+                        env.subs,
+                        [("rest".into(), rest_field), ("result".into(), result_field)],
+                    ),
+                    Variable::EMPTY_RECORD,
+                );
+
+                synth_var(env.subs, Content::Structure(flat_type))
+            };
+
+            let custom_callback_body = {
+                let rec_symbol = env.new_symbol("rec");
+
+                let branch_body = {
+                    let result_val = {
+                        // result: when rec.result is
+                        //     Ok args -> Ok (Suspended args.0)
+                        //     Err err -> Err err
+                        let ok_args_symbol = env.new_symbol("args");
+                        let err_val_symbol = env.new_symbol("err");
+                        let ok_branch_expr = {
+                            // Suspended args.0
+                            let arguments = payload_vars
+                                .iter()
+                                .enumerate()
+                                .map(|(index, &elem_var)| {
+                                    let elem_access = Expr::TupleAccess {
+                                        tuple_var: args_var,
+                                        ext_var: env.subs.fresh_unnamed_flex_var(),
+                                        elem_var,
+                                        loc_expr: Box::new(Loc::at_zero(Expr::Var(
+                                            ok_args_symbol,
+                                            args_var,
+                                        ))),
+                                        index,
+                                    };
+
+                                    (elem_var, Loc::at_zero(elem_access))
+                                })
+                                .collect();
+
+                            let tag = Expr::Tag {
+                                tag_union_var,
+                                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                                name: tag_name.clone(),
+                                arguments,
+                            };
+
+                            // Ok (Suspended args.0)
+                            Expr::Tag {
+                                tag_union_var: when_expr_var,
+                                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                                name: "Ok".into(),
+                                arguments: vec![(tag_union_var, Loc::at_zero(tag))],
+                            }
+                        };
+
+                        let branches = vec![
+                            // Ok args -> Ok (Suspended args.0)
+                            WhenBranch {
+                                patterns: vec![WhenBranchPattern {
+                                    pattern: Loc::at_zero(Pattern::AppliedTag {
+                                        whole_var: rec_dot_result,
+                                        ext_var: Variable::EMPTY_TAG_UNION,
+                                        tag_name: "Ok".into(),
+                                        arguments: vec![(
+                                            args_var,
+                                            Loc::at_zero(Pattern::Identifier(ok_args_symbol)),
+                                        )],
+                                    }),
+                                    degenerate: false,
+                                }],
+                                value: Loc::at_zero(ok_branch_expr),
+                                guard: None,
+                                redundant: RedundantMark::known_non_redundant(),
+                            },
+                            // Err err -> Err err
+                            WhenBranch {
+                                patterns: vec![WhenBranchPattern {
+                                    pattern: Loc::at_zero(Pattern::AppliedTag {
+                                        whole_var: rec_dot_result,
+                                        ext_var: Variable::EMPTY_TAG_UNION,
+                                        tag_name: "Err".into(),
+                                        arguments: vec![(
+                                            decode_err_var,
+                                            Loc::at_zero(Pattern::Identifier(err_val_symbol)),
+                                        )],
+                                    }),
+                                    degenerate: false,
+                                }],
+                                value: Loc::at_zero(Expr::Tag {
+                                    tag_union_var: when_expr_var,
+                                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                                    name: "Err".into(),
+                                    arguments: vec![(
+                                        decode_err_var,
+                                        Loc::at_zero(Expr::Var(err_val_symbol, decode_err_var)),
+                                    )],
+                                }),
+                                guard: None,
+                                redundant: RedundantMark::known_non_redundant(),
+                            },
+                        ];
+
+                        // when rec.result is
+                        //     Ok args -> Ok (Suspended args.0)
+                        //     Err err -> Err err
+                        Expr::When {
+                            loc_cond: Box::new(Loc::at_zero(Expr::RecordAccess {
+                                record_var: rec_var,
+                                ext_var: env.new_ext_var(ExtensionKind::Record),
+                                field_var: rec_dot_result,
+                                loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol, rec_var))),
+                                field: "result".into(),
+                            })),
+                            cond_var: rec_dot_result,
+                            expr_var: when_expr_var,
+                            region: Region::zero(),
+                            branches,
+                            branches_cond_var: rec_dot_result,
+                            exhaustive: ExhaustiveMark::known_exhaustive(),
+                        }
+                    };
+
+                    // {
+                    //     rest: rec.rest,
+                    //     result: when rec.result is
+                    //         Ok args -> Ok (Suspended args.0)
+                    //         Err err -> Err err
+                    // }
+                    let mut fields_map = SendMap::default();
+
+                    fields_map.insert(
+                        "rest".into(),
+                        Field {
+                            var: Variable::LIST_U8,
+                            region: Region::zero(),
+                            loc_expr: Box::new(Loc::at_zero(Expr::RecordAccess {
+                                record_var: rec_var,
+                                ext_var: env.new_ext_var(ExtensionKind::Record),
+                                field_var: Variable::LIST_U8,
+                                loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol, rec_var))),
+                                field: "rest".into(),
+                            })),
+                        },
+                    );
+
+                    fields_map.insert(
+                        "result".into(),
+                        Field {
+                            var: when_expr_var,
+                            region: Region::zero(),
+                            loc_expr: Box::new(Loc::at_zero(result_val)),
+                        },
+                    );
+
+                    Expr::Record {
+                        record_var: custom_callback_ret_var,
+                        fields: fields_map,
+                    }
+                };
+
+                let branch = WhenBranch {
+                    patterns: vec![WhenBranchPattern {
+                        pattern: Loc::at_zero(Pattern::Identifier(rec_symbol)),
+                        degenerate: false,
+                    }],
+                    value: Loc::at_zero(branch_body),
+                    guard: None,
+                    redundant: RedundantMark::known_non_redundant(),
+                };
+
+                let condition_expr = Expr::Call(
+                    Box::new((
+                        this_decode_with_var,
+                        Loc::at_zero(Expr::Var(Symbol::DECODE_DECODE_WITH, this_decode_with_var)),
+                        lambda_set_var,
+                        rec_var,
+                    )),
+                    vec![
+                        (
+                            Variable::LIST_U8,
+                            Loc::at_zero(Expr::Var(bytes_arg_symbol, Variable::LIST_U8)),
+                        ),
+                        (
+                            decoder_var,
+                            Loc::at_zero(Expr::AbilityMember(
+                                Symbol::DECODE_DECODER,
+                                None,
+                                decoder_var,
+                            )),
+                        ),
+                        (
+                            fmt_arg_var,
+                            Loc::at_zero(Expr::Var(fmt_arg_symbol, fmt_arg_var)),
+                        ),
+                    ],
+                    CalledVia::Space,
+                );
+
+                // when Decode.decodeWith bytes Decode.decoder fmt is
+                Expr::When {
+                    loc_cond: Box::new(Loc::at_zero(condition_expr)),
+                    cond_var: rec_var,
+                    expr_var: custom_callback_ret_var,
+                    region: Region::zero(),
+                    branches: vec![branch],
+                    branches_cond_var: rec_var,
+                    exhaustive: ExhaustiveMark::known_exhaustive(),
+                }
+            };
+
+            let custom_closure_symbol = env.new_symbol("customCallback");
+            this_custom_callback_var = env.subs.fresh_unnamed_flex_var();
+            let custom_callback_lambda_set_var = {
+                let content = Content::LambdaSet(LambdaSet {
+                    solved: UnionLambdas::tag_without_arguments(env.subs, custom_closure_symbol),
+                    recursion_var: OptVariable::NONE,
+                    unspecialized: Default::default(),
+                    ambient_function: this_custom_callback_var,
+                });
+                let custom_callback_lambda_set_var = synth_var(env.subs, content);
+                let subs_slice =
+                    SubsSlice::insert_into_subs(env.subs, [bytes_arg_var, fmt_arg_var]);
+
+                env.subs.set_content(
+                    this_custom_callback_var,
+                    Content::Structure(FlatType::Func(
+                        subs_slice,
+                        custom_callback_lambda_set_var,
+                        custom_callback_ret_var,
+                    )),
+                );
+
+                custom_callback_lambda_set_var
+            };
+
+            // \bytes, fmt -> …
+            Expr::Closure(ClosureData {
+                function_type: this_custom_callback_var,
+                closure_type: custom_callback_lambda_set_var,
+                return_type: custom_callback_ret_var,
+                name: custom_closure_symbol,
+                captured_symbols: Vec::new(),
+                recursive: Recursive::NotRecursive,
+                arguments: vec![
+                    (
+                        bytes_arg_var,
+                        AnnotatedMark::known_exhaustive(),
+                        Loc::at_zero(Pattern::Identifier(bytes_arg_symbol)),
+                    ),
+                    (
+                        fmt_arg_var,
+                        AnnotatedMark::known_exhaustive(),
+                        Loc::at_zero(Pattern::Identifier(fmt_arg_symbol)),
+                    ),
+                ],
+                loc_body: Box::new(Loc::at_zero(custom_callback_body)),
+            })
+        };
+
+        let decode_custom_ret_var = env.subs.fresh_unnamed_flex_var();
+        let decode_custom = {
+            let decode_custom_var = env.import_builtin_symbol_var(Symbol::DECODE_CUSTOM);
+            let decode_custom_closure_var = env.subs.fresh_unnamed_flex_var();
+            let this_decode_custom_var = {
+                let subs_slice = SubsSlice::insert_into_subs(env.subs, [this_custom_callback_var]);
+                let flat_type =
+                    FlatType::Func(subs_slice, decode_custom_closure_var, decode_custom_ret_var);
+
+                synth_var(env.subs, Content::Structure(flat_type))
+            };
+
+            env.unify(decode_custom_var, this_decode_custom_var);
+
+            // Decode.custom \bytes, fmt -> …
+            Expr::Call(
+                Box::new((
+                    this_decode_custom_var,
+                    Loc::at_zero(Expr::Var(Symbol::DECODE_CUSTOM, this_decode_custom_var)),
+                    decode_custom_closure_var,
+                    decode_custom_ret_var,
+                )),
+                vec![(this_custom_callback_var, Loc::at_zero(custom_callback))],
+                CalledVia::Space,
+            )
+        };
+
+        env.unify(keep_payload_var, decode_custom_ret_var);
+
+        // Keep (Decode.custom \bytes, fmt -> …)
+        let keep = Expr::Tag {
+            tag_union_var: keep_or_skip_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Keep".into(),
+            arguments: vec![(decode_custom_ret_var, Loc::at_zero(decode_custom))],
+        };
+
+        // "Suspended" -> Keep (Decode.custom \bytes, fmt -> …)
+        branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::StrLiteral(tag_name.0.as_str().into())),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(keep),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    // Example: `_ -> Skip`
+    branches.push(WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(Expr::Tag {
+            tag_union_var: keep_or_skip_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Skip".into(),
+            arguments: Vec::new(),
+        }),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    });
+
+    // when name is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(name_arg_symbol, Variable::STR))),
+        cond_var: Variable::STR,
+        expr_var: keep_or_skip_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: Variable::STR,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    let step_tag_closure = env.new_symbol("stepTag");
+    let function_type = env.subs.fresh_unnamed_flex_var();
+    let closure_type = {
+        let lambda_set = LambdaSet {
+            solved: UnionLambdas::tag_without_arguments(env.subs, step_tag_closure),
+            recursion_var: OptVariable::NONE,
+            unspecialized: Default::default(),
+            ambient_function: function_type,
+        };
+
+        synth_var(env.subs, Content::LambdaSet(lambda_set))
+    };
+
+    {
+        let args_slice = SubsSlice::insert_into_subs(env.subs, [Variable::STR]);
+
+        env.subs.set_content(
+            function_type,
+            Content::Structure(FlatType::Func(args_slice, closure_type, keep_or_skip_var)),
+        )
+    };
+
+    let expr = Expr::Closure(ClosureData {
+        function_type,
+        closure_type,
+        return_type: keep_or_skip_var,
+        name: step_tag_closure,
+        captured_symbols: Vec::new(),
+        recursive: Recursive::NotRecursive,
+        arguments: vec![(
+            Variable::STR,
+            AnnotatedMark::known_exhaustive(),
+            Loc::at_zero(Pattern::Identifier(name_arg_symbol)),
+        )],
+        loc_body: Box::new(Loc::at_zero(body)),
+    });
+
+    (expr, function_type)
+}
@@ -17,7 +17,7 @@ use roc_types::types::RecordField;
 use crate::synth_var;
 use crate::util::{Env, ExtensionKind};
 
-use super::wrap_in_decode_custom_decode_with;
+use super::{call_prepend_path_step, wrap_in_decode_custom_decode_with};
 
 /// Implements decoding of a tuple. For example, for
 ///
@@ -39,12 +39,12 @@ use super::wrap_in_decode_custom_decode_with;
 ///                 Next (Decode.custom \bytes, fmt ->
 ///                     when Decode.decodeWith bytes Decode.decoder fmt is
 ///                         {result, rest} ->
-///                             {result: Result.map result \val -> {state & e0: Ok val}, rest})
+///                             {result: Result.mapErr (Result.map result \val -> {state & e0: Ok val}) \err -> Decode.inElem err 0, rest})
 ///             1 ->
 ///                 Next (Decode.custom \bytes, fmt ->
 ///                     when Decode.decodeWith bytes Decode.decoder fmt is
 ///                         {result, rest} ->
-///                             {result: Result.map result \val -> {state & e1: Ok val}, rest})
+///                             {result: Result.mapErr (Result.map result \val -> {state & e1: Ok val}) \err -> Decode.inElem err 1, rest})
 ///             _ -> TooLong
 ///
 ///     finalizer = \st ->
@@ -52,8 +52,8 @@ use super::wrap_in_decode_custom_decode_with;
 ///             Ok e0 ->
 ///                 when st.e1 is
 ///                     Ok e1 -> Ok (e0, e1)
-///                     Err NoElem -> Err TooShort
-///             Err NoElem -> Err TooShort
+///                     Err NoElem -> Err (Decode.inElem TooShort 1)
+///             Err NoElem -> Err (Decode.inElem TooShort 0)
 ///
 ///     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tuple initialState stepElem finalizer) fmt
 /// ```
@@ -160,7 +160,7 @@ pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr,
 //                             rest: rec.rest,
 //                             result: when rec.result is
 //                                 Ok val -> Ok {state & e0: Ok val},
-//                                 Err err -> Err err
+//                                 Err err -> Err (Decode.inElem err 0)
 //                         })
 //
 //         "e1" ->
@@ -171,7 +171,7 @@ pub(crate) fn decoder(env: &mut Env, _def_symbol: Symbol, arity: u32) -> (Expr,
 //                             rest: rec.rest,
 //                             result: when rec.result is
 //                                 Ok val -> Ok {state & e1: Ok val},
-//                                 Err err -> Err err
+//                                 Err err -> Err (Decode.inElem err 1)
 //                         })
 //
 //         _ -> TooLong
@@ -220,7 +220,7 @@ fn step_elem(
         //                     rest: rec.rest,
         //                     result: when rec.result is
         //                         Ok val -> Ok {state & e0: Ok val},
-        //                         Err err -> Err err
+        //                         Err err -> Err (Decode.inElem err 0)
         //                 }
         //     )
 
@@ -234,7 +234,7 @@ fn step_elem(
             //                 rest: rec.rest,
             //                 result: when rec.result is
             //                     Ok val -> Ok {state & e0: Ok val},
-            //                     Err err -> Err err
+            //                     Err err -> Err (Decode.inElem err 0)
             //             }
             let bytes_arg_symbol = env.new_symbol("bytes");
             let fmt_arg_symbol = env.new_symbol("fmt");
@@ -321,15 +321,22 @@ fn step_elem(
                 //             rest: rec.rest,
                 //             result: when rec.result is
                 //                 Ok val -> Ok {state & e0: Ok val},
-                //                 Err err -> Err err
+                //                 Err err -> Err (Decode.inElem err 0)
                 //         }
                 let branch_body = {
                     let result_val = {
                         // result: when rec.result is
                         //     Ok val -> Ok {state & e0: Ok val},
-                        //     Err err -> Err err
+                        //     Err err -> Err (Decode.inElem err 0)
                         let ok_val_symbol = env.new_symbol("val");
                         let err_val_symbol = env.new_symbol("err");
+                        // Decode.inElem err 0
+                        let elem_err = call_prepend_path_step(
+                            env,
+                            Symbol::DECODE_IN_ELEM,
+                            (Expr::Var(err_val_symbol, decode_err_var), decode_err_var),
+                            (nat_literal(index), Variable::NAT),
+                        );
                         let ok_branch_expr = {
                             // Ok {state & e0: Ok val},
                             let mut updates = SendMap::default();
@@ -385,7 +392,7 @@ fn step_elem(
                                 guard: None,
                                 redundant: RedundantMark::known_non_redundant(),
                             },
-                            // Err err -> Err err
+                            // Err err -> Err (Decode.inElem err 0)
                             WhenBranch {
                                 patterns: vec![WhenBranchPattern {
                                     pattern: Loc::at_zero(Pattern::AppliedTag {
@@ -403,10 +410,7 @@ fn step_elem(
                                     tag_union_var: when_expr_var,
                                     ext_var: env.new_ext_var(ExtensionKind::TagUnion),
                                     name: "Err".into(),
-                                    arguments: vec![(
-                                        decode_err_var,
-                                        Loc::at_zero(Expr::Var(err_val_symbol, decode_err_var)),
-                                    )],
+                                    arguments: vec![(decode_err_var, Loc::at_zero(elem_err))],
                                 }),
                                 guard: None,
                                 redundant: RedundantMark::known_non_redundant(),
@@ -415,7 +419,7 @@ fn step_elem(
 
                         // when rec.result is
                         //     Ok val -> Ok {state & e0: Ok val},
-                        //     Err err -> Err err
+                        //     Err err -> Err (Decode.inElem err 0)
                         Expr::When {
                             loc_cond: Box::new(Loc::at_zero(Expr::RecordAccess {
                                 record_var: rec_var,
@@ -437,7 +441,7 @@ fn step_elem(
                     //     rest: rec.rest,
                     //     result: when rec.result is
                     //         Ok val -> Ok {state & e0: Ok val},
-                    //         Err err -> Err err
+                    //         Err err -> Err (Decode.inElem err 0)
                     // }
                     let mut fields_map = SendMap::default();
 
@@ -458,7 +462,7 @@ fn step_elem(
 
                     // result: when rec.result is
                     //     Ok val -> Ok {state & e0: Ok val},
-                    //     Err err -> Err err
+                    //     Err err -> Err (Decode.inElem err 0)
                     fields_map.insert(
                         "result".into(),
                         Field {
@@ -613,7 +617,7 @@ fn step_elem(
             //                 rest: rec.rest,
             //                 result: when rec.result is
             //                     Ok val -> Ok {state & e0: Ok val},
-            //                     Err err -> Err err
+            //                     Err err -> Err (Decode.inElem err 0)
             //             }
             // )
             Expr::Tag {
@@ -633,7 +637,7 @@ fn step_elem(
             //                     rest: rec.rest,
             //                     result: when rec.result is
             //                         Ok val -> Ok {state & e0: Ok val},
-            //                         Err err -> Err err
+            //                         Err err -> Err (Decode.inElem err 0)
             //                 }
             //     )
             WhenBranch {
@@ -738,8 +742,8 @@ fn step_elem(
 //         Ok e0 ->
 //             when rec.e1 is
 //                 Ok e1 -> Ok (e0, e1)
-//                 Err NoElem -> Err TooShort
-//         Err NoElem -> Err TooShort
+//                 Err NoElem -> Err (Decode.inElem TooShort 1)
+//         Err NoElem -> Err (Decode.inElem TooShort 0)
 fn finalizer(
     env: &mut Env,
     index_vars: &[Variable],
@@ -750,14 +754,8 @@ fn finalizer(
     let state_arg_symbol = env.new_symbol("stateRecord");
     let mut tuple_elems = Vec::with_capacity(index_vars.len());
     let mut pattern_symbols = Vec::with_capacity(index_vars.len());
-    let decode_err_var = {
-        let flat_type = FlatType::TagUnion(
-            UnionTags::tag_without_arguments(env.subs, "TooShort".into()),
-            TagExt::Any(Variable::EMPTY_TAG_UNION),
-        );
-
-        synth_var(env.subs, Content::Structure(flat_type))
-    };
+    // Fixed to `DecodeError` by the calls to `Decode.inElem` below.
+    let decode_err_var = env.subs.fresh_unnamed_flex_var();
 
     for (i, &index_var) in index_vars.iter().enumerate() {
         let symbol = env.new_symbol(i);
@@ -806,9 +804,10 @@ fn finalizer(
     //
     // when rec.e0 is
     //     Ok e0 -> ...happy path...
-    //     Err NoElem -> Err TooShort
-    for (((symbol, field), &index_var), &result_index_var) in pattern_symbols
+    //     Err NoElem -> Err (Decode.inElem TooShort 0)
+    for ((((index, symbol), field), &index_var), &result_index_var) in pattern_symbols
         .iter()
+        .enumerate()
         .zip(state_fields)
         .zip(index_vars)
         .zip(state_field_vars)
@@ -839,7 +838,21 @@ fn finalizer(
             redundant: RedundantMark::known_non_redundant(),
         };
 
-        // Example: `_ -> Err TooShort`
+        // Example: `_ -> Err (Decode.inElem TooShort 0)`
+        let missing_elem_err = call_prepend_path_step(
+            env,
+            Symbol::DECODE_IN_ELEM,
+            (
+                Expr::Tag {
+                    tag_union_var: decode_err_var,
+                    ext_var: Variable::EMPTY_TAG_UNION,
+                    name: "TooShort".into(),
+                    arguments: Vec::new(),
+                },
+                decode_err_var,
+            ),
+            (nat_literal(index), Variable::NAT),
+        );
         let err_branch = WhenBranch {
             patterns: vec![WhenBranchPattern {
                 pattern: Loc::at_zero(Pattern::Underscore),
@@ -849,15 +862,7 @@ fn finalizer(
                 tag_union_var: return_type_var,
                 ext_var: env.new_ext_var(ExtensionKind::TagUnion),
                 name: "Err".into(),
-                arguments: vec![(
-                    decode_err_var,
-                    Loc::at_zero(Expr::Tag {
-                        tag_union_var: decode_err_var,
-                        ext_var: Variable::EMPTY_TAG_UNION,
-                        name: "TooShort".into(),
-                        arguments: Vec::new(),
-                    }),
-                )],
+                arguments: vec![(decode_err_var, Loc::at_zero(missing_elem_err))],
             }),
             guard: None,
             redundant: RedundantMark::known_non_redundant(),
@@ -992,3 +997,13 @@ fn initial_state(
         },
     )
 }
+
+fn nat_literal(n: usize) -> Expr {
+    Expr::Int(
+        Variable::NAT,
+        Variable::NATURAL,
+        n.to_string().into_boxed_str(),
+        IntValue::I128((n as i128).to_ne_bytes()),
+        IntBound::Exact(IntLitWidth::Nat),
+    )
+}
@@ -0,0 +1,500 @@
+//! Derivers for the `Inspect` ability.
+
+use std::iter::once;
+
+use roc_can::expr::{
+    AnnotatedMark, ClosureData, Expr, Field, Recursive, WhenBranch, WhenBranchPattern,
+};
+use roc_can::pattern::Pattern;
+use roc_collections::SendMap;
+use roc_derive_key::inspect::FlatInspectableKey;
+use roc_module::called_via::CalledVia;
+use roc_module::ident::{Lowercase, TagName};
+use roc_module::symbol::Symbol;
+use roc_region::all::{Loc, Region};
+use roc_types::subs::{
+    Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable, RecordFields,
+    RedundantMark, SubsSlice, TagExt, TupleElems, UnionLambdas, UnionTags, Variable,
+    VariableSubsSlice,
+};
+use roc_types::types::RecordField;
+
+use crate::util::Env;
+use crate::{synth_var, DerivedBody};
+
+pub(crate) fn derive_inspect(
+    env: &mut Env<'_>,
+    key: FlatInspectableKey,
+    def_symbol: Symbol,
+) -> DerivedBody {
+    let (body_type, body) = match key {
+        FlatInspectableKey::Record(fields) => inspect_record(env, def_symbol, fields),
+        FlatInspectableKey::Tuple(arity) => inspect_tuple(env, def_symbol, arity),
+        FlatInspectableKey::TagUnion(tags) => inspect_tag_union(env, def_symbol, tags),
+    };
+
+    let specialization_lambda_sets =
+        env.get_specialization_lambda_sets(body_type, Symbol::INSPECT_INSPECT);
+
+    DerivedBody {
+        body,
+        body_type,
+        specialization_lambda_sets,
+    }
+}
+
+fn inspect_record(env: &mut Env<'_>, fn_name: Symbol, fields: Vec<Lowercase>) -> (Variable, Expr) {
+    // Suppose rcd = { f1, ..., fn }.
+    // Build a generalized type t_rcd = { f1: t1, ..., fn: tn }, with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many records of the same fields.
+    let (record_var, record_fields) = {
+        let flex_fields = fields
+            .into_iter()
+            .map(|name| {
+                (
+                    name,
+                    RecordField::Required(env.subs.fresh_unnamed_flex_var()),
+                )
+            })
+            .collect::<Vec<(Lowercase, _)>>();
+        let fields = RecordFields::insert_into_subs(env.subs, flex_fields);
+        let record_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Record(fields, Variable::EMPTY_RECORD)),
+        );
+
+        (record_var, fields)
+    };
+
+    // Now, an inspector for this record is
+    //
+    // inspect_rcd : { f1: t1, ..., fn: tn } -> Inspector
+    // inspect_rcd = \rcd ->
+    //   Inspect.record [
+    //      { key: "f1", value: Inspect.inspect rcd.f1 },
+    //      ...
+    //      { key: "fn", value: Inspect.inspect rcd.fn },
+    //   ]
+    let rcd_sym = env.new_symbol("rcd");
+
+    let entries = record_fields
+        .iter_all()
+        .map(|(field_name, field_var, _)| {
+            let field_name = env.subs[field_name].clone();
+            let field_var = env.subs[field_var];
+
+            // "f1"
+            let key = (Variable::STR, Expr::Str(field_name.as_str().into()));
+
+            // Inspect.inspect rcd.f1
+            let field_access = Expr::RecordAccess {
+                record_var,
+                field_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(
+                    rcd_sym,
+                    env.subs.fresh_unnamed_flex_var(),
+                ))),
+                field: field_name,
+            };
+            let value = call_inspect(env, (field_var, field_access));
+
+            // { key: "f1", value: Inspect.inspect rcd.f1 }
+            build_key_value_record(env, key, value)
+        })
+        .collect();
+
+    let entries = build_list(env, entries);
+    let body = call_inspect_builtin(env, Symbol::INSPECT_RECORD, vec![entries]);
+
+    // Finally, build the closure
+    // \rcd -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        (record_var, Pattern::Identifier(rcd_sym)),
+        body,
+    )
+}
+
+fn inspect_tuple(env: &mut Env<'_>, fn_name: Symbol, arity: u32) -> (Variable, Expr) {
+    // Suppose tup = (v1, ..., vn).
+    // Build a generalized type t_tup = (t1, ..., tn), with fresh t1, ..., tn,
+    // so that we can re-use the derived impl for many tuples of the same arity.
+    let (tuple_var, tuple_elems) = {
+        let flex_elems: Vec<_> = (0..arity)
+            .map(|i| (i as usize, env.subs.fresh_unnamed_flex_var()))
+            .collect();
+        let elems = TupleElems::insert_into_subs(env.subs, flex_elems);
+        let tuple_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Tuple(elems, Variable::EMPTY_TUPLE)),
+        );
+
+        (tuple_var, elems)
+    };
+
+    // Now, an inspector for this tuple is
+    //
+    // inspect_tup : (t1, ..., tn) -> Inspector
+    // inspect_tup = \tup ->
+    //   Inspect.tuple [Inspect.inspect tup.0, ..., Inspect.inspect tup.n]
+    let tup_sym = env.new_symbol("tup");
+
+    let elems = tuple_elems
+        .iter_all()
+        .map(|(elem_idx, elem_var)| {
+            let index = env.subs[elem_idx];
+            let elem_var = env.subs[elem_var];
+
+            let elem_access = Expr::TupleAccess {
+                tuple_var,
+                elem_var,
+                ext_var: env.subs.fresh_unnamed_flex_var(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Var(
+                    tup_sym,
+                    env.subs.fresh_unnamed_flex_var(),
+                ))),
+                index,
+            };
+
+            call_inspect(env, (elem_var, elem_access))
+        })
+        .collect();
+
+    let elems = build_list(env, elems);
+    let body = call_inspect_builtin(env, Symbol::INSPECT_TUPLE, vec![elems]);
+
+    // Finally, build the closure
+    // \tup -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        (tuple_var, Pattern::Identifier(tup_sym)),
+        body,
+    )
+}
+
+fn inspect_tag_union(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Variable, Expr) {
+    // Suppose tags = [ A p11 .. p1n, ..., Q pq1 .. pqm ]
+    // Build a generalized type t_tags = [ A t11 .. t1n, ..., Q tq1 .. tqm ],
+    // with fresh t1, ..., tqm, so that we can re-use the derived impl for many
+    // unions of the same tags and payloads.
+    let (union_var, union_tags) = {
+        let flex_tag_labels = tags
+            .into_iter()
+            .map(|(label, arity)| {
+                let variables_slice = VariableSubsSlice::reserve_into_subs(env.subs, arity.into());
+                for var_index in variables_slice {
+                    env.subs[var_index] = env.subs.fresh_unnamed_flex_var();
+                }
+                (label, variables_slice)
+            })
+            .collect::<Vec<_>>();
+        let union_tags = UnionTags::insert_slices_into_subs(env.subs, flex_tag_labels);
+        let tag_union_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::TagUnion(
+                union_tags,
+                TagExt::Any(Variable::EMPTY_TAG_UNION),
+            )),
+        );
+
+        (tag_union_var, union_tags)
+    };
+
+    // Now, an inspector for this tag union is
+    //
+    // inspect_union : [ A t11 .. t1n, ..., Q tq1 .. tqm ] -> Inspector
+    // inspect_union = \union ->
+    //   when union is
+    //      A x11 .. x1n -> Inspect.tag "A" [Inspect.inspect x11, ..., Inspect.inspect x1n]
+    //      ...
+    //      Q xq1 .. xqm -> Inspect.tag "Q" [Inspect.inspect xq1, ..., Inspect.inspect xqm]
+    let union_sym = env.new_symbol("union");
+
+    let whole_inspector_var = env.subs.fresh_unnamed_flex_var();
+    let branches = union_tags
+        .iter_all()
+        .map(|(tag, payloads)| {
+            // A
+            let tag_name = env.subs[tag].clone();
+            // t11 .. t1n
+            let payload_vars = env.subs.get_subs_slice(env.subs[payloads]).to_vec();
+            // x11 .. x1n
+            let payload_syms: Vec<_> = std::iter::repeat_with(|| env.unique_symbol())
+                .take(payload_vars.len())
+                .collect();
+
+            // `A x11 .. x1n` pattern
+            let pattern = Pattern::AppliedTag {
+                whole_var: union_var,
+                tag_name: tag_name.clone(),
+                ext_var: Variable::EMPTY_TAG_UNION,
+                // (t1, v1) (t2, v2)
+                arguments: (payload_vars.iter())
+                    .zip(payload_syms.iter())
+                    .map(|(var, sym)| (*var, Loc::at_zero(Pattern::Identifier(*sym))))
+                    .collect(),
+            };
+            let branch_pattern = WhenBranchPattern {
+                pattern: Loc::at_zero(pattern),
+                degenerate: false,
+            };
+
+            // [Inspect.inspect x11, ..., Inspect.inspect x1n]
+            let payloads = (payload_vars.into_iter())
+                .zip(payload_syms)
+                .map(|(payload_var, payload_sym)| {
+                    call_inspect(env, (payload_var, Expr::Var(payload_sym, payload_var)))
+                })
+                .collect();
+            let payloads = build_list(env, payloads);
+
+            // Inspect.tag "A" [..]
+            let name = (Variable::STR, Expr::Str(tag_name.0.as_str().into()));
+            let (body_var, body_expr) =
+                call_inspect_builtin(env, Symbol::INSPECT_TAG, vec![name, payloads]);
+
+            env.unify(whole_inspector_var, body_var);
+
+            WhenBranch {
+                patterns: vec![branch_pattern],
+                value: Loc::at_zero(body_expr),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            }
+        })
+        .collect();
+
+    // when union is
+    //   ...
+    let when_var = whole_inspector_var;
+    let when_expr = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(union_sym, union_var))),
+        cond_var: union_var,
+        expr_var: when_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: union_var,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    // Finally, build the closure
+    // \union -> body
+    build_outer_derived_closure(
+        env,
+        fn_name,
+        (union_var, Pattern::Identifier(union_sym)),
+        (when_var, when_expr),
+    )
+}
+
+/// Builds `Inspect.inspect val`.
+fn call_inspect(env: &mut Env<'_>, val: (Variable, Expr)) -> (Variable, Expr) {
+    let (in_val_var, in_val_expr) = val;
+
+    // val -[uls]-> Inspector | val has Inspect
+    let exposed_inspect_fn_var = env.import_builtin_symbol_var(Symbol::INSPECT_INSPECT);
+
+    // (typeof val) -[clos]-> inspector
+    let this_arguments_slice = VariableSubsSlice::insert_into_subs(env.subs, once(in_val_var));
+    let this_inspect_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_inspector_var = env.subs.fresh_unnamed_flex_var();
+    let this_inspect_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            this_arguments_slice,
+            this_inspect_clos_var,
+            this_inspector_var,
+        )),
+    );
+
+    //   val          -[uls]->  Inspector | val has Inspect
+    // ~ (typeof val) -[clos]-> inspector
+    env.unify(exposed_inspect_fn_var, this_inspect_fn_var);
+
+    // Inspect.inspect : (typeof val) -[clos]-> Inspector
+    let inspect_fn_head = Expr::AbilityMember(Symbol::INSPECT_INSPECT, None, this_inspect_fn_var);
+    let inspect_fn_data = Box::new((
+        this_inspect_fn_var,
+        Loc::at_zero(inspect_fn_head),
+        this_inspect_clos_var,
+        this_inspector_var,
+    ));
+
+    let call_inspect = Expr::Call(
+        inspect_fn_data,
+        vec![(in_val_var, Loc::at_zero(in_val_expr))],
+        CalledVia::Space,
+    );
+
+    (this_inspector_var, call_inspect)
+}
+
+/// Builds a call to one of the `Inspector` constructors in the `Inspect` module, like
+/// `Inspect.record` or `Inspect.tag`.
+fn call_inspect_builtin(
+    env: &mut Env<'_>,
+    builtin: Symbol,
+    arguments: Vec<(Variable, Expr)>,
+) -> (Variable, Expr) {
+    // e.g. List { key : Str, value : Inspector } -> Inspector
+    let exposed_fn_var = env.import_builtin_symbol_var(builtin);
+
+    // (typeof arg1), ..., (typeof argn) -[clos]-> inspector
+    let this_arguments_slice =
+        VariableSubsSlice::insert_into_subs(env.subs, arguments.iter().map(|(var, _)| *var));
+    let this_clos_var = env.subs.fresh_unnamed_flex_var();
+    let this_inspector_var = env.subs.fresh_unnamed_flex_var();
+    let this_fn_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Func(
+            this_arguments_slice,
+            this_clos_var,
+            this_inspector_var,
+        )),
+    );
+
+    env.unify(exposed_fn_var, this_fn_var);
+
+    let fn_data = Box::new((
+        this_fn_var,
+        Loc::at_zero(Expr::Var(builtin, this_fn_var)),
+        this_clos_var,
+        this_inspector_var,
+    ));
+
+    let arguments = arguments
+        .into_iter()
+        .map(|(var, expr)| (var, Loc::at_zero(expr)))
+        .collect();
+    let call = Expr::Call(fn_data, arguments, CalledVia::Space);
+
+    (this_inspector_var, call)
+}
+
+/// Builds `{ key: .., value: .. }`.
+fn build_key_value_record(
+    env: &mut Env<'_>,
+    key: (Variable, Expr),
+    value: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (key_var, key_expr) = key;
+    let (value_var, value_expr) = value;
+
+    let record_fields = RecordFields::insert_into_subs(
+        env.subs,
+        (once(("key".into(), RecordField::Required(key_var))))
+            .chain(once(("value".into(), RecordField::Required(value_var)))),
+    );
+    let record_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Record(record_fields, Variable::EMPTY_RECORD)),
+    );
+
+    let mut fields = SendMap::default();
+    fields.insert(
+        "key".into(),
+        Field {
+            var: key_var,
+            region: Region::zero(),
+            loc_expr: Box::new(Loc::at_zero(key_expr)),
+        },
+    );
+    fields.insert(
+        "value".into(),
+        Field {
+            var: value_var,
+            region: Region::zero(),
+            loc_expr: Box::new(Loc::at_zero(value_expr)),
+        },
+    );
+
+    (record_var, Expr::Record { record_var, fields })
+}
+
+/// Builds `[ e1, ..., en ]`, unifying the types of all elements.
+fn build_list(env: &mut Env<'_>, elems: Vec<(Variable, Expr)>) -> (Variable, Expr) {
+    let elem_var = env.subs.fresh_unnamed_flex_var();
+
+    let loc_elems = elems
+        .into_iter()
+        .map(|(var, expr)| {
+            env.unify(elem_var, var);
+            Loc::at_zero(expr)
+        })
+        .collect();
+
+    let elem_var_slice = VariableSubsSlice::insert_into_subs(env.subs, once(elem_var));
+    let list_var = synth_var(
+        env.subs,
+        Content::Structure(FlatType::Apply(Symbol::LIST_LIST, elem_var_slice)),
+    );
+
+    (
+        list_var,
+        Expr::List {
+            elem_var,
+            loc_elems,
+        },
+    )
+}
+
+fn build_outer_derived_closure(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    val: (Variable, Pattern),
+    body: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (val_var, val_pattern) = val;
+    let (body_var, body_expr) = body;
+
+    let (fn_var, fn_clos_var) = {
+        // Create fn_var for ambient capture; we fix it up below.
+        let fn_var = synth_var(env.subs, Content::Error);
+
+        // -[fn_name]->
+        let fn_captures = vec![];
+        let fn_name_labels = UnionLambdas::insert_into_subs(env.subs, once((fn_name, fn_captures)));
+        let fn_clos_var = synth_var(
+            env.subs,
+            Content::LambdaSet(LambdaSet {
+                solved: fn_name_labels,
+                recursion_var: OptVariable::NONE,
+                unspecialized: SubsSlice::default(),
+                ambient_function: fn_var,
+            }),
+        );
+
+        // val_var -[fn_name]-> body_var
+        let args_slice = SubsSlice::insert_into_subs(env.subs, once(val_var));
+        env.subs.set_content(
+            fn_var,
+            Content::Structure(FlatType::Func(args_slice, fn_clos_var, body_var)),
+        );
+
+        (fn_var, fn_clos_var)
+    };
+
+    let clos_expr = Expr::Closure(ClosureData {
+        function_type: fn_var,
+        closure_type: fn_clos_var,
+        return_type: body_var,
+        name: fn_name,
+        captured_symbols: vec![],
+        recursive: Recursive::NotRecursive,
+        arguments: vec![(
+            val_var,
+            AnnotatedMark::known_exhaustive(),
+            Loc::at_zero(val_pattern),
+        )],
+        loc_body: Box::new(Loc::at_zero(body_expr)),
+    });
+
+    (fn_var, clos_expr)
+}
@@ -16,9 +16,11 @@ use roc_types::subs::{
 };
 use util::Env;
 
+mod compare;
 mod decoding;
 mod encoding;
 mod hash;
+mod inspect;
 
 mod util;
 
@@ -79,6 +81,12 @@ fn build_derived_body(
             decoding::derive_decoder(&mut env, decoder_key, derived_symbol)
         }
         DeriveKey::Hash(hash_key) => hash::derive_hash(&mut env, hash_key, derived_symbol),
+        DeriveKey::Inspect(inspect_key) => {
+            inspect::derive_inspect(&mut env, inspect_key, derived_symbol)
+        }
+        DeriveKey::Compare(compare_key) => {
+            compare::derive_compare(&mut env, compare_key, derived_symbol)
+        }
     };
 
     let def = Def {
@@ -1,8 +1,11 @@
-use roc_module::{ident::Lowercase, symbol::Symbol};
-use roc_types::subs::{Content, FlatType, Subs, Variable};
+use roc_module::{
+    ident::{Lowercase, TagName},
+    symbol::Symbol,
+};
+use roc_types::subs::{Content, FlatType, GetSubsSlice, Subs, Variable};
 
 use crate::{
-    util::{check_derivable_ext_var, debug_name_record, debug_name_tuple},
+    util::{check_derivable_ext_var, debug_name_record, debug_name_tag, debug_name_tuple},
     DeriveError,
 };
 
@@ -17,16 +20,37 @@ pub enum FlatDecodableKey {
     List(/* takes one variable */),
 
     // Unfortunate that we must allocate here, c'est la vie
-    Record(Vec<Lowercase>),
+    Record(Vec<(Lowercase, DecodableField)>),
     Tuple(u32),
+    TagUnion(Vec<(TagName, u16)>),
+}
+
+/// What a derived record decoder does when a field is missing from the input.
+#[derive(Hash, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum DecodableField {
+    /// Decoding fails with `TooShort` at the field.
+    Required,
+    /// The field has type `Result a [Missing]`, and is decoded as `Err Missing`.
+    MaybeMissing,
 }
 
 impl FlatDecodableKey {
     pub(crate) fn debug_name(&self) -> String {
         match self {
             FlatDecodableKey::List() => "list".to_string(),
-            FlatDecodableKey::Record(fields) => debug_name_record(fields),
+            FlatDecodableKey::Record(fields) => {
+                let names: Vec<Lowercase> = fields
+                    .iter()
+                    .map(|(name, field)| match field {
+                        DecodableField::Required => name.clone(),
+                        DecodableField::MaybeMissing => format!("{name}?").into(),
+                    })
+                    .collect();
+
+                debug_name_record(&names)
+            }
             FlatDecodableKey::Tuple(arity) => debug_name_tuple(*arity),
+            FlatDecodableKey::TagUnion(tags) => debug_name_tag(tags),
         }
     }
 }
@@ -49,19 +73,29 @@ impl FlatDecodable {
                         matches!(ext, Content::Structure(FlatType::EmptyRecord))
                     })?;
 
-                    let mut field_names = Vec::with_capacity(fields.len());
+                    let mut decodable_fields = Vec::with_capacity(fields.len());
                     for (field_name, record_field) in fields_iter {
                         if record_field.is_optional() {
                             // Can't derive a concrete decoder for optional fields, since those are
-                            // compile-time-polymorphic
+                            // compile-time-polymorphic: a record value only has slots for its
+                            // required fields, so a decoded optional field could not be stored,
+                            // and its default only exists at the destructuring site.
+                            // Fields that may be missing are typed `Result a [Missing]` instead.
                             return Err(Underivable);
                         }
-                        field_names.push(field_name.clone());
+
+                        let field = if is_result_missing(subs, *record_field.as_inner()) {
+                            DecodableField::MaybeMissing
+                        } else {
+                            DecodableField::Required
+                        };
+
+                        decodable_fields.push((field_name.clone(), field));
                     }
 
-                    field_names.sort();
+                    decodable_fields.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
 
-                    Ok(Key(FlatDecodableKey::Record(field_names)))
+                    Ok(Key(FlatDecodableKey::Record(decodable_fields)))
                 }
                 FlatType::Tuple(elems, ext) => {
                     let (elems_iter, ext) = elems.sorted_iterator_and_ext(subs, ext);
@@ -72,17 +106,37 @@ impl FlatDecodable {
 
                     Ok(Key(FlatDecodableKey::Tuple(elems_iter.count() as _)))
                 }
-                FlatType::TagUnion(_tags, _ext) | FlatType::RecursiveTagUnion(_, _tags, _ext) => {
-                    Err(Underivable) // yet
+                FlatType::TagUnion(tags, ext) | FlatType::RecursiveTagUnion(_, tags, ext) => {
+                    // As with encoding, only the surface of the tag union matters; the payloads
+                    // are decoded with their own `Decoding` implementations, so the recursion var
+                    // can be ignored.
+                    let (tags_iter, ext) = tags.unsorted_tags_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext.var(), |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTagUnion))
+                    })?;
+
+                    let mut tag_names_and_payload_sizes: Vec<_> = tags_iter
+                        .tags
+                        .into_iter()
+                        .map(|(name, payload_slice)| (name.clone(), payload_slice.len() as _))
+                        .collect();
+
+                    tag_names_and_payload_sizes.sort_by(|(t1, _), (t2, _)| t1.cmp(t2));
+
+                    Ok(Key(FlatDecodableKey::TagUnion(tag_names_and_payload_sizes)))
                 }
-                FlatType::FunctionOrTagUnion(_name_index, _, _) => {
-                    Err(Underivable) // yet
+                FlatType::FunctionOrTagUnion(names_index, _, _) => {
+                    Ok(Key(FlatDecodableKey::TagUnion(
+                        subs.get_subs_slice(names_index)
+                            .iter()
+                            .map(|t| (t.clone(), 0))
+                            .collect(),
+                    )))
                 }
                 FlatType::EmptyRecord => Ok(Key(FlatDecodableKey::Record(vec![]))),
-                FlatType::EmptyTuple => todo!(),
-                FlatType::EmptyTagUnion => {
-                    Err(Underivable) // yet
-                }
+                FlatType::EmptyTuple => Ok(Key(FlatDecodableKey::Tuple(0))),
+                FlatType::EmptyTagUnion => Ok(Key(FlatDecodableKey::TagUnion(vec![]))),
                 //
                 FlatType::Func(..) => Err(Underivable),
             },
@@ -112,6 +166,37 @@ impl FlatDecodable {
     }
 }
 
+/// Whether `var` is `Result a [Missing]`, which lets a record field be missing from the input.
+fn is_result_missing(subs: &Subs, var: Variable) -> bool {
+    let real_var = match subs.get_content_without_compacting(var) {
+        Content::Alias(Symbol::RESULT_RESULT, _, real_var, _) => *real_var,
+        _ => return false,
+    };
+
+    let err_payload = match subs.get_content_without_compacting(real_var) {
+        Content::Structure(FlatType::TagUnion(tags, ext)) => {
+            let (tags, _) = tags.unsorted_tags_and_ext(subs, *ext);
+
+            tags.tags
+                .into_iter()
+                .find_map(|(name, payload)| match payload {
+                    [err] if name.0.as_str() == "Err" => Some(*err),
+                    _ => None,
+                })
+        }
+        _ => None,
+    };
+
+    match err_payload.map(|var| subs.get_content_without_compacting(var)) {
+        Some(Content::Structure(FlatType::TagUnion(tags, ext))) => {
+            let (tags, _) = tags.unsorted_tags_and_ext(subs, *ext);
+
+            matches!(tags.tags.as_slice(), [(name, [])] if name.0.as_str() == "Missing")
+        }
+        _ => false,
+    }
+}
+
 const fn from_builtin_symbol(symbol: Symbol) -> Option<Result<FlatDecodable, DeriveError>> {
     use FlatDecodable::*;
     match symbol {
@@ -115,7 +115,7 @@ impl FlatEncodable {
                     )))
                 }
                 FlatType::EmptyRecord => Ok(Key(FlatEncodableKey::Record(vec![]))),
-                FlatType::EmptyTuple => todo!(),
+                FlatType::EmptyTuple => Ok(Key(FlatEncodableKey::Tuple(0))),
                 FlatType::EmptyTagUnion => Ok(Key(FlatEncodableKey::TagUnion(vec![]))),
                 //
                 FlatType::Func(..) => Err(Underivable),
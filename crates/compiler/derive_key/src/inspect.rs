@@ -0,0 +1,170 @@
+use roc_module::{
+    ident::{Lowercase, TagName},
+    symbol::Symbol,
+};
+use roc_types::subs::{Content, FlatType, GetSubsSlice, Subs, Variable};
+
+use crate::{
+    util::{check_derivable_ext_var, debug_name_record, debug_name_tag, debug_name_tuple},
+    DeriveError,
+};
+
+#[derive(Hash)]
+pub enum FlatInspectable {
+    // `inspect` is always of form `val -> Inspector` where `Inspector` is concrete, so all
+    // immediates must have exactly one lambda set!
+    SingleLambdaSetImmediate(Symbol),
+    Key(FlatInspectableKey),
+}
+
+#[derive(Hash, PartialEq, Eq, Debug, Clone)]
+pub enum FlatInspectableKey {
+    Record(Vec<Lowercase>),
+    Tuple(u32),
+    TagUnion(Vec<(TagName, u16)>),
+}
+
+impl FlatInspectableKey {
+    pub(crate) fn debug_name(&self) -> String {
+        match self {
+            FlatInspectableKey::Record(fields) => debug_name_record(fields),
+            FlatInspectableKey::Tuple(arity) => debug_name_tuple(*arity),
+            FlatInspectableKey::TagUnion(tags) => debug_name_tag(tags),
+        }
+    }
+}
+
+impl FlatInspectable {
+    pub(crate) fn from_var(subs: &Subs, var: Variable) -> Result<FlatInspectable, DeriveError> {
+        use DeriveError::*;
+        use FlatInspectable::*;
+        match *subs.get_content_without_compacting(var) {
+            Content::Structure(flat_type) => match flat_type {
+                FlatType::Apply(sym, _) => match sym {
+                    Symbol::LIST_LIST => Ok(SingleLambdaSetImmediate(Symbol::INSPECT_LIST)),
+                    Symbol::STR_STR => Ok(SingleLambdaSetImmediate(Symbol::INSPECT_STR)),
+                    _ => Err(Underivable),
+                },
+                FlatType::Record(fields, ext) => {
+                    let (fields_iter, ext) = fields.unsorted_iterator_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext, |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyRecord))
+                    })?;
+
+                    let mut field_names = Vec::with_capacity(fields.len());
+                    for (field_name, record_field) in fields_iter {
+                        if record_field.is_optional() {
+                            // Optional fields are compile-time-polymorphic, so there is no
+                            // concrete value to inspect.
+                            return Err(Underivable);
+                        }
+                        field_names.push(field_name.clone());
+                    }
+
+                    field_names.sort();
+
+                    Ok(Key(FlatInspectableKey::Record(field_names)))
+                }
+                FlatType::Tuple(elems, ext) => {
+                    let (elems_iter, ext) = elems.sorted_iterator_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext, |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTuple))
+                    })?;
+
+                    Ok(Key(FlatInspectableKey::Tuple(elems_iter.count() as _)))
+                }
+                FlatType::TagUnion(tags, ext) | FlatType::RecursiveTagUnion(_, tags, ext) => {
+                    // As with hashing, only the surface of the tag union matters; payload types
+                    // are left generic for the monomorphizer to fill in.
+                    let (tags_iter, ext) = tags.unsorted_tags_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext.var(), |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTagUnion))
+                    })?;
+
+                    let mut tag_names_and_payload_sizes: Vec<_> = tags_iter
+                        .tags
+                        .into_iter()
+                        .map(|(name, payload_slice)| {
+                            let payload_size = payload_slice.len();
+                            (name.clone(), payload_size as _)
+                        })
+                        .collect();
+
+                    tag_names_and_payload_sizes.sort_by(|(t1, _), (t2, _)| t1.cmp(t2));
+
+                    Ok(Key(FlatInspectableKey::TagUnion(
+                        tag_names_and_payload_sizes,
+                    )))
+                }
+                FlatType::FunctionOrTagUnion(names_index, _, _) => {
+                    Ok(Key(FlatInspectableKey::TagUnion(
+                        subs.get_subs_slice(names_index)
+                            .iter()
+                            .map(|t| (t.clone(), 0))
+                            .collect(),
+                    )))
+                }
+                FlatType::EmptyRecord => Ok(Key(FlatInspectableKey::Record(vec![]))),
+                FlatType::EmptyTuple => Ok(Key(FlatInspectableKey::Tuple(0))),
+                FlatType::EmptyTagUnion => Ok(Key(FlatInspectableKey::TagUnion(vec![]))),
+                //
+                FlatType::Func(..) => Err(Underivable),
+            },
+            Content::Alias(sym, _, real_var, _) => {
+                match builtin_symbol_to_inspect_lambda(sym) {
+                    Some(lambda) => Ok(lambda),
+                    // Opaques that are not builtins have their own `inspect` implementation (or
+                    // are not inspectable at all), so by the time we get here this must be a
+                    // structural alias, which we see through.
+                    None => Self::from_var(subs, real_var),
+                }
+            }
+            // Every number is rendered the same way, so we don't need to pick a width here.
+            Content::RangedNumber(_) => Ok(SingleLambdaSetImmediate(Symbol::INSPECT_NUM)),
+            //
+            Content::RecursionVar { structure, .. } => Self::from_var(subs, structure),
+            //
+            Content::Error => Err(Underivable),
+            Content::FlexVar(_)
+            | Content::RigidVar(_)
+            | Content::FlexAbleVar(_, _)
+            | Content::RigidAbleVar(_, _) => Err(UnboundVar),
+            Content::LambdaSet(_) => Err(Underivable),
+        }
+    }
+
+    pub fn from_builtin_symbol(symbol: Symbol) -> Result<FlatInspectable, DeriveError> {
+        builtin_symbol_to_inspect_lambda(symbol).ok_or(DeriveError::Underivable)
+    }
+}
+
+const fn builtin_symbol_to_inspect_lambda(symbol: Symbol) -> Option<FlatInspectable> {
+    use FlatInspectable::*;
+    match symbol {
+        Symbol::BOOL_BOOL => Some(SingleLambdaSetImmediate(Symbol::INSPECT_BOOL)),
+        Symbol::DICT_DICT => Some(SingleLambdaSetImmediate(Symbol::INSPECT_DICT)),
+        Symbol::SET_SET => Some(SingleLambdaSetImmediate(Symbol::INSPECT_SET)),
+        // `Num.Num` is the opaque at the bottom of every number alias.
+        Symbol::NUM_NUM
+        | Symbol::NUM_INT
+        | Symbol::NUM_FRAC
+        | Symbol::NUM_U8
+        | Symbol::NUM_U16
+        | Symbol::NUM_U32
+        | Symbol::NUM_U64
+        | Symbol::NUM_U128
+        | Symbol::NUM_I8
+        | Symbol::NUM_I16
+        | Symbol::NUM_I32
+        | Symbol::NUM_I64
+        | Symbol::NUM_I128
+        | Symbol::NUM_NAT
+        | Symbol::NUM_F32
+        | Symbol::NUM_F64
+        | Symbol::NUM_DEC => Some(SingleLambdaSetImmediate(Symbol::INSPECT_NUM)),
+        _ => None,
+    }
+}
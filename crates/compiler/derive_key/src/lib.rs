@@ -9,18 +9,26 @@
 //!   between e.g. required and optional record fields.
 //! - `Decoding` is like encoding, but has some differences. For one, it *does* need to distinguish
 //!   between required and optional record fields.
+//! - `Inspect` is like encoding, in that it needs the surface representation of a type, but
+//!   always produces a concrete `Inspector` rather than something parameterized over a format.
+//! - `Compare` looks at surface representations too, since records are ordered by field name
+//!   and tag unions by tag name.
 //!
 //! For these reasons the content keying is based on a strategy as well, which are the variants of
 //! [`DeriveKey`].
 
+pub mod compare;
 pub mod decoding;
 pub mod encoding;
 pub mod hash;
+pub mod inspect;
 mod util;
 
+use compare::{FlatCompare, FlatCompareKey};
 use decoding::{FlatDecodable, FlatDecodableKey};
 use encoding::{FlatEncodable, FlatEncodableKey};
 use hash::{FlatHash, FlatHashKey};
+use inspect::{FlatInspectable, FlatInspectableKey};
 
 use roc_module::symbol::Symbol;
 use roc_types::subs::{Subs, Variable};
@@ -40,6 +48,8 @@ pub enum DeriveKey {
     ToEncoder(FlatEncodableKey),
     Decoder(FlatDecodableKey),
     Hash(FlatHashKey),
+    Inspect(FlatInspectableKey),
+    Compare(FlatCompareKey),
 }
 
 impl DeriveKey {
@@ -48,6 +58,8 @@ impl DeriveKey {
             DeriveKey::ToEncoder(key) => format!("toEncoder_{}", key.debug_name()),
             DeriveKey::Decoder(key) => format!("decoder_{}", key.debug_name()),
             DeriveKey::Hash(key) => format!("hash_{}", key.debug_name()),
+            DeriveKey::Inspect(key) => format!("inspect_{}", key.debug_name()),
+            DeriveKey::Compare(key) => format!("compare_{}", key.debug_name()),
         }
     }
 }
@@ -77,6 +89,8 @@ pub enum DeriveBuiltin {
     Decoder,
     Hash,
     IsEq,
+    Inspect,
+    Compare,
 }
 
 impl TryFrom<Symbol> for DeriveBuiltin {
@@ -88,6 +102,8 @@ impl TryFrom<Symbol> for DeriveBuiltin {
             Symbol::DECODE_DECODER => Ok(DeriveBuiltin::Decoder),
             Symbol::HASH_HASH => Ok(DeriveBuiltin::Hash),
             Symbol::BOOL_IS_EQ => Ok(DeriveBuiltin::IsEq),
+            Symbol::INSPECT_INSPECT => Ok(DeriveBuiltin::Inspect),
+            Symbol::COMPARE_COMPARE => Ok(DeriveBuiltin::Compare),
             _ => Err(value),
         }
     }
@@ -121,6 +137,18 @@ impl Derived {
                     Symbol::BOOL_STRUCTURAL_EQ,
                 ))
             }
+            DeriveBuiltin::Inspect => match inspect::FlatInspectable::from_var(subs, var)? {
+                FlatInspectable::SingleLambdaSetImmediate(imm) => {
+                    Ok(Derived::SingleLambdaSetImmediate(imm))
+                }
+                FlatInspectable::Key(repr) => Ok(Derived::Key(DeriveKey::Inspect(repr))),
+            },
+            DeriveBuiltin::Compare => match compare::FlatCompare::from_var(subs, var)? {
+                FlatCompare::SingleLambdaSetImmediate(imm) => {
+                    Ok(Derived::SingleLambdaSetImmediate(imm))
+                }
+                FlatCompare::Key(repr) => Ok(Derived::Key(DeriveKey::Compare(repr))),
+            },
         }
     }
 
@@ -151,6 +179,20 @@ impl Derived {
                     Symbol::BOOL_STRUCTURAL_EQ,
                 ))
             }
+            DeriveBuiltin::Inspect => {
+                match inspect::FlatInspectable::from_builtin_symbol(symbol)? {
+                    FlatInspectable::SingleLambdaSetImmediate(imm) => {
+                        Ok(Derived::SingleLambdaSetImmediate(imm))
+                    }
+                    FlatInspectable::Key(repr) => Ok(Derived::Key(DeriveKey::Inspect(repr))),
+                }
+            }
+            DeriveBuiltin::Compare => match compare::FlatCompare::from_builtin_symbol(symbol)? {
+                FlatCompare::SingleLambdaSetImmediate(imm) => {
+                    Ok(Derived::SingleLambdaSetImmediate(imm))
+                }
+                FlatCompare::Key(repr) => Ok(Derived::Key(DeriveKey::Compare(repr))),
+            },
         }
     }
 }
@@ -160,6 +160,14 @@ impl Error {
             Error::Unmatchable { .. } => Warning,
         }
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Error::Incomplete(..) => "non-exhaustive-pattern",
+            Error::Redundant { .. } => "redundant-pattern",
+            Error::Unmatchable { .. } => "unmatchable-pattern",
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -193,10 +193,35 @@ impl<'a> Formattable for TypeAnnotation<'a> {
 
         let self_is_multiline = self.is_multiline();
 
+        // Only these break up when they're too wide (and take care of that themselves);
+        // anything else keeps whatever is inside it from breaking.
+        if !matches!(
+            self,
+            SpaceBefore(..)
+                | SpaceAfter(..)
+                | Function(..)
+                | Record { .. }
+                | TagUnion { .. }
+                | Tuple { .. }
+        ) {
+            buf.take_may_break();
+        }
+
         match self {
             Function(args, ret) => {
                 let needs_parens = parens != Parens::NotNeeded;
 
+                // A function type that's too wide is only broken up where the caller allows
+                // it, which is when the caller has already put it on a line of its own
+                // (see `fmt_general_def`).
+                let may_break = buf.take_may_break();
+                let self_is_multiline = self_is_multiline
+                    || (may_break
+                        && !needs_parens
+                        && !buf.fits(indent, |buf| {
+                            self.format_with_options(buf, parens, newlines, indent)
+                        }));
+
                 buf.indent(indent);
 
                 if needs_parens {
@@ -213,12 +238,15 @@ impl<'a> Formattable for TypeAnnotation<'a> {
                         buf.newline();
                     }
 
-                    argument.value.format_with_options(
-                        buf,
-                        Parens::InFunctionType,
-                        Newlines::Yes,
-                        indent,
-                    );
+                    // with one argument per line, each one can break up further
+                    buf.allow_breaking_if(self_is_multiline, |buf| {
+                        argument.value.format_with_options(
+                            buf,
+                            Parens::InFunctionType,
+                            Newlines::Yes,
+                            indent,
+                        )
+                    });
 
                     if it.peek().is_some() {
                         buf.push_str(",");
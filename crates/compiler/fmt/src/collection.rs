@@ -34,7 +34,14 @@ pub fn fmt_collection<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
         Braces::Square => ']',
     };
 
-    if is_collection_multiline(&items) {
+    let may_break = buf.take_may_break();
+    let is_multiline = is_collection_multiline(&items)
+        || (may_break
+            && !buf.fits(indent, |buf| {
+                fmt_collection(buf, indent, braces, items, Newlines::No)
+            }));
+
+    if is_multiline {
         let braces_indent = indent;
         let item_indent = braces_indent + INDENT;
         if newline == Newlines::Yes {
@@ -86,7 +93,7 @@ pub fn fmt_collection<'a, 'buf, T: ExtractSpaces<'a> + Formattable>(
             }
 
             buf.indent(item_indent);
-            item.item.format(buf, item_indent);
+            buf.allow_breaking(|buf| item.item.format(buf, item_indent));
 
             buf.push(',');
 
@@ -272,14 +272,41 @@ fn fmt_general_def<L: Formattable>(
         } else {
             rhs.format_with_options(buf, Parens::NotNeeded, newlines, indent + INDENT);
         }
+    } else if is_function(rhs)
+        && !buf.fits(indent, |buf| {
+            buf.spaces(1);
+            buf.push_str(sep);
+            buf.spaces(1);
+            rhs.format_with_options(buf, Parens::NotNeeded, Newlines::No, indent);
+        })
+    {
+        // A function type that doesn't fit starts on its own line, with one argument per line:
+        //
+        //     foo :
+        //         Str,
+        //         Str
+        //         -> Str
+        buf.spaces(1);
+        buf.push_str(sep);
+        buf.newline();
+        buf.allow_breaking(|buf| {
+            rhs.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent + INDENT)
+        });
     } else {
         buf.spaces(1);
         buf.push_str(sep);
         buf.spaces(1);
-        rhs.format_with_options(buf, Parens::NotNeeded, Newlines::No, indent);
+        // a record or tag union that's too wide breaks up like a multiline one would
+        buf.allow_breaking(|buf| {
+            rhs.format_with_options(buf, Parens::NotNeeded, Newlines::No, indent)
+        });
     }
 }
 
+fn is_function(rhs: &TypeAnnotation) -> bool {
+    matches!(rhs, TypeAnnotation::Function(..))
+}
+
 fn should_outdent(mut rhs: &TypeAnnotation) -> bool {
     loop {
         match rhs {
@@ -320,7 +347,7 @@ fn fmt_dbg_in_def<'a>(
         indent
     };
 
-    condition.format(buf, return_indent);
+    buf.allow_breaking_if(is_multiline, |buf| condition.format(buf, return_indent));
 }
 
 fn fmt_expect<'a>(buf: &mut Buf, condition: &'a Loc<Expr<'a>>, is_multiline: bool, indent: u16) {
@@ -336,7 +363,7 @@ fn fmt_expect<'a>(buf: &mut Buf, condition: &'a Loc<Expr<'a>>, is_multiline: boo
         indent
     };
 
-    condition.format(buf, return_indent);
+    buf.allow_breaking_if(is_multiline, |buf| condition.format(buf, return_indent));
 }
 
 fn fmt_expect_fx<'a>(buf: &mut Buf, condition: &'a Loc<Expr<'a>>, is_multiline: bool, indent: u16) {
@@ -352,7 +379,7 @@ fn fmt_expect_fx<'a>(buf: &mut Buf, condition: &'a Loc<Expr<'a>>, is_multiline:
         indent
     };
 
-    condition.format(buf, return_indent);
+    buf.allow_breaking_if(is_multiline, |buf| condition.format(buf, return_indent));
 }
 
 pub fn fmt_value_def(buf: &mut Buf, def: &roc_parse::ast::ValueDef, indent: u16) {
@@ -372,21 +399,46 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
     buf.indent(indent);
     buf.push_str(" =");
 
-    if body.is_multiline() {
-        match body {
-            Expr::SpaceBefore(sub_def, spaces) => {
-                let should_outdent = match sub_def {
-                    Expr::Record { .. } | Expr::List { .. } => {
-                        let is_only_newlines = spaces.iter().all(|s| s.is_newline());
-                        is_only_newlines && sub_def.is_multiline()
+    // Whether the body ends up on the `=` line or on a line of its own, breaking it up
+    // doesn't change how the def around it is laid out.
+    buf.allow_breaking(|buf| {
+        if body.is_multiline() {
+            match body {
+                Expr::SpaceBefore(sub_def, spaces) => {
+                    let should_outdent = match sub_def {
+                        Expr::Record { .. } | Expr::List { .. } => {
+                            let is_only_newlines = spaces.iter().all(|s| s.is_newline());
+                            is_only_newlines && sub_def.is_multiline()
+                        }
+                        _ => false,
+                    };
+
+                    if should_outdent {
+                        buf.spaces(1);
+                        sub_def.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
+                    } else {
+                        body.format_with_options(
+                            buf,
+                            Parens::NotNeeded,
+                            Newlines::Yes,
+                            indent + INDENT,
+                        );
                     }
-                    _ => false,
-                };
-
-                if should_outdent {
-                    buf.spaces(1);
-                    sub_def.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
-                } else {
+                }
+                Expr::Defs(..) | Expr::BinOps(_, _) | Expr::Backpassing(..) => {
+                    // Binop chains always get a newline. Otherwise you can have things like:
+                    //
+                    //     something = foo
+                    //        |> bar baz
+                    //
+                    // By always inserting a newline, this becomes:
+                    //
+                    //     something =
+                    //         foo
+                    //         |> bar baz
+                    //
+                    // This makes it clear what the binop is applying to!
+                    buf.newline();
                     body.format_with_options(
                         buf,
                         Parens::NotNeeded,
@@ -394,36 +446,34 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
                         indent + INDENT,
                     );
                 }
+                Expr::When(..) | Expr::Str(StrLiteral::Block(_)) => {
+                    buf.ensure_ends_with_newline();
+                    body.format_with_options(
+                        buf,
+                        Parens::NotNeeded,
+                        Newlines::Yes,
+                        indent + INDENT,
+                    );
+                }
+                _ => {
+                    buf.spaces(1);
+                    body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
+                }
             }
-            Expr::Defs(..) | Expr::BinOps(_, _) | Expr::Backpassing(..) => {
-                // Binop chains always get a newline. Otherwise you can have things like:
-                //
-                //     something = foo
-                //        |> bar baz
-                //
-                // By always inserting a newline, this becomes:
-                //
-                //     something =
-                //         foo
-                //         |> bar baz
-                //
-                // This makes it clear what the binop is applying to!
-                buf.newline();
-                body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent + INDENT);
-            }
-            Expr::When(..) | Expr::Str(StrLiteral::Block(_)) => {
-                buf.ensure_ends_with_newline();
-                body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent + INDENT);
-            }
-            _ => {
+        } else if matches!(body, Expr::BinOps(..))
+            && !buf.fits(indent, |buf| {
                 buf.spaces(1);
                 body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
-            }
+            })
+        {
+            // A binop chain that doesn't fit gets the same layout as a multiline one.
+            buf.newline();
+            body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent + INDENT);
+        } else {
+            buf.spaces(1);
+            body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
         }
-    } else {
-        buf.spaces(1);
-        body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
-    }
+    });
 }
 
 impl<'a> Formattable for AbilityMember<'a> {
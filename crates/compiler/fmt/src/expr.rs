@@ -51,6 +51,8 @@ impl<'a> Formattable for Expr<'a> {
             // These expressions always have newlines
             Defs(_, _) | When(_, _) => true,
 
+            Malformed(raw) => raw.contains('\n'),
+
             List(items) => is_collection_multiline(items),
 
             Str(literal) => is_str_multiline(literal),
@@ -112,6 +114,23 @@ impl<'a> Formattable for Expr<'a> {
 
         let apply_needs_parens = parens == Parens::InApply;
 
+        // Only these break up when they're too wide (and take care of that themselves);
+        // anything else keeps whatever is inside it from breaking, unless it says otherwise.
+        if !matches!(
+            self,
+            SpaceBefore(..)
+                | SpaceAfter(..)
+                | Apply(..)
+                | BinOps(..)
+                | List(_)
+                | Tuple(_)
+                | Record(_)
+                | RecordUpdate { .. }
+                | RecordBuilder(_)
+        ) {
+            buf.take_may_break();
+        }
+
         match self {
             SpaceBefore(sub_expr, spaces) => {
                 format_spaces(buf, spaces, newlines, indent);
@@ -197,6 +216,52 @@ impl<'a> Formattable for Expr<'a> {
                 //
                 // assert!(loc_expr.extract_spaces().before.is_empty(), "{:#?}", self);
 
+                // An application that doesn't fit on one line either lets its last argument
+                // break (when that's a collection and everything before it fits), like so:
+                //
+                // ```
+                // foo bar [
+                //     1,
+                //     2,
+                // ]
+                // ```
+                //
+                // ...or else puts every argument on its own line. (Parenthesized, it would
+                // reparse as a multiline `ParensAround`, which is laid out differently.)
+                let may_break = buf.take_may_break();
+                let is_too_wide = may_break
+                    && !apply_needs_parens
+                    && !loc_args.is_empty()
+                    && !self.is_multiline()
+                    && !buf.fits(indent, |buf| {
+                        self.format_with_options(buf, parens, newlines, indent)
+                    });
+
+                let should_break_last_arg = is_too_wide
+                    && loc_args
+                        .last()
+                        .map(|a| is_breakable_collection(&a.value))
+                        .unwrap_or_default()
+                    && buf.fits(indent, |buf| {
+                        buf.indent(indent);
+                        if apply_needs_parens {
+                            buf.push('(');
+                        }
+                        loc_expr.format_with_options(buf, Parens::InApply, Newlines::Yes, indent);
+                        for loc_arg in except_last(loc_args) {
+                            buf.spaces(1);
+                            loc_arg.format_with_options(
+                                buf,
+                                Parens::InApply,
+                                Newlines::Yes,
+                                indent,
+                            );
+                        }
+                        // leave room for the opening brace of the last argument
+                        buf.spaces(1);
+                        buf.push('[');
+                    });
+
                 buf.indent(indent);
                 if apply_needs_parens && !loc_args.is_empty() {
                     buf.push('(');
@@ -237,7 +302,7 @@ impl<'a> Formattable for Expr<'a> {
                         })
                         .unwrap_or_default();
 
-                let needs_indent = !should_reflow_outdentable
+                let needs_indent = (!should_reflow_outdentable
                     && (!loc_expr.extract_spaces().after.is_empty()
                         || except_last(loc_args).any(|a| a.is_multiline())
                         || loc_args
@@ -247,7 +312,8 @@ impl<'a> Formattable for Expr<'a> {
                                     && (!a.extract_spaces().before.is_empty()
                                         || !is_outdentable(&a.value))
                             })
-                            .unwrap_or_default());
+                            .unwrap_or_default()))
+                    || (is_too_wide && !should_break_last_arg);
 
                 let arg_indent = if needs_indent {
                     indent + INDENT
@@ -270,7 +336,7 @@ impl<'a> Formattable for Expr<'a> {
                     buf.push(')');
                 }
 
-                for loc_arg in loc_args.iter() {
+                for (index, loc_arg) in loc_args.iter().enumerate() {
                     if should_reflow_outdentable {
                         buf.spaces(1);
 
@@ -289,21 +355,28 @@ impl<'a> Formattable for Expr<'a> {
                         let arg = loc_arg.extract_spaces();
                         fmt_spaces(buf, arg.before.iter(), arg_indent);
                         buf.ensure_ends_with_newline();
-                        arg.item.format_with_options(
-                            buf,
-                            Parens::InApply,
-                            Newlines::Yes,
-                            arg_indent,
-                        );
+                        // each argument has a line of its own, so it can break up further
+                        buf.allow_breaking(|buf| {
+                            arg.item.format_with_options(
+                                buf,
+                                Parens::InApply,
+                                Newlines::Yes,
+                                arg_indent,
+                            )
+                        });
                         fmt_spaces(buf, arg.after.iter(), arg_indent);
                     } else {
+                        let is_last = index + 1 == loc_args.len();
+
                         buf.spaces(1);
-                        loc_arg.format_with_options(
-                            buf,
-                            Parens::InApply,
-                            Newlines::Yes,
-                            arg_indent,
-                        );
+                        buf.allow_breaking_if(should_break_last_arg && is_last, |buf| {
+                            loc_arg.format_with_options(
+                                buf,
+                                Parens::InApply,
+                                Newlines::Yes,
+                                arg_indent,
+                            )
+                        });
                     }
                 }
 
@@ -406,19 +479,28 @@ impl<'a> Formattable for Expr<'a> {
 
                             buf.indent(indent);
 
-                            sub_expr.format_with_options(
-                                buf,
-                                Parens::NotNeeded,
-                                Newlines::Yes,
-                                indent,
-                            );
+                            buf.allow_breaking(|buf| {
+                                sub_expr.format_with_options(
+                                    buf,
+                                    Parens::NotNeeded,
+                                    Newlines::Yes,
+                                    indent,
+                                )
+                            });
                         }
                         _ => {
                             buf.ensure_ends_with_newline();
                             buf.indent(indent);
                             // Even if there were no defs, which theoretically should never happen,
                             // still print the return value.
-                            ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
+                            buf.allow_breaking(|buf| {
+                                ret.format_with_options(
+                                    buf,
+                                    Parens::NotNeeded,
+                                    Newlines::Yes,
+                                    indent,
+                                )
+                            });
                         }
                     }
                 }
@@ -502,6 +584,10 @@ impl<'a> Formattable for Expr<'a> {
                 buf.push_str(str)
             }
             MalformedClosure => {}
+            Malformed(raw) => {
+                buf.indent(indent);
+                buf.push_str_allow_spaces(raw)
+            }
             PrecedenceConflict { .. } => {}
             MultipleRecordBuilders { .. } => {}
             UnappliedRecordBuilder { .. } => {}
@@ -571,6 +657,15 @@ fn is_outdentable(expr: &Expr) -> bool {
     )
 }
 
+/// A non-empty list, tuple or record, which can be broken across lines on its own.
+fn is_breakable_collection(expr: &Expr) -> bool {
+    match expr {
+        Expr::List(items) | Expr::Tuple(items) => !items.is_empty(),
+        Expr::Record(fields) => !fields.is_empty(),
+        _ => false,
+    }
+}
+
 fn starts_with_newline(expr: &Expr) -> bool {
     use roc_parse::ast::Expr::*;
 
@@ -711,14 +806,24 @@ fn fmt_binops<'a>(
     part_of_multi_line_binops: bool,
     indent: u16,
 ) {
+    let may_break = buf.take_may_break();
     let is_multiline = part_of_multi_line_binops
         || loc_right_side.value.is_multiline()
-        || lefts.iter().any(|(expr, _)| expr.value.is_multiline());
+        || lefts.iter().any(|(expr, _)| expr.value.is_multiline())
+        // A chain that's too wide gets one operator per line, but only if it starts
+        // a line of its own; otherwise the operators would line up with the wrong thing.
+        || (may_break
+            && buf.is_at_line_start()
+            && !buf.fits(indent, |buf| {
+                fmt_binops(buf, lefts, loc_right_side, false, indent)
+            }));
 
     for (loc_left_side, loc_binop) in lefts {
         let binop = loc_binop.value;
 
-        loc_left_side.format_with_options(buf, Parens::InOperator, Newlines::No, indent);
+        buf.allow_breaking_if(is_multiline, |buf| {
+            loc_left_side.format_with_options(buf, Parens::InOperator, Newlines::No, indent)
+        });
 
         if is_multiline {
             buf.ensure_ends_with_newline();
@@ -732,7 +837,9 @@ fn fmt_binops<'a>(
         buf.spaces(1);
     }
 
-    loc_right_side.format_with_options(buf, Parens::InOperator, Newlines::Yes, indent);
+    buf.allow_breaking_if(is_multiline, |buf| {
+        loc_right_side.format_with_options(buf, Parens::InOperator, Newlines::Yes, indent)
+    });
 }
 
 fn format_spaces(buf: &mut Buf, spaces: &[CommentOrNewline], newlines: Newlines, indent: u16) {
@@ -919,12 +1026,14 @@ fn fmt_when<'a>(
                     buf.spaces(1);
                 }
 
-                nested.format_with_options(
-                    buf,
-                    Parens::NotNeeded,
-                    Newlines::Yes,
-                    indent + 2 * INDENT,
-                );
+                buf.allow_breaking_if(is_multiline_expr, |buf| {
+                    nested.format_with_options(
+                        buf,
+                        Parens::NotNeeded,
+                        Newlines::Yes,
+                        indent + 2 * INDENT,
+                    )
+                });
             }
             _ => {
                 if is_multiline_expr {
@@ -933,12 +1042,14 @@ fn fmt_when<'a>(
                     buf.spaces(1);
                 }
 
-                expr.format_with_options(
-                    buf,
-                    Parens::NotNeeded,
-                    Newlines::Yes,
-                    indent + 2 * INDENT,
-                );
+                buf.allow_breaking_if(is_multiline_expr, |buf| {
+                    expr.format_with_options(
+                        buf,
+                        Parens::NotNeeded,
+                        Newlines::Yes,
+                        indent + 2 * INDENT,
+                    )
+                });
             }
         }
 
@@ -965,12 +1076,12 @@ fn fmt_dbg<'a>(
         indent
     };
 
-    condition.format(buf, return_indent);
+    buf.allow_breaking_if(is_multiline, |buf| condition.format(buf, return_indent));
 
     // Always put a blank line after the `dbg` line(s)
     buf.ensure_ends_with_blank_line();
 
-    continuation.format(buf, indent);
+    buf.allow_breaking_if(is_multiline, |buf| continuation.format(buf, indent));
 }
 
 fn fmt_expect<'a>(
@@ -992,12 +1103,12 @@ fn fmt_expect<'a>(
         indent
     };
 
-    condition.format(buf, return_indent);
+    buf.allow_breaking_if(is_multiline, |buf| condition.format(buf, return_indent));
 
     // Always put a blank line after the `expect` line(s)
     buf.ensure_ends_with_blank_line();
 
-    continuation.format(buf, indent);
+    buf.allow_breaking_if(is_multiline, |buf| continuation.format(buf, indent));
 }
 
 fn fmt_if<'a>(
@@ -1102,7 +1213,7 @@ fn fmt_if<'a>(
 
                     match &expr_below {
                         Expr::SpaceAfter(expr_above, spaces_above) => {
-                            expr_above.format(buf, return_indent);
+                            buf.allow_breaking(|buf| expr_above.format(buf, return_indent));
 
                             // If any of the spaces is a newline, add a newline at the top.
                             // Otherwise leave it as just a comment.
@@ -1120,13 +1231,13 @@ fn fmt_if<'a>(
                         }
 
                         _ => {
-                            expr_below.format(buf, return_indent);
+                            buf.allow_breaking(|buf| expr_below.format(buf, return_indent));
                         }
                     }
                 }
                 _ => {
                     buf.newline();
-                    loc_then.format(buf, return_indent);
+                    buf.allow_breaking(|buf| loc_then.format(buf, return_indent));
                     buf.newline();
                 }
             }
@@ -1146,7 +1257,7 @@ fn fmt_if<'a>(
         buf.spaces(1);
     }
 
-    final_else.format(buf, return_indent);
+    buf.allow_breaking_if(is_multiline, |buf| final_else.format(buf, return_indent));
 }
 
 fn fmt_closure<'a>(
@@ -1235,14 +1346,23 @@ fn fmt_closure<'a>(
                     buf.spaces(1);
                     sub_expr.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
                 } else {
-                    loc_ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, body_indent);
+                    buf.allow_breaking(|buf| {
+                        loc_ret.format_with_options(
+                            buf,
+                            Parens::NotNeeded,
+                            Newlines::Yes,
+                            body_indent,
+                        )
+                    });
                 }
             }
             Record { .. } | List { .. } => {
                 loc_ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
             }
             _ => {
-                loc_ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, body_indent);
+                buf.allow_breaking(|buf| {
+                    loc_ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, body_indent)
+                });
             }
         }
     } else {
@@ -1325,7 +1445,9 @@ fn fmt_backpassing<'a>(
     };
 
     loc_body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, body_indent);
-    loc_ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent);
+    buf.allow_breaking_if(is_multiline, |buf| {
+        loc_ret.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent)
+    });
 }
 
 fn pattern_needs_parens_when_backpassing(pat: &Pattern) -> bool {
@@ -1352,6 +1474,7 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
 {
     let loc_fields = fields.items;
     let final_comments = fields.final_comments();
+    let may_break = buf.take_may_break();
     buf.indent(indent);
     if loc_fields.is_empty() && final_comments.iter().all(|c| c.is_newline()) && update.is_none() {
         buf.push_str("{}");
@@ -1372,7 +1495,12 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
         }
 
         let is_multiline = loc_fields.iter().any(|loc_field| loc_field.is_multiline())
-            || !final_comments.is_empty();
+            || !final_comments.is_empty()
+            || (may_break
+                && !buf.fits(indent, |buf| {
+                    fmt_fields_on_one_line(buf, loc_fields, indent);
+                    buf.push('}');
+                }));
 
         if is_multiline {
             let field_indent = indent + INDENT;
@@ -1401,7 +1529,9 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
                     }
                 }
 
-                format_field_multiline(buf, &field.value, field_indent, "");
+                buf.allow_breaking(|buf| {
+                    format_field_multiline(buf, &field.value, field_indent, "")
+                });
             }
 
             if count_leading_newlines(final_comments.iter()) > 1 {
@@ -1413,18 +1543,7 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
             buf.newline();
         } else {
             // is_multiline == false
-            buf.spaces(1);
-            let field_indent = indent;
-            let mut iter = loc_fields.iter().peekable();
-            while let Some(field) = iter.next() {
-                field.format_with_options(buf, Parens::NotNeeded, Newlines::No, field_indent);
-
-                if iter.peek().is_some() {
-                    buf.push_str(",");
-                    buf.spaces(1);
-                }
-            }
-            buf.spaces(1);
+            fmt_fields_on_one_line(buf, loc_fields, indent);
             // if we are here, that means that `final_comments` is empty, thus we don't have
             // to add a comment. Anyway, it is not possible to have a single line record with
             // a comment in it.
@@ -1436,6 +1555,24 @@ fn fmt_record_like<'a, Field, Format, ToSpaceBefore>(
     }
 }
 
+fn fmt_fields_on_one_line<Field: Formattable>(
+    buf: &mut Buf,
+    loc_fields: &[Loc<Field>],
+    indent: u16,
+) {
+    buf.spaces(1);
+    let mut iter = loc_fields.iter().peekable();
+    while let Some(field) = iter.next() {
+        field.format_with_options(buf, Parens::NotNeeded, Newlines::No, indent);
+
+        if iter.peek().is_some() {
+            buf.push_str(",");
+            buf.spaces(1);
+        }
+    }
+    buf.spaces(1);
+}
+
 fn format_assigned_field_multiline<T>(
     buf: &mut Buf,
     field: &AssignedField<T>,
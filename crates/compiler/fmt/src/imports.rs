@@ -0,0 +1,219 @@
+//! Organizing a module header's imports, for `roc format --organize-imports`.
+//!
+//! This sorts the header's `exposes` list and its imports, merges imports of the same module
+//! into one, and drops the imports that the compiler reported as unused. Comments and newlines
+//! stay attached to the entry they were written next to.
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_parse::ast::{Collection, Header, Module, Spaced};
+use roc_parse::header::{ExposedName, ImportsEntry};
+use roc_region::all::{Loc, Region};
+
+/// A copy of `module` with its exposes and imports organized.
+///
+/// `unused` are the regions of `Problem::UnusedModuleImport` and `Problem::UnusedImport`,
+/// which are the regions of whole import entries and of the names they expose, respectively.
+/// Those entries and names get dropped. Pass no regions to only sort and merge.
+pub fn organize_imports<'a>(arena: &'a Bump, module: &Module<'a>, unused: &[Region]) -> Module<'a> {
+    let header = match &module.header {
+        Header::Interface(header) => {
+            let mut header = header.clone();
+
+            header.exposes.item = sort_by_name(arena, header.exposes.item);
+            header.imports.item = organize(arena, header.imports.item, unused);
+
+            Header::Interface(header)
+        }
+        Header::Hosted(header) => {
+            let mut header = header.clone();
+
+            header.exposes.item = sort_by_name(arena, header.exposes.item);
+            header.imports.item = organize(arena, header.imports.item, unused);
+
+            Header::Hosted(header)
+        }
+        Header::App(header) => {
+            let mut header = header.clone();
+
+            if let Some(imports) = header.imports.as_mut() {
+                imports.item = organize(arena, imports.item, unused);
+            }
+
+            Header::App(header)
+        }
+        Header::Package(header) => {
+            let mut header = header.clone();
+
+            header.exposes.item = sort_by_name(arena, header.exposes.item);
+
+            Header::Package(header)
+        }
+        Header::Platform(header) => {
+            let mut header = header.clone();
+
+            header.exposes.item = sort_by_name(arena, header.exposes.item);
+            header.imports.item = organize(arena, header.imports.item, unused);
+
+            Header::Platform(header)
+        }
+    };
+
+    Module {
+        comments: module.comments,
+        header,
+    }
+}
+
+type Imports<'a> = Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>>;
+type Exposed<'a> = Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>;
+
+/// Modules are merged when they have the same key: the package shorthand (if any) and the
+/// module name. Sorting by it puts the imports from this package before the ones from others.
+fn import_key<'a>(entry: &ImportsEntry<'a>) -> Option<(Option<&'a str>, &'a str)> {
+    match entry {
+        ImportsEntry::Module(name, _) => Some((None, (*name).into())),
+        ImportsEntry::Package(shorthand, name, _) => Some((Some(*shorthand), (*name).into())),
+        ImportsEntry::IngestedFile(_, _) => None,
+    }
+}
+
+fn organize<'a>(arena: &'a Bump, imports: Imports<'a>, unused: &[Region]) -> Imports<'a> {
+    let mut modules: Vec<'a, (_, Loc<Spaced<'a, ImportsEntry<'a>>>)> = Vec::new_in(arena);
+    let mut ingested_files = Vec::new_in(arena);
+    let mut dropped_keys = Vec::new_in(arena);
+
+    for loc_entry in imports.items {
+        let entry = spaced_item(&loc_entry.value);
+
+        let key = match import_key(&entry) {
+            Some(key) => key,
+            None => {
+                // Ingested files define a value, so unused ones are reported as unused defs
+                // rather than imports. Their order can matter to the reader, so keep it.
+                ingested_files.push(*loc_entry);
+                continue;
+            }
+        };
+
+        // A module imported in several places is only reported once, at one of them.
+        if unused.contains(&loc_entry.region) {
+            dropped_keys.push(key);
+            continue;
+        }
+
+        match modules.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, merged)) => {
+                let combined = with_exposed(
+                    arena,
+                    &spaced_item(&merged.value),
+                    exposed_names(&spaced_item(&merged.value))
+                        .items
+                        .iter()
+                        .chain(exposed_names(&entry).items),
+                );
+
+                merged.value = replace_item(arena, &merged.value, combined);
+            }
+            None => modules.push((key, *loc_entry)),
+        }
+    }
+
+    modules.retain(|(key, _)| !dropped_keys.contains(key));
+    modules.sort_by_key(|(key, _)| *key);
+
+    let mut organized = Vec::with_capacity_in(modules.len() + ingested_files.len(), arena);
+
+    for (_, loc_entry) in modules {
+        let entry = spaced_item(&loc_entry.value);
+        let used = exposed_names(&entry)
+            .items
+            .iter()
+            .filter(|loc_name| !unused.contains(&loc_name.region));
+        let entry = with_exposed(arena, &entry, used);
+
+        organized.push(Loc::at(
+            loc_entry.region,
+            replace_item(arena, &loc_entry.value, entry),
+        ));
+    }
+
+    organized.extend(ingested_files);
+
+    imports.replace_items(organized.into_bump_slice())
+}
+
+fn exposed_names<'a>(entry: &ImportsEntry<'a>) -> Exposed<'a> {
+    match entry {
+        ImportsEntry::Module(_, exposed) | ImportsEntry::Package(_, _, exposed) => *exposed,
+        ImportsEntry::IngestedFile(_, _) => Collection::empty(),
+    }
+}
+
+/// `entry`, but exposing `names` (sorted, and without duplicates) instead of what it did.
+fn with_exposed<'a, 'b>(
+    arena: &'a Bump,
+    entry: &ImportsEntry<'a>,
+    names: impl Iterator<Item = &'b Loc<Spaced<'a, ExposedName<'a>>>>,
+) -> ImportsEntry<'a> {
+    let mut deduped: Vec<'a, Loc<Spaced<'a, ExposedName<'a>>>> = Vec::new_in(arena);
+
+    for loc_name in names {
+        let name = spaced_item(&loc_name.value);
+
+        if !deduped
+            .iter()
+            .any(|existing| spaced_item(&existing.value) == name)
+        {
+            deduped.push(*loc_name);
+        }
+    }
+
+    let exposed = sort_by_name(
+        arena,
+        exposed_names(entry).replace_items(deduped.into_bump_slice()),
+    );
+
+    match entry {
+        ImportsEntry::Module(name, _) => ImportsEntry::Module(*name, exposed),
+        ImportsEntry::Package(shorthand, name, _) => {
+            ImportsEntry::Package(*shorthand, *name, exposed)
+        }
+        ImportsEntry::IngestedFile(_, _) => *entry,
+    }
+}
+
+/// Sorts exposed names (or module names) alphabetically, which puts types before values.
+fn sort_by_name<'a, T>(
+    arena: &'a Bump,
+    names: Collection<'a, Loc<Spaced<'a, T>>>,
+) -> Collection<'a, Loc<Spaced<'a, T>>>
+where
+    T: Copy + Into<&'a str>,
+{
+    let mut sorted = Vec::with_capacity_in(names.len(), arena);
+
+    sorted.extend_from_slice(names.items);
+    sorted.sort_by_key(|loc_name| -> &'a str { spaced_item(&loc_name.value).into() });
+
+    names.replace_items(sorted.into_bump_slice())
+}
+
+fn spaced_item<'a, T: Copy>(spaced: &Spaced<'a, T>) -> T {
+    match spaced {
+        Spaced::Item(item) => *item,
+        Spaced::SpaceBefore(inner, _) | Spaced::SpaceAfter(inner, _) => spaced_item(inner),
+    }
+}
+
+/// `spaced` with its item replaced, keeping the spaces and comments around it.
+fn replace_item<'a, T: Copy>(arena: &'a Bump, spaced: &Spaced<'a, T>, item: T) -> Spaced<'a, T> {
+    match spaced {
+        Spaced::Item(_) => Spaced::Item(item),
+        Spaced::SpaceBefore(inner, spaces) => {
+            Spaced::SpaceBefore(arena.alloc(replace_item(arena, inner, item)), spaces)
+        }
+        Spaced::SpaceAfter(inner, spaces) => {
+            Spaced::SpaceAfter(arena.alloc(replace_item(arena, inner, item)), spaces)
+        }
+    }
+}
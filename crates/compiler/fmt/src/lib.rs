@@ -6,6 +6,7 @@ pub mod annotation;
 pub mod collection;
 pub mod def;
 pub mod expr;
+pub mod imports;
 pub mod module;
 pub mod pattern;
 pub mod spaces;
@@ -19,21 +20,103 @@ pub struct Ast<'a> {
     pub defs: roc_parse::ast::Defs<'a>,
 }
 
+/// The line width `roc format` aims to stay within, unless told otherwise.
+pub const DEFAULT_LINE_WIDTH: usize = 100;
+
 #[derive(Debug)]
 pub struct Buf<'a> {
+    arena: &'a Bump,
     text: String<'a>,
     spaces_to_flush: usize,
     newlines_to_flush: usize,
     beginning_of_line: bool,
+    max_width: Option<usize>,
+    /// Whether the next expression or type annotation may break up to fit in `max_width`.
+    may_break: bool,
 }
 
 impl<'a> Buf<'a> {
+    /// A buffer that never breaks lines on its own; only newlines that were
+    /// already in the source end up in the output.
     pub fn new_in(arena: &'a Bump) -> Buf<'a> {
         Buf {
+            arena,
             text: String::new_in(arena),
             spaces_to_flush: 0,
             newlines_to_flush: 0,
             beginning_of_line: true,
+            max_width: None,
+            may_break: false,
+        }
+    }
+
+    /// A buffer that additionally breaks expressions and type annotations across several
+    /// lines when they would otherwise run past `max_width` columns, wherever that's
+    /// allowed (see `allow_breaking`).
+    pub fn with_max_width(arena: &'a Bump, max_width: usize) -> Buf<'a> {
+        Buf {
+            max_width: Some(max_width),
+            ..Buf::new_in(arena)
+        }
+    }
+
+    /// Returns true if whatever `format` writes would fit on the current line
+    /// (given the current position, or `indent` if we're at the start of a line).
+    ///
+    /// `format` writes into a scratch buffer which never breaks lines, so any newline
+    /// in its output comes from the source and means the content does not fit.
+    pub fn fits(&self, indent: u16, format: impl FnOnce(&mut Buf<'a>)) -> bool {
+        let max_width = match self.max_width {
+            Some(max_width) => max_width,
+            None => return true,
+        };
+
+        let mut scratch = Buf::new_in(self.arena);
+        scratch.beginning_of_line = false;
+        format(&mut scratch);
+
+        let text = scratch.text.as_str();
+
+        !text.contains('\n') && self.column(indent) + text.chars().count() <= max_width
+    }
+
+    /// Formats the next expression or type annotation with `format`, letting it break across
+    /// lines if it doesn't fit.
+    ///
+    /// Only do this where the layout around it stays the same once it is multiline; otherwise,
+    /// formatting the output again would see a multiline child and lay out its parent
+    /// differently, and a single pass of the formatter would no longer be a fixed point.
+    pub fn allow_breaking(&mut self, format: impl FnOnce(&mut Buf<'a>)) {
+        self.allow_breaking_if(true, format)
+    }
+
+    pub fn allow_breaking_if(&mut self, allow: bool, format: impl FnOnce(&mut Buf<'a>)) {
+        self.may_break = allow;
+        format(self);
+        self.may_break = false;
+    }
+
+    /// Returns whether the expression or type annotation about to be formatted may break
+    /// across lines, and resets that, so anything inside it only breaks where it allows so.
+    pub fn take_may_break(&mut self) -> bool {
+        std::mem::take(&mut self.may_break)
+    }
+
+    /// Returns true if nothing but indentation has been written since the last newline.
+    pub fn is_at_line_start(&self) -> bool {
+        self.beginning_of_line || self.newlines_to_flush > 0 || self.text.is_empty()
+    }
+
+    /// The column the next character pushed to this buffer will end up in.
+    fn column(&self, indent: u16) -> usize {
+        if self.beginning_of_line {
+            indent as usize
+        } else if self.newlines_to_flush > 0 {
+            self.spaces_to_flush
+        } else {
+            let line_start = self.text.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+            self.text[line_start..].chars().count() + self.spaces_to_flush
         }
     }
 
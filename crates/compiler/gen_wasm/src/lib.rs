@@ -14,6 +14,7 @@ use bumpalo::collections::Vec;
 use bumpalo::{self, Bump};
 
 use roc_collections::all::{MutMap, MutSet};
+use roc_error_macros::internal_error;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::CodeGenHelp;
 use roc_mono::ir::{Proc, ProcLayout};
@@ -184,6 +185,14 @@ pub fn build_app_module<'a, 'r>(
     }
 
     let (module, called_fns) = backend.finalize();
+
+    // Catch backend bugs at compile time rather than when the module is run
+    if cfg!(debug_assertions) {
+        if let Err(e) = module.validate(env.arena) {
+            internal_error!("{}", e);
+        }
+    }
+
     let main_function_index =
         maybe_main_fn_index.expect("The app must expose at least one value to the host");
 
@@ -26,6 +26,8 @@ const MODULES: &[(ModuleId, &str)] = &[
     (ModuleId::DECODE, "Decode.roc"),
     (ModuleId::HASH, "Hash.roc"),
     (ModuleId::JSON, "Json.roc"),
+    (ModuleId::INSPECT, "Inspect.roc"),
+    (ModuleId::COMPARE, "Compare.roc"),
 ];
 
 fn main() {
@@ -107,6 +109,7 @@ fn write_types_for_module_real(module_id: ModuleId, filename: &str, output_path:
         &module.interns,
         &mut module.can_problems,
         &mut module.type_problems,
+        &roc_problem::Levels::default(),
     );
 
     if problems.errors + problems.warnings > 0 {
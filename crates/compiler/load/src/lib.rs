@@ -20,6 +20,7 @@ pub use roc_load_internal::docs;
 pub use roc_load_internal::file::{
     EntryPoint, ExecutionMode, ExpectMetadata, Expectations, ExposedToHost, LoadConfig, LoadResult,
     LoadStart, LoadedModule, LoadingProblem, MonomorphizedModule, Phase, Threading,
+    ToplevelExpects,
 };
 
 #[allow(clippy::too_many_arguments)]
@@ -65,6 +66,7 @@ pub fn load_single_threaded<'a>(
         render,
         palette,
         exec_mode,
+        false,
         roc_cache_dir,
     )
 }
@@ -204,6 +206,8 @@ const BOX: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Box.dat")) as &[_];
 const ENCODE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Encode.dat")) as &[_];
 const DECODE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Decode.dat")) as &[_];
 const HASH: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Hash.dat")) as &[_];
+const INSPECT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Inspect.dat")) as &[_];
+const COMPARE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Compare.dat")) as &[_];
 
 fn deserialize_help(bytes: &[u8]) -> TypeState {
     let (state, _offset) = TypeState::deserialize(bytes);
@@ -234,6 +238,9 @@ fn read_cached_types() -> MutMap<ModuleId, TypeState> {
         output.insert(ModuleId::DECODE, deserialize_help(DECODE));
 
         output.insert(ModuleId::HASH, deserialize_help(HASH));
+
+        output.insert(ModuleId::INSPECT, deserialize_help(INSPECT));
+        output.insert(ModuleId::COMPARE, deserialize_help(COMPARE));
     }
 
     output
@@ -1,12 +1,22 @@
 use crate::docs::DocEntry::DetachedDoc;
-use crate::docs::TypeAnnotation::{Apply, BoundVariable, Function, NoTypeAnn, Record, TagUnion};
+use crate::docs::TypeAnnotation::{
+    Apply, BoundVariable, Function, NoTypeAnn, Record, TagUnion, Where,
+};
+use bumpalo::Bump;
+use roc_can::abilities::AbilitiesStore;
+use roc_can::expr::Declarations;
 use roc_can::scope::Scope;
-use roc_collections::VecSet;
+use roc_collections::{VecMap, VecSet};
 use roc_module::ident::ModuleName;
-use roc_module::symbol::{IdentIds, ModuleId, ModuleIds, Symbol};
+use roc_module::symbol::{IdentIds, Interns, ModuleId, ModuleIds, Symbol};
 use roc_parse::ast::AssignedField;
 use roc_parse::ast::{self, ExtractSpaces, TypeHeader};
 use roc_parse::ast::{CommentOrNewline, TypeDef, ValueDef};
+use roc_parse::parser::Parser;
+use roc_parse::state::State;
+use roc_region::all::{Loc, Region};
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use roc_types::subs::Subs;
 
 // Documentation generation requirements
 
@@ -16,6 +26,10 @@ pub struct ModuleDocumentation {
     pub entries: Vec<DocEntry>,
     pub scope: Scope,
     pub exposed_symbols: VecSet<Symbol>,
+    /// The abilities that each opaque type in this module implements, whether through
+    /// its own implementations or derived ones. Only filled in when loading with
+    /// `ExecutionMode::Docs`, once the module is solved.
+    pub implementations: VecMap<Symbol, Vec<Symbol>>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,9 +45,11 @@ pub struct DocDef {
     pub type_vars: Vec<String>,
     pub type_annotation: TypeAnnotation,
     pub docs: Option<String>,
+    /// Where the def is in its module's source
+    pub region: Region,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TypeAnnotation {
     TagUnion {
         tags: Vec<Tag>,
@@ -57,11 +73,16 @@ pub enum TypeAnnotation {
     Ability {
         members: Vec<AbilityMember>,
     },
+    /// A type with a `has` clause, like `a -> U64 | a has Hash`
+    Where {
+        ann: Box<TypeAnnotation>,
+        able_variables: Vec<(String, Vec<TypeAnnotation>)>,
+    },
     Wildcard,
     NoTypeAnn,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RecordField {
     RecordField {
         name: String,
@@ -76,7 +97,7 @@ pub enum RecordField {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AbilityMember {
     pub name: String,
     pub type_annotation: TypeAnnotation,
@@ -84,7 +105,7 @@ pub struct AbilityMember {
     pub docs: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Tag {
     pub name: String,
     pub values: Vec<TypeAnnotation>,
@@ -115,6 +136,93 @@ pub fn generate_module_docs(
         scope,
         entries,
         exposed_symbols,
+        implementations: VecMap::default(),
+    }
+}
+
+/// Fill in the parts of `docs` that are only known once its module has been solved: the
+/// inferred types of the values that don't have an annotation, and the abilities that each
+/// opaque type implements. This only happens when loading for docs, since it needs its own
+/// `Interns` and pretty-prints types that nothing else looks at.
+pub(crate) fn add_solved_types(
+    docs: &mut ModuleDocumentation,
+    home: ModuleId,
+    decls: &Declarations,
+    subs: &mut Subs,
+    abilities_store: &AbilitiesStore,
+    interns: &Interns,
+) {
+    let exposed_symbols = &docs.exposed_symbols;
+
+    for entry in docs.entries.iter_mut() {
+        // Only exposed defs get rendered, so there's no need to infer the others' types.
+        let doc_def = match entry {
+            DocEntry::DocDef(doc_def)
+                if matches!(doc_def.type_annotation, NoTypeAnn)
+                    && exposed_symbols.contains(&doc_def.symbol) =>
+            {
+                doc_def
+            }
+            _ => continue,
+        };
+
+        // Types aren't declarations, so this skips opaque types and the aliases whose
+        // annotations were left out on purpose.
+        let opt_index = decls
+            .symbols
+            .iter()
+            .position(|loc_symbol| loc_symbol.value == doc_def.symbol);
+
+        if let Some(index) = opt_index {
+            let printed = name_and_print_var(
+                decls.variables[index],
+                subs,
+                home,
+                interns,
+                DebugPrint::NOTHING,
+            );
+
+            doc_def.type_annotation = printed_type_to_docs(&printed);
+        }
+    }
+
+    for (impl_key, _) in abilities_store.iter_declared_implementations() {
+        if impl_key.opaque.module_id() != home {
+            continue;
+        }
+
+        if let Some(member_def) = abilities_store.member_def(impl_key.ability_member) {
+            let abilities = docs
+                .implementations
+                .get_or_insert(impl_key.opaque, Vec::new);
+
+            if !abilities.contains(&member_def.parent_ability) {
+                abilities.push(member_def.parent_ability);
+            }
+        }
+    }
+
+    // The implementations are stored in a hash map, so sort them to keep the docs stable.
+    for (_, abilities) in docs.implementations.iter_mut() {
+        abilities.sort();
+    }
+}
+
+/// Types are printed the way they'd be written in an annotation, so parsing one back gives
+/// the same docs as if the value had been annotated with it. Types that only the compiler can
+/// name don't parse, and get no annotation in the docs.
+fn printed_type_to_docs(printed: &str) -> TypeAnnotation {
+    let arena = Bump::new();
+
+    match roc_parse::type_annotation::located(false).parse(
+        &arena,
+        State::new(printed.as_bytes()),
+        0,
+    ) {
+        Ok((_, loc_ann, state)) if state.pos().offset as usize == printed.trim_end().len() => {
+            type_to_docs(false, loc_ann.value)
+        }
+        _ => NoTypeAnn,
     }
 }
 
@@ -177,6 +285,7 @@ fn generate_entry_docs(
         scratchpad.extend(spaces_before);
 
         let docs = comments_or_new_lines_to_docs(&scratchpad);
+        let region = defs.regions[index];
 
         match either_index.split() {
             Err(value_index) => match &defs.value_defs[value_index.index()] {
@@ -186,6 +295,7 @@ fn generate_entry_docs(
                         if let Some(ident_id) = ident_ids.get_id(identifier) {
                             let name = identifier.to_string();
                             let doc_def = DocDef {
+                                region,
                                 name,
                                 symbol: Symbol::new(home, ident_id),
                                 type_annotation: type_to_docs(false, loc_ann.value),
@@ -206,6 +316,7 @@ fn generate_entry_docs(
                         // Check if this module exposes the def
                         if let Some(ident_id) = ident_ids.get_id(identifier) {
                             let doc_def = DocDef {
+                                region,
                                 name: identifier.to_string(),
                                 type_annotation: type_to_docs(false, ann_type.value),
                                 type_vars: Vec::new(),
@@ -217,8 +328,23 @@ fn generate_entry_docs(
                     }
                 }
 
-                ValueDef::Body(_, _) => {
-                    // TODO generate docs for un-annotated bodies
+                ValueDef::Body(loc_pattern, _) => {
+                    if let Pattern::Identifier(identifier) = loc_pattern.value {
+                        // Check if this module exposes the def
+                        if let Some(ident_id) = ident_ids.get_id(identifier) {
+                            // The type gets inferred once the module is solved;
+                            // see `add_solved_types`.
+                            let doc_def = DocDef {
+                                region,
+                                name: identifier.to_string(),
+                                type_annotation: NoTypeAnn,
+                                type_vars: Vec::new(),
+                                symbol: Symbol::new(home, ident_id),
+                                docs,
+                            };
+                            acc.push(DocEntry::DocDef(doc_def));
+                        }
+                    }
                 }
 
                 ValueDef::Dbg { .. } => {
@@ -259,6 +385,7 @@ fn generate_entry_docs(
 
                     let ident_id = ident_ids.get_id(name.value).unwrap();
                     let doc_def = DocDef {
+                        region,
                         name: name.value.to_string(),
                         type_annotation,
                         type_vars,
@@ -282,6 +409,7 @@ fn generate_entry_docs(
 
                     let ident_id = ident_ids.get_id(name.value).unwrap();
                     let doc_def = DocDef {
+                        region,
                         name: name.value.to_string(),
                         type_annotation: TypeAnnotation::NoTypeAnn,
                         type_vars,
@@ -322,6 +450,7 @@ fn generate_entry_docs(
 
                     let ident_id = ident_ids.get_id(name.value).unwrap();
                     let doc_def = DocDef {
+                        region,
                         name: name.value.to_string(),
                         type_annotation: TypeAnnotation::Ability { members },
                         symbol: Symbol::new(home, ident_id),
@@ -539,6 +668,10 @@ fn type_to_docs(in_func_type_ann: bool, type_annotation: ast::TypeAnnotation) ->
                 output: Box::new(type_to_docs(true, output_ann.value)),
             }
         }
+        ast::TypeAnnotation::Where(ann, has_clauses) => Where {
+            ann: Box::new(type_to_docs(in_func_type_ann, ann.value)),
+            able_variables: has_clauses_to_docs(has_clauses),
+        },
         ast::TypeAnnotation::Wildcard => TypeAnnotation::Wildcard,
         _ => NoTypeAnn,
     }
@@ -548,28 +681,30 @@ fn ability_member_type_to_docs(
     type_annotation: ast::TypeAnnotation,
 ) -> (TypeAnnotation, Vec<(String, Vec<TypeAnnotation>)>) {
     match type_annotation {
-        ast::TypeAnnotation::Where(ta, has_clauses) => {
-            let ta = type_to_docs(false, ta.value);
-            let has_clauses = has_clauses
-                .iter()
-                .map(|hc| {
-                    let ast::HasClause { var, abilities } = hc.value;
-                    (
-                        var.value.extract_spaces().item.to_string(),
-                        abilities
-                            .iter()
-                            .map(|ability| type_to_docs(false, ability.value))
-                            .collect(),
-                    )
-                })
-                .collect();
-
-            (ta, has_clauses)
-        }
+        ast::TypeAnnotation::Where(ta, has_clauses) => (
+            type_to_docs(false, ta.value),
+            has_clauses_to_docs(has_clauses),
+        ),
         _ => (type_to_docs(false, type_annotation), vec![]),
     }
 }
 
+fn has_clauses_to_docs(has_clauses: &[Loc<ast::HasClause>]) -> Vec<(String, Vec<TypeAnnotation>)> {
+    has_clauses
+        .iter()
+        .map(|hc| {
+            let ast::HasClause { var, abilities } = hc.value;
+            (
+                var.value.extract_spaces().item.to_string(),
+                abilities
+                    .iter()
+                    .map(|ability| type_to_docs(false, ability.value))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
 fn record_field_to_doc(
     in_func_ann: bool,
     field: ast::AssignedField<'_, ast::TypeAnnotation>,
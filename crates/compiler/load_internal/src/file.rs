@@ -49,7 +49,7 @@ use roc_parse::header::{
     ExposedName, ImportsEntry, PackageEntry, PackageHeader, PlatformHeader, To, TypedIdent,
 };
 use roc_parse::header::{HeaderType, PackageName};
-use roc_parse::module::module_defs;
+use roc_parse::module::{module_defs_with_recovery, SkippedDef};
 use roc_parse::parser::{FileError, Parser, SourceError, SyntaxError};
 use roc_problem::Severity;
 use roc_region::all::{LineInfo, Loc, Region};
@@ -105,11 +105,17 @@ pub struct LoadConfig {
     pub palette: Palette,
     pub threading: Threading,
     pub exec_mode: ExecutionMode,
+    /// Report the type of each `_` in expression position, instead of rejecting it as a
+    /// malformed identifier.
+    pub typed_holes: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum ExecutionMode {
     Check,
+    /// Like [`ExecutionMode::Check`], but also fills in the parts of the docs that are only
+    /// known once a module is solved, like the inferred types of unannotated values.
+    Docs,
     Executable,
     /// Like [`ExecutionMode::Executable`], but stops in the presence of type errors.
     ExecutableIfCheck,
@@ -124,7 +130,7 @@ impl ExecutionMode {
 
         match self {
             Executable => Phase::MakeSpecializations,
-            Check | ExecutableIfCheck | Test => Phase::SolveTypes,
+            Check | Docs | ExecutableIfCheck | Test => Phase::SolveTypes,
         }
     }
 
@@ -393,6 +399,7 @@ fn start_phase<'a>(
                     abilities_store,
                     skip_constraint_gen,
                     exposed_module_ids: state.exposed_modules,
+                    typed_holes: state.typed_holes,
                 }
             }
 
@@ -836,6 +843,8 @@ struct ParsedModule<'a> {
     exposed_ident_ids: IdentIds,
     exposed_imports: MutMap<Ident, (Symbol, Region)>,
     parsed_defs: Defs<'a>,
+    /// Top-level defs that had syntax errors, which the parser skipped over
+    syntax_errors: Vec<SkippedDef<'a>>,
     symbols_from_requires: Vec<(Loc<Symbol>, Loc<TypeAnnotation<'a>>)>,
     header_type: HeaderType<'a>,
     header_comments: &'a [CommentOrNewline<'a>],
@@ -1016,6 +1025,7 @@ struct State<'a> {
     pub render: RenderTarget,
     pub palette: Palette,
     pub exec_mode: ExecutionMode,
+    pub typed_holes: bool,
 
     /// All abilities across all modules.
     pub world_abilities: WorldAbilities,
@@ -1047,6 +1057,7 @@ impl<'a> State<'a> {
         palette: Palette,
         number_of_workers: usize,
         exec_mode: ExecutionMode,
+        typed_holes: bool,
     ) -> Self {
         let arc_shorthands = Arc::new(Mutex::new(MutMap::default()));
         let cache_dir = roc_packaging::cache::roc_cache_dir();
@@ -1081,6 +1092,7 @@ impl<'a> State<'a> {
             render,
             palette,
             exec_mode,
+            typed_holes,
             make_specializations_pass: MakeSpecializationsPass::Pass(1),
             world_abilities: Default::default(),
             layout_interner: GlobalLayoutInterner::with_capacity(128, target_info),
@@ -1225,6 +1237,7 @@ enum BuildTask<'a> {
         abilities_store: PendingAbilitiesStore,
         exposed_module_ids: &'a [ModuleId],
         skip_constraint_gen: bool,
+        typed_holes: bool,
     },
     Solve {
         module: Module,
@@ -1357,6 +1370,7 @@ pub fn load_and_typecheck_str<'a>(
         palette,
         threading,
         exec_mode: ExecutionMode::Check,
+        typed_holes: false,
     };
 
     match load(
@@ -1616,6 +1630,7 @@ pub fn load<'a>(
             load_config.render,
             load_config.palette,
             load_config.exec_mode,
+            load_config.typed_holes,
             roc_cache_dir,
         ),
         Threads::Many(threads) => load_multi_threaded(
@@ -1628,6 +1643,7 @@ pub fn load<'a>(
             load_config.palette,
             threads,
             load_config.exec_mode,
+            load_config.typed_holes,
             roc_cache_dir,
         ),
     }
@@ -1643,6 +1659,7 @@ pub fn load_single_threaded<'a>(
     render: RenderTarget,
     palette: Palette,
     exec_mode: ExecutionMode,
+    typed_holes: bool,
     roc_cache_dir: RocCacheDir<'_>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
@@ -1674,6 +1691,7 @@ pub fn load_single_threaded<'a>(
         palette,
         number_of_workers,
         exec_mode,
+        typed_holes,
     );
 
     // We'll add tasks to this, and then worker threads will take tasks from it.
@@ -1958,6 +1976,7 @@ fn load_multi_threaded<'a>(
     palette: Palette,
     available_threads: usize,
     exec_mode: ExecutionMode,
+    typed_holes: bool,
     roc_cache_dir: RocCacheDir<'_>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
@@ -2004,6 +2023,7 @@ fn load_multi_threaded<'a>(
         palette,
         num_workers,
         exec_mode,
+        typed_holes,
     );
 
     // an arena for every worker, stored in an arena-allocated bumpalo vec to make the lifetimes work
@@ -2615,6 +2635,8 @@ fn update<'a>(
                 extend_header_with_builtin(header, ModuleId::ENCODE);
                 extend_header_with_builtin(header, ModuleId::DECODE);
                 extend_header_with_builtin(header, ModuleId::HASH);
+                extend_header_with_builtin(header, ModuleId::INSPECT);
+                extend_header_with_builtin(header, ModuleId::COMPARE);
             }
 
             state
@@ -2657,12 +2679,34 @@ fn update<'a>(
 
             Ok(state)
         }
-        Parsed(parsed) => {
+        Parsed(mut parsed) => {
             state
                 .module_cache
                 .sources
                 .insert(parsed.module_id, (parsed.module_path.clone(), parsed.src));
 
+            if !parsed.syntax_errors.is_empty() {
+                let problems = parsed
+                    .syntax_errors
+                    .drain(..)
+                    .map(|skipped| {
+                        to_syntax_error_problem(
+                            parsed.module_id,
+                            parsed.module_path.clone(),
+                            parsed.src,
+                            skipped,
+                            state.render,
+                            &state.palette,
+                        )
+                    })
+                    .collect();
+
+                state
+                    .module_cache
+                    .can_problems
+                    .insert(parsed.module_id, problems);
+            }
+
             // If this was an app module, set the output path to be
             // the module's declared "name".
             //
@@ -2699,7 +2743,9 @@ fn update<'a>(
             state
                 .module_cache
                 .can_problems
-                .insert(module_id, canonicalization_problems);
+                .entry(module_id)
+                .or_default()
+                .extend(canonicalization_problems);
 
             if let Some(docs) = module_docs {
                 state.module_cache.documentation.insert(module_id, docs);
@@ -2734,7 +2780,7 @@ fn update<'a>(
             module_id,
             ident_ids,
             solved_module,
-            solved_subs,
+            mut solved_subs,
             decls,
             dep_idents,
             mut module_timing,
@@ -2745,6 +2791,30 @@ fn update<'a>(
             log!("solved types for {:?}", module_id);
             module_timing.end_time = Instant::now();
 
+            let opt_docs = match state.exec_mode {
+                ExecutionMode::Docs => state.module_cache.documentation.get_mut(&module_id),
+                _ => None,
+            };
+
+            if let Some(docs) = opt_docs {
+                let mut all_ident_ids = dep_idents.clone();
+                all_ident_ids.insert(module_id, ident_ids.clone());
+
+                let interns = Interns {
+                    module_ids: state.arc_modules.lock().clone().into_module_ids(),
+                    all_ident_ids,
+                };
+
+                crate::docs::add_solved_types(
+                    docs,
+                    module_id,
+                    &decls,
+                    solved_subs.inner_mut(),
+                    &abilities_store,
+                    &interns,
+                );
+            }
+
             state
                 .module_cache
                 .type_problems
@@ -3383,7 +3453,7 @@ fn finish_specialization<'a>(
                     platform_path,
                 })
             }
-            ExecutionMode::Check => unreachable!(),
+            ExecutionMode::Check | ExecutionMode::Docs => unreachable!(),
         }
     }?;
 
@@ -3846,6 +3916,8 @@ fn load_module<'a>(
         "Decode", ModuleId::DECODE
         "Hash", ModuleId::HASH
         "Json", ModuleId::JSON
+        "Inspect", ModuleId::INSPECT
+        "Compare", ModuleId::COMPARE
     }
 
     let (filename, opt_shorthand) = module_name_to_path(src_dir, &module_name, arc_shorthands);
@@ -5408,6 +5480,7 @@ fn canonicalize_and_constrain<'a>(
     parsed: ParsedModule<'a>,
     skip_constraint_gen: bool,
     exposed_module_ids: &[ModuleId],
+    typed_holes: bool,
 ) -> CanAndCon {
     let canonicalize_start = Instant::now();
 
@@ -5444,6 +5517,7 @@ fn canonicalize_and_constrain<'a>(
         exposed_symbols,
         &symbols_from_requires,
         &mut var_store,
+        typed_holes,
     );
     let mut types = Types::new();
 
@@ -5546,6 +5620,8 @@ fn canonicalize_and_constrain<'a>(
                         | ModuleId::DICT
                         | ModuleId::SET
                         | ModuleId::HASH
+                        | ModuleId::INSPECT
+                        | ModuleId::COMPARE
                 );
 
                 if !name.is_builtin() || should_include_builtin {
@@ -5594,14 +5670,7 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
     let parse_start = Instant::now();
     let source = header.parse_state.original_bytes();
     let parse_state = header.parse_state;
-    let mut parsed_defs = match module_defs().parse(arena, parse_state.clone(), 0) {
-        Ok((_, success, _state)) => success,
-        Err((_, fail)) => {
-            return Err(LoadingProblem::ParsingFailed(
-                fail.into_file_error(header.module_path, &parse_state),
-            ));
-        }
-    };
+    let (mut parsed_defs, syntax_errors) = module_defs_with_recovery(arena, parse_state);
     for value in header.defined_values.into_iter() {
         // TODO: should these have a region?
         parsed_defs.push_value_def(value, Region::zero(), &[], &[]);
@@ -5642,6 +5711,7 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
         exposed_ident_ids,
         exposed_imports,
         parsed_defs,
+        syntax_errors,
         symbols_from_requires,
         header_type,
         header_comments: header_docs,
@@ -6456,6 +6526,7 @@ fn run_task<'a>(
             abilities_store,
             skip_constraint_gen,
             exposed_module_ids,
+            typed_holes,
         } => {
             let can_and_con = canonicalize_and_constrain(
                 arena,
@@ -6467,6 +6538,7 @@ fn run_task<'a>(
                 parsed,
                 skip_constraint_gen,
                 exposed_module_ids,
+                typed_holes,
             );
 
             Ok(Msg::CanonicalizedAndConstrained(can_and_con))
@@ -6719,6 +6791,44 @@ fn to_parse_problem_report<'a>(
     buf
 }
 
+/// Syntax errors borrow from the arena, so the report for a top-level def that the parser
+/// skipped over is rendered right away, and then kept with the module's other problems.
+fn to_syntax_error_problem(
+    module_id: ModuleId,
+    filename: PathBuf,
+    src: &str,
+    skipped: SkippedDef<'_>,
+    render: RenderTarget,
+    palette: &Palette,
+) -> roc_problem::can::Problem {
+    use roc_reporting::report::{parse_problem, RocDocAllocator};
+
+    let src_lines = src.lines().collect::<Vec<_>>();
+    let interns = Interns::default();
+    let alloc = RocDocAllocator::new(&src_lines, module_id, &interns);
+    let lines = LineInfo::new(src);
+
+    let problem = FileError {
+        problem: SourceError {
+            problem: skipped.error,
+            bytes: src.as_bytes(),
+        },
+        filename: filename.clone(),
+    };
+    let mut report = parse_problem(&alloc, &lines, filename, 0, problem);
+
+    // The title is rendered later on, along with the titles of the other problems
+    let title = std::mem::take(&mut report.title);
+    let mut rendered = String::new();
+    report.render(render, &mut rendered, &alloc, palette);
+
+    roc_problem::can::Problem::SyntaxError {
+        region: skipped.region,
+        title,
+        rendered,
+    }
+}
+
 fn to_missing_platform_report(module_id: ModuleId, other: &PlatformPath) -> String {
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
@@ -24,4 +24,6 @@ pub const BUILTIN_MODULES: &[(ModuleId, &str)] = &[
     (ModuleId::DECODE, "Decode"),
     (ModuleId::HASH, "Hash"),
     (ModuleId::JSON, "Json"),
+    (ModuleId::INSPECT, "Inspect"),
+    (ModuleId::COMPARE, "Compare"),
 ];
@@ -54,6 +54,7 @@ fn load_and_typecheck(
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Check,
+        typed_holes: false,
     };
 
     match roc_load_internal::file::load(
@@ -632,13 +633,92 @@ fn parse_problem() {
 
                     Note: When I get stuck like this, it usually means that there is a
                     missing parenthesis or bracket somewhere earlier. It could also be a
-                    stray keyword or operator."
+                    stray keyword or operator.
+                    "
             )
         ),
         Ok(_) => unreachable!("we expect failure here"),
     }
 }
 
+#[test]
+fn parse_problems_in_several_defs() {
+    let modules = vec![(
+        "Main",
+        indoc!(
+            r#"
+                interface Main exposes [main, other, total] imports []
+
+                main : List U8
+                main = [
+
+                other = [1, 2
+
+                total = List.len main + List.len other
+                "#
+        ),
+    )];
+
+    match multiple_modules("parse_problems_in_several_defs", modules) {
+        Err(report) => {
+            // Both broken defs are reported, and the def after them is still checked
+            // without complaining that they don't exist.
+            assert_eq!(
+                report.matches("── UNFINISHED LIST").count(),
+                2,
+                "{}",
+                report
+            );
+            assert!(!report.contains("UNRECOGNIZED NAME"), "{}", report);
+        }
+        Ok(_) => unreachable!("we expect failure here"),
+    }
+}
+
+#[test]
+fn parse_problems_around_multiline_string() {
+    let modules = vec![(
+        "Main",
+        indoc!(
+            r#"
+                interface Main exposes [main, usage, other] imports []
+
+                main = [
+
+                usage =
+                    """
+                Usage: main [options]
+                    """
+
+                other = [1, 2
+                "#
+        ),
+    )];
+
+    match multiple_modules("parse_problems_around_multiline_string", modules) {
+        Err(report) => {
+            // The line in the string that starts at column 0 isn't mistaken for a def,
+            // so the def with the string in it is fine, and only the two lists are reported.
+            assert_eq!(
+                report.matches("── UNFINISHED LIST").count(),
+                2,
+                "{}",
+                report
+            );
+            assert_eq!(
+                report
+                    .lines()
+                    .filter(|line| line.starts_with("── "))
+                    .count(),
+                2,
+                "{}",
+                report
+            );
+        }
+        Ok(_) => unreachable!("we expect failure here"),
+    }
+}
+
 #[test]
 #[should_panic(expected = "FILE NOT FOUND")]
 fn file_not_found() {
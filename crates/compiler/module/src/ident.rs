@@ -114,6 +114,8 @@ impl ModuleName {
     pub const DECODE: &'static str = "Decode";
     pub const HASH: &'static str = "Hash";
     pub const JSON: &'static str = "Json";
+    pub const INSPECT: &'static str = "Inspect";
+    pub const COMPARE: &'static str = "Compare";
 
     pub fn as_str(&self) -> &str {
         self.0.as_str()
@@ -53,6 +53,8 @@ pub const DERIVABLE_ABILITIES: &[(Symbol, &[Symbol])] = &[
     (Symbol::DECODE_DECODING, &[Symbol::DECODE_DECODER]),
     (Symbol::HASH_HASH_ABILITY, &[Symbol::HASH_HASH]),
     (Symbol::BOOL_EQ, &[Symbol::BOOL_IS_EQ]),
+    (Symbol::INSPECT_INSPECT_ABILITY, &[Symbol::INSPECT_INSPECT]),
+    (Symbol::COMPARE_COMPARE_ABILITY, &[Symbol::COMPARE_COMPARE]),
 ];
 
 /// In Debug builds only, Symbol has a name() method that lets
@@ -958,6 +960,16 @@ macro_rules! define_builtins {
                 )*
             )+
 
+            /// The name of each builtin module, along with the names of the symbols it exposes,
+            /// e.g. `("Str", &["Str", "Utf8Problem", ..., "isEmpty", ...])`.
+            ///
+            /// Names starting with `#` are compiler-internal, and can't be referred to from Roc code.
+            pub const BUILTIN_MODULE_IDENTS: &'static [(&'static str, &'static [&'static str])] = &[
+                $(
+                    ($module_name, &[ $($ident_name),* ]),
+                )+
+            ];
+
             /// The default `Apply` types that should be in scope,
             /// and what symbols they should resolve to.
             ///
@@ -1543,11 +1555,16 @@ define_builtins! {
         21 DECODE_LIST: "list"
         22 DECODE_RECORD: "record"
         23 DECODE_TUPLE: "tuple"
-        24 DECODE_CUSTOM: "custom"
-        25 DECODE_DECODE_WITH: "decodeWith"
-        26 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
-        27 DECODE_FROM_BYTES: "fromBytes"
-        28 DECODE_MAP_RESULT: "mapResult"
+        24 DECODE_TAG: "tag"
+        25 DECODE_CUSTOM: "custom"
+        26 DECODE_DECODE_WITH: "decodeWith"
+        27 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
+        28 DECODE_FROM_BYTES: "fromBytes"
+        29 DECODE_MAP_RESULT: "mapResult"
+        30 DECODE_DECODE_PATH: "DecodePath"
+        31 DECODE_IN_FIELD: "inField"
+        32 DECODE_IN_ELEM: "inElem"
+        33 DECODE_PATH_TO_STR: "pathToStr"
     }
     13 HASH: "Hash" => {
         0 HASH_HASH_ABILITY: "Hash" exposed_type=true
@@ -1576,6 +1593,31 @@ define_builtins! {
     14 JSON: "Json" => {
         0 JSON_JSON: "Json"
     }
-
-    num_modules: 15 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
+    15 INSPECT: "Inspect" => {
+        0 INSPECT_INSPECT_ABILITY: "Inspect" exposed_type=true
+        1 INSPECT_INSPECT: "inspect"
+        2 INSPECT_INSPECTOR: "Inspector" exposed_type=true
+        3 INSPECT_TO_STR: "toStr"
+        4 INSPECT_RECORD: "record"
+        5 INSPECT_TUPLE: "tuple"
+        6 INSPECT_TAG: "tag"
+        7 INSPECT_LIST: "list"
+        8 INSPECT_DICT: "dict"
+        9 INSPECT_SET: "set"
+        10 INSPECT_NUM: "num"
+        11 INSPECT_STR: "str"
+        12 INSPECT_BOOL: "bool"
+        13 INSPECT_OPAQUE: "opaque"
+    }
+    16 COMPARE: "Compare" => {
+        0 COMPARE_COMPARE_ABILITY: "Compare" exposed_type=true
+        1 COMPARE_COMPARE: "compare"
+        2 COMPARE_TIE_BREAK: "tieBreak"
+        3 COMPARE_LIST: "list"
+        4 COMPARE_STR: "str"
+        5 COMPARE_NUM: "num"
+        6 COMPARE_BOOL: "bool"
+    }
+
+    num_modules: 17 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
 }
@@ -5584,7 +5584,7 @@ pub fn with_hole<'a>(
                 }
             }
         }
-        TypedHole(_) => runtime_error(env, "Hit a blank"),
+        TypedHole(_) => runtime_error(env, "Hit a typed hole"),
         RuntimeError(e) => runtime_error(env, env.arena.alloc(e.runtime_message())),
         Crash { msg, ret_var: _ } => {
             let msg_sym = possible_reuse_symbol_or_specialize(
@@ -324,6 +324,8 @@ pub enum Expr<'a> {
     // Problems
     MalformedIdent(&'a str, crate::ident::BadIdent),
     MalformedClosure,
+    /// The source of a top-level def with a syntax error, which the parser skipped over
+    Malformed(&'a str),
     // Both operators were non-associative, e.g. (True == False == False).
     // We should tell the author to disambiguate by grouping them with parens.
     PrecedenceConflict(&'a PrecedenceConflict<'a>),
@@ -532,6 +534,22 @@ impl<'a> Defs<'a> {
         let tag = EitherIndex::from_left(type_def_index);
         self.push_def_help(tag, region, spaces_before, spaces_after)
     }
+
+    /// Add all of the defs in `other` after the ones that are already here.
+    pub fn extend(&mut self, other: &Defs<'a>) {
+        for (index, def) in other.defs().enumerate() {
+            let region = other.regions[index];
+            let spaces_before = &other.spaces[other.space_before[index].indices()];
+            let spaces_after = &other.spaces[other.space_after[index].indices()];
+
+            match def {
+                Ok(type_def) => self.push_type_def(*type_def, region, spaces_before, spaces_after),
+                Err(value_def) => {
+                    self.push_value_def(*value_def, region, spaces_before, spaces_after)
+                }
+            }
+        }
+    }
 }
 
 /// Should always be a zero-argument `Apply`; we'll check this in canonicalization
@@ -1540,6 +1558,7 @@ impl<'a> Malformed for Expr<'a> {
 
             MalformedIdent(_, _) |
             MalformedClosure |
+            Malformed(_) |
             PrecedenceConflict(_) |
             MultipleRecordBuilders(_) |
             UnappliedRecordBuilder(_) => true,
@@ -1924,6 +1924,7 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
         | Expr::Expect(_, _)
         | Expr::Dbg(_, _)
         | Expr::MalformedClosure
+        | Expr::Malformed(_)
         | Expr::PrecedenceConflict { .. }
         | Expr::MultipleRecordBuilders { .. }
         | Expr::UnappliedRecordBuilder { .. }
@@ -1,4 +1,4 @@
-use crate::ast::{Collection, Defs, Header, Module, Spaced, Spaces};
+use crate::ast::{Collection, Defs, Expr, Header, Module, Pattern, Spaced, Spaces, ValueDef};
 use crate::blankspace::{space0_around_ee, space0_before_e, space0_e};
 use crate::header::{
     package_entry, package_name, AppHeader, ExposedName, ExposesKeyword, GeneratesKeyword,
@@ -16,7 +16,8 @@ use crate::parser::{
 use crate::state::State;
 use crate::string_literal::{self, parse_str_literal};
 use crate::type_annotation;
-use roc_region::all::{Loc, Position};
+use bumpalo::Bump;
+use roc_region::all::{Loc, Position, Region};
 
 fn end_of_file<'a>() -> impl Parser<'a, (), SyntaxError<'a>> {
     |_arena, state: State<'a>, _min_indent: u32| {
@@ -36,6 +37,241 @@ pub fn module_defs<'a>() -> impl Parser<'a, Defs<'a>, SyntaxError<'a>> {
     )
 }
 
+/// A top-level def with a syntax error, which [module_defs_with_recovery] skipped over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedDef<'a> {
+    /// The source of the def that was skipped
+    pub region: Region,
+    /// The error the parser ran into in that def
+    pub error: SyntaxError<'a>,
+}
+
+/// Like [module_defs], but when a top-level def has a syntax error, this skips ahead to the next
+/// line that starts with a def at column 0 and keeps going, so that the rest of the module can
+/// still be checked.
+///
+/// A def that was skipped over gets an [Expr::Malformed] body if it looks like `name = ...`.
+/// All of the skipped defs are returned alongside the defs, along with their syntax errors.
+pub fn module_defs_with_recovery<'a>(
+    arena: &'a Bump,
+    state: State<'a>,
+) -> (Defs<'a>, Vec<SkippedDef<'a>>) {
+    let first_error = match module_defs().parse(arena, state.clone(), 0) {
+        Ok((_, defs, _)) => return (defs, Vec::new()),
+        Err((_, fail)) => fail,
+    };
+
+    let bytes = state.original_bytes();
+    let first_def = state.pos().offset as usize;
+
+    // Any line that starts with a name (outside of a string) might be the start of a new def.
+    // This is only a guess, which is why we only split the module up like this once we know
+    // it has a syntax error.
+    let mut boundaries = vec![first_def];
+    boundaries.extend(possible_def_starts(bytes, first_def));
+    boundaries.push(bytes.len());
+
+    let parse_between = |start: usize, end: usize| {
+        let state = State::at_line_start(bytes, start, end);
+
+        match module_defs().parse(arena, state, 0) {
+            Ok((_, defs, _)) => Ok(defs),
+            Err((_, fail)) => Err(fail),
+        }
+    };
+
+    let mut defs = Defs::default();
+    let mut skipped = Vec::new();
+    let mut start = 0;
+    let mut error = Some(first_error);
+
+    while start + 1 < boundaries.len() {
+        let last = boundaries.len() - 1;
+
+        let mut bad_error = match error.take() {
+            Some(error) => error,
+            None => match parse_between(boundaries[start], boundaries[last]) {
+                Ok(rest) => {
+                    defs.extend(&rest);
+                    break;
+                }
+                Err(fail) => fail,
+            },
+        };
+
+        // Find the most defs from `start` onward that parse without errors;
+        // the def right after them is the one that's broken.
+        let mut good = start;
+        let mut bad = last;
+        let mut good_defs = None;
+
+        while bad - good > 1 {
+            let middle = (good + bad) / 2;
+
+            match parse_between(boundaries[start], boundaries[middle]) {
+                Ok(parsed) => {
+                    good = middle;
+                    good_defs = Some(parsed);
+                }
+                Err(fail) => {
+                    bad = middle;
+                    bad_error = fail;
+                }
+            }
+        }
+
+        if let Some(good_defs) = good_defs {
+            defs.extend(&good_defs);
+        }
+
+        let broken = Region::new(
+            Position::new(boundaries[good] as u32),
+            Position::new(boundaries[bad] as u32),
+        );
+
+        push_malformed_def(arena, &mut defs, bytes, broken);
+        skipped.push(SkippedDef {
+            region: broken,
+            error: bad_error,
+        });
+
+        start = bad;
+    }
+
+    (defs, skipped)
+}
+
+/// The offsets of the lines after `start` that begin with a name at column 0, leaving out
+/// lines that are part of a multiline string.
+fn possible_def_starts(bytes: &[u8], start: usize) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut offset = start;
+    let mut in_multiline_string = false;
+
+    while offset < bytes.len() {
+        let rest = &bytes[offset..];
+
+        if in_multiline_string {
+            if rest.starts_with(b"\"\"\"") {
+                in_multiline_string = false;
+                offset += 3;
+            } else if rest[0] == b'\\' {
+                offset += 2;
+            } else {
+                offset += 1;
+            }
+
+            continue;
+        }
+
+        match rest[0] {
+            b'"' if rest.starts_with(b"\"\"\"") => {
+                in_multiline_string = true;
+                offset += 3;
+            }
+            quote @ (b'"' | b'\'') => {
+                // Single-line strings and characters can't contain a newline,
+                // so stop at the end of the line even if the closing quote is missing.
+                offset += 1;
+
+                while offset < bytes.len() && bytes[offset] != quote && bytes[offset] != b'\n' {
+                    let escaped = bytes[offset] == b'\\' && bytes.get(offset + 1) != Some(&b'\n');
+
+                    offset += if escaped { 2 } else { 1 };
+                }
+
+                if offset < bytes.len() && bytes[offset] == quote {
+                    offset += 1;
+                }
+            }
+            b'#' => {
+                while offset < bytes.len() && bytes[offset] != b'\n' {
+                    offset += 1;
+                }
+            }
+            b'\n' => {
+                offset += 1;
+
+                if let Some(&byte) = bytes.get(offset) {
+                    if byte.is_ascii_alphabetic() || byte == b'_' {
+                        starts.push(offset);
+                    }
+                }
+            }
+            _ => {
+                offset += 1;
+            }
+        }
+    }
+
+    starts
+}
+
+/// If the def in `region` looks like `name = ...`, keep it around with a malformed body,
+/// so that the rest of the module can still refer to it.
+fn push_malformed_def<'a>(arena: &'a Bump, defs: &mut Defs<'a>, bytes: &'a [u8], region: Region) {
+    let start = region.start().offset as usize;
+    let end = region.end().offset as usize;
+
+    let raw = match std::str::from_utf8(&bytes[start..end]) {
+        Ok(raw) => raw.trim_end(),
+        Err(_) => return,
+    };
+
+    let name_len = raw
+        .bytes()
+        .take_while(|byte| byte.is_ascii_alphanumeric() || *byte == b'_')
+        .count();
+    let name = &raw[..name_len];
+    let after_name = raw[name_len..].trim_start_matches([' ', '\t']);
+
+    let is_body = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && after_name.starts_with('=')
+        && !after_name.starts_with("==");
+
+    if !is_body {
+        return;
+    }
+
+    let name_region = Region::new(region.start(), region.start().bump_column(name_len as u32));
+    let def_region = Region::new(region.start(), region.start().bump_column(raw.len() as u32));
+    let body_pattern = &*arena.alloc(Loc::at(name_region, Pattern::Identifier(name)));
+    let body_expr = &*arena.alloc(Loc::at(def_region, Expr::Malformed(raw)));
+
+    let annotation = match defs.last() {
+        Some(Err(ValueDef::Annotation(ann_pattern, ann_type)))
+            if ann_pattern.value == Pattern::Identifier(name) =>
+        {
+            Some((*ann_pattern, *ann_type))
+        }
+        _ => None,
+    };
+
+    // The annotation for this def parsed fine, so it needs to go with the malformed body
+    if let Some((ann_pattern, ann_type)) = annotation {
+        let index = defs.len() - 1;
+        let value_def = ValueDef::AnnotatedBody {
+            ann_pattern: arena.alloc(ann_pattern),
+            ann_type: arena.alloc(ann_type),
+            comment: None,
+            body_pattern,
+            body_expr,
+        };
+        let region = Region::span_across(&defs.regions[index], &def_region);
+
+        defs.replace_with_value_def(index, value_def, region);
+
+        return;
+    }
+
+    defs.push_value_def(
+        ValueDef::Body(body_pattern, body_expr),
+        def_region,
+        &[],
+        &[],
+    );
+}
+
 pub fn parse_header<'a>(
     arena: &'a bumpalo::Bump,
     state: State<'a>,
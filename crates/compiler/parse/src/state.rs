@@ -36,6 +36,19 @@ impl<'a> State<'a> {
         }
     }
 
+    /// A state for parsing only `bytes[offset..end]`, where `offset` is the start of a line.
+    /// Positions are still relative to the start of `bytes`.
+    pub(crate) fn at_line_start(bytes: &'a [u8], offset: usize, end: usize) -> State<'a> {
+        let line_start = Position::new(offset as u32);
+
+        State {
+            original_bytes: &bytes[..end],
+            offset,
+            line_start,
+            line_start_after_whitespace: line_start,
+        }
+    }
+
     pub fn original_bytes(&self) -> &'a [u8] {
         self.original_bytes
     }
@@ -211,9 +211,37 @@ pub enum Problem {
         filename: PathBuf,
         error: io::ErrorKind,
     },
+    /// A top-level def with a syntax error, which the parser skipped over.
+    /// The parse error borrows from the parser's arena, so the loader renders its report
+    /// right away; `rendered` is that report without the title.
+    SyntaxError {
+        region: Region,
+        title: String,
+        rendered: String,
+    },
 }
 
 impl Problem {
+    /// The names of the kinds of problems that are warnings, which are the ones whose level
+    /// can be changed.
+    pub const WARNING_NAMES: &[&str] = &[
+        "unused-def",
+        "unused-import",
+        "unused-module-import",
+        "unused-argument",
+        "unused-branch-def",
+        "defs-only-used-in-recursion",
+        "phantom-type-argument",
+        "duplicate-record-field-value",
+        "duplicate-has-ability",
+        "duplicate-impl",
+        "not-an-ability",
+        "implements-non-required",
+        "no-identifiers-introduced",
+        "overloaded-specialization",
+        "unnecessary-output-wildcard",
+    ];
+
     pub fn severity(&self) -> Severity {
         use Severity::{Fatal, RuntimeError, Warning};
 
@@ -277,6 +305,70 @@ impl Problem {
             Problem::OverAppliedCrash { .. } => RuntimeError,
             Problem::DefsOnlyUsedInRecursion(_, _) => Warning,
             Problem::FileProblem { .. } => Fatal,
+            Problem::SyntaxError { .. } => RuntimeError,
+        }
+    }
+
+    /// A stable name for this kind of problem, like `unused-import`, which is how CLI flags
+    /// and project configs refer to it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Problem::UnusedDef(..) => "unused-def",
+            Problem::UnusedImport(..) => "unused-import",
+            Problem::UnusedModuleImport(..) => "unused-module-import",
+            Problem::ExposedButNotDefined(..) => "exposed-but-not-defined",
+            Problem::UnknownGeneratesWith(..) => "unknown-generates-with",
+            Problem::UnusedArgument(..) => "unused-argument",
+            Problem::UnusedBranchDef(..) => "unused-branch-def",
+            Problem::DefsOnlyUsedInRecursion(..) => "defs-only-used-in-recursion",
+            Problem::PrecedenceProblem(..) => "precedence-problem",
+            Problem::UnsupportedPattern(..) => "unsupported-pattern",
+            Problem::Shadowing { .. } => "shadowing",
+            Problem::CyclicAlias(..) => "cyclic-alias",
+            Problem::BadRecursion(..) => "bad-recursion",
+            Problem::PhantomTypeArgument { .. } => "phantom-type-argument",
+            Problem::UnboundTypeVariable { .. } => "unbound-type-variable",
+            Problem::DuplicateRecordFieldValue { .. } => "duplicate-record-field-value",
+            Problem::DuplicateRecordFieldType { .. } => "duplicate-record-field-type",
+            Problem::InvalidOptionalValue { .. } => "invalid-optional-value",
+            Problem::DuplicateTag { .. } => "duplicate-tag",
+            Problem::RuntimeError(..) => "runtime-error",
+            Problem::SignatureDefMismatch { .. } => "signature-def-mismatch",
+            Problem::InvalidAliasRigid { .. } => "invalid-alias-rigid",
+            Problem::InvalidInterpolation(..) => "invalid-interpolation",
+            Problem::InvalidHexadecimal(..) => "invalid-hexadecimal",
+            Problem::InvalidUnicodeCodePt(..) => "invalid-unicode-code-point",
+            Problem::NestedDatatype { .. } => "nested-datatype",
+            Problem::InvalidExtensionType { .. } => "invalid-extension-type",
+            Problem::AbilityHasTypeVariables { .. } => "ability-has-type-variables",
+            Problem::HasClauseIsNotAbility { .. } => "has-clause-is-not-ability",
+            Problem::IllegalHasClause { .. } => "illegal-has-clause",
+            Problem::DuplicateHasAbility { .. } => "duplicate-has-ability",
+            Problem::AbilityMemberMissingHasClause { .. } => "ability-member-missing-has-clause",
+            Problem::AbilityMemberMultipleBoundVars { .. } => "ability-member-multiple-bound-vars",
+            Problem::AbilityNotOnToplevel { .. } => "ability-not-on-toplevel",
+            Problem::AbilityUsedAsType(..) => "ability-used-as-type",
+            Problem::NestedSpecialization(..) => "nested-specialization",
+            Problem::IllegalDerivedAbility(..) => "illegal-derived-ability",
+            Problem::ImplementationNotFound { .. } => "implementation-not-found",
+            Problem::NotAnAbilityMember { .. } => "not-an-ability-member",
+            Problem::OptionalAbilityImpl { .. } => "optional-ability-impl",
+            Problem::QualifiedAbilityImpl { .. } => "qualified-ability-impl",
+            Problem::AbilityImplNotIdent { .. } => "ability-impl-not-ident",
+            Problem::DuplicateImpl { .. } => "duplicate-impl",
+            Problem::NotAnAbility(..) => "not-an-ability",
+            Problem::ImplementsNonRequired { .. } => "implements-non-required",
+            Problem::DoesNotImplementAbility { .. } => "does-not-implement-ability",
+            Problem::NotBoundInAllPatterns { .. } => "not-bound-in-all-patterns",
+            Problem::NoIdentifiersIntroduced(..) => "no-identifiers-introduced",
+            Problem::OverloadedSpecialization { .. } => "overloaded-specialization",
+            Problem::UnnecessaryOutputWildcard { .. } => "unnecessary-output-wildcard",
+            Problem::MultipleListRestPattern { .. } => "multiple-list-rest-pattern",
+            Problem::BadTypeArguments { .. } => "bad-type-arguments",
+            Problem::UnappliedCrash { .. } => "unapplied-crash",
+            Problem::OverAppliedCrash { .. } => "over-applied-crash",
+            Problem::FileProblem { .. } => "file-problem",
+            Problem::SyntaxError { .. } => "syntax-error",
         }
     }
 
@@ -352,6 +444,7 @@ impl Problem {
             | Problem::RuntimeError(RuntimeError::MalformedIdentifier(_, _, region))
             | Problem::RuntimeError(RuntimeError::MalformedTypeName(_, region))
             | Problem::RuntimeError(RuntimeError::MalformedClosure(region))
+            | Problem::RuntimeError(RuntimeError::MalformedDef(region))
             | Problem::RuntimeError(RuntimeError::InvalidRecordUpdate { region })
             | Problem::RuntimeError(RuntimeError::InvalidFloat(_, region, _))
             | Problem::RuntimeError(RuntimeError::InvalidInt(_, _, region, _))
@@ -412,7 +505,8 @@ impl Problem {
             | Problem::UnnecessaryOutputWildcard { region }
             | Problem::OverAppliedCrash { region }
             | Problem::UnappliedCrash { region }
-            | Problem::DefsOnlyUsedInRecursion(_, region) => Some(*region),
+            | Problem::DefsOnlyUsedInRecursion(_, region)
+            | Problem::SyntaxError { region, .. } => Some(*region),
             Problem::RuntimeError(RuntimeError::CircularDef(cycle_entries))
             | Problem::BadRecursion(cycle_entries) => {
                 cycle_entries.first().map(|entry| entry.expr_region)
@@ -560,6 +654,8 @@ pub enum RuntimeError {
     MalformedIdentifier(Box<str>, roc_parse::ident::BadIdent, Region),
     MalformedTypeName(Box<str>, Region),
     MalformedClosure(Region),
+    /// A top-level def that had a syntax error, which was reported as [Problem::SyntaxError]
+    MalformedDef(Region),
     InvalidRecordUpdate {
         region: Region,
     },
@@ -623,3 +719,78 @@ pub enum MalformedPatternProblem {
     MultipleCharsInSingleQuote,
     DuplicateListRestPattern,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One problem of each kind that is a warning.
+    fn warnings() -> Vec<Problem> {
+        let symbol = Symbol::LIST_MAP;
+        let region = Region::zero();
+
+        vec![
+            Problem::UnusedDef(symbol, region),
+            Problem::UnusedImport(symbol, region),
+            Problem::UnusedModuleImport(ModuleId::LIST, region),
+            Problem::UnusedArgument(symbol, false, symbol, region),
+            Problem::UnusedBranchDef(symbol, region),
+            Problem::DefsOnlyUsedInRecursion(1, region),
+            Problem::PhantomTypeArgument {
+                typ: symbol,
+                variable_region: region,
+                variable_name: "a".into(),
+                alias_kind: AliasKind::Structural,
+            },
+            Problem::DuplicateRecordFieldValue {
+                field_name: "a".into(),
+                record_region: region,
+                field_region: region,
+                replaced_region: region,
+            },
+            Problem::DuplicateHasAbility {
+                ability: symbol,
+                region,
+            },
+            Problem::DuplicateImpl {
+                original: region,
+                duplicate: region,
+            },
+            Problem::NotAnAbility(region),
+            Problem::ImplementsNonRequired {
+                region,
+                ability: symbol,
+                not_required: vec![symbol],
+            },
+            Problem::NoIdentifiersIntroduced(region),
+            Problem::OverloadedSpecialization {
+                overload: region,
+                original_opaque: symbol,
+                ability_member: symbol,
+            },
+            Problem::UnnecessaryOutputWildcard { region },
+        ]
+    }
+
+    #[test]
+    fn warning_names_are_the_names_of_warnings() {
+        let warnings = warnings();
+
+        for warning in warnings.iter() {
+            assert_eq!(warning.severity(), Severity::Warning, "{:?}", warning);
+            assert!(
+                Problem::WARNING_NAMES.contains(&warning.name()),
+                "{} is missing from WARNING_NAMES",
+                warning.name()
+            );
+        }
+
+        for name in Problem::WARNING_NAMES {
+            assert!(
+                warnings.iter().any(|warning| warning.name() == *name),
+                "{} is in WARNING_NAMES, but isn't the name of a warning",
+                name
+            );
+        }
+    }
+}
@@ -19,3 +19,60 @@ pub enum Severity {
     /// (e.g. unused def, unused import)
     Warning,
 }
+
+/// How to treat a kind of warning (or lint), e.g. from `--deny unused-import`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Level {
+    /// Don't report it at all
+    Allow,
+    /// Report it, but don't fail
+    Warn,
+    /// Report it as an error
+    Deny,
+}
+
+impl Level {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            _ => None,
+        }
+    }
+
+    /// The severity of reports at this level, or `None` if they shouldn't be reported.
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            Level::Allow => None,
+            Level::Warn => Some(Severity::Warning),
+            Level::Deny => Some(Severity::RuntimeError),
+        }
+    }
+}
+
+/// The levels set for kinds of problems, by their names (e.g. `unused-import`).
+/// Problems without a level here keep their usual severity.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Levels {
+    by_name: std::collections::BTreeMap<String, Level>,
+}
+
+impl Levels {
+    pub fn get(&self, name: &str) -> Option<Level> {
+        self.by_name.get(name).copied()
+    }
+
+    pub fn set(&mut self, name: &str, level: Level) {
+        self.by_name.insert(name.to_string(), level);
+    }
+
+    /// The severity to report a problem named `name` with, or `None` if it shouldn't be reported.
+    /// Only warnings can be allowed or denied; errors are always errors.
+    pub fn severity(&self, name: &str, severity: Severity) -> Option<Severity> {
+        match (severity, self.get(name)) {
+            (Severity::Warning, Some(level)) => level.severity(),
+            _ => Some(severity),
+        }
+    }
+}
@@ -25,7 +25,7 @@ use roc_error_macros::internal_error;
 use roc_module::ident::TagName;
 use roc_module::symbol::{ModuleId, Symbol};
 use roc_problem::can::CycleEntry;
-use roc_region::all::Loc;
+use roc_region::all::{Loc, Region};
 use roc_solve_problem::TypeError;
 use roc_types::subs::{
     self, AliasVariables, Content, Descriptor, FlatType, GetSubsSlice, LambdaSet, Mark,
@@ -683,6 +683,7 @@ fn solve(
     };
 
     let mut stack = vec![initial];
+    let mut typed_holes = Vec::new();
 
     while let Some(work_item) = stack.pop() {
         let (env, rank, constraint) = match work_item {
@@ -1852,12 +1853,84 @@ fn solve(
                     }
                 }
             }
+            TypedHole(var, region) => {
+                // The type of the hole may depend on constraints we haven't solved yet,
+                // so only remember what's in scope here, and look for fits at the end.
+                typed_holes.push(PendingTypedHole {
+                    var: *var,
+                    region: *region,
+                    in_scope: env.vars_by_symbol().collect(),
+                });
+
+                state
+            }
         };
     }
 
+    for hole in typed_holes {
+        problems.push(check_typed_hole(arena, subs, hole));
+    }
+
     state
 }
 
+struct PendingTypedHole {
+    var: Variable,
+    region: Region,
+    in_scope: Vec<(Symbol, Variable)>,
+}
+
+/// Report the type of a typed hole, along with the values that were in scope there whose types
+/// unify with it, innermost first.
+fn check_typed_hole(arena: &Bump, subs: &mut Subs, hole: PendingTypedHole) -> TypeError {
+    let PendingTypedHole {
+        var,
+        region,
+        in_scope,
+    } = hole;
+
+    // If nothing is known about the type of the hole, everything in scope would fit.
+    let is_unconstrained = matches!(
+        subs.get_content_without_compacting(var),
+        Content::FlexVar(_) | Content::FlexAbleVar(..)
+    );
+    let mut fits = Vec::new();
+
+    if !is_unconstrained {
+        for (symbol, symbol_var) in in_scope.into_iter().rev() {
+            // Everything here gets rolled back, so the copies don't need to live in the real pools.
+            let snapshot = subs.snapshot();
+            let mut pools = Pools::default();
+            let copy = deep_copy_var_in(subs, Rank::toplevel(), &mut pools, symbol_var, arena);
+            let fits_hole = matches!(
+                unify(
+                    &mut UEnv::new(subs),
+                    copy,
+                    var,
+                    Mode::EQ,
+                    Polarity::OF_VALUE
+                ),
+                Success { .. }
+            );
+            subs.rollback_to(snapshot);
+
+            if fits_hole {
+                let snapshot = subs.snapshot();
+                let typ = subs.var_to_error_type(symbol_var, Polarity::OF_VALUE);
+                subs.rollback_to(snapshot);
+
+                fits.push((symbol, typ));
+            }
+        }
+    }
+
+    TypeError::TypedHole {
+        region,
+        typ: subs.var_to_error_type(var, Polarity::OF_VALUE),
+        fits,
+    }
+}
+
 fn chase_alias_content(subs: &Subs, mut var: Variable) -> (Variable, &Content) {
     loop {
         match subs.get_content_without_compacting(var) {
@@ -33,9 +33,19 @@ pub enum TypeError {
     },
     IngestedFileBadUtf8(Box<PathBuf>, Utf8Error),
     IngestedFileUnsupportedType(Box<PathBuf>, ErrorType),
+    /// The type of a `_` in expression position, and the values in scope there with that type
+    TypedHole {
+        region: Region,
+        typ: ErrorType,
+        fits: Vec<(Symbol, ErrorType)>,
+    },
 }
 
 impl TypeError {
+    /// The names of the kinds of type problems that are warnings, which are the ones whose level
+    /// can be changed.
+    pub const WARNING_NAMES: &[&str] = &["redundant-pattern", "unmatchable-pattern"];
+
     pub fn severity(&self) -> Severity {
         use Severity::*;
         match self {
@@ -54,6 +64,27 @@ impl TypeError {
             TypeError::WrongSpecialization { .. } => RuntimeError,
             TypeError::IngestedFileBadUtf8(..) => Fatal,
             TypeError::IngestedFileUnsupportedType(..) => Fatal,
+            TypeError::TypedHole { .. } => RuntimeError,
+        }
+    }
+
+    /// A stable name for this kind of problem, like `type-mismatch`, which is how CLI flags
+    /// and project configs refer to it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TypeError::BadExpr(..) | TypeError::BadPattern(..) => "type-mismatch",
+            TypeError::CircularType(..) => "circular-type",
+            TypeError::CircularDef(_) => "circular-def",
+            TypeError::UnexposedLookup(_) => "unexposed-lookup",
+            TypeError::UnfulfilledAbility(_)
+            | TypeError::BadExprMissingAbility(..)
+            | TypeError::BadPatternMissingAbility(..) => "missing-ability",
+            TypeError::Exhaustive(exhtv) => exhtv.name(),
+            TypeError::StructuralSpecialization { .. } => "structural-specialization",
+            TypeError::WrongSpecialization { .. } => "wrong-specialization",
+            TypeError::IngestedFileBadUtf8(..) => "ingested-file-bad-utf8",
+            TypeError::IngestedFileUnsupportedType(..) => "ingested-file-unsupported-type",
+            TypeError::TypedHole { .. } => "typed-hole",
         }
     }
 }
@@ -113,3 +144,49 @@ pub enum NotDerivableDecode {
 pub enum NotDerivableEq {
     FloatingPoint,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roc_collections::all::HumanIndex;
+
+    /// One type problem of each kind that is a warning.
+    fn warnings() -> Vec<TypeError> {
+        let region = Region::zero();
+
+        vec![
+            TypeError::Exhaustive(roc_exhaustive::Error::Redundant {
+                overall_region: region,
+                branch_region: region,
+                index: HumanIndex::FIRST,
+            }),
+            TypeError::Exhaustive(roc_exhaustive::Error::Unmatchable {
+                overall_region: region,
+                branch_region: region,
+                index: HumanIndex::FIRST,
+            }),
+        ]
+    }
+
+    #[test]
+    fn warning_names_are_the_names_of_warnings() {
+        let warnings = warnings();
+
+        for warning in warnings.iter() {
+            assert_eq!(warning.severity(), Severity::Warning, "{:?}", warning);
+            assert!(
+                TypeError::WARNING_NAMES.contains(&warning.name()),
+                "{} is missing from WARNING_NAMES",
+                warning.name()
+            );
+        }
+
+        for name in TypeError::WARNING_NAMES {
+            assert!(
+                warnings.iter().any(|warning| warning.name() == *name),
+                "{} is in WARNING_NAMES, but isn't the name of a warning",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,164 @@
+#![cfg(test)]
+// Even with #[allow(non_snake_case)] on individual idents, rust-analyzer issues diagnostics.
+// See https://github.com/rust-lang/rust-analyzer/issues/6541.
+// For the `v!` macro we use uppercase variables when constructing tag unions.
+#![allow(non_snake_case)]
+
+use crate::{
+    test_key_eq, test_key_neq,
+    util::{check_derivable, check_single_lset_immediate, check_underivable, derive_test},
+    v,
+};
+use insta::assert_snapshot;
+use roc_module::symbol::Symbol;
+use roc_types::subs::Variable;
+
+use roc_derive_key::{compare::FlatCompareKey, DeriveBuiltin::Compare, DeriveError, DeriveKey};
+
+// Keys are built the same way as for `Hash`, so only the cases where `Compare` differs are
+// tested here.
+
+test_key_eq! {
+    Compare,
+
+    explicit_empty_tuple_and_implicit_empty_tuple:
+        v!(EMPTY_TUPLE), v!(())
+    all_numbers_are_the_same:
+        v!(U8), v!(F64)
+}
+
+test_key_neq! {
+    Compare,
+
+    empty_record_vs_empty_tuple:
+        v!(EMPTY_RECORD), v!(EMPTY_TUPLE)
+}
+
+#[test]
+fn immediates() {
+    check_single_lset_immediate(Compare, v!(U8), Symbol::COMPARE_NUM);
+    check_single_lset_immediate(Compare, v!(I128), Symbol::COMPARE_NUM);
+    check_single_lset_immediate(Compare, v!(NAT), Symbol::COMPARE_NUM);
+    check_single_lset_immediate(Compare, v!(F64), Symbol::COMPARE_NUM);
+    check_single_lset_immediate(Compare, v!(DEC), Symbol::COMPARE_NUM);
+    check_single_lset_immediate(Compare, v!(BOOL), Symbol::COMPARE_BOOL);
+    check_single_lset_immediate(Compare, v!(STR), Symbol::COMPARE_STR);
+    check_single_lset_immediate(Compare, v!(Symbol::LIST_LIST v!(U8)), Symbol::COMPARE_LIST);
+    check_single_lset_immediate(Compare, v!(Symbol::LIST_LIST v!(STR)), Symbol::COMPARE_LIST);
+}
+
+#[test]
+fn optional_record_field_derive_error() {
+    check_underivable(Compare, v!({ ?a: v!(U8), }), DeriveError::Underivable);
+}
+
+#[test]
+fn derivable_record_ext_flex_var() {
+    check_derivable(
+        Compare,
+        v!({ a: v!(STR), }* ),
+        DeriveKey::Compare(FlatCompareKey::Record(vec!["a".into()])),
+    );
+}
+
+#[test]
+fn derivable_record_with_record_ext() {
+    check_derivable(
+        Compare,
+        v!({ b: v!(STR), }{ a: v!(STR), } ),
+        DeriveKey::Compare(FlatCompareKey::Record(vec!["a".into(), "b".into()])),
+    );
+}
+
+#[test]
+fn derivable_tag_ext_flex_var() {
+    check_derivable(
+        Compare,
+        v!([ A v!(STR) ]* ),
+        DeriveKey::Compare(FlatCompareKey::TagUnion(vec![("A".into(), 1)])),
+    );
+}
+
+#[test]
+fn derivable_tag_with_tag_ext() {
+    check_derivable(
+        Compare,
+        v!([ B v!(STR) v!(U8) ][ A v!(STR) ]),
+        DeriveKey::Compare(FlatCompareKey::TagUnion(vec![
+            ("A".into(), 1),
+            ("B".into(), 2),
+        ])),
+    );
+}
+
+#[test]
+fn derivable_empty_tuple() {
+    check_derivable(
+        Compare,
+        v!(EMPTY_TUPLE),
+        DeriveKey::Compare(FlatCompareKey::Tuple(0)),
+    );
+}
+
+#[test]
+fn two_field_record() {
+    derive_test(Compare, v!({ a: v!(U8), b: v!(STR), }), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for { a : U8, b : Str }
+        # { a : a, b : a1 }, { a : a, b : a1 } -[[compare_{a,b}(0)]]-> [EQ, GT, LT] | a has Compare, a1 has Compare
+        # { a : a, b : a1 }, { a : a, b : a1 } -[[compare_{a,b}(0)]]-> [EQ, GT, LT] | a has Compare, a1 has Compare
+        # Specialization lambda sets:
+        #   @<1>: [[compare_{a,b}(0)]]
+        #Derived.compare_{a,b} =
+          \#Derived.rcd1, #Derived.rcd2 ->
+            tieBreak
+              (compare #Derived.rcd1.a #Derived.rcd2.a)
+              (compare #Derived.rcd1.b #Derived.rcd2.b)
+        "###
+        )
+    })
+}
+
+#[test]
+fn two_element_tuple() {
+    derive_test(Compare, v!((v!(U8), v!(STR),)), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for ( U8, Str )*
+        # ( a, a1 )*, ( a, a1 )* -[[compare_(arity:2)(0)]]-> [EQ, GT, LT] | a has Compare, a1 has Compare
+        # ( a, a1 )*, ( a, a1 )* -[[compare_(arity:2)(0)]]-> [EQ, GT, LT] | a has Compare, a1 has Compare
+        # Specialization lambda sets:
+        #   @<1>: [[compare_(arity:2)(0)]]
+        #Derived.compare_(arity:2) =
+          \#Derived.tup1, #Derived.tup2 ->
+            tieBreak
+              (compare #Derived.tup1.0 #Derived.tup2.0)
+              (compare #Derived.tup1.1 #Derived.tup2.1)
+        "###
+        )
+    })
+}
+
+#[test]
+fn tag_two_labels() {
+    derive_test(Compare, v!([A v!(U8), B]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A U8, B]
+        # [A a, B], [A a, B] -[[compare_[A 1,B 0](0)]]-> [EQ, GT, LT] | a has Compare
+        # [A a, B], [A a, B] -[[compare_[A 1,B 0](0)]]-> [EQ, GT, LT] | a has Compare
+        # Specialization lambda sets:
+        #   @<1>: [[compare_[A 1,B 0](0)]]
+        #Derived.compare_[A 1,B 0] =
+          \#Derived.union1, #Derived.union2 ->
+            when #Derived.union1 is
+              A #Derived.3 ->
+                when #Derived.union2 is
+                  A #Derived.4 -> compare #Derived.3 #Derived.4
+                  B -> LT
+              B ->
+                when #Derived.union2 is
+                  A _ -> GT
+                  B -> EQ
+        "###
+        )
+    })
+}
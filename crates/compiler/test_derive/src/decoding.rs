@@ -13,7 +13,11 @@ use insta::assert_snapshot;
 use roc_module::symbol::Symbol;
 use roc_types::subs::Variable;
 
-use roc_derive_key::{decoding::FlatDecodableKey, DeriveBuiltin::Decoder, DeriveError, DeriveKey};
+use roc_derive_key::{
+    decoding::{DecodableField, FlatDecodableKey},
+    DeriveBuiltin::Decoder,
+    DeriveError, DeriveKey,
+};
 
 test_key_eq! {
     Decoder,
@@ -33,6 +37,20 @@ test_key_eq! {
     same_tuple_fields_diff_types:
         v!((v!(U8), v!(U16),)), v!((v!(U32), v!(U64),))
 
+    same_tag_union:
+        v!([ A v!(U8) v!(STR), B v!(STR) ]), v!([ A v!(U8) v!(STR), B v!(STR) ])
+    same_tag_union_tags_diff_types:
+        v!([ A v!(U8) v!(U8), B v!(U8) ]), v!([ A v!(STR) v!(STR), B v!(STR) ])
+    same_tag_union_tags_any_order:
+        v!([ A v!(U8) v!(U8), B v!(U8), C ]), v!([ C, B v!(STR), A v!(STR) v!(STR) ])
+    explicit_empty_tag_union_and_implicit_empty_tag_union:
+        v!(EMPTY_TAG_UNION), v!([])
+
+    same_recursive_tag_union:
+        v!([ Nil, Cons v!(^lst)] as lst), v!([ Nil, Cons v!(^lst)] as lst)
+    same_tag_union_and_recursive_tag_union_fields:
+        v!([ Nil, Cons v!(STR)]), v!([ Nil, Cons v!(^lst)] as lst)
+
     list_list_diff_types:
         v!(Symbol::LIST_LIST v!(STR)), v!(Symbol::LIST_LIST v!(U8))
     str_str:
@@ -44,11 +62,21 @@ test_key_neq! {
 
     different_record_fields:
         v!({ a: v!(U8), }), v!({ b: v!(U8), })
+    record_required_vs_maybe_missing_field:
+        v!({ a: v!(U8), }),
+        v!({ a: v!(Symbol::RESULT_RESULT v!(U8) v!([ Missing ]) => v!([ Ok v!(U8), Err v!([ Missing ]) ])), })
     record_empty_vs_nonempty:
         v!(EMPTY_RECORD), v!({ a: v!(U8), })
 
     different_tuple_arities:
         v!((v!(U8), v!(U16),)), v!((v!(U8), v!(U16), v!(U32),))
+
+    different_tag_union_tags:
+        v!([ A v!(U8) ]), v!([ B v!(U8) ])
+    tag_union_empty_vs_nonempty:
+        v!(EMPTY_TAG_UNION), v!([ B v!(U8) ])
+    different_recursive_tag_union_tags:
+        v!([ Nil, Cons v!(^lst) ] as lst), v!([ Nil, Next v!(^lst) ] as lst)
 }
 
 #[test]
@@ -79,7 +107,10 @@ fn derivable_record_ext_flex_var() {
     check_derivable(
         Decoder,
         v!({ a: v!(STR), }* ),
-        DeriveKey::Decoder(FlatDecodableKey::Record(vec!["a".into()])),
+        DeriveKey::Decoder(FlatDecodableKey::Record(vec![(
+            "a".into(),
+            DecodableField::Required,
+        )])),
     );
 }
 
@@ -88,7 +119,10 @@ fn derivable_record_ext_flex_able_var() {
     check_derivable(
         Decoder,
         v!({ a: v!(STR), }a has Symbol::DECODE_DECODER ),
-        DeriveKey::Decoder(FlatDecodableKey::Record(vec!["a".into()])),
+        DeriveKey::Decoder(FlatDecodableKey::Record(vec![(
+            "a".into(),
+            DecodableField::Required,
+        )])),
     );
 }
 
@@ -97,7 +131,69 @@ fn derivable_record_with_record_ext() {
     check_derivable(
         Decoder,
         v!({ b: v!(STR), }{ a: v!(STR), } ),
-        DeriveKey::Decoder(FlatDecodableKey::Record(vec!["a".into(), "b".into()])),
+        DeriveKey::Decoder(FlatDecodableKey::Record(vec![
+            ("a".into(), DecodableField::Required),
+            ("b".into(), DecodableField::Required),
+        ])),
+    );
+}
+
+#[test]
+fn derivable_record_with_maybe_missing_field() {
+    check_derivable(
+        Decoder,
+        v!({
+            a: v!(STR),
+            b: v!(Symbol::RESULT_RESULT v!(U16) v!([ Missing ]) => v!([ Ok v!(U16), Err v!([ Missing ]) ])),
+        }),
+        DeriveKey::Decoder(FlatDecodableKey::Record(vec![
+            ("a".into(), DecodableField::Required),
+            ("b".into(), DecodableField::MaybeMissing),
+        ])),
+    );
+}
+
+#[test]
+fn derivable_record_with_result_field_of_other_error() {
+    check_derivable(
+        Decoder,
+        v!({
+            a: v!(Symbol::RESULT_RESULT v!(U16) v!([ Absent ]) => v!([ Ok v!(U16), Err v!([ Absent ]) ])),
+        }),
+        DeriveKey::Decoder(FlatDecodableKey::Record(vec![(
+            "a".into(),
+            DecodableField::Required,
+        )])),
+    );
+}
+
+#[test]
+fn derivable_tag_ext_flex_var() {
+    check_derivable(
+        Decoder,
+        v!([ A v!(STR) ]* ),
+        DeriveKey::Decoder(FlatDecodableKey::TagUnion(vec![("A".into(), 1)])),
+    );
+}
+
+#[test]
+fn derivable_tag_with_tag_ext() {
+    check_derivable(
+        Decoder,
+        v!([ B v!(STR) v!(U8) ][ A v!(STR) ]),
+        DeriveKey::Decoder(FlatDecodableKey::TagUnion(vec![
+            ("A".into(), 1),
+            ("B".into(), 2),
+        ])),
+    );
+}
+
+#[test]
+fn derivable_empty_tuple() {
+    check_derivable(
+        Decoder,
+        v!(EMPTY_TUPLE),
+        DeriveKey::Decoder(FlatDecodableKey::Tuple(0)),
     );
 }
 
@@ -107,7 +203,7 @@ fn list() {
         assert_snapshot!(golden, @r###"
         # derived for List Str
         # Decoder (List val) fmt | fmt has DecoderFormatting, val has Decoding
-        # List U8, fmt -[[custom(3)]]-> { rest : List U8, result : [Err [TooShort], Ok (List val)] } | fmt has DecoderFormatting, val has Decoding
+        # List U8, fmt -[[custom(3)]]-> { rest : List U8, result : [Err [At (List [Elem Nat, Field Str]) [Expected Str, TooShort], Expected Str, TooShort], Ok (List val)] } | fmt has DecoderFormatting, val has Decoding
         # Specialization lambda sets:
         #   @<1>: [[custom(3)]]
         #Derived.decoder_list =
@@ -125,7 +221,7 @@ fn record_2_fields() {
         assert_snapshot!(golden, @r###"
         # derived for { first : Str, second : Str }
         # Decoder { first : val, second : val1 } fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
-        # List U8, fmt -[[custom(22)]]-> { rest : List U8, result : [Err [TooShort], Ok { first : val, second : val1 }] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(22)]]-> { rest : List U8, result : [Err [At (List [Elem Nat, Field Str]) [Expected Str, TooShort], Expected Str, TooShort], Ok { first : val, second : val1 }] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
         # Specialization lambda sets:
         #   @<1>: [[custom(22)]]
         #Derived.decoder_{first,second} =
@@ -146,7 +242,8 @@ fn record_2_fields() {
                                   result: when #Derived.rec.result is
                                       Ok #Derived.val ->
                                         Ok { stateRecord2 & first: Ok #Derived.val }
-                                      Err #Derived.err -> Err #Derived.err,
+                                      Err #Derived.err ->
+                                        Err (inField #Derived.err "first"),
                                   rest: #Derived.rec.rest
                                 })
                       "second" ->
@@ -158,7 +255,8 @@ fn record_2_fields() {
                                   result: when #Derived.rec2.result is
                                       Ok #Derived.val2 ->
                                         Ok { stateRecord2 & second: Ok #Derived.val2 }
-                                      Err #Derived.err2 -> Err #Derived.err2,
+                                      Err #Derived.err2 ->
+                                        Err (inField #Derived.err2 "second"),
                                   rest: #Derived.rec2.rest
                                 })
                       _ -> Skip
@@ -168,8 +266,8 @@ fn record_2_fields() {
                         when #Derived.stateRecord.second is
                           Ok #Derived.second ->
                             Ok { second: #Derived.second, first: #Derived.first }
-                          _ -> Err TooShort
-                      _ -> Err TooShort)
+                          _ -> Err (inField TooShort "second")
+                      _ -> Err (inField TooShort "first"))
                 #Derived.fmt3
         "###
         )
@@ -182,7 +280,7 @@ fn tuple_2_fields() {
         assert_snapshot!(golden, @r###"
         # derived for ( Str, U8 )*
         # Decoder ( val, val1 )* fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
-        # List U8, fmt -[[custom(22)]]-> { rest : List U8, result : [Err [TooShort], Ok ( val, val1 )a] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(22)]]-> { rest : List U8, result : [Err [At (List [Elem Nat, Field Str]) [Expected Str, TooShort], Expected Str, TooShort], Ok ( val, val1 )a] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
         # Specialization lambda sets:
         #   @<1>: [[custom(22)]]
         #Derived.decoder_(arity:2) =
@@ -203,7 +301,7 @@ fn tuple_2_fields() {
                                   result: when #Derived.rec.result is
                                       Ok #Derived.val ->
                                         Ok { stateRecord2 & e0: Ok #Derived.val }
-                                      Err #Derived.err -> Err #Derived.err,
+                                      Err #Derived.err -> Err (inElem #Derived.err 0),
                                   rest: #Derived.rec.rest
                                 })
                       1 ->
@@ -215,7 +313,7 @@ fn tuple_2_fields() {
                                   result: when #Derived.rec2.result is
                                       Ok #Derived.val2 ->
                                         Ok { stateRecord2 & e1: Ok #Derived.val2 }
-                                      Err #Derived.err2 -> Err #Derived.err2,
+                                      Err #Derived.err2 -> Err (inElem #Derived.err2 1),
                                   rest: #Derived.rec2.rest
                                 })
                       _ -> TooLong
@@ -224,8 +322,102 @@ fn tuple_2_fields() {
                       Ok #Derived.0 ->
                         when #Derived.stateRecord.e1 is
                           Ok #Derived.1 -> Ok ( #Derived.0, #Derived.1 )
-                          _ -> Err TooShort
-                      _ -> Err TooShort)
+                          _ -> Err (inElem TooShort 1)
+                      _ -> Err (inElem TooShort 0))
+                #Derived.fmt3
+        "###
+        )
+    })
+}
+
+#[test]
+fn tag_two_labels() {
+    derive_test(Decoder, v!([A v!(STR) v!(U8), B]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A Str U8, B]
+        # Decoder [A val val1, B] fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(17)]]-> { rest : List U8, result : [Err [At (List [Elem Nat, Field Str]) [Expected Str, TooShort], Expected Str, TooShort], Ok [A val val1, B]] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # Specialization lambda sets:
+        #   @<1>: [[custom(17)]]
+        #Derived.decoder_[A 2,B 0] =
+          custom
+            \#Derived.bytes3, #Derived.fmt3 ->
+              decodeWith
+                #Derived.bytes3
+                (tag
+                  \#Derived.name ->
+                    when #Derived.name is
+                      "A" ->
+                        Keep (custom
+                          \#Derived.bytes, #Derived.fmt ->
+                            when decodeWith #Derived.bytes decoder #Derived.fmt is
+                              #Derived.rec ->
+                                {
+                                  result: when #Derived.rec.result is
+                                      Ok #Derived.args ->
+                                        Ok (A #Derived.args.0 #Derived.args.1)
+                                      Err #Derived.err -> Err #Derived.err,
+                                  rest: #Derived.rec.rest
+                                })
+                      "B" ->
+                        Keep (custom
+                          \#Derived.bytes2, #Derived.fmt2 ->
+                            when decodeWith #Derived.bytes2 decoder #Derived.fmt2 is
+                              #Derived.rec2 ->
+                                {
+                                  result: when #Derived.rec2.result is
+                                      Ok #Derived.args2 -> Ok B
+                                      Err #Derived.err2 -> Err #Derived.err2,
+                                  rest: #Derived.rec2.rest
+                                })
+                      _ -> Skip)
+                #Derived.fmt3
+        "###
+        )
+    })
+}
+
+#[test]
+fn recursive_tag_union() {
+    derive_test(Decoder, v!([Nil, Cons v!(U8) v!(^lst) ] as lst), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [Cons U8 $rec, Nil] as $rec
+        # Decoder [Cons val val1, Nil] fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(17)]]-> { rest : List U8, result : [Err [At (List [Elem Nat, Field Str]) [Expected Str, TooShort], Expected Str, TooShort], Ok [Cons val val1, Nil]] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # Specialization lambda sets:
+        #   @<1>: [[custom(17)]]
+        #Derived.decoder_[Cons 2,Nil 0] =
+          custom
+            \#Derived.bytes3, #Derived.fmt3 ->
+              decodeWith
+                #Derived.bytes3
+                (tag
+                  \#Derived.name ->
+                    when #Derived.name is
+                      "Cons" ->
+                        Keep (custom
+                          \#Derived.bytes, #Derived.fmt ->
+                            when decodeWith #Derived.bytes decoder #Derived.fmt is
+                              #Derived.rec ->
+                                {
+                                  result: when #Derived.rec.result is
+                                      Ok #Derived.args ->
+                                        Ok (Cons #Derived.args.0 #Derived.args.1)
+                                      Err #Derived.err -> Err #Derived.err,
+                                  rest: #Derived.rec.rest
+                                })
+                      "Nil" ->
+                        Keep (custom
+                          \#Derived.bytes2, #Derived.fmt2 ->
+                            when decodeWith #Derived.bytes2 decoder #Derived.fmt2 is
+                              #Derived.rec2 ->
+                                {
+                                  result: when #Derived.rec2.result is
+                                      Ok #Derived.args2 -> Ok Nil
+                                      Err #Derived.err2 -> Err #Derived.err2,
+                                  rest: #Derived.rec2.rest
+                                })
+                      _ -> Skip)
                 #Derived.fmt3
         "###
         )
@@ -0,0 +1,158 @@
+#![cfg(test)]
+// Even with #[allow(non_snake_case)] on individual idents, rust-analyzer issues diagnostics.
+// See https://github.com/rust-lang/rust-analyzer/issues/6541.
+// For the `v!` macro we use uppercase variables when constructing tag unions.
+#![allow(non_snake_case)]
+
+use crate::{
+    test_key_eq, test_key_neq,
+    util::{check_derivable, check_single_lset_immediate, check_underivable, derive_test},
+    v,
+};
+use insta::assert_snapshot;
+use roc_module::symbol::Symbol;
+use roc_types::subs::Variable;
+
+use roc_derive_key::{inspect::FlatInspectableKey, DeriveBuiltin::Inspect, DeriveError, DeriveKey};
+
+// Keys are built the same way as for `Hash`, so only the cases where `Inspect` differs are
+// tested here.
+
+test_key_eq! {
+    Inspect,
+
+    explicit_empty_tuple_and_implicit_empty_tuple:
+        v!(EMPTY_TUPLE), v!(())
+    all_numbers_are_the_same:
+        v!(U8), v!(F64)
+}
+
+test_key_neq! {
+    Inspect,
+
+    empty_record_vs_empty_tuple:
+        v!(EMPTY_RECORD), v!(EMPTY_TUPLE)
+}
+
+#[test]
+fn immediates() {
+    check_single_lset_immediate(Inspect, v!(U8), Symbol::INSPECT_NUM);
+    check_single_lset_immediate(Inspect, v!(I128), Symbol::INSPECT_NUM);
+    check_single_lset_immediate(Inspect, v!(NAT), Symbol::INSPECT_NUM);
+    check_single_lset_immediate(Inspect, v!(F64), Symbol::INSPECT_NUM);
+    check_single_lset_immediate(Inspect, v!(DEC), Symbol::INSPECT_NUM);
+    check_single_lset_immediate(Inspect, v!(BOOL), Symbol::INSPECT_BOOL);
+    check_single_lset_immediate(Inspect, v!(STR), Symbol::INSPECT_STR);
+    check_single_lset_immediate(Inspect, v!(Symbol::LIST_LIST v!(U8)), Symbol::INSPECT_LIST);
+    check_single_lset_immediate(Inspect, v!(Symbol::LIST_LIST v!(STR)), Symbol::INSPECT_LIST);
+}
+
+#[test]
+fn optional_record_field_derive_error() {
+    check_underivable(Inspect, v!({ ?a: v!(U8), }), DeriveError::Underivable);
+}
+
+#[test]
+fn derivable_record_ext_flex_var() {
+    check_derivable(
+        Inspect,
+        v!({ a: v!(STR), }* ),
+        DeriveKey::Inspect(FlatInspectableKey::Record(vec!["a".into()])),
+    );
+}
+
+#[test]
+fn derivable_record_with_record_ext() {
+    check_derivable(
+        Inspect,
+        v!({ b: v!(STR), }{ a: v!(STR), } ),
+        DeriveKey::Inspect(FlatInspectableKey::Record(vec!["a".into(), "b".into()])),
+    );
+}
+
+#[test]
+fn derivable_tag_ext_flex_var() {
+    check_derivable(
+        Inspect,
+        v!([ A v!(STR) ]* ),
+        DeriveKey::Inspect(FlatInspectableKey::TagUnion(vec![("A".into(), 1)])),
+    );
+}
+
+#[test]
+fn derivable_tag_with_tag_ext() {
+    check_derivable(
+        Inspect,
+        v!([ B v!(STR) v!(U8) ][ A v!(STR) ]),
+        DeriveKey::Inspect(FlatInspectableKey::TagUnion(vec![
+            ("A".into(), 1),
+            ("B".into(), 2),
+        ])),
+    );
+}
+
+#[test]
+fn derivable_empty_tuple() {
+    check_derivable(
+        Inspect,
+        v!(EMPTY_TUPLE),
+        DeriveKey::Inspect(FlatInspectableKey::Tuple(0)),
+    );
+}
+
+#[test]
+fn two_field_record() {
+    derive_test(Inspect, v!({ a: v!(U8), b: v!(STR), }), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for { a : U8, b : Str }
+        # { a : val, b : val1 } -[[inspect_{a,b}(0)]]-> Inspector | val has Inspect, val1 has Inspect
+        # { a : val, b : val1 } -[[inspect_{a,b}(0)]]-> Inspector | val has Inspect, val1 has Inspect
+        # Specialization lambda sets:
+        #   @<1>: [[inspect_{a,b}(0)]]
+        #Derived.inspect_{a,b} =
+          \#Derived.rcd ->
+            record
+              [
+                { value: inspect #Derived.rcd.a, key: "a" },
+                { value: inspect #Derived.rcd.b, key: "b" },
+              ]
+        "###
+        )
+    })
+}
+
+#[test]
+fn two_element_tuple() {
+    derive_test(Inspect, v!((v!(U8), v!(STR),)), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for ( U8, Str )*
+        # ( val, val1 )* -[[inspect_(arity:2)(0)]]-> Inspector | val has Inspect, val1 has Inspect
+        # ( val, val1 )* -[[inspect_(arity:2)(0)]]-> Inspector | val has Inspect, val1 has Inspect
+        # Specialization lambda sets:
+        #   @<1>: [[inspect_(arity:2)(0)]]
+        #Derived.inspect_(arity:2) =
+          \#Derived.tup -> tuple [inspect #Derived.tup.0, inspect #Derived.tup.1]
+        "###
+        )
+    })
+}
+
+#[test]
+fn tag_two_labels() {
+    derive_test(Inspect, v!([A v!(U8) v!(STR), B]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A U8 Str, B]
+        # [A val val1, B] -[[inspect_[A 2,B 0](0)]]-> Inspector | val has Inspect, val1 has Inspect
+        # [A val val1, B] -[[inspect_[A 2,B 0](0)]]-> Inspector | val has Inspect, val1 has Inspect
+        # Specialization lambda sets:
+        #   @<1>: [[inspect_[A 2,B 0](0)]]
+        #Derived.inspect_[A 2,B 0] =
+          \#Derived.union ->
+            when #Derived.union is
+              A #Derived.2 #Derived.3 ->
+                tag "A" [inspect #Derived.2, inspect #Derived.3]
+              B -> tag "B" []
+        "###
+        )
+    })
+}
@@ -63,6 +63,16 @@ fn module_source_and_path(builtin: DeriveBuiltin) -> (ModuleId, &'static str, Pa
             module_source(ModuleId::BOOL),
             builtins_path.join("Bool.roc"),
         ),
+        DeriveBuiltin::Inspect => (
+            ModuleId::INSPECT,
+            module_source(ModuleId::INSPECT),
+            builtins_path.join("Inspect.roc"),
+        ),
+        DeriveBuiltin::Compare => (
+            ModuleId::COMPARE,
+            module_source(ModuleId::COMPARE),
+            builtins_path.join("Compare.roc"),
+        ),
     }
 }
 
@@ -1325,7 +1325,6 @@ fn decode_record_two_fields_string_and_string_infer_local_var_destructured() {
 
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
-#[ignore = "json parsing impl must be fixed first"]
 fn decode_empty_record() {
     assert_evals_to!(
         indoc!(
@@ -1410,6 +1409,134 @@ fn decode_tuple_of_tuples() {
     )
 }
 
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_with_payload() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            main =
+                when Str.toUtf8 "{\"Suspended\":[\"ab\",10]}" |> Decode.fromBytes Json.json is
+                    Ok (Suspended "ab" 10u8) -> "abcd"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("abcd"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_tag_round_trip() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            Status : [Active, Suspended Str]
+
+            main =
+                statuses : List Status
+                statuses = [Active, Suspended "ab"]
+
+                when statuses |> Encode.toBytes Json.json |> Decode.fromBytes Json.json is
+                    Ok [Active, Suspended "ab"] -> "abcd"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("abcd"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_json_lines() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            main =
+                input = Str.toUtf8 "{\"n\":1}\n{\"n\":x}\n{\"n\":3}\n{\"n"
+
+                decoded : { values : List (Result { n : U8 } _), rest : List U8 }
+                decoded = Json.decodeLines input Json.json
+
+                when decoded is
+                    { values: [Ok { n: 1 }, Err { offset: 13 }, Ok { n: 3 }], rest: ['{', '"', 'n'] } -> "abcd"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("abcd"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_error_path() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            main =
+                input = Str.toUtf8 "{\"users\":[{\"email\":\"a\"},{\"email\":2}]}"
+
+                decoded : Result { users : List { email : Str } } _
+                decoded = Decode.fromBytes input Json.json
+
+                when decoded is
+                    Err (At path (Expected shape)) -> "expected \(shape) at \(Decode.pathToStr path)"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("expected Str at .users[1].email"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn decode_record_missing_field_with_default() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Json] provides [main] to "./platform"
+
+            main =
+                decoded : Result { host : Str, port : Result U16 [Missing] } _
+                decoded = Decode.fromBytes (Str.toUtf8 "{\"host\":\"localhost\"}") Json.json
+
+                when decoded is
+                    Ok { host, port } -> "\(host):\(Num.toStr (Result.withDefault port 8080))"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("localhost:8080"),
+        RocStr
+    )
+}
+
 #[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
 mod hash {
     #[cfg(feature = "gen-llvm")]
@@ -2109,6 +2236,251 @@ mod eq {
     }
 }
 
+#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+mod inspect {
+    #[cfg(feature = "gen-llvm")]
+    use crate::helpers::llvm::assert_evals_to;
+
+    #[cfg(feature = "gen-wasm")]
+    use crate::helpers::wasm::assert_evals_to;
+
+    use indoc::indoc;
+    use roc_std::RocStr;
+
+    #[test]
+    fn inspect_record() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Inspect.toStr { name: "Ada", age: 36u8, admin: Bool.true }
+                "#
+            ),
+            RocStr::from(r#"{ admin: Bool.true, age: 36, name: "Ada" }"#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn inspect_tuple_and_list() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Inspect.toStr ([1u8, 2], "x")
+                "#
+            ),
+            RocStr::from(r#"([1, 2], "x")"#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn inspect_tag_union() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Inspect.toStr [Ok (Some 1.5f64), Err Empty]
+                "#
+            ),
+            RocStr::from(r#"[Ok (Some 1.5), Err Empty]"#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn inspect_recursive_tag_union() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                LinkedList : [Nil, Cons U8 LinkedList]
+
+                list : LinkedList
+                list = Cons 1 (Cons 2 Nil)
+
+                main = Inspect.toStr list
+                "#
+            ),
+            RocStr::from(r#"Cons 1 (Cons 2 Nil)"#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn inspect_derived_opaque() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Id := U64 has [Inspect]
+
+                main = Inspect.toStr { id: @Id 7 }
+                "#
+            ),
+            RocStr::from(r#"{ id: 7 }"#),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn inspect_custom_opaque() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Password := Str has [Inspect { inspect: inspectPassword }]
+
+                inspectPassword = \@Password _ -> Inspect.opaque "Password"
+
+                main = Inspect.toStr { user: "ada", password: @Password "hunter2" }
+                "#
+            ),
+            RocStr::from(r#"{ password: <Password>, user: "ada" }"#),
+            RocStr
+        )
+    }
+}
+
+#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+mod compare {
+    #[cfg(feature = "gen-llvm")]
+    use crate::helpers::llvm::assert_evals_to;
+
+    #[cfg(feature = "gen-wasm")]
+    use crate::helpers::wasm::assert_evals_to;
+
+    use indoc::indoc;
+    use roc_std::{RocList, RocStr};
+
+    #[test]
+    fn compare_numbers() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main = Compare.compare 1u8 2u8 == LT
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn sort_records_by_multiple_fields() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main =
+                    [
+                        { age: 30u8, name: "b" },
+                        { age: 20u8, name: "c" },
+                        { age: 30u8, name: "a" },
+                    ]
+                    |> List.sortWith Compare.compare
+                    |> List.map .name
+                "#
+            ),
+            RocList::from_slice(&[RocStr::from("c"), RocStr::from("a"), RocStr::from("b")]),
+            RocList<RocStr>
+        )
+    }
+
+    #[test]
+    fn sort_tuples() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main =
+                    [(2u8, 1u8), (1u8, 9u8), (2u8, 0u8)]
+                    |> List.sortWith Compare.compare
+                    |> List.map \t -> t.1
+                "#
+            ),
+            RocList::from_slice(&[9u8, 0, 1]),
+            RocList<u8>
+        )
+    }
+
+    #[test]
+    fn compare_tags_then_payloads() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main =
+                    [Some 3u8, None, Some 1u8]
+                    |> List.sortWith Compare.compare
+                    |> List.map \opt ->
+                        when opt is
+                            None -> 0u8
+                            Some n -> n
+                "#
+            ),
+            RocList::from_slice(&[0u8, 1, 3]),
+            RocList<u8>
+        )
+    }
+
+    #[test]
+    fn compare_strings_and_lists() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                main =
+                    [
+                        Compare.compare "apple" "apples",
+                        Compare.compare [2u8] [1u8, 5u8],
+                        Compare.compare ["x"] ["x"],
+                    ]
+                    |> List.map \order ->
+                        when order is
+                            LT -> -1i8
+                            EQ -> 0
+                            GT -> 1
+                "#
+            ),
+            RocList::from_slice(&[-1i8, 1, 0]),
+            RocList<i8>
+        )
+    }
+
+    #[test]
+    fn derive_compare_for_opaque() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Version := { major : U8, minor : U8 } has [Compare]
+
+                main =
+                    Compare.compare (@Version { major: 1, minor: 10 }) (@Version { major: 2, minor: 0 })
+                    == LT
+                "#
+            ),
+            true,
+            bool
+        )
+    }
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn issue_4772_weakened_monomorphic_destructure() {
@@ -58,6 +58,7 @@ pub fn helper(
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        typed_holes: false,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
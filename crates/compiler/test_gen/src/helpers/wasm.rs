@@ -46,6 +46,16 @@ fn write_final_wasm() -> bool {
     DEBUG_SETTINGS.keep_test_binary
 }
 
+fn write_final_wat() -> bool {
+    use roc_debug_flags::dbg_do;
+
+    dbg_do!(roc_debug_flags::ROC_WRITE_FINAL_WAT, {
+        return true;
+    });
+
+    false
+}
+
 #[allow(dead_code)]
 pub fn compile_to_wasm_bytes<'a, T: Wasm32Result>(
     arena: &'a bumpalo::Bump,
@@ -63,6 +73,11 @@ pub fn compile_to_wasm_bytes<'a, T: Wasm32Result>(
         crate::helpers::save_wasm_file(&compiled_bytes, build_dir_hash)
     };
 
+    if write_final_wat() {
+        let build_dir_hash = crate::helpers::src_hash(src);
+        save_wat_file(arena, &compiled_bytes, build_dir_hash)
+    };
+
     compiled_bytes
 }
 
@@ -92,6 +107,7 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         palette: DEFAULT_PALETTE_HTML,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        typed_holes: false,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -172,6 +188,34 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
     app_module_bytes
 }
 
+fn save_wat_file(arena: &Bump, app_module_bytes: &[u8], build_dir_hash: u64) {
+    use std::path::Path;
+
+    let require_relocatable = false;
+    let module =
+        WasmModule::preload(arena, app_module_bytes, require_relocatable).unwrap_or_else(|e| {
+            panic!(
+                "Failed to parse final.wasm at offset {:#x}: {}",
+                e.offset, e.message
+            )
+        });
+    let wat = module.to_wat(arena).unwrap_or_else(|e| {
+        panic!(
+            "Failed to print final.wat at offset {:#x}: {}",
+            e.offset, e.message
+        )
+    });
+
+    let debug_dir_str = format!("/tmp/roc/gen_wasm/{:016x}", build_dir_hash);
+    let debug_dir_path = Path::new(&debug_dir_str);
+    let final_wat_file = debug_dir_path.join("final.wat");
+
+    std::fs::create_dir_all(debug_dir_path).unwrap();
+    std::fs::write(&final_wat_file, wat).unwrap();
+
+    println!("Wrote {}", final_wat_file.to_str().unwrap());
+}
+
 #[allow(dead_code)]
 pub fn assert_evals_to_help<T>(src: &str, phantom: PhantomData<T>) -> Result<T, String>
 where
@@ -108,6 +108,7 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
         render: roc_reporting::report::RenderTarget::Generic,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         exec_mode,
+        typed_holes: false,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
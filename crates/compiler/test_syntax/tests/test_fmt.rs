@@ -128,6 +128,32 @@ mod test_fmt {
         module_formats_to(input, input);
     }
 
+    fn fmt_defs_with_width<'a>(arena: &'a Bump, src: &'a str, max_width: usize) -> &'a str {
+        let (_, defs, _) = module_defs()
+            .parse(arena, State::new(src.as_bytes()), 0)
+            .unwrap_or_else(|(_, error)| {
+                panic!("Unexpected parse failure:\n\n{:?}\n\n{}", error, src)
+            });
+
+        let mut buf = Buf::with_max_width(arena, max_width);
+        fmt_defs(&mut buf, &defs, 0);
+
+        buf.into_bump_str().trim()
+    }
+
+    /// Checks that `input` formats to `expected` when lines are broken at `max_width`,
+    /// and that formatting `expected` again doesn't change it.
+    fn defs_format_to_within(max_width: usize, input: &str, expected: &str) {
+        let arena = Bump::new();
+        let expected = expected.trim();
+
+        let output = fmt_defs_with_width(&arena, input.trim(), max_width);
+        assert_multiline_str_eq!(expected, output);
+
+        let reformatted = fmt_defs_with_width(&arena, output, max_width);
+        assert_multiline_str_eq!(output, reformatted);
+    }
+
     // STRING LITERALS
 
     #[test]
@@ -6007,6 +6033,193 @@ mod test_fmt {
         );
     }
 
+    // LINE WIDTH
+
+    #[test]
+    fn short_lines_are_left_alone() {
+        defs_format_to_within(
+            40,
+            indoc!(
+                r#"
+                x = List.map items transform
+                "#
+            ),
+            indoc!(
+                r#"
+                x = List.map items transform
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_apply_puts_each_arg_on_its_own_line() {
+        defs_format_to_within(
+            40,
+            indoc!(
+                r#"
+                x = someFunction firstArgument secondArgument thirdArgument
+                "#
+            ),
+            indoc!(
+                r#"
+                x = someFunction
+                    firstArgument
+                    secondArgument
+                    thirdArgument
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_apply_breaks_last_list_arg() {
+        defs_format_to_within(
+            40,
+            indoc!(
+                r#"
+                x = List.concat items [firstItem, secondItem, thirdItem]
+                "#
+            ),
+            indoc!(
+                r#"
+                x = List.concat items [
+                    firstItem,
+                    secondItem,
+                    thirdItem,
+                ]
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_record() {
+        defs_format_to_within(
+            40,
+            indoc!(
+                r#"
+                user = { name: "Alice", email: "alice@example.com", age: 42 }
+                "#
+            ),
+            indoc!(
+                r#"
+                user = {
+                    name: "Alice",
+                    email: "alice@example.com",
+                    age: 42,
+                }
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_pipeline() {
+        defs_format_to_within(
+            40,
+            indoc!(
+                r#"
+                x = items |> List.map transform |> List.keepIf isValid
+                "#
+            ),
+            indoc!(
+                r#"
+                x =
+                    items
+                    |> List.map transform
+                    |> List.keepIf isValid
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_function_annotation() {
+        defs_format_to_within(
+            40,
+            indoc!(
+                r#"
+                f : Str, Str, Str -> Result Str [NotFound]
+                "#
+            ),
+            indoc!(
+                r#"
+                f :
+                    Str,
+                    Str,
+                    Str
+                    -> Result Str [NotFound]
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_closure_body_on_its_own_line() {
+        defs_format_to_within(
+            40,
+            indoc!(
+                r#"
+                f = \a ->
+                    someFunction firstArgument secondArgument
+                "#
+            ),
+            indoc!(
+                r#"
+                f = \a ->
+                    someFunction
+                        firstArgument
+                        secondArgument
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_when_branch_on_the_arrow_line_is_left_alone() {
+        // Breaking it up would move it to the next line the next time around.
+        defs_format_to_within(
+            40,
+            indoc!(
+                r#"
+                f = \a ->
+                    when a is
+                        A -> someFunction firstArgument secondArgument
+                        B -> 0
+                "#
+            ),
+            indoc!(
+                r#"
+                f = \a ->
+                    when a is
+                        A -> someFunction firstArgument secondArgument
+                        B -> 0
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn wide_record_nested_in_wide_apply() {
+        defs_format_to_within(
+            40,
+            indoc!(
+                r#"
+                x = List.walk items { total: 0, count: 0, largest: 0 } step
+                "#
+            ),
+            indoc!(
+                r#"
+                x = List.walk
+                    items
+                    { total: 0, count: 0, largest: 0 }
+                    step
+                "#
+            ),
+        );
+    }
+
     // this is a parse error atm
     //    #[test]
     //    fn multiline_apply() {
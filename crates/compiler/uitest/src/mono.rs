@@ -45,6 +45,7 @@ pub fn write_compiled_ir<'a>(
         render: roc_reporting::report::RenderTarget::Generic,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         exec_mode,
+        typed_holes: false,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -0,0 +1,700 @@
+//! `roc api-diff`, which compares what two versions of a package expose, and says which of the
+//! changes between them would break the package's users.
+//!
+//! Whether a type change breaks anything depends on which way values of that type flow. A
+//! function that returns a record can start returning more fields, but a function that takes a
+//! record can't start requiring more fields from its callers. Type arguments (like the `a` in
+//! `List a`) and the types in type aliases can flow either way, so any change to them breaks.
+use crate::{load_module_for_docs, signature, type_annotation_to_html};
+use roc_load::docs::{AbilityMember, DocDef, DocEntry, ModuleDocumentation, RecordField};
+use roc_load::docs::{Tag, TypeAnnotation};
+use roc_load::LoadedModule;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Compatibility {
+    Same,
+    /// Code that used the old version still works with the new one
+    Compatible,
+    Breaking,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ApiChange {
+    AddedModule(String),
+    RemovedModule(String),
+    Added {
+        module: String,
+        signature: String,
+    },
+    Removed {
+        module: String,
+        signature: String,
+    },
+    Changed {
+        module: String,
+        old: String,
+        new: String,
+        compatibility: Compatibility,
+    },
+}
+
+impl ApiChange {
+    pub fn compatibility(&self) -> Compatibility {
+        match self {
+            ApiChange::AddedModule(_) | ApiChange::Added { .. } => Compatibility::Compatible,
+            ApiChange::RemovedModule(_) | ApiChange::Removed { .. } => Compatibility::Breaking,
+            ApiChange::Changed { compatibility, .. } => *compatibility,
+        }
+    }
+}
+
+/// Which way values of a type flow between the package and the code that uses it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Flow {
+    /// From the package's users to the package, like function arguments
+    In,
+    /// From the package to its users, like values and what functions return
+    Out,
+    Both,
+}
+
+impl Flow {
+    fn flip(self) -> Self {
+        match self {
+            Flow::In => Flow::Out,
+            Flow::Out => Flow::In,
+            Flow::Both => Flow::Both,
+        }
+    }
+
+    /// A change that accepts more (for inputs) or promises more (for outputs) than before
+    fn widened(self) -> Compatibility {
+        match self {
+            Flow::In | Flow::Out => Compatibility::Compatible,
+            Flow::Both => Compatibility::Breaking,
+        }
+    }
+}
+
+/// Compare the packages whose main .roc files are at the given paths, print what changed
+/// between them, and return the exit code: 1 if any of the changes are breaking, 0 otherwise.
+pub fn api_diff(old_root_file: PathBuf, new_root_file: PathBuf) -> i32 {
+    let old = load_module_for_docs(old_root_file);
+    let new = load_module_for_docs(new_root_file);
+    let changes = diff_packages(&old, &new);
+
+    print_changes(&changes);
+
+    let breaking = changes
+        .iter()
+        .filter(|change| change.compatibility() == Compatibility::Breaking)
+        .count();
+
+    if breaking > 0 {
+        println!(
+            "Found {} breaking {}, so releasing this needs a new major version.",
+            breaking,
+            if breaking == 1 { "change" } else { "changes" }
+        );
+
+        1
+    } else if !changes.is_empty() {
+        println!(
+            "All of these changes are backwards-compatible, so a new minor version is enough."
+        );
+
+        0
+    } else {
+        println!("The exposed API didn't change.");
+
+        0
+    }
+}
+
+fn print_changes(changes: &[ApiChange]) {
+    let mut current_module = None;
+
+    for change in changes {
+        let module = match change {
+            ApiChange::AddedModule(module) => {
+                println!("+ module {}\n", module);
+                continue;
+            }
+            ApiChange::RemovedModule(module) => {
+                println!("- module {} (breaking)\n", module);
+                continue;
+            }
+            ApiChange::Added { module, .. }
+            | ApiChange::Removed { module, .. }
+            | ApiChange::Changed { module, .. } => module,
+        };
+
+        if current_module != Some(module) {
+            if current_module.is_some() {
+                println!();
+            }
+
+            println!("module {}", module);
+            current_module = Some(module);
+        }
+
+        match change {
+            ApiChange::Added { signature, .. } => {
+                println!("    + {}", indent_continuation_lines(signature));
+            }
+            ApiChange::Removed { signature, .. } => {
+                println!("    - {} (breaking)", indent_continuation_lines(signature));
+            }
+            ApiChange::Changed {
+                old,
+                new,
+                compatibility,
+                ..
+            } => {
+                let label = match compatibility {
+                    Compatibility::Breaking => " (breaking)",
+                    Compatibility::Compatible | Compatibility::Same => "",
+                };
+
+                println!("    ~ {}{}", indent_continuation_lines(old), label);
+                println!("      became {}", indent_continuation_lines(new));
+            }
+            ApiChange::AddedModule(_) | ApiChange::RemovedModule(_) => unreachable!(),
+        }
+    }
+
+    if current_module.is_some() {
+        println!();
+    }
+}
+
+/// Multiline signatures are indented relative to their first line; keep them that way
+/// when the first line is indented.
+fn indent_continuation_lines(signature: &str) -> String {
+    signature.replace('\n', "\n      ")
+}
+
+/// The changes between the APIs of two versions of a package, module by module.
+pub fn diff_packages(old: &LoadedModule, new: &LoadedModule) -> Vec<ApiChange> {
+    let old_modules = modules_by_name(old);
+    let new_modules = modules_by_name(new);
+    let mut changes = Vec::new();
+
+    for (name, old_module) in old_modules.iter() {
+        match new_modules.get(name) {
+            Some(new_module) => {
+                changes.extend(diff_modules(old, old_module, new, new_module));
+            }
+            None => changes.push(ApiChange::RemovedModule(name.to_string())),
+        }
+    }
+
+    for name in new_modules.keys() {
+        if !old_modules.contains_key(name) {
+            changes.push(ApiChange::AddedModule(name.to_string()));
+        }
+    }
+
+    changes
+}
+
+fn modules_by_name(loaded: &LoadedModule) -> BTreeMap<&str, &ModuleDocumentation> {
+    loaded
+        .docs_by_module
+        .values()
+        .map(|module| (module.name.as_str(), module))
+        .collect()
+}
+
+fn exposed_defs(module: &ModuleDocumentation) -> BTreeMap<&str, &DocDef> {
+    module
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            DocEntry::DocDef(doc_def) if module.exposed_symbols.contains(&doc_def.symbol) => {
+                Some((doc_def.name.as_str(), doc_def))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn diff_modules(
+    old: &LoadedModule,
+    old_module: &ModuleDocumentation,
+    new: &LoadedModule,
+    new_module: &ModuleDocumentation,
+) -> Vec<ApiChange> {
+    let old_defs = exposed_defs(old_module);
+    let new_defs = exposed_defs(new_module);
+    let module = &new_module.name;
+    let mut changes = Vec::new();
+
+    for (name, old_def) in old_defs.iter() {
+        let old_desc = describe(old, old_module, old_def);
+
+        match new_defs.get(name) {
+            Some(new_def) => {
+                let old_impls = implementations(old, old_module, old_def);
+                let new_impls = implementations(new, new_module, new_def);
+                // Users can only rely on the abilities a type implements, so unlike the
+                // `has` clauses in types, those can be added but not removed.
+                let compatibility = compare_defs(old_def, new_def).max(compare_sets(
+                    &new_impls,
+                    &old_impls,
+                    Flow::Out,
+                ));
+
+                if compatibility != Compatibility::Same {
+                    changes.push(ApiChange::Changed {
+                        module: module.clone(),
+                        old: old_desc,
+                        new: describe(new, new_module, new_def),
+                        compatibility,
+                    });
+                }
+            }
+            None => changes.push(ApiChange::Removed {
+                module: module.clone(),
+                signature: old_desc,
+            }),
+        }
+    }
+
+    for (name, new_def) in new_defs.iter() {
+        if !old_defs.contains_key(name) {
+            changes.push(ApiChange::Added {
+                module: module.clone(),
+                signature: describe(new, new_module, new_def),
+            });
+        }
+    }
+
+    changes
+}
+
+/// The def's signature, along with the abilities it implements if it's an opaque type
+fn describe(loaded: &LoadedModule, module: &ModuleDocumentation, doc_def: &DocDef) -> String {
+    let impls = implementations(loaded, module, doc_def);
+
+    if impls.is_empty() {
+        signature(doc_def)
+    } else {
+        let names: Vec<&str> = impls.iter().map(String::as_str).collect();
+
+        format!("{} (implements {})", signature(doc_def), names.join(", "))
+    }
+}
+
+/// The names of the abilities an opaque type implements. These are compared by name, since the
+/// two versions of the package are loaded separately and don't share symbols.
+fn implementations(
+    loaded: &LoadedModule,
+    module: &ModuleDocumentation,
+    doc_def: &DocDef,
+) -> BTreeSet<String> {
+    module
+        .implementations
+        .get(&doc_def.symbol)
+        .into_iter()
+        .flatten()
+        .map(|ability| ability.as_str(&loaded.interns).to_string())
+        .collect()
+}
+
+/// How the type variables in the old version of a signature were renamed in the new one.
+/// Renaming them doesn't change anything, as long as it's done consistently throughout the
+/// signature: `a, b -> a` and `x, y -> x` are the same, but `a, b -> a` and `a, b -> b` aren't.
+#[derive(Default)]
+struct Renaming {
+    old_to_new: BTreeMap<String, String>,
+    new_to_old: BTreeMap<String, String>,
+    wildcards: usize,
+}
+
+impl Renaming {
+    /// Whether the old variable can be renamed to the new one, given how the variables
+    /// before it were renamed.
+    fn rename(&mut self, old: &str, new: &str) -> bool {
+        match (self.old_to_new.get(old), self.new_to_old.get(new)) {
+            (None, None) => {
+                self.old_to_new.insert(old.to_string(), new.to_string());
+                self.new_to_old.insert(new.to_string(), old.to_string());
+
+                true
+            }
+            (Some(renamed), Some(original)) => renamed == new && original == old,
+            _ => false,
+        }
+    }
+
+    /// The name of a type variable. Every `*` is a variable of its own, so each one gets
+    /// a name that nothing else has.
+    fn var_name(&mut self, type_ann: &TypeAnnotation) -> String {
+        match type_ann {
+            TypeAnnotation::BoundVariable(name) => name.clone(),
+            _ => {
+                self.wildcards += 1;
+
+                format!("*{}", self.wildcards)
+            }
+        }
+    }
+
+    /// What an old variable is called in the new signature, if it was renamed.
+    fn renamed<'a>(&'a self, old: &'a str) -> &'a str {
+        self.old_to_new.get(old).map_or(old, String::as_str)
+    }
+}
+
+fn compare_defs(old: &DocDef, new: &DocDef) -> Compatibility {
+    if old.type_vars.len() != new.type_vars.len() {
+        return Compatibility::Breaking;
+    }
+
+    let is_type = new.name.starts_with(char::is_uppercase);
+    let mut renaming = Renaming::default();
+
+    // The type arguments of an alias (like the `a` in `Pair a : (a, a)`) can be renamed too.
+    for (old_var, new_var) in old.type_vars.iter().zip(&new.type_vars) {
+        renaming.rename(old_var, new_var);
+    }
+
+    match (&old.type_annotation, &new.type_annotation) {
+        (
+            TypeAnnotation::Ability {
+                members: old_members,
+            },
+            TypeAnnotation::Ability {
+                members: new_members,
+            },
+        ) => compare_abilities(old_members, new_members),
+        // A type alias can be used both for arguments and for what functions return.
+        (old_ann, new_ann) if is_type => compare(old_ann, new_ann, Flow::Both, &mut renaming),
+        (old_ann, new_ann) => compare(old_ann, new_ann, Flow::Out, &mut renaming),
+    }
+}
+
+/// Abilities are implemented by the package's users as well as used by them, so any change to
+/// their members breaks someone.
+fn compare_abilities(old: &[AbilityMember], new: &[AbilityMember]) -> Compatibility {
+    let unchanged = old.len() == new.len()
+        && old.iter().all(|old_member| {
+            new.iter().any(|new_member| {
+                new_member.name == old_member.name
+                    && compare(
+                        &old_member.type_annotation,
+                        &new_member.type_annotation,
+                        Flow::Both,
+                        &mut Renaming::default(),
+                    ) == Compatibility::Same
+                    && old_member.able_variables == new_member.able_variables
+            })
+        });
+
+    if unchanged {
+        Compatibility::Same
+    } else {
+        Compatibility::Breaking
+    }
+}
+
+fn compare(
+    old: &TypeAnnotation,
+    new: &TypeAnnotation,
+    flow: Flow,
+    renaming: &mut Renaming,
+) -> Compatibility {
+    use TypeAnnotation::*;
+
+    match (old, new) {
+        (BoundVariable(_) | Wildcard, BoundVariable(_) | Wildcard) => {
+            let old_name = renaming.var_name(old);
+            let new_name = renaming.var_name(new);
+
+            if renaming.rename(&old_name, &new_name) {
+                Compatibility::Same
+            } else {
+                Compatibility::Breaking
+            }
+        }
+        (
+            Function {
+                args: old_args,
+                output: old_output,
+            },
+            Function {
+                args: new_args,
+                output: new_output,
+            },
+        ) => {
+            if old_args.len() != new_args.len() {
+                return Compatibility::Breaking;
+            }
+
+            // The arguments come first, so that they're what a renaming starts from.
+            old_args
+                .iter()
+                .zip(new_args)
+                .map(|(old_arg, new_arg)| compare(old_arg, new_arg, flow.flip(), renaming))
+                .fold(Compatibility::Same, Ord::max)
+                .max(compare(old_output, new_output, flow, renaming))
+        }
+        (
+            Record {
+                fields: old_fields,
+                extension: old_ext,
+            },
+            Record {
+                fields: new_fields,
+                extension: new_ext,
+            },
+        ) => compare_records(old_fields, new_fields, flow, renaming)
+            .max(compare(old_ext, new_ext, flow, renaming)),
+        (
+            TagUnion {
+                tags: old_tags,
+                extension: old_ext,
+            },
+            TagUnion {
+                tags: new_tags,
+                extension: new_ext,
+            },
+        ) => compare_tag_unions(old_tags, new_tags, flow, renaming)
+            .max(compare(old_ext, new_ext, flow, renaming)),
+        (
+            Apply {
+                name: old_name,
+                parts: old_parts,
+            },
+            Apply {
+                name: new_name,
+                parts: new_parts,
+            },
+        ) => {
+            if old_name != new_name || old_parts.len() != new_parts.len() {
+                return Compatibility::Breaking;
+            }
+
+            old_parts
+                .iter()
+                .zip(new_parts)
+                .map(|(old_part, new_part)| compare(old_part, new_part, Flow::Both, renaming))
+                .fold(Compatibility::Same, Ord::max)
+        }
+        (
+            Where {
+                ann: old_ann,
+                able_variables: old_vars,
+            },
+            Where {
+                ann: new_ann,
+                able_variables: new_vars,
+            },
+        ) => {
+            // The `has` clauses are about the variables in the annotation, so compare
+            // them once we know how those variables were renamed.
+            let compatibility = compare(old_ann, new_ann, flow, renaming);
+
+            compatibility.max(compare_sets(
+                &has_clauses(old_vars, renaming),
+                &has_clauses(new_vars, &Renaming::default()),
+                flow,
+            ))
+        }
+        (
+            Where {
+                ann: old_ann,
+                able_variables: old_vars,
+            },
+            _,
+        ) => {
+            let compatibility = compare(old_ann, new, flow, renaming);
+
+            compatibility.max(compare_sets(
+                &has_clauses(old_vars, renaming),
+                &BTreeSet::new(),
+                flow,
+            ))
+        }
+        (
+            _,
+            Where {
+                ann: new_ann,
+                able_variables: new_vars,
+            },
+        ) => compare(old, new_ann, flow, renaming).max(compare_sets(
+            &BTreeSet::new(),
+            &has_clauses(new_vars, &Renaming::default()),
+            flow,
+        )),
+        // A function that took a specific type and now takes any type is fine for its callers,
+        // but code that used a specific type the package gave it can't use any type instead.
+        (_, BoundVariable(_) | Wildcard) if flow == Flow::In => Compatibility::Compatible,
+        // Types without type variables in them, like `Str` or opaque types
+        _ if old == new => Compatibility::Same,
+        _ => Compatibility::Breaking,
+    }
+}
+
+/// For sets that can lose items but not gain them, like the `has` clauses in a type:
+/// dropping a constraint on a type variable is fine, but a new one can rule out types
+/// that users were using.
+fn compare_sets(old: &BTreeSet<String>, new: &BTreeSet<String>, flow: Flow) -> Compatibility {
+    if new.iter().any(|item| !old.contains(item)) {
+        Compatibility::Breaking
+    } else if old.len() != new.len() {
+        flow.widened()
+    } else {
+        Compatibility::Same
+    }
+}
+
+/// The `has` clauses, with their variables called what `renaming` renamed them to.
+fn has_clauses(
+    able_variables: &[(String, Vec<TypeAnnotation>)],
+    renaming: &Renaming,
+) -> BTreeSet<String> {
+    let mut clauses = BTreeSet::new();
+
+    for (var_name, abilities) in able_variables {
+        for ability in abilities {
+            let mut buf = format!("{} has ", renaming.renamed(var_name));
+
+            type_annotation_to_html(0, &mut buf, ability, false);
+            clauses.insert(buf);
+        }
+    }
+
+    clauses
+}
+
+fn compare_records(
+    old: &[RecordField],
+    new: &[RecordField],
+    flow: Flow,
+    renaming: &mut Renaming,
+) -> Compatibility {
+    let mut compatibility = Compatibility::Same;
+
+    for old_field in old {
+        let opt_new_field = new
+            .iter()
+            .find(|new_field| field_name(new_field) == field_name(old_field));
+
+        let field_compatibility = match (old_field, opt_new_field) {
+            // Records are closed, so passing a field that's gone is an error too.
+            (_, None) => Compatibility::Breaking,
+            (
+                RecordField::RecordField {
+                    type_annotation: old_ann,
+                    ..
+                },
+                Some(RecordField::RecordField {
+                    type_annotation: new_ann,
+                    ..
+                }),
+            )
+            | (
+                RecordField::OptionalField {
+                    type_annotation: old_ann,
+                    ..
+                },
+                Some(RecordField::OptionalField {
+                    type_annotation: new_ann,
+                    ..
+                }),
+            ) => compare(old_ann, new_ann, flow, renaming),
+            (
+                RecordField::RecordField {
+                    type_annotation: old_ann,
+                    ..
+                },
+                Some(RecordField::OptionalField {
+                    type_annotation: new_ann,
+                    ..
+                }),
+            ) if flow == Flow::In => {
+                // Callers can keep passing the field, or stop.
+                compare(old_ann, new_ann, flow, renaming).max(Compatibility::Compatible)
+            }
+            (RecordField::LabelOnly { .. }, Some(RecordField::LabelOnly { .. })) => {
+                Compatibility::Same
+            }
+            _ => Compatibility::Breaking,
+        };
+
+        compatibility = compatibility.max(field_compatibility);
+    }
+
+    for new_field in new {
+        if old
+            .iter()
+            .any(|old_field| field_name(old_field) == field_name(new_field))
+        {
+            continue;
+        }
+
+        let field_compatibility = match (new_field, flow) {
+            // Callers don't have to pass a new optional field...
+            (RecordField::OptionalField { .. }, Flow::In) => Compatibility::Compatible,
+            // ...but they do have to pass a new required one.
+            (_, Flow::In) => Compatibility::Breaking,
+            (_, flow) => flow.widened(),
+        };
+
+        compatibility = compatibility.max(field_compatibility);
+    }
+
+    compatibility
+}
+
+fn field_name(field: &RecordField) -> &str {
+    match field {
+        RecordField::RecordField { name, .. }
+        | RecordField::OptionalField { name, .. }
+        | RecordField::LabelOnly { name } => name,
+    }
+}
+
+/// Code that receives a tag union has to handle every tag in it, so giving users more tags
+/// than before breaks them, while taking more tags from them doesn't.
+fn compare_tag_unions(
+    old: &[Tag],
+    new: &[Tag],
+    flow: Flow,
+    renaming: &mut Renaming,
+) -> Compatibility {
+    let mut compatibility = Compatibility::Same;
+
+    for old_tag in old {
+        let tag_compatibility = match new.iter().find(|new_tag| new_tag.name == old_tag.name) {
+            Some(new_tag) if new_tag.values.len() == old_tag.values.len() => old_tag
+                .values
+                .iter()
+                .zip(&new_tag.values)
+                .map(|(old_value, new_value)| compare(old_value, new_value, flow, renaming))
+                .fold(Compatibility::Same, Ord::max),
+            Some(_) => Compatibility::Breaking,
+            None if flow == Flow::Out => Compatibility::Compatible,
+            None => Compatibility::Breaking,
+        };
+
+        compatibility = compatibility.max(tag_compatibility);
+    }
+
+    for new_tag in new {
+        if !old.iter().any(|old_tag| old_tag.name == new_tag.name) {
+            let tag_compatibility = match flow {
+                Flow::In => Compatibility::Compatible,
+                Flow::Out | Flow::Both => Compatibility::Breaking,
+            };
+
+            compatibility = compatibility.max(tag_compatibility);
+        }
+    }
+
+    compatibility
+}
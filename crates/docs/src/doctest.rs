@@ -0,0 +1,381 @@
+//! Code examples in `##` doc comments, which `roc test --doc` runs and `roc docs` type-checks.
+//!
+//! Every code block in a doc comment is an example, unless it's tagged with another language.
+//! How it gets checked depends on its tag:
+//!
+//! - Untagged (or `roc`) blocks get type-checked in the scope of their module, and any
+//!   `expect`s in them get run. Each one becomes a top-level `expect` of its own.
+//! - `repl` blocks are REPL sessions: lines starting with `»` get evaluated one after another,
+//!   and what the REPL prints has to match the lines below them.
+//! - `unchecked` blocks are left alone.
+use bumpalo::Bump;
+use pulldown_cmark::{CodeBlockKind, Event, Parser as MarkdownParser, Tag};
+use roc_collections::MutMap;
+use roc_load::{LoadedModule, LoadingProblem};
+use roc_module::symbol::ModuleId;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::module::module_defs;
+use roc_parse::parser::Parser;
+use roc_parse::state::State;
+use roc_problem::{Levels, Severity};
+use roc_region::all::{Position, Region};
+use roc_reporting::cli::{report_problems, Problems};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::path::{Path, PathBuf};
+
+/// The prompt that starts each input in a `repl` block.
+pub const REPL_PROMPT: &str = "»";
+
+/// The prompt that continues a multiline input in a `repl` block.
+pub const REPL_CONTINUATION: &str = "…";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExampleKind {
+    Checked,
+    Repl,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocExample {
+    pub kind: ExampleKind,
+    /// The (0-based) line in the module's source where the code starts
+    pub line: u32,
+    pub code: String,
+}
+
+/// One input of a `repl` block, along with what the REPL should print for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplInput {
+    /// The (0-based) line in the module's source where the input starts
+    pub line: u32,
+    pub input: String,
+    pub expected: String,
+}
+
+/// The code examples in the doc comments of the module whose source is `src`, in order.
+pub fn doc_examples(src: &str) -> Vec<DocExample> {
+    let mut examples = Vec::new();
+    let mut markdown = String::new();
+    // The source line of each line in `markdown`
+    let mut markdown_lines = Vec::new();
+
+    for (line, text) in src.lines().enumerate() {
+        match text.trim_start().strip_prefix("##") {
+            Some(doc) => {
+                markdown.push_str(doc.strip_prefix(' ').unwrap_or(doc));
+                markdown.push('\n');
+                markdown_lines.push(line as u32);
+            }
+            None if !markdown.is_empty() => {
+                examples.extend(examples_in_markdown(&markdown, &markdown_lines));
+                markdown.clear();
+                markdown_lines.clear();
+            }
+            None => {}
+        }
+    }
+
+    examples.extend(examples_in_markdown(&markdown, &markdown_lines));
+
+    examples
+}
+
+fn examples_in_markdown(markdown: &str, markdown_lines: &[u32]) -> Vec<DocExample> {
+    let mut examples = Vec::new();
+    let mut current: Option<DocExample> = None;
+
+    for (event, range) in MarkdownParser::new(markdown).into_offset_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let (opt_kind, code_starts_after_fence) = match &kind {
+                    CodeBlockKind::Fenced(info) => (example_kind(info), true),
+                    CodeBlockKind::Indented => (Some(ExampleKind::Checked), false),
+                };
+                let markdown_line = markdown[..range.start].matches('\n').count()
+                    + code_starts_after_fence as usize;
+
+                current = opt_kind.map(|kind| DocExample {
+                    kind,
+                    line: markdown_lines
+                        .get(markdown_line)
+                        .copied()
+                        .unwrap_or_default(),
+                    code: String::new(),
+                });
+            }
+            Event::Text(text) => {
+                if let Some(example) = current.as_mut() {
+                    example.code.push_str(&text);
+                }
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                examples.extend(
+                    current
+                        .take()
+                        .filter(|example| !example.code.trim().is_empty()),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    examples
+}
+
+/// Blocks tagged with a language other than Roc aren't examples.
+fn example_kind(info: &str) -> Option<ExampleKind> {
+    let mut tags = info.split(|c: char| c == ',' || c.is_whitespace());
+
+    match tags.find(|tag| !tag.is_empty()) {
+        None | Some("roc") => match tags.any(|tag| tag == "unchecked") {
+            true => None,
+            false => Some(ExampleKind::Checked),
+        },
+        Some("repl") => Some(ExampleKind::Repl),
+        Some(_) => None,
+    }
+}
+
+/// `src` with each of the checked `examples` appended as a top-level `expect`,
+/// so that they're type-checked (and their `expect`s run) in the module's scope.
+///
+/// The examples are wrapped in `expect`s rather than appended as they are, so that
+/// names defined in one example don't clash with names defined in another.
+pub fn doc_test_src(src: &str, examples: &[DocExample]) -> String {
+    let mut buf = String::with_capacity(src.len());
+
+    buf.push_str(src);
+
+    for example in examples {
+        if example.kind != ExampleKind::Checked {
+            continue;
+        }
+
+        buf.push_str(&format!(
+            "\n\n# The example on line {} of this file\nexpect\n",
+            example.line + 1
+        ));
+
+        if is_only_defs(&example.code) {
+            push_indented(&mut buf, &example.code, 1);
+        } else {
+            // The example ends in an expression, which can have any type.
+            buf.push_str("    _ =\n");
+            push_indented(&mut buf, &example.code, 2);
+            buf.push('\n');
+        }
+
+        buf.push_str("    Bool.true\n");
+    }
+
+    buf
+}
+
+/// Whether `code` is just defs and `expect`s, without an expression at the end.
+fn is_only_defs(code: &str) -> bool {
+    let arena = Bump::new();
+
+    module_defs()
+        .parse(&arena, State::new(code.as_bytes()), 0)
+        .is_ok()
+}
+
+fn push_indented(buf: &mut String, code: &str, levels: usize) {
+    for line in code.trim_end().lines() {
+        if !line.trim().is_empty() {
+            buf.push_str(&"    ".repeat(levels));
+            buf.push_str(line);
+        }
+
+        buf.push('\n');
+    }
+}
+
+/// The inputs of a `repl` example.
+pub fn repl_inputs(example: &DocExample) -> Vec<ReplInput> {
+    let mut inputs: Vec<ReplInput> = Vec::new();
+
+    for (index, line) in example.code.lines().enumerate() {
+        let trimmed = line.trim_start();
+
+        if let Some(input) = trimmed.strip_prefix(REPL_PROMPT) {
+            inputs.push(ReplInput {
+                line: example.line + index as u32,
+                input: input.trim().to_string(),
+                expected: String::new(),
+            });
+        } else if let Some(last) = inputs.last_mut() {
+            match trimmed.strip_prefix(REPL_CONTINUATION) {
+                Some(continued) if last.expected.is_empty() => {
+                    last.input.push('\n');
+                    last.input
+                        .push_str(continued.strip_prefix(' ').unwrap_or(continued));
+                }
+                _ => {
+                    last.expected.push_str(line.trim_end());
+                    last.expected.push('\n');
+                }
+            }
+        }
+    }
+
+    for input in inputs.iter_mut() {
+        input.expected = input.expected.trim().to_string();
+    }
+
+    inputs
+}
+
+/// What the REPL printed, without colors or the name it gave the value (like `# val1`),
+/// so that it can be compared with what the docs show.
+pub fn normalize_repl_output(output: &str) -> String {
+    let uncolored = strip_ansi_codes(output);
+    let mut lines: Vec<&str> = uncolored.trim().lines().map(str::trim_end).collect();
+
+    if let Some(last) = lines.last_mut() {
+        if let Some((before, name)) = last.rsplit_once("# ") {
+            let is_name = !name.is_empty()
+                && name.starts_with(|c: char| c.is_ascii_lowercase())
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+            if is_name && (before.is_empty() || before.ends_with(' ')) {
+                *last = before.trim_end();
+            }
+        }
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+fn strip_ansi_codes(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip ahead to the letter that ends the escape code, e.g. the `m` in `\x1b[35m`
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+/// The region of the given (0-based) line of `src`, for pointing at an example in reports.
+pub fn line_region(src: &str, line: u32) -> Region {
+    let start: usize = src
+        .split_inclusive('\n')
+        .take(line as usize)
+        .map(str::len)
+        .sum();
+    let end = src[start..]
+        .find('\n')
+        .map_or(src.len(), |newline| start + newline);
+
+    Region::new(Position::new(start as u32), Position::new(end as u32))
+}
+
+/// Type-check the examples in the docs of every module that `loaded` loaded from
+/// `root_dir` (or below it), printing any errors. Warnings aren't reported, since the
+/// examples often define things only to show how they're written.
+pub fn check_doc_examples(loaded: &LoadedModule, root_dir: &Path) -> Problems {
+    let mut problems = Problems::default();
+
+    for (path, src) in modules_in(&loaded.sources, root_dir) {
+        let examples = doc_examples(src);
+
+        if examples.iter().all(|ex| ex.kind != ExampleKind::Checked) {
+            continue;
+        }
+
+        let module_problems = check_examples_in(path, &doc_test_src(src, &examples));
+
+        problems.errors += module_problems.errors;
+        problems.warnings += module_problems.warnings;
+    }
+
+    problems
+}
+
+/// The paths and sources of the modules in `sources` that are in `root_dir` (or below it),
+/// sorted by path.
+pub fn modules_in<'s>(
+    sources: &'s MutMap<ModuleId, (PathBuf, Box<str>)>,
+    root_dir: &Path,
+) -> Vec<(&'s Path, &'s str)> {
+    let mut modules: Vec<_> = sources
+        .iter()
+        .filter(|(home, (path, _))| !home.is_builtin() && path.starts_with(root_dir))
+        .map(|(_, (path, src))| (path.as_path(), &**src))
+        .collect();
+
+    modules.sort_by_key(|(path, _)| *path);
+
+    modules
+}
+
+fn check_examples_in(path: &Path, doc_test_src: &str) -> Problems {
+    let arena = Bump::new();
+    let src_dir = path.parent().map_or_else(PathBuf::new, Path::to_path_buf);
+
+    let mut loaded = match roc_load::load_and_typecheck_str(
+        &arena,
+        path.to_path_buf(),
+        arena.alloc_str(doc_test_src),
+        src_dir,
+        // This is just type-checking, so "target" doesn't matter
+        TargetInfo::default_x86_64(),
+        RenderTarget::ColorTerminal,
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        DEFAULT_PALETTE,
+    ) {
+        Ok(loaded) => loaded,
+        Err(LoadingProblem::FormattedReport(report)) => {
+            eprintln!("{}", report);
+
+            return Problems {
+                errors: 1,
+                ..Problems::default()
+            };
+        }
+        Err(other) => {
+            eprintln!(
+                "Could not check the doc examples in {}: {:?}",
+                path.display(),
+                other
+            );
+
+            return Problems::default();
+        }
+    };
+
+    let mut can_problems = std::mem::take(&mut loaded.can_problems);
+    let mut type_problems = std::mem::take(&mut loaded.type_problems);
+
+    for problems in can_problems.values_mut() {
+        problems.retain(|problem| problem.severity() != Severity::Warning);
+    }
+
+    for problems in type_problems.values_mut() {
+        problems.retain(|problem| problem.severity() != Severity::Warning);
+    }
+
+    let total_problems = can_problems.values().map(Vec::len).sum::<usize>()
+        + type_problems.values().map(Vec::len).sum::<usize>();
+
+    report_problems(
+        total_problems,
+        &loaded.sources,
+        &loaded.interns,
+        &mut can_problems,
+        &mut type_problems,
+        &Levels::default(),
+    )
+}
@@ -0,0 +1,219 @@
+//! `roc docs --format json`, which writes everything we know about a package's docs to one
+//! `docs.json` file, for tools like docs portals and API diffs to consume.
+use crate::{modules_by_name, signature};
+use roc_load::docs::{AbilityMember, DocDef, DocEntry, ModuleDocumentation, RecordField};
+use roc_load::docs::{Tag, TypeAnnotation};
+use roc_load::LoadedModule;
+use roc_module::symbol::{Interns, ModuleId};
+use roc_region::all::LineInfo;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+pub fn write_json(
+    loaded_module: &LoadedModule,
+    root_dir: &Path,
+    out_dir: &Path,
+    package_name: &str,
+    version: &str,
+) {
+    let modules: Vec<Value> = modules_by_name(loaded_module)
+        .into_iter()
+        .map(|(module_id, module)| module_to_json(loaded_module, root_dir, module_id, module))
+        .collect();
+
+    let docs = json!({
+        "package": {
+            "name": package_name,
+            "version": version,
+        },
+        "modules": modules,
+    });
+
+    let path = out_dir.join("docs.json");
+
+    // Pretty-printed, so that the diffs between versions of it are readable
+    let contents = serde_json::to_string_pretty(&docs).expect("docs are always valid JSON");
+
+    fs::write(&path, contents).unwrap_or_else(|error| {
+        panic!(
+            "Attempted to write {} but failed with this error: {}",
+            path.display(),
+            error
+        )
+    });
+}
+
+fn module_to_json(
+    loaded_module: &LoadedModule,
+    root_dir: &Path,
+    module_id: ModuleId,
+    module: &ModuleDocumentation,
+) -> Value {
+    let (path, src) = &loaded_module.sources[&module_id];
+    let path = path.strip_prefix(root_dir).unwrap_or(path);
+    let lines = LineInfo::new(src);
+    let mut entries = Vec::with_capacity(module.entries.len());
+
+    for entry in &module.entries {
+        match entry {
+            DocEntry::DocDef(doc_def) => {
+                // Like the other formats, only what the module exposes is documented.
+                if module.exposed_symbols.contains(&doc_def.symbol) {
+                    entries.push(def_to_json(&loaded_module.interns, module, &lines, doc_def));
+                }
+            }
+            DocEntry::DetachedDoc(docs) => {
+                entries.push(json!({
+                    "kind": "docs",
+                    "docs": docs,
+                }));
+            }
+        }
+    }
+
+    json!({
+        "name": module.name,
+        "path": path.display().to_string(),
+        "entries": entries,
+    })
+}
+
+fn def_to_json(
+    interns: &Interns,
+    module: &ModuleDocumentation,
+    lines: &LineInfo,
+    doc_def: &DocDef,
+) -> Value {
+    let kind = match &doc_def.type_annotation {
+        TypeAnnotation::Ability { .. } => "ability",
+        _ if doc_def.name.starts_with(char::is_uppercase) => "type",
+        _ => "value",
+    };
+    let start = lines.convert_pos(doc_def.region.start());
+    let implements: Vec<&str> = module
+        .implementations
+        .get(&doc_def.symbol)
+        .map(|abilities| {
+            abilities
+                .iter()
+                .map(|ability| ability.as_str(interns))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    json!({
+        "kind": kind,
+        "name": doc_def.name,
+        "signature": signature(doc_def),
+        "type_vars": doc_def.type_vars,
+        "type": type_to_json(&doc_def.type_annotation),
+        "implements": implements,
+        "docs": doc_def.docs,
+        // 1-based, like in editors and compiler errors
+        "location": {
+            "line": start.line + 1,
+            "column": start.column + 1,
+        },
+    })
+}
+
+/// The structure of a type, for tools that want more than its `signature`.
+/// Defs without a type (like opaque types) have `null` for it.
+fn type_to_json(type_ann: &TypeAnnotation) -> Value {
+    match type_ann {
+        TypeAnnotation::TagUnion { tags, extension } => json!({
+            "kind": "tag_union",
+            "tags": tags.iter().map(tag_to_json).collect::<Vec<_>>(),
+            "extension": type_to_json(extension),
+        }),
+        TypeAnnotation::Function { args, output } => json!({
+            "kind": "function",
+            "args": args.iter().map(type_to_json).collect::<Vec<_>>(),
+            "output": type_to_json(output),
+        }),
+        TypeAnnotation::ObscuredTagUnion => json!({ "kind": "obscured_tag_union" }),
+        TypeAnnotation::ObscuredRecord => json!({ "kind": "obscured_record" }),
+        TypeAnnotation::BoundVariable(name) => json!({
+            "kind": "variable",
+            "name": name,
+        }),
+        TypeAnnotation::Apply { name, parts } => json!({
+            "kind": "apply",
+            "name": name,
+            "args": parts.iter().map(type_to_json).collect::<Vec<_>>(),
+        }),
+        TypeAnnotation::Record { fields, extension } => json!({
+            "kind": "record",
+            "fields": fields.iter().map(field_to_json).collect::<Vec<_>>(),
+            "extension": type_to_json(extension),
+        }),
+        TypeAnnotation::Ability { members } => json!({
+            "kind": "ability",
+            "members": members.iter().map(member_to_json).collect::<Vec<_>>(),
+        }),
+        TypeAnnotation::Where {
+            ann,
+            able_variables,
+        } => json!({
+            "kind": "where",
+            "type": type_to_json(ann),
+            "has": able_variables_to_json(able_variables),
+        }),
+        TypeAnnotation::Wildcard => json!({ "kind": "wildcard" }),
+        TypeAnnotation::NoTypeAnn => Value::Null,
+    }
+}
+
+fn tag_to_json(tag: &Tag) -> Value {
+    json!({
+        "name": tag.name,
+        "args": tag.values.iter().map(type_to_json).collect::<Vec<_>>(),
+    })
+}
+
+fn field_to_json(field: &RecordField) -> Value {
+    match field {
+        RecordField::RecordField {
+            name,
+            type_annotation,
+        } => json!({
+            "kind": "required",
+            "name": name,
+            "type": type_to_json(type_annotation),
+        }),
+        RecordField::OptionalField {
+            name,
+            type_annotation,
+        } => json!({
+            "kind": "optional",
+            "name": name,
+            "type": type_to_json(type_annotation),
+        }),
+        RecordField::LabelOnly { name } => json!({
+            "kind": "label_only",
+            "name": name,
+        }),
+    }
+}
+
+fn member_to_json(member: &AbilityMember) -> Value {
+    json!({
+        "name": member.name,
+        "type": type_to_json(&member.type_annotation),
+        "has": able_variables_to_json(&member.able_variables),
+        "docs": member.docs,
+    })
+}
+
+fn able_variables_to_json(able_variables: &[(String, Vec<TypeAnnotation>)]) -> Vec<Value> {
+    able_variables
+        .iter()
+        .map(|(var_name, abilities)| {
+            json!({
+                "variable": var_name,
+                "abilities": abilities.iter().map(type_to_json).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
@@ -1,40 +1,132 @@
-//! Generates html documentation from Roc files. Used for
-//! [roc-lang.org/builtins/Num](https://www.roc-lang.org/builtins/Num).
+//! Generates documentation from Roc files, as a website (which is what
+//! [roc-lang.org/builtins/Num](https://www.roc-lang.org/builtins/Num) is), JSON, or Markdown.
 extern crate pulldown_cmark;
 extern crate roc_load;
 use bumpalo::Bump;
 use roc_can::scope::Scope;
 use roc_collections::VecSet;
-use roc_load::docs::{DocEntry, TypeAnnotation};
+use roc_load::docs::{DocDef, DocEntry, TypeAnnotation};
 use roc_load::docs::{ModuleDocumentation, RecordField};
 use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
-use roc_module::symbol::{Interns, Symbol};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::ast::{Header, Module};
 use roc_parse::ident::{parse_ident, Accessor, Ident};
+use roc_parse::module::parse_header;
 use roc_parse::state::State;
 use roc_region::all::Region;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-const BUILD_DIR: &str = "./generated-docs";
+pub mod api_diff;
+pub mod doctest;
+mod json;
+mod markdown;
+
+/// Where the docs go if no other directory is given.
+pub const DEFAULT_OUTPUT_DIR: &str = "./generated-docs";
 
 const LINK_SVG: &str = include_str!("./static/link.svg");
 
-pub fn generate_docs_html(root_file: PathBuf) {
-    let build_dir = Path::new(BUILD_DIR);
+/// What `roc docs` generates
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocsFormat {
+    /// A website, with a page per module
+    Html,
+    /// One `docs.json` file, for tools to consume
+    Json,
+    /// A Markdown file per module
+    Markdown,
+}
+
+impl std::str::FromStr for DocsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "html" => Ok(DocsFormat::Html),
+            "json" => Ok(DocsFormat::Json),
+            "markdown" => Ok(DocsFormat::Markdown),
+            other => Err(format!("`{}` is not a docs format", other)),
+        }
+    }
+}
+
+pub fn generate_docs_html(root_file: PathBuf, build_dir: &Path) {
+    generate_docs(root_file, build_dir, DocsFormat::Html)
+}
+
+pub fn generate_docs(root_file: PathBuf, out_dir: &Path, format: DocsFormat) {
+    let root_dir = root_file
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
     let loaded_module = load_module_for_docs(root_file);
 
-    // TODO get these from the platform's source file rather than hardcoding them!
-    let package_name = "Documentation".to_string();
+    // Examples that don't compile get reported, but we still generate the docs;
+    // `roc test --doc` is what fails on them.
+    let example_problems = doctest::check_doc_examples(&loaded_module, &root_dir);
+
+    let package_name = package_name(&loaded_module);
+    // Package and platform headers don't have a version (yet).
     let version = String::new();
 
-    // Clear out the generated-docs dir (we'll create a fresh one at the end)
-    if build_dir.exists() {
-        fs::remove_dir_all(build_dir)
+    // Clear out the default generated-docs dir (we'll create a fresh one below).
+    // A directory that was passed in might have other files in it, so that one we leave alone.
+    if out_dir == Path::new(DEFAULT_OUTPUT_DIR) && out_dir.exists() {
+        fs::remove_dir_all(out_dir)
             .expect("TODO gracefully handle being unable to delete build dir");
     }
-    fs::create_dir_all(build_dir).expect("TODO gracefully handle being unable to create build dir");
+    fs::create_dir_all(out_dir).expect("TODO gracefully handle being unable to create build dir");
+
+    match format {
+        DocsFormat::Html => write_html(&loaded_module, out_dir, &package_name, &version),
+        DocsFormat::Json => {
+            json::write_json(&loaded_module, &root_dir, out_dir, &package_name, &version)
+        }
+        DocsFormat::Markdown => markdown::write_markdown(&loaded_module, out_dir, &package_name),
+    }
 
+    if example_problems.errors > 0 {
+        println!(
+            "Found {} {} in the examples in these docs.",
+            example_problems.errors,
+            match example_problems.errors {
+                1 => "error",
+                _ => "errors",
+            }
+        );
+    }
+
+    println!("🎉 Docs generated in {}", out_dir.display());
+}
+
+/// The name from the root module's package or platform header. Other kinds of modules
+/// don't have a package name, so they get a generic one.
+fn package_name(loaded_module: &LoadedModule) -> String {
+    let arena = Bump::new();
+    let (_, src) = &loaded_module.sources[&loaded_module.module_id];
+
+    match parse_header(&arena, State::new(src.as_bytes())) {
+        Ok((
+            Module {
+                header: Header::Package(header),
+                ..
+            },
+            _,
+        )) => header.name.value.to_str().to_string(),
+        Ok((
+            Module {
+                header: Header::Platform(header),
+                ..
+            },
+            _,
+        )) => header.name.value.to_str().to_string(),
+        _ => "Documentation".to_string(),
+    }
+}
+
+fn write_html(loaded_module: &LoadedModule, build_dir: &Path, package_name: &str, version: &str) {
     // Copy over the assets
     // For debug builds, read assets from fs to speed up build
     // Otherwise, include as string literal
@@ -137,17 +229,14 @@ pub fn generate_docs_html(root_file: PathBuf) {
     // Write index.html for package (/index.html)
     {
         let rendered_package = template_html
-            .replace(
-                "<!-- Page title -->",
-                page_title(package_name.as_str(), "").as_str(),
-            )
+            .replace("<!-- Page title -->", page_title(package_name, "").as_str())
             .replace(
                 "<!-- Package Name and Version -->",
-                render_name_and_version(package_name.as_str(), version.as_str()).as_str(),
+                render_name_and_version(package_name, version).as_str(),
             )
             .replace(
                 "<!-- Module Docs -->",
-                render_package_index(&loaded_module).as_str(),
+                render_package_index(loaded_module).as_str(),
             );
 
         fs::write(build_dir.join("index.html"), rendered_package).unwrap_or_else(|error| {
@@ -169,23 +258,21 @@ pub fn generate_docs_html(root_file: PathBuf) {
         let rendered_module = template_html
             .replace(
                 "<!-- Page title -->",
-                page_title(package_name.as_str(), module_name).as_str(),
+                page_title(package_name, module_name).as_str(),
             )
             .replace(
                 "<!-- Package Name and Version -->",
-                render_name_and_version(package_name.as_str(), version.as_str()).as_str(),
+                render_name_and_version(package_name, version).as_str(),
             )
             .replace(
                 "<!-- Module Docs -->",
-                render_module_documentation(module_docs, &loaded_module, &all_exposed_symbols)
+                render_module_documentation(module_docs, loaded_module, &all_exposed_symbols)
                     .as_str(),
             );
 
         fs::write(module_dir.join("index.html"), rendered_module)
             .expect("TODO gracefully handle failing to write index.html inside module's dir");
     }
-
-    println!("🎉 Docs generated in {}", build_dir.display());
 }
 
 fn module_link_url(module_name: &str) -> String {
@@ -262,18 +349,7 @@ fn render_module_documentation(
 
                     push_html(&mut content, "a", vec![("href", href.as_str())], LINK_SVG);
                     push_html(&mut content, "strong", vec![], name);
-
-                    for type_var in &doc_def.type_vars {
-                        content.push(' ');
-                        content.push_str(type_var.as_str());
-                    }
-
-                    let type_ann = &doc_def.type_annotation;
-
-                    if !matches!(type_ann, TypeAnnotation::NoTypeAnn) {
-                        content.push_str(" : ");
-                        type_annotation_to_html(0, &mut content, type_ann, false);
-                    }
+                    push_signature(&mut content, doc_def);
 
                     push_html(
                         &mut buf,
@@ -282,6 +358,14 @@ fn render_module_documentation(
                         content.as_str(),
                     );
 
+                    if let Some(abilities) = module.implementations.get(&doc_def.symbol) {
+                        buf.push_str(&render_implementations(
+                            abilities,
+                            root_module,
+                            all_exposed_symbols,
+                        ));
+                    }
+
                     if let Some(docs) = &doc_def.docs {
                         markdown_to_html(
                             &mut buf,
@@ -310,6 +394,97 @@ fn render_module_documentation(
     buf
 }
 
+/// Everything in a def's signature that comes after its name, like the ` k v : ...` in
+/// `Dict k v : ...`. Types have nothing in them that HTML would need escaped, so this works
+/// for HTML as well as plain text.
+fn push_signature(buf: &mut String, doc_def: &DocDef) {
+    for type_var in &doc_def.type_vars {
+        buf.push(' ');
+        buf.push_str(type_var.as_str());
+    }
+
+    let type_ann = &doc_def.type_annotation;
+
+    match type_ann {
+        TypeAnnotation::NoTypeAnn => {}
+        TypeAnnotation::Ability { .. } => {
+            buf.push_str(" has");
+            type_annotation_to_html(0, buf, type_ann, false);
+        }
+        _ => {
+            buf.push_str(" : ");
+            type_annotation_to_html(0, buf, type_ann, false);
+        }
+    }
+}
+
+/// The whole signature of a def, like `len : List a -> U64`
+fn signature(doc_def: &DocDef) -> String {
+    let mut buf = doc_def.name.clone();
+
+    push_signature(&mut buf, doc_def);
+
+    buf
+}
+
+/// The docs of each module, sorted by module name. Modules finish loading in whatever order
+/// the threads get to them, so this keeps the generated files the same from run to run.
+fn modules_by_name(loaded_module: &LoadedModule) -> Vec<(ModuleId, &ModuleDocumentation)> {
+    let mut modules: Vec<_> = loaded_module
+        .docs_by_module
+        .iter()
+        .map(|(module_id, module)| (*module_id, module))
+        .collect();
+
+    modules.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+
+    modules
+}
+
+/// The abilities an opaque type implements, linked to their docs when we have them.
+fn render_implementations(
+    abilities: &[Symbol],
+    root_module: &LoadedModule,
+    all_exposed_symbols: &VecSet<Symbol>,
+) -> String {
+    let mut content = String::from("Implements ");
+
+    for (index, ability) in abilities.iter().enumerate() {
+        if index > 0 {
+            content.push_str(", ");
+        }
+
+        let name = ability.as_str(&root_module.interns);
+        let mut code = String::new();
+
+        if ability.is_builtin() || all_exposed_symbols.contains(ability) {
+            let href = format!(
+                "{}{}#{}",
+                base_url(),
+                ability.module_string(&root_module.interns),
+                name
+            );
+
+            push_html(&mut code, "a", vec![("href", href.as_str())], name);
+        } else {
+            code.push_str(name);
+        }
+
+        push_html(&mut content, "code", vec![], code);
+    }
+
+    let mut buf = String::new();
+
+    push_html(
+        &mut buf,
+        "p",
+        vec![("class", "entry-implementations")],
+        content,
+    );
+
+    buf
+}
+
 fn push_html(buf: &mut String, tag_name: &str, attrs: Vec<(&str, &str)>, content: impl AsRef<str>) {
     buf.push('<');
     buf.push_str(tag_name);
@@ -460,7 +635,8 @@ pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
         render: roc_reporting::report::RenderTarget::ColorTerminal,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::AllAvailable,
-        exec_mode: ExecutionMode::Check,
+        exec_mode: ExecutionMode::Docs,
+        typed_holes: false,
     };
     match roc_load::load_and_typecheck(
         &arena,
@@ -685,8 +861,23 @@ fn type_annotation_to_html(
                 buf.push(')');
             }
         }
-        TypeAnnotation::Ability { members: _ } => {
-            // TODO(abilities): fill me in
+        TypeAnnotation::Ability { members } => {
+            for member in members {
+                new_line(buf);
+                indent(buf, indent_level + 1);
+
+                buf.push_str(member.name.as_str());
+                buf.push_str(" : ");
+                type_annotation_to_html(indent_level + 1, buf, &member.type_annotation, false);
+                able_variables_to_html(indent_level + 1, buf, &member.able_variables);
+            }
+        }
+        TypeAnnotation::Where {
+            ann,
+            able_variables,
+        } => {
+            type_annotation_to_html(indent_level, buf, ann, needs_parens);
+            able_variables_to_html(indent_level, buf, able_variables);
         }
         TypeAnnotation::ObscuredTagUnion => {
             buf.push_str("[@..]");
@@ -699,6 +890,27 @@ fn type_annotation_to_html(
     }
 }
 
+/// A `has` clause, like ` | a has Hash & Eq, b has Decoding`
+fn able_variables_to_html(
+    indent_level: usize,
+    buf: &mut String,
+    able_variables: &[(String, Vec<TypeAnnotation>)],
+) {
+    for (index, (var_name, abilities)) in able_variables.iter().enumerate() {
+        buf.push_str(if index == 0 { " | " } else { ", " });
+        buf.push_str(var_name);
+        buf.push_str(" has ");
+
+        for (index, ability) in abilities.iter().enumerate() {
+            if index > 0 {
+                buf.push_str(" & ");
+            }
+
+            type_annotation_to_html(indent_level, buf, ability, true);
+        }
+    }
+}
+
 fn should_be_multiline(type_ann: &TypeAnnotation) -> bool {
     match type_ann {
         TypeAnnotation::TagUnion { tags, extension } => {
@@ -765,6 +977,7 @@ fn should_be_multiline(type_ann: &TypeAnnotation) -> bool {
             is_multiline
         }
         TypeAnnotation::Ability { .. } => true,
+        TypeAnnotation::Where { ann, .. } => should_be_multiline(ann),
         TypeAnnotation::Wildcard => false,
         TypeAnnotation::NoTypeAnn => false,
     }
@@ -958,27 +1171,10 @@ fn markdown_to_html(
                 in_code_block = Some(cow_str);
             }
             Event::End(CodeBlock(_)) => {
-                match in_code_block {
-                    Some(cow_str) => {
-                        if cow_str.contains("unchecked") {
-                            // TODO HANDLE UNCHECKED
-                        }
-
-                        if cow_str.contains("repl") {
-                            // TODO HANDLE REPL
-                        }
-
-                        // TODO HANDLE CHECKING BY DEFAULT
-                        let highlighted_html = roc_highlight::highlight_roc_code(&to_highlight);
-                        docs_parser.push(Event::Html(CowStr::from(highlighted_html)));
-                    }
-                    None => {
-                        // Indented code block
-
-                        let highlighted_html = roc_highlight::highlight_roc_code(&to_highlight);
-                        docs_parser.push(Event::Html(CowStr::from(highlighted_html)));
-                    }
-                }
+                // Examples get checked separately (see the doctest module), so whatever
+                // their tags, fenced and indented code blocks just get highlighted here.
+                let highlighted_html = roc_highlight::highlight_roc_code(&to_highlight);
+                docs_parser.push(Event::Html(CowStr::from(highlighted_html)));
 
                 // Reset codeblock buffer
                 to_highlight = String::new();
@@ -0,0 +1,73 @@
+//! `roc docs --format markdown`, which writes a Markdown file per module, plus an index
+//! that links to them. Doc comments are Markdown already, so they're copied over as they are.
+use crate::{modules_by_name, signature};
+use roc_load::docs::{DocEntry, ModuleDocumentation};
+use roc_load::LoadedModule;
+use std::fs;
+use std::path::Path;
+
+pub fn write_markdown(loaded_module: &LoadedModule, out_dir: &Path, package_name: &str) {
+    let mut index = format!("# {}\n\n## Exposed Modules\n\n", package_name);
+
+    for (_, module) in modules_by_name(loaded_module) {
+        let file_name = format!("{}.md", module.name);
+
+        index.push_str(&format!("- [{}]({})\n", module.name, file_name));
+
+        write_file(
+            &out_dir.join(file_name),
+            render_module(loaded_module, module),
+        );
+    }
+
+    write_file(&out_dir.join("index.md"), index);
+}
+
+fn render_module(loaded_module: &LoadedModule, module: &ModuleDocumentation) -> String {
+    let mut buf = format!("# {}\n", module.name);
+
+    for entry in &module.entries {
+        match entry {
+            DocEntry::DocDef(doc_def) => {
+                if !module.exposed_symbols.contains(&doc_def.symbol) {
+                    continue;
+                }
+
+                buf.push_str(&format!("\n## {}\n\n", doc_def.name));
+                buf.push_str(&format!("```roc\n{}\n```\n", signature(doc_def)));
+
+                if let Some(abilities) = module.implementations.get(&doc_def.symbol) {
+                    let names: Vec<String> = abilities
+                        .iter()
+                        .map(|ability| format!("`{}`", ability.as_str(&loaded_module.interns)))
+                        .collect();
+
+                    buf.push_str(&format!("\nImplements {}\n", names.join(", ")));
+                }
+
+                if let Some(docs) = &doc_def.docs {
+                    buf.push('\n');
+                    buf.push_str(docs.trim_end());
+                    buf.push('\n');
+                }
+            }
+            DocEntry::DetachedDoc(docs) => {
+                buf.push('\n');
+                buf.push_str(docs.trim_end());
+                buf.push('\n');
+            }
+        }
+    }
+
+    buf
+}
+
+fn write_file(path: &Path, contents: String) {
+    fs::write(path, contents).unwrap_or_else(|error| {
+        panic!(
+            "Attempted to write {} but failed with this error: {}",
+            path.display(),
+            error
+        )
+    });
+}
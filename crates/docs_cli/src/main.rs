@@ -1,8 +1,8 @@
 //! Provides a binary that is only used for static build servers.
 use clap::{value_parser, Arg, Command};
-use roc_docs::generate_docs_html;
+use roc_docs::{generate_docs_html, DEFAULT_OUTPUT_DIR};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub const ROC_FILE: &str = "ROC_FILE";
 const DEFAULT_ROC_FILENAME: &str = "main.roc";
@@ -20,7 +20,10 @@ fn main() -> io::Result<()> {
         .get_matches();
 
     // Populate roc_files
-    generate_docs_html(matches.get_one::<PathBuf>(ROC_FILE).unwrap().to_owned());
+    generate_docs_html(
+        matches.get_one::<PathBuf>(ROC_FILE).unwrap().to_owned(),
+        Path::new(DEFAULT_OUTPUT_DIR),
+    );
 
     Ok(())
 }
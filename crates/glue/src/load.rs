@@ -14,6 +14,7 @@ use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threadin
 use roc_mono::ir::{generate_glue_procs, GlueProc, OptLevel};
 use roc_mono::layout::{GlobalLayoutInterner, LayoutCache, LayoutInterner};
 use roc_packaging::cache::{self, RocCacheDir};
+use roc_problem::Levels;
 use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
 use roc_target::{Architecture, TargetInfo};
 use roc_types::subs::{Subs, Variable};
@@ -82,6 +83,7 @@ pub fn generate(
                 None,
                 RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
                 load_config,
+                &Levels::default(),
             );
 
             match res_binary_path {
@@ -191,9 +193,13 @@ pub fn generate(
 
                     Ok(0)
                 }
-                Err(BuildFileError::ErrorModule { module, total_time }) => {
-                    handle_error_module(module, total_time, spec_path.as_os_str(), true)
-                }
+                Err(BuildFileError::ErrorModule { module, total_time }) => handle_error_module(
+                    module,
+                    total_time,
+                    spec_path.as_os_str(),
+                    true,
+                    &Levels::default(),
+                ),
                 Err(BuildFileError::LoadingProblem(problem)) => handle_loading_problem(problem),
             }
         }
@@ -354,6 +360,7 @@ pub fn load_types(
             palette: DEFAULT_PALETTE,
             threading,
             exec_mode: ExecutionMode::Check,
+            typed_holes: false,
         },
     )
     .unwrap_or_else(|problem| match problem {
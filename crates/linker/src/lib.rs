@@ -105,6 +105,7 @@ pub fn generate_stub_lib(
             palette: DEFAULT_PALETTE,
             threading: Threading::AllAvailable,
             exec_mode: ExecutionMode::Executable,
+            typed_holes: false,
         },
     )
     .unwrap_or_else(|problem| todo!("{:?}", problem));
@@ -0,0 +1,143 @@
+//! Per-project lint settings, read from a `roc-lint.json` next to the app or package's main file:
+//!
+//! ```json
+//! {
+//!     "levels": { "crash-in-library": "deny", "large-tuple": "allow" },
+//!     "max-tuple-size": 3
+//! }
+//! ```
+use crate::LintKind;
+use roc_problem::Level;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILENAME: &str = "roc-lint.json";
+
+/// Tuples with more elements than this are usually clearer as records.
+const DEFAULT_MAX_TUPLE_SIZE: usize = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LintConfig {
+    levels: BTreeMap<LintKind, Level>,
+    pub max_tuple_size: usize,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            levels: BTreeMap::new(),
+            max_tuple_size: DEFAULT_MAX_TUPLE_SIZE,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum RawLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl From<RawLevel> for Level {
+    fn from(level: RawLevel) -> Self {
+        match level {
+            RawLevel::Allow => Level::Allow,
+            RawLevel::Warn => Level::Warn,
+            RawLevel::Deny => Level::Deny,
+        }
+    }
+}
+
+/// The contents of a config file, before we've checked that the lint names are real.
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RawConfig {
+    #[serde(default)]
+    levels: BTreeMap<String, RawLevel>,
+    max_tuple_size: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Json(PathBuf, serde_json::Error),
+    UnknownLint(PathBuf, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "Could not read {}: {}", path.display(), err),
+            ConfigError::Json(path, err) => {
+                write!(f, "{} is not a valid lint config: {}", path.display(), err)
+            }
+            ConfigError::UnknownLint(path, name) => write!(
+                f,
+                "{} sets a level for `{}`, but there is no lint with that name. The lints are: {}",
+                path.display(),
+                name,
+                LintKind::ALL
+                    .iter()
+                    .map(|kind| kind.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl LintConfig {
+    /// Read the config in `dir`, or use the defaults if there isn't one.
+    pub fn load(dir: &Path) -> Result<Self, ConfigError> {
+        let path = dir.join(CONFIG_FILENAME);
+
+        match std::fs::read_to_string(&path) {
+            Ok(json) => Self::from_json(&json).map_err(|err| match err {
+                ConfigError::Json(_, err) => ConfigError::Json(path, err),
+                ConfigError::UnknownLint(_, name) => ConfigError::UnknownLint(path, name),
+                ConfigError::Io(_, err) => ConfigError::Io(path, err),
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(ConfigError::Io(path, err)),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        let raw: RawConfig = serde_json::from_str(json)
+            .map_err(|err| ConfigError::Json(PathBuf::from(CONFIG_FILENAME), err))?;
+        let mut config = LintConfig::default();
+
+        for (name, level) in raw.levels {
+            match LintKind::from_name(&name) {
+                Some(kind) => config.set_level(kind, level.into()),
+                None => {
+                    return Err(ConfigError::UnknownLint(
+                        PathBuf::from(CONFIG_FILENAME),
+                        name,
+                    ))
+                }
+            }
+        }
+
+        if let Some(max_tuple_size) = raw.max_tuple_size {
+            config.max_tuple_size = max_tuple_size;
+        }
+
+        Ok(config)
+    }
+
+    pub fn level(&self, kind: LintKind) -> Level {
+        self.levels
+            .get(&kind)
+            .copied()
+            .unwrap_or_else(|| kind.default_level())
+    }
+
+    pub fn set_level(&mut self, kind: LintKind, level: Level) {
+        self.levels.insert(kind, level);
+    }
+}
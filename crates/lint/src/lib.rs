@@ -0,0 +1,340 @@
+//! Checks for code that compiles, but could be clearer or more robust, which `roc lint` runs.
+//!
+//! Each check has a name (e.g. `large-tuple`) that projects use to configure it in their
+//! `roc-lint.json`, and that a `# lint: allow(large-tuple)` comment above a def uses to turn it off
+//! for just that def.
+use bumpalo::Bump;
+use roc_can::def::Def;
+use roc_can::expr::{Declarations, Expr};
+use roc_can::traverse::{walk_decl, walk_def, walk_expr, DeclarationInfo, Visitor};
+use roc_module::called_via::{BinOp, CalledVia};
+use roc_module::symbol::Symbol;
+use roc_parse::ast::{Header, Module};
+use roc_parse::module::parse_header;
+use roc_parse::state::State;
+use roc_region::all::{Loc, Position, Region};
+use roc_types::subs::Variable;
+
+pub mod config;
+pub mod report;
+
+pub use config::{ConfigError, LintConfig};
+pub use roc_problem::Level;
+
+/// There's no lint for names shadowed by backpassing (e.g. `x <- ...` when `x` is already in
+/// scope), because Roc doesn't allow shadowing anywhere. A backpassed argument is a closure
+/// argument, so canonicalization already reports it as `RuntimeError::Shadowing`, which is an
+/// error rather than something a lint could warn about or let a project allow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintKind {
+    BoolLiteralComparison,
+    ListLenZero,
+    LargeTuple,
+    CrashInLibrary,
+}
+
+impl LintKind {
+    pub const ALL: [LintKind; 4] = [
+        LintKind::BoolLiteralComparison,
+        LintKind::ListLenZero,
+        LintKind::LargeTuple,
+        LintKind::CrashInLibrary,
+    ];
+
+    /// The name to use for this lint in config files and `# lint: allow(...)` comments.
+    pub const fn name(self) -> &'static str {
+        match self {
+            LintKind::BoolLiteralComparison => "bool-literal-comparison",
+            LintKind::ListLenZero => "list-len-zero",
+            LintKind::LargeTuple => "large-tuple",
+            LintKind::CrashInLibrary => "crash-in-library",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub const fn default_level(self) -> Level {
+        match self {
+            LintKind::BoolLiteralComparison
+            | LintKind::ListLenZero
+            | LintKind::LargeTuple
+            | LintKind::CrashInLibrary => Level::Warn,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Lint {
+    /// e.g. `x == Bool.true`, which is just `x`
+    BoolLiteralComparison {
+        region: Region,
+        literal: bool,
+        /// `true` for `==`, `false` for `!=`
+        equal: bool,
+        /// The side of the comparison that isn't the literal
+        operand: Region,
+    },
+    /// e.g. `List.len list == 0`, which is `List.isEmpty list`
+    ListLenZero {
+        region: Region,
+        list: Region,
+        /// Whether the comparison is true for empty lists
+        empty: bool,
+    },
+    LargeTuple {
+        region: Region,
+        size: usize,
+        max_size: usize,
+    },
+    /// A `crash` in an interface or package module
+    CrashInLibrary { region: Region },
+}
+
+impl Lint {
+    pub fn kind(&self) -> LintKind {
+        match self {
+            Lint::BoolLiteralComparison { .. } => LintKind::BoolLiteralComparison,
+            Lint::ListLenZero { .. } => LintKind::ListLenZero,
+            Lint::LargeTuple { .. } => LintKind::LargeTuple,
+            Lint::CrashInLibrary { .. } => LintKind::CrashInLibrary,
+        }
+    }
+
+    pub fn region(&self) -> Region {
+        match self {
+            Lint::BoolLiteralComparison { region, .. }
+            | Lint::ListLenZero { region, .. }
+            | Lint::LargeTuple { region, .. }
+            | Lint::CrashInLibrary { region } => *region,
+        }
+    }
+}
+
+/// Run every lint that `config` doesn't allow over the declarations of the module whose source
+/// is `src`.
+pub fn lint_module(src: &str, decls: &Declarations, config: &LintConfig) -> Vec<Lint> {
+    let mut linter = Linter {
+        src,
+        config,
+        is_library: is_library(src),
+        allowed: Vec::new(),
+        lints: Vec::new(),
+    };
+
+    linter.visit_decls(decls);
+
+    linter.lints
+}
+
+struct Linter<'a> {
+    src: &'a str,
+    config: &'a LintConfig,
+    is_library: bool,
+    /// The lints allowed by comments on the defs we're currently inside of
+    allowed: Vec<LintKind>,
+    lints: Vec<Lint>,
+}
+
+impl Linter<'_> {
+    fn push(&mut self, lint: Lint) {
+        let kind = lint.kind();
+
+        if self.config.level(kind) != Level::Allow && !self.allowed.contains(&kind) {
+            self.lints.push(lint);
+        }
+    }
+
+    fn with_allowed(&mut self, def_start: Position, visit: impl FnOnce(&mut Self)) {
+        let outer_allowed = self.allowed.len();
+
+        self.allowed
+            .extend(allowed_by_comments(self.src, def_start.offset as usize));
+        visit(self);
+        self.allowed.truncate(outer_allowed);
+    }
+
+    fn check_expr(&mut self, expr: &Expr, region: Region) {
+        match expr {
+            Expr::Call(_, args, CalledVia::BinOp(op)) if args.len() == 2 => {
+                let (lhs, rhs) = (&args[0].1, &args[1].1);
+
+                match op {
+                    BinOp::Equals | BinOp::NotEquals => {
+                        let equal = *op == BinOp::Equals;
+
+                        if let Some((literal, operand)) = bool_literal(lhs)
+                            .map(|literal| (literal, rhs))
+                            .or_else(|| bool_literal(rhs).map(|literal| (literal, lhs)))
+                        {
+                            self.push(Lint::BoolLiteralComparison {
+                                region,
+                                literal,
+                                equal,
+                                operand: operand.region,
+                            });
+                        } else if let Some(list) = len_compared_to_zero(lhs, rhs)
+                            .or_else(|| len_compared_to_zero(rhs, lhs))
+                        {
+                            self.push(Lint::ListLenZero {
+                                region,
+                                list,
+                                empty: equal,
+                            });
+                        }
+                    }
+                    BinOp::GreaterThan => {
+                        if let Some(list) = len_compared_to_zero(lhs, rhs) {
+                            self.push(Lint::ListLenZero {
+                                region,
+                                list,
+                                empty: false,
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Expr::Tuple { elems, .. } if elems.len() > self.config.max_tuple_size => {
+                self.push(Lint::LargeTuple {
+                    region,
+                    size: elems.len(),
+                    max_size: self.config.max_tuple_size,
+                });
+            }
+            Expr::Crash { .. } if self.is_library => {
+                self.push(Lint::CrashInLibrary { region });
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Visitor for Linter<'_> {
+    fn visit_decl(&mut self, decl: DeclarationInfo<'_>) {
+        self.with_allowed(decl.region().start(), |linter| walk_decl(linter, decl));
+    }
+
+    fn visit_def(&mut self, def: &Def) {
+        self.with_allowed(def.loc_pattern.region.start(), |linter| {
+            walk_def(linter, def)
+        });
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        self.check_expr(expr, region);
+        walk_expr(self, expr, var);
+    }
+}
+
+fn bool_literal(expr: &Loc<Expr>) -> Option<bool> {
+    match expr.value {
+        Expr::Var(Symbol::BOOL_TRUE, _) => Some(true),
+        Expr::Var(Symbol::BOOL_FALSE, _) => Some(false),
+        _ => None,
+    }
+}
+
+/// If `len` is `List.len list` and `zero` is `0`, the region of `list`.
+fn len_compared_to_zero(len: &Loc<Expr>, zero: &Loc<Expr>) -> Option<Region> {
+    let is_zero = match &zero.value {
+        Expr::Num(_, _, value, _) | Expr::Int(_, _, _, value, _) => value.to_string() == "0",
+        _ => false,
+    };
+
+    match &len.value {
+        Expr::Call(fn_expr, args, _) if is_zero && args.len() == 1 => {
+            matches!(fn_expr.1.value, Expr::Var(Symbol::LIST_LEN, _)).then_some(args[0].1.region)
+        }
+        _ => None,
+    }
+}
+
+/// Interface and package modules are libraries; apps, platforms, and hosted modules aren't.
+fn is_library(src: &str) -> bool {
+    let arena = Bump::new();
+
+    matches!(
+        parse_header(&arena, State::new(src.as_bytes())),
+        Ok((
+            Module {
+                header: Header::Interface(_) | Header::Package(_),
+                ..
+            },
+            _
+        ))
+    )
+}
+
+fn line_start(src: &str, offset: usize) -> usize {
+    src[..offset].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// The lints allowed by `# lint: allow(...)` comments right above the def that starts at `offset`,
+/// or right above its annotation. Names that aren't lints are ignored.
+fn allowed_by_comments(src: &str, offset: usize) -> Vec<LintKind> {
+    let def_line_start = line_start(src, offset);
+    let indent = indentation(&src[def_line_start..]);
+    let mut allowed = Vec::new();
+    let mut cursor = def_line_start;
+
+    while cursor > 0 {
+        let previous = line_start(src, cursor - 1);
+        let line = &src[previous..cursor];
+
+        cursor = previous;
+
+        if line.trim().is_empty() || indentation(line) < indent {
+            break;
+        }
+
+        if indentation(line) > indent {
+            // This line is part of a multiline annotation (or the previous def's body,
+            // in which case the next line up will end the search).
+            continue;
+        }
+
+        let line = line.trim();
+
+        match line.strip_prefix('#') {
+            Some(comment) => allowed.extend(allowed_by_comment(comment)),
+            None if is_annotation(line) => {}
+            None => break,
+        }
+    }
+
+    allowed
+}
+
+fn allowed_by_comment(comment: &str) -> Vec<LintKind> {
+    comment
+        .trim()
+        .strip_prefix("lint:")
+        .and_then(|rest| rest.trim().strip_prefix("allow("))
+        .and_then(|rest| rest.trim_end().strip_suffix(')'))
+        .map(|names| {
+            names
+                .split(',')
+                .filter_map(|name| LintKind::from_name(name.trim()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_annotation(line: &str) -> bool {
+    match line.split_once(':') {
+        Some((name, rest)) => {
+            let name = name.trim_end();
+
+            !rest.starts_with('=')
+                && name.starts_with(|c: char| c.is_ascii_lowercase())
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,158 @@
+use crate::config::CONFIG_FILENAME;
+use crate::{Lint, LintKind};
+use roc_module::symbol::Symbol;
+use roc_problem::Severity;
+use roc_region::all::{LineInfo, Region};
+use roc_reporting::report::{Report, RocDocAllocator, RocDocBuilder};
+use std::path::PathBuf;
+use ven_pretty::DocAllocator;
+
+pub fn lint_report<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    lines: &LineInfo,
+    filename: PathBuf,
+    src: &str,
+    lint: Lint,
+    severity: Severity,
+) -> Report<'b> {
+    let kind = lint.kind();
+    let title;
+    let doc;
+
+    match lint {
+        Lint::BoolLiteralComparison {
+            region,
+            literal,
+            equal,
+            operand,
+        } => {
+            let operand = &src[region_range(operand)];
+            let suggestion = if literal == equal {
+                operand.to_string()
+            } else {
+                format!("!{}", parenthesized(operand))
+            };
+
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This compares a value to "),
+                    alloc.symbol_qualified(if literal {
+                        Symbol::BOOL_TRUE
+                    } else {
+                        Symbol::BOOL_FALSE
+                    }),
+                    alloc.reflow(":"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.reflow("That's the same as using the value directly, like this:"),
+                alloc.string(suggestion).indent(4),
+                allow_tip(alloc, kind),
+            ]);
+
+            title = "REDUNDANT BOOL COMPARISON".to_string();
+        }
+        Lint::ListLenZero {
+            region,
+            list,
+            empty,
+        } => {
+            let is_empty = format!("List.isEmpty {}", parenthesized(&src[region_range(list)]));
+            let suggestion = if empty {
+                is_empty
+            } else {
+                format!("!({})", is_empty)
+            };
+
+            doc = alloc.stack([
+                alloc
+                    .reflow("This checks whether a list is empty by comparing its length to zero:"),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.symbol_qualified(Symbol::LIST_IS_EMPTY),
+                    alloc.reflow(" says that more directly:"),
+                ]),
+                alloc.string(suggestion).indent(4),
+                allow_tip(alloc, kind),
+            ]);
+
+            title = "LIST LENGTH COMPARED TO ZERO".to_string();
+        }
+        Lint::LargeTuple {
+            region,
+            size,
+            max_size,
+        } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This tuple has "),
+                    alloc.string(size.to_string()),
+                    alloc.reflow(" elements:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.reflow(
+                    r#"The elements of a tuple don't have names, so in tuples this big it's hard to tell what each of them means. A record would make that clear."#,
+                ),
+                alloc.concat([
+                    alloc.reflow("This project allows tuples of up to "),
+                    alloc.string(max_size.to_string()),
+                    alloc.reflow(" elements, which you can change with "),
+                    alloc.keyword("max-tuple-size"),
+                    alloc.reflow(" in "),
+                    alloc.keyword(CONFIG_FILENAME),
+                    alloc.reflow("."),
+                ]),
+                allow_tip(alloc, kind),
+            ]);
+
+            title = "LARGE TUPLE".to_string();
+        }
+        Lint::CrashInLibrary { region } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This "),
+                    alloc.keyword("crash"),
+                    alloc.reflow(" is in a library module:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.reflow(
+                    r#"Code that uses this library has no way to recover from a crash. Returning a Result instead would let it decide what to do."#,
+                ),
+                allow_tip(alloc, kind),
+            ]);
+
+            title = "CRASH IN LIBRARY".to_string();
+        }
+    }
+
+    Report {
+        title,
+        filename,
+        doc,
+        severity,
+    }
+}
+
+fn allow_tip<'b>(alloc: &'b RocDocAllocator<'b>, kind: LintKind) -> RocDocBuilder<'b> {
+    alloc.tip().append(alloc.concat([
+        alloc.reflow("To allow this here, put "),
+        alloc.string(format!("# lint: allow({})", kind.name())),
+        alloc.reflow(" above the def it's in."),
+    ]))
+}
+
+fn region_range(region: Region) -> std::ops::Range<usize> {
+    region.start().offset as usize..region.end().offset as usize
+}
+
+/// Wrap `code` in parens, unless it's simple enough to go anywhere as it is.
+fn parenthesized(code: &str) -> String {
+    let is_simple = !code.contains(char::is_whitespace)
+        || (code.starts_with('(') && code.ends_with(')'))
+        || (code.starts_with('[') && code.ends_with(']'));
+
+    if is_simple {
+        code.to_string()
+    } else {
+        format!("({})", code)
+    }
+}
@@ -0,0 +1,193 @@
+#[macro_use]
+extern crate indoc;
+#[macro_use]
+extern crate pretty_assertions;
+
+#[cfg(test)]
+mod test_lint {
+    use bumpalo::Bump;
+    use roc_lint::config::ConfigError;
+    use roc_lint::{lint_module, Level, Lint, LintConfig, LintKind};
+    use roc_load::LoadedModule;
+    use roc_packaging::cache::RocCacheDir;
+    use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+    use roc_target::TargetInfo;
+    use std::path::PathBuf;
+
+    fn load(src: &str) -> LoadedModule {
+        let arena = Bump::new();
+
+        roc_load::load_and_typecheck_str(
+            &arena,
+            PathBuf::from("Test.roc"),
+            arena.alloc_str(src),
+            std::env::temp_dir(),
+            TargetInfo::default_x86_64(),
+            RenderTarget::Generic,
+            RocCacheDir::Disallowed,
+            DEFAULT_PALETTE,
+        )
+        .unwrap()
+    }
+
+    fn lints_with(config: &LintConfig, src: &str) -> Vec<Lint> {
+        let loaded = load(src);
+        let decls = &loaded.declarations_by_id[&loaded.module_id];
+
+        lint_module(src, decls, config)
+    }
+
+    fn lint_kinds(src: &str) -> Vec<LintKind> {
+        lints_with(&LintConfig::default(), src)
+            .iter()
+            .map(Lint::kind)
+            .collect()
+    }
+
+    #[test]
+    fn bool_literal_comparison() {
+        let src = indoc!(
+            r#"
+            interface Test exposes [isOn, isOff] imports []
+
+            isOn = \x -> x == Bool.true
+
+            isOff = \x -> Bool.true != x
+            "#
+        );
+
+        let lints = lints_with(&LintConfig::default(), src);
+
+        assert_eq!(
+            lints
+                .iter()
+                .map(|lint| match lint {
+                    Lint::BoolLiteralComparison { equal, literal, .. } => (*literal, *equal),
+                    other => panic!("unexpected lint {:?}", other),
+                })
+                .collect::<Vec<_>>(),
+            vec![(true, true), (true, false)]
+        );
+    }
+
+    #[test]
+    fn list_len_zero() {
+        let src = indoc!(
+            r#"
+            interface Test exposes [isEmpty, isNotEmpty, hasItems] imports []
+
+            isEmpty = \list -> List.len list == 0
+
+            isNotEmpty = \list -> 0 != List.len list
+
+            hasItems = \list -> List.len list > 0
+            "#
+        );
+
+        let lints = lints_with(&LintConfig::default(), src);
+
+        assert_eq!(
+            lints
+                .iter()
+                .map(|lint| match lint {
+                    Lint::ListLenZero { empty, .. } => *empty,
+                    other => panic!("unexpected lint {:?}", other),
+                })
+                .collect::<Vec<_>>(),
+            vec![true, false, false]
+        );
+    }
+
+    #[test]
+    fn large_tuple() {
+        let src = indoc!(
+            r#"
+            interface Test exposes [small, big] imports []
+
+            small = (1, 2, 3)
+
+            big = (1, 2, 3, 4, 5)
+            "#
+        );
+
+        assert_eq!(lint_kinds(src), vec![LintKind::LargeTuple]);
+
+        let config = LintConfig::from_json(r#"{ "max-tuple-size": 2 }"#).unwrap();
+
+        assert_eq!(lints_with(&config, src).len(), 2);
+    }
+
+    #[test]
+    fn crash_in_library() {
+        let src = indoc!(
+            r#"
+            interface Test exposes [first] imports []
+
+            first = \list ->
+                when List.first list is
+                    Ok elem -> elem
+                    Err _ -> crash "empty list"
+            "#
+        );
+
+        assert_eq!(lint_kinds(src), vec![LintKind::CrashInLibrary]);
+    }
+
+    #[test]
+    fn allowed_by_comment() {
+        let src = indoc!(
+            r#"
+            interface Test exposes [big, bigger, isOn] imports []
+
+            # lint: allow(large-tuple)
+            big : (U8, U8, U8, U8, U8)
+            big = (1, 2, 3, 4, 5)
+
+            bigger =
+                # lint: allow(large-tuple, bool-literal-comparison)
+                inner = (1, 2, 3, 4, 5, 6)
+
+                (inner, 7, 8, 9, 10)
+
+            # lint: allow(large-tuple)
+            isOn = \x -> x == Bool.true
+            "#
+        );
+
+        assert_eq!(
+            lint_kinds(src),
+            vec![LintKind::LargeTuple, LintKind::BoolLiteralComparison]
+        );
+    }
+
+    #[test]
+    fn allowed_by_config() {
+        let src = indoc!(
+            r#"
+            interface Test exposes [isOn, big] imports []
+
+            isOn = \x -> x == Bool.true
+
+            big = (1, 2, 3, 4, 5)
+            "#
+        );
+
+        let config = LintConfig::from_json(r#"{ "levels": { "large-tuple": "allow" } }"#).unwrap();
+
+        assert_eq!(config.level(LintKind::LargeTuple), Level::Allow);
+        assert_eq!(
+            lints_with(&config, src)
+                .iter()
+                .map(Lint::kind)
+                .collect::<Vec<_>>(),
+            vec![LintKind::BoolLiteralComparison]
+        );
+    }
+
+    #[test]
+    fn unknown_lint_in_config() {
+        let result = LintConfig::from_json(r#"{ "levels": { "no-such-lint": "deny" } }"#);
+
+        assert!(matches!(result, Err(ConfigError::UnknownLint(_, name)) if name == "no-such-lint"));
+    }
+}
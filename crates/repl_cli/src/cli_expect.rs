@@ -0,0 +1,91 @@
+use bumpalo::Bump;
+use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_mono::ir::OptLevel;
+use roc_repl_eval::gen::{compile_expect_to_mono, Problems, ReplImport};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use target_lexicon::Triple;
+
+/// The result of running an `expect` (or `expect-fx`) entered into the repl
+pub enum ExpectOutcome {
+    Passed,
+    /// The rendered report, including the values of the variables the expect referenced
+    Failed(String),
+}
+
+pub fn gen_and_run_expect<'a, I: Iterator<Item = &'a str>>(
+    defs: I,
+    expect_src: &str,
+    opt_import: Option<&ReplImport>,
+    target: Triple,
+    opt_level: OptLevel,
+) -> (Option<ExpectOutcome>, Problems) {
+    let arena = Bump::new();
+    let target_info = TargetInfo::from(&target);
+
+    // Failed expects are rendered using the module's source on disk,
+    // so the module has to live somewhere while it runs.
+    let dir = match tempfile::tempdir() {
+        Ok(dir) => dir,
+        Err(err) => {
+            let problems = Problems {
+                errors: vec![format!("I couldn't create a temporary directory: {}", err)],
+                warnings: Vec::new(),
+            };
+
+            return (None, problems);
+        }
+    };
+    let path = dir.path().join("REPL.roc");
+
+    let (mut loaded, problems) = match compile_expect_to_mono(
+        &arena,
+        defs,
+        expect_src,
+        &path,
+        opt_import,
+        target_info,
+        DEFAULT_PALETTE,
+    ) {
+        (Some(loaded), problems) if problems.errors.is_empty() => (loaded, problems),
+        (_, problems) => {
+            return (None, problems);
+        }
+    };
+
+    let mut expectations = std::mem::take(&mut loaded.expectations);
+    let interns = loaded.interns.clone();
+
+    let (lib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
+        &arena,
+        target,
+        loaded,
+        opt_level,
+        LlvmBackendMode::CliTest,
+    )
+    .expect("we produce a valid Dylib");
+
+    let arena = &Bump::new();
+    let interns = arena.alloc(interns);
+    let mut writer = Vec::new();
+
+    let (failed, _passed) = roc_repl_expect::run::run_toplevel_expects(
+        &mut writer,
+        RenderTarget::ColorTerminal,
+        arena,
+        interns,
+        &layout_interner.into_global(),
+        &lib,
+        &mut expectations,
+        expects,
+    )
+    .expect("writing to a Vec cannot fail");
+
+    let outcome = if failed == 0 {
+        ExpectOutcome::Passed
+    } else {
+        ExpectOutcome::Failed(String::from_utf8_lossy(&writer).into_owned())
+    };
+
+    (Some(outcome), problems)
+}
@@ -11,7 +11,9 @@ use roc_mono::ir::OptLevel;
 use roc_mono::layout::STLayoutInterner;
 use roc_parse::ast::Expr;
 use roc_repl_eval::eval::jit_to_ast;
-use roc_repl_eval::gen::{compile_to_mono, format_answer, Problems, ReplOutput};
+use roc_repl_eval::gen::{
+    compile_to_mono, format_answer, Problems, RecordFieldNames, ReplImport, ReplOutput,
+};
 use roc_repl_eval::{ReplApp, ReplAppMemory};
 use roc_reporting::report::DEFAULT_PALETTE;
 use roc_std::RocStr;
@@ -23,6 +25,7 @@ use target_lexicon::Triple;
 pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
     defs: I,
     src: &str,
+    opt_import: Option<&ReplImport>,
     target: Triple,
     opt_level: OptLevel,
 ) -> (Option<ReplOutput>, Problems) {
@@ -32,7 +35,7 @@ pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
     let mut loaded;
     let problems;
 
-    match compile_to_mono(&arena, defs, src, target_info, DEFAULT_PALETTE) {
+    match compile_to_mono(&arena, defs, src, opt_import, target_info, DEFAULT_PALETTE) {
         (Some(mono), probs) => {
             loaded = mono;
             problems = probs;
@@ -60,6 +63,7 @@ pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
         &loaded.interns,
         DebugPrint::NOTHING,
     );
+    let expr_fields = RecordFieldNames::of_var(&loaded.subs, main_fn_var);
 
     let (_, main_fn_layout) = match loaded.procedures.keys().find(|(s, _)| *s == main_fn_symbol) {
         Some(layout) => *layout,
@@ -95,6 +99,7 @@ pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
         Some(ReplOutput {
             expr: expr_str,
             expr_type: expr_type_str,
+            expr_fields,
         }),
         problems,
     )
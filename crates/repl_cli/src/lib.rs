@@ -1,4 +1,6 @@
 //! Command Line Interface (CLI) functionality for the Read-Evaluate-Print-Loop (REPL).
+#[cfg(not(windows))]
+mod cli_expect;
 mod cli_gen;
 mod colors;
 pub mod repl_state;
@@ -6,6 +8,8 @@ pub mod repl_state;
 use colors::{BLUE, END_COL, PINK};
 use const_format::concatcp;
 use repl_state::ReplState;
+use roc_packaging::cache::roc_cache_dir;
+use std::path::PathBuf;
 
 use crate::repl_state::PROMPT;
 
@@ -25,6 +29,19 @@ pub const WELCOME_MESSAGE: &str = concatcp!(
 // TODO add link to repl tutorial(does not yet exist).
 pub const SHORT_INSTRUCTIONS: &str = "Enter an expression, or :help, or :q to quit.\n\n";
 
+const HISTORY_FILE_NAME: &str = "repl_history";
+
+/// Where to persist the history of REPL inputs across sessions, e.g. `~/.cache/roc/repl_history`
+fn history_path() -> PathBuf {
+    // The roc cache dir is where packages get downloaded to, e.g. `~/.cache/roc/packages`
+    let packages_dir = roc_cache_dir();
+
+    packages_dir
+        .parent()
+        .unwrap_or(&packages_dir)
+        .join(HISTORY_FILE_NAME)
+}
+
 pub fn main() -> i32 {
     use rustyline::error::ReadlineError;
     use rustyline::Editor;
@@ -38,11 +55,35 @@ pub fn main() -> i32 {
     let repl_helper = ReplState::new();
     editor.set_helper(Some(repl_helper));
 
+    let history_path = history_path();
+
+    if let Some(history_dir) = history_path.parent() {
+        // If this fails, so will saving the history, which we treat as non-fatal.
+        let _ = std::fs::create_dir_all(history_dir);
+    }
+
+    // There won't be a history file the first time the repl is run, so ignore errors here.
+    let _ = editor.load_history(&history_path);
+    let mut warned_about_history = false;
+
     loop {
         match editor.readline(PROMPT) {
             Ok(line) => {
                 editor.add_history_entry(line.trim());
 
+                // Save after every entry rather than on exit, so that the history survives
+                // the repl being killed (or exiting via Ctrl-C).
+                if let Err(err) = editor.save_history(&history_path) {
+                    if !warned_about_history {
+                        eprintln!(
+                            "Unable to save the repl history to {:?}: {}",
+                            history_path, err
+                        );
+
+                        warned_about_history = true;
+                    }
+                }
+
                 let dimensions = editor.dimensions();
                 let repl_helper = editor.helper_mut().expect("Editor helper was not set");
 
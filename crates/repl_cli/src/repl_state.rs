@@ -3,20 +3,27 @@ use crate::colors::{BLUE, END_COL, GREEN, PINK};
 use bumpalo::Bump;
 use const_format::concatcp;
 use roc_collections::MutSet;
+use roc_module::symbol::Symbol;
 use roc_mono::ir::OptLevel;
-use roc_parse::ast::{Expr, Pattern, TypeDef, TypeHeader, ValueDef};
+use roc_parse::ast::{Expr, ExtractSpaces, Module, Pattern, TypeDef, TypeHeader, ValueDef};
 use roc_parse::expr::{parse_single_def, ExprParseOptions, SingleDef};
+use roc_parse::header::Header;
 use roc_parse::parser::Parser;
 use roc_parse::parser::{EClosure, EExpr, EPattern};
 use roc_parse::parser::{EWhen, Either};
 use roc_parse::state::State;
 use roc_parse::{join_alias_to_body, join_ann_to_body};
 use roc_region::all::Loc;
-use roc_repl_eval::gen::{Problems, ReplOutput};
+use roc_repl_eval::gen::{compile_to_type, Problems, RecordFieldNames, ReplImport, ReplOutput};
+use roc_reporting::report::DEFAULT_PALETTE;
+use roc_target::TargetInfo;
+use rustyline::completion::{Completer, Pair};
 use rustyline::highlight::{Highlighter, PromptInfo};
 use rustyline::validate::{self, ValidationContext, ValidationResult, Validator};
-use rustyline_derive::{Completer, Helper, Hinter};
+use rustyline::Context;
+use rustyline_derive::{Helper, Hinter};
 use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use target_lexicon::Triple;
 
 pub const PROMPT: &str = concatcp!(BLUE, "»", END_COL, " ");
@@ -59,21 +66,59 @@ pub const TIPS: &str = concatcp!(
     BLUE,
     "  - ",
     END_COL,
+    COMMANDS,
+    "\n\n",
+    BLUE,
+    "  - ",
+    END_COL,
     ":help"
 );
 
+pub const COMMANDS: &str = concatcp!(
+    "Commands:\n\n",
+    PINK,
+    "      :type <expr>",
+    END_COL,
+    "  print the type of an expression without evaluating it\n",
+    PINK,
+    "      :load <path>",
+    END_COL,
+    "  bring the values and types exposed by an interface module into scope\n",
+    PINK,
+    "      :reload",
+    END_COL,
+    "       load the most recently loaded module again, e.g. after editing it\n",
+    PINK,
+    "      :defs",
+    END_COL,
+    "         list the definitions entered so far, along with their types"
+);
+
 #[derive(Debug, Clone, PartialEq)]
 struct PastDef {
     ident: String,
     src: String,
+    /// The type that was printed when this def was evaluated. This is `None` for
+    /// standalone annotations and type definitions, which don't get evaluated.
+    opt_type: Option<String>,
+    /// The fields of this def's value, for completing e.g. `user.address.`
+    fields: RecordFieldNames,
 }
 
-#[derive(Completer, Helper, Hinter)]
+/// An interface module brought into scope with `:load`
+#[derive(Debug, Clone, PartialEq)]
+struct LoadedModule {
+    path: PathBuf,
+    import: ReplImport,
+}
+
+#[derive(Helper, Hinter)]
 pub struct ReplState {
     validator: InputValidator,
     past_defs: Vec<PastDef>,
     past_def_idents: MutSet<String>,
     last_auto_ident: u64,
+    opt_loaded: Option<LoadedModule>,
 }
 
 impl Default for ReplState {
@@ -89,6 +134,7 @@ impl ReplState {
             past_defs: Default::default(),
             past_def_idents: Default::default(),
             last_auto_ident: 0,
+            opt_loaded: None,
         }
     }
 
@@ -106,10 +152,142 @@ impl ReplState {
                 // TODO add link to repl tutorial(does not yet exist).
                 Ok(TIPS.to_string())
             }
+            ParseOutcome::TypeOf(expr) => Ok(self.type_of(expr)),
+            ParseOutcome::Load(path) => Ok(self.load(PathBuf::from(path))),
+            ParseOutcome::Reload => match &self.opt_loaded {
+                Some(loaded) => {
+                    let path = loaded.path.clone();
+
+                    Ok(self.load(path))
+                }
+                None => Ok(format!(
+                    "\nNo module has been loaded yet. Use {PINK}:load path/to/Module.roc{END_COL} to load one.\n"
+                )),
+            },
+            ParseOutcome::Defs => Ok(self.format_defs()),
+            ParseOutcome::UnknownCommand(command) => Ok(format!(
+                "\nI don't know how to run {PINK}{command}{END_COL}\n\n{COMMANDS}\n"
+            )),
             ParseOutcome::Exit => Err(0),
         }
     }
 
+    /// Type-check the given expr along with all the past defs, and print its type
+    /// without evaluating it.
+    fn type_of(&self, src: &str) -> String {
+        let (opt_type, problems) = compile_to_type(
+            self.past_defs.iter().map(|def| def.src.as_str()),
+            src,
+            self.opt_import(),
+            TargetInfo::from(&Triple::host()),
+            DEFAULT_PALETTE,
+        );
+
+        let mut buf = format_problems(&problems);
+
+        if let Some(expr_type) = opt_type {
+            if problems.errors.is_empty() {
+                buf.push('\n');
+                buf.push_str(src.trim());
+                buf.push_str(PINK);
+                buf.push_str(EXPR_TYPE_SEPARATOR);
+                buf.push_str(END_COL);
+                buf.push_str(&expr_type);
+                buf.push('\n');
+            }
+        }
+
+        buf
+    }
+
+    /// Bring the module at the given path into scope, replacing whichever module
+    /// was loaded before. The module is only kept if it type-checks.
+    fn load(&mut self, path: PathBuf) -> String {
+        let import = match read_import(&path) {
+            Ok(import) => import,
+            Err(message) => return message,
+        };
+
+        // Check the module right away, so its errors get reported now
+        // rather than after the next input.
+        let (_, problems) = compile_to_type(
+            self.past_defs.iter().map(|def| def.src.as_str()),
+            "{}",
+            Some(&import),
+            TargetInfo::from(&Triple::host()),
+            DEFAULT_PALETTE,
+        );
+
+        let mut buf = format_problems(&problems);
+
+        if problems.errors.is_empty() {
+            buf.push_str(&format!(
+                "\nLoaded {BLUE}{}{END_COL} from {}\n",
+                import.module_name,
+                path.display()
+            ));
+
+            if !import.exposed.is_empty() {
+                buf.push_str(&format!("\n{}\n", import.exposed.join(", ")));
+            }
+
+            self.opt_loaded = Some(LoadedModule { path, import });
+        }
+
+        buf
+    }
+
+    /// List the most recent version of each past def, along with its type if it has one.
+    fn format_defs(&self) -> String {
+        let mut buf = String::new();
+
+        for (index, def) in self.past_defs.iter().enumerate() {
+            // Skip defs which were later redefined.
+            let redefined = self.past_defs[index + 1..]
+                .iter()
+                .any(|later| later.ident == def.ident);
+
+            if redefined {
+                continue;
+            }
+
+            buf.push('\n');
+
+            match &def.opt_type {
+                Some(def_type) => {
+                    buf.push_str(&def.ident);
+                    buf.push_str(PINK);
+                    buf.push_str(EXPR_TYPE_SEPARATOR);
+                    buf.push_str(END_COL);
+                    buf.push_str(def_type);
+                }
+                None => {
+                    buf.push_str(def.src.trim());
+                }
+            }
+        }
+
+        if let Some(loaded) = &self.opt_loaded {
+            buf.push_str(&format!(
+                "\n\nIn scope from {BLUE}{}{END_COL}: {}",
+                loaded.import.module_name,
+                loaded.import.exposed.join(", ")
+            ));
+        }
+
+        if buf.is_empty() {
+            buf.push_str("\nNo definitions yet.");
+        }
+
+        buf.push('\n');
+
+        buf
+    }
+
+    fn opt_import(&self) -> Option<&ReplImport> {
+        self.opt_loaded.as_ref().map(|loaded| &loaded.import)
+    }
+
     pub fn eval_and_format(&mut self, src: &str, dimensions: Option<(usize, usize)>) -> String {
         let arena = Bump::new();
         let pending_past_def;
@@ -134,7 +312,7 @@ impl ReplState {
                         _,
                     ) => {
                         // Record the standalone type annotation for future use.
-                        self.add_past_def(ident.trim_end().to_string(), src.to_string());
+                        self.add_past_def(ident.trim_end().to_string(), src.to_string(), None);
 
                         // Return early without running eval, since standalone annotations
                         // cannnot be evaluated as expressions.
@@ -178,14 +356,24 @@ impl ReplState {
                     | ValueDef::AnnotatedBody { .. } => {
                         todo!("handle pattern other than identifier (which repl doesn't support)")
                     }
-                    ValueDef::Dbg { .. } => {
-                        todo!("handle receiving a `dbg` - what should the repl do for that?")
-                    }
-                    ValueDef::Expect { .. } => {
-                        todo!("handle receiving an `expect` - what should the repl do for that?")
+                    ValueDef::Dbg { condition, .. } => {
+                        // Print the value being inspected, without giving it a name.
+                        let start = condition.region.start().offset as usize;
+                        let end = condition.region.end().offset as usize;
+
+                        let (output, problems) = gen_and_eval_llvm(
+                            self.past_defs.iter().map(|def| def.src.as_str()),
+                            &src[start..end],
+                            self.opt_import(),
+                            Triple::host(),
+                            OptLevel::Normal,
+                        );
+
+                        return format_output(output, problems, None, dimensions);
                     }
-                    ValueDef::ExpectFx { .. } => {
-                        todo!("handle receiving an `expect-fx` - what should the repl do for that?")
+                    ValueDef::Expect { .. } | ValueDef::ExpectFx { .. } => {
+                        // Expects don't get recorded as past defs, since they have no name.
+                        return self.run_expect(src);
                     }
                 }
             }
@@ -214,13 +402,20 @@ impl ReplState {
                 ..
             }) => {
                 // Record the type for future use.
-                self.add_past_def(ident.trim_end().to_string(), src.to_string());
+                self.add_past_def(ident.trim_end().to_string(), src.to_string(), None);
 
                 // Return early without running eval, since none of these
                 // can be evaluated as expressions.
                 return String::new();
             }
-            ParseOutcome::Empty | ParseOutcome::Help | ParseOutcome::Exit => unreachable!(),
+            ParseOutcome::Empty
+            | ParseOutcome::Help
+            | ParseOutcome::TypeOf(_)
+            | ParseOutcome::Load(_)
+            | ParseOutcome::Reload
+            | ParseOutcome::Defs
+            | ParseOutcome::UnknownCommand(_)
+            | ParseOutcome::Exit => unreachable!(),
         };
 
         // Record e.g. "val1" as a past def, unless our input was exactly the name of
@@ -234,6 +429,7 @@ impl ReplState {
                     gen_and_eval_llvm(
                         self.past_defs.iter().map(|def| def.src.as_str()),
                         src,
+                        self.opt_import(),
                         Triple::host(),
                         OptLevel::Normal,
                     )
@@ -242,6 +438,7 @@ impl ReplState {
                     let (output, problems) = gen_and_eval_llvm(
                         self.past_defs.iter().map(|def| def.src.as_str()),
                         src,
+                        self.opt_import(),
                         Triple::host(),
                         OptLevel::Normal,
                     );
@@ -253,7 +450,7 @@ impl ReplState {
 
                         opt_var_name = Some(var_name.clone());
 
-                        self.add_past_def(var_name, src);
+                        self.add_past_def(var_name, src, output.as_ref());
                     } else {
                         opt_var_name = None;
                     }
@@ -263,23 +460,64 @@ impl ReplState {
             };
 
         if let Some((ident, src)) = pending_past_def {
-            self.add_past_def(ident, src);
+            self.add_past_def(ident, src, output.as_ref());
         }
 
         format_output(output, problems, opt_var_name, dimensions)
     }
 
+    #[cfg(not(windows))]
+    fn run_expect(&self, src: &str) -> String {
+        use crate::cli_expect::{gen_and_run_expect, ExpectOutcome};
+
+        let (opt_outcome, problems) = gen_and_run_expect(
+            self.past_defs.iter().map(|def| def.src.as_str()),
+            src,
+            self.opt_import(),
+            Triple::host(),
+            OptLevel::Normal,
+        );
+
+        let mut buf = format_problems(&problems);
+
+        match opt_outcome {
+            Some(ExpectOutcome::Passed) => {
+                buf.push_str(&format!("\n{GREEN}Expectation passed.{END_COL}\n"));
+            }
+            Some(ExpectOutcome::Failed(report)) => {
+                buf.push('\n');
+                buf.push_str(report.trim_end());
+                buf.push('\n');
+            }
+            None => {}
+        }
+
+        buf
+    }
+
+    #[cfg(windows)]
+    fn run_expect(&self, _src: &str) -> String {
+        "\nRunning expectations in the repl does not work on Windows yet.\n".to_string()
+    }
+
     fn next_auto_ident(&mut self) -> u64 {
         self.last_auto_ident += 1;
         self.last_auto_ident
     }
 
-    fn add_past_def(&mut self, ident: String, src: String) {
+    fn add_past_def(&mut self, ident: String, src: String, opt_output: Option<&ReplOutput>) {
         let existing_idents = &mut self.past_def_idents;
 
         existing_idents.insert(ident.clone());
 
-        self.past_defs.push(PastDef { ident, src });
+        self.past_defs.push(PastDef {
+            ident,
+            src,
+            opt_type: opt_output.map(|output| output.expr_type.clone()),
+            fields: opt_output
+                .map(|output| output.expr_fields.clone())
+                .unwrap_or_default(),
+        });
     }
 }
 
@@ -292,6 +530,11 @@ enum ParseOutcome<'a> {
     SyntaxErr,
     Empty,
     Help,
+    TypeOf(&'a str),
+    Load(&'a str),
+    Reload,
+    Defs,
+    UnknownCommand(&'a str),
     Exit,
 }
 
@@ -300,6 +543,9 @@ fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
         "" => ParseOutcome::Empty,
         ":help" => ParseOutcome::Help,
         ":exit" | ":quit" | ":q" => ParseOutcome::Exit,
+        ":reload" => ParseOutcome::Reload,
+        ":defs" => ParseOutcome::Defs,
+        _ if line.trim_start().starts_with(':') => parse_command(line.trim()),
         _ => {
             let src_bytes = line.as_bytes();
 
@@ -457,6 +703,71 @@ fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
     }
 }
 
+/// Parse a command which takes an argument, e.g. `:type List.len` or `:load Parser.roc`
+fn parse_command(line: &str) -> ParseOutcome<'_> {
+    let (command, arg) = match line.split_once(char::is_whitespace) {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (line, ""),
+    };
+
+    match command.to_lowercase().as_str() {
+        ":type" if !arg.is_empty() => ParseOutcome::TypeOf(arg),
+        ":load" if !arg.is_empty() => ParseOutcome::Load(arg.trim_matches('"')),
+        _ => ParseOutcome::UnknownCommand(line),
+    }
+}
+
+/// Read the header of the interface module at the given path, to find out what
+/// it's called and what it exposes.
+fn read_import(path: &Path) -> Result<ReplImport, String> {
+    let path = path
+        .canonicalize()
+        .map_err(|err| format!("\nI couldn't find {}: {}\n", path.display(), err))?;
+    let src = std::fs::read_to_string(&path)
+        .map_err(|err| format!("\nI couldn't read {}: {}\n", path.display(), err))?;
+    let arena = Bump::new();
+
+    match roc_parse::module::parse_header(&arena, State::new(src.as_bytes())) {
+        Ok((
+            Module {
+                header: Header::Interface(header),
+                ..
+            },
+            _,
+        )) => {
+            let module_name = header.name.value.as_str();
+            let exposed = header
+                .exposes
+                .item
+                .iter()
+                .map(|loc_name| loc_name.value.extract_spaces().item.as_str().to_string())
+                .collect();
+
+            // A module named e.g. `Parser.Core` lives in `Parser/Core.roc`, so its name
+            // gets resolved relative to the directory two levels up from the file.
+            let mut src_dir = path.clone();
+
+            for _ in module_name.split('.') {
+                src_dir.pop();
+            }
+
+            Ok(ReplImport {
+                module_name: module_name.to_string(),
+                exposed,
+                src_dir,
+            })
+        }
+        Ok(_) => Err(format!(
+            "\n{} is not an interface module. Only interface modules can be loaded.\n",
+            path.display()
+        )),
+        Err(_) => Err(format!(
+            "\nI couldn't parse the header of {}. Try running {PINK}roc check{END_COL} on it for details.\n",
+            path.display()
+        )),
+    }
+}
+
 struct InputValidator {}
 
 impl InputValidator {
@@ -493,6 +804,11 @@ pub fn is_incomplete(input: &str) -> bool {
         }
         ParseOutcome::Empty
         | ParseOutcome::Help
+        | ParseOutcome::TypeOf(_)
+        | ParseOutcome::Load(_)
+        | ParseOutcome::Reload
+        | ParseOutcome::Defs
+        | ParseOutcome::UnknownCommand(_)
         | ParseOutcome::Exit
         | ParseOutcome::ValueDef(_)
         | ParseOutcome::TypeDef(_)
@@ -501,6 +817,133 @@ pub fn is_incomplete(input: &str) -> bool {
     }
 }
 
+/// The commands to offer when completing input which starts with `:`
+const COMMAND_NAMES: &[&str] = &[
+    ":defs", ":exit", ":help", ":load", ":q", ":quit", ":reload", ":type",
+];
+
+impl Completer for ReplState {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, names) = self.completions(line, pos);
+        let candidates = names
+            .into_iter()
+            .map(|name| Pair {
+                display: name.clone(),
+                replacement: name,
+            })
+            .collect();
+
+        Ok((start, candidates))
+    }
+}
+
+impl ReplState {
+    /// The possible completions of the word before the cursor, along with the
+    /// position in the line where that word starts.
+    pub fn completions(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before_cursor = &line[..pos];
+        let word_start = before_cursor
+            .char_indices()
+            .rev()
+            .find(|(_, ch)| !(ch.is_alphanumeric() || matches!(ch, '_' | '.' | ':')))
+            .map(|(index, ch)| index + ch.len_utf8())
+            .unwrap_or(0);
+        let word = &before_cursor[word_start..];
+
+        if word.starts_with(':') {
+            if before_cursor[..word_start].trim().is_empty() {
+                let commands = COMMAND_NAMES.iter().map(|name| name.to_string());
+
+                (word_start, matching(commands, word))
+            } else {
+                // Commands are only recognized at the start of the input.
+                (pos, Vec::new())
+            }
+        } else if let Some((qualifier, partial)) = word.rsplit_once('.') {
+            let names = if qualifier.starts_with(char::is_uppercase) {
+                // e.g. `Str.` or `List.`
+                self.module_members(qualifier)
+            } else {
+                // e.g. `user.` or `user.address.`
+                self.record_field_names(qualifier)
+            };
+
+            (pos - partial.len(), matching(names.into_iter(), partial))
+        } else {
+            (word_start, matching(self.unqualified_names(), word))
+        }
+    }
+
+    /// Everything which can be referred to without a module qualifier
+    fn unqualified_names(&self) -> impl Iterator<Item = String> + '_ {
+        let builtin_modules = Symbol::BUILTIN_MODULE_IDENTS
+            .iter()
+            .map(|(module_name, _)| *module_name)
+            .filter(|module_name| !module_name.starts_with('#'))
+            .map(|module_name| module_name.to_string());
+        let loaded = self.opt_import().into_iter().flat_map(|import| {
+            std::iter::once(import.module_name.clone()).chain(import.exposed.iter().cloned())
+        });
+
+        self.past_def_idents
+            .iter()
+            .cloned()
+            .chain(builtin_modules)
+            .chain(loaded)
+    }
+
+    /// Everything exposed by the given builtin or `:load`ed module
+    fn module_members(&self, module_name: &str) -> Vec<String> {
+        let mut names: Vec<String> = Symbol::BUILTIN_MODULE_IDENTS
+            .iter()
+            .filter(|(name, _)| *name == module_name)
+            .flat_map(|(_, idents)| idents.iter())
+            .filter(|ident| !ident.starts_with('#'))
+            .map(|ident| ident.to_string())
+            .collect();
+
+        if let Some(import) = self.opt_import() {
+            if import.module_name == module_name {
+                names.extend(import.exposed.iter().cloned());
+            }
+        }
+
+        names
+    }
+
+    /// The fields of the record at the given path (e.g. `user.address`), based on
+    /// the type its past def was solved to when it was evaluated.
+    fn record_field_names(&self, path: &str) -> Vec<String> {
+        let mut segments = path.split('.');
+        let ident = segments.next().unwrap_or_default();
+
+        self.past_defs
+            .iter()
+            .rev()
+            .find(|def| def.ident == ident)
+            .and_then(|def| def.fields.at_path(segments))
+            .map(|fields| fields.0.iter().map(|(name, _)| name.clone()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// The names which start with the given prefix, sorted and without duplicates
+fn matching<I: Iterator<Item = String>>(names: I, prefix: &str) -> Vec<String> {
+    let mut answer: Vec<String> = names.filter(|name| name.starts_with(prefix)).collect();
+
+    answer.sort();
+    answer.dedup();
+
+    answer
+}
+
 impl Highlighter for ReplState {
     fn has_continuation_prompt(&self) -> bool {
         true
@@ -532,12 +975,9 @@ impl Validator for ReplState {
     }
 }
 
-fn format_output(
-    opt_output: Option<ReplOutput>,
-    problems: Problems,
-    opt_var_name: Option<String>,
-    dimensions: Option<(usize, usize)>,
-) -> String {
+const EXPR_TYPE_SEPARATOR: &str = " : "; // e.g. in "5 : Num *"
+
+fn format_problems(problems: &Problems) -> String {
     let mut buf = String::new();
 
     for message in problems.errors.iter().chain(problems.warnings.iter()) {
@@ -550,15 +990,27 @@ fn format_output(
         buf.push('\n');
     }
 
-    if let Some(ReplOutput { expr, expr_type }) = opt_output {
+    buf
+}
+
+fn format_output(
+    opt_output: Option<ReplOutput>,
+    problems: Problems,
+    opt_var_name: Option<String>,
+    dimensions: Option<(usize, usize)>,
+) -> String {
+    let mut buf = format_problems(&problems);
+
+    if let Some(ReplOutput {
+        expr, expr_type, ..
+    }) = opt_output
+    {
         // If expr was empty, it was a type annotation or ability declaration;
         // don't print anything!
         //
         // Also, for now we also don't print anything if there was a compile-time error.
         // In the future, it would be great to run anyway and print useful output here!
         if !expr.is_empty() && problems.errors.is_empty() {
-            const EXPR_TYPE_SEPARATOR: &str = " : "; // e.g. in "5 : Num *"
-
             // Print the expr and its type
             {
                 buf.push('\n');
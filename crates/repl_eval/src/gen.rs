@@ -1,22 +1,73 @@
 use bumpalo::Bump;
+use roc_collections::MutMap;
 use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_problem::Severity;
 use roc_reporting::report::Palette;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use roc_fmt::annotation::Formattable;
 use roc_fmt::annotation::{Newlines, Parens};
-use roc_load::{LoadingProblem, MonomorphizedModule};
+use roc_load::{LoadedModule, LoadingProblem, MonomorphizedModule};
+use roc_module::symbol::{Interns, ModuleId};
 use roc_parse::ast::Expr;
 use roc_region::all::LineInfo;
-use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
+use roc_reporting::report::{
+    can_problem, to_file_problem_report_string, type_problem, RenderTarget, RocDocAllocator,
+};
+use roc_solve_problem::TypeError;
 use roc_target::TargetInfo;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use roc_types::subs::{Content, FlatType, Subs, Variable};
+use roc_types::types::AliasKind;
 
 #[derive(Debug)]
 pub struct ReplOutput {
     pub expr: String,
     pub expr_type: String,
+    /// The fields of the expr, if its type is a record
+    pub expr_fields: RecordFieldNames,
+}
+
+/// The names of a record's fields, each along with the fields of its own value
+/// if that is a record too. Empty for anything which isn't a record.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordFieldNames(pub Vec<(String, RecordFieldNames)>);
+
+impl RecordFieldNames {
+    /// The fields of the given solved type, looking through structural aliases.
+    /// Opaque types have no fields, since those can't be accessed from outside.
+    pub fn of_var(subs: &Subs, var: Variable) -> Self {
+        match subs.get_content_without_compacting(var) {
+            Content::Structure(FlatType::Record(fields, ext)) => RecordFieldNames(
+                fields
+                    .sorted_iterator(subs, *ext)
+                    .map(|(label, field)| {
+                        (
+                            label.to_string(),
+                            RecordFieldNames::of_var(subs, field.into_inner()),
+                        )
+                    })
+                    .collect(),
+            ),
+            Content::Alias(_, _, real_var, AliasKind::Structural) => {
+                RecordFieldNames::of_var(subs, *real_var)
+            }
+            _ => RecordFieldNames::default(),
+        }
+    }
+
+    /// The fields of the record at the given path of field names, e.g. `["address"]`
+    /// for the fields of `user.address`.
+    pub fn at_path<'a, I: IntoIterator<Item = &'a str>>(&self, path: I) -> Option<&Self> {
+        path.into_iter().try_fold(self, |fields, name| {
+            fields
+                .0
+                .iter()
+                .find(|(field_name, _)| field_name == name)
+                .map(|(_, field_fields)| field_fields)
+        })
+    }
 }
 
 pub fn format_answer<'a>(arena: &'a Bump, answer: Expr<'_>) -> &'a str {
@@ -32,6 +83,18 @@ pub fn format_answer<'a>(arena: &'a Bump, answer: Expr<'_>) -> &'a str {
     }
 }
 
+/// An interface module whose exposed values and types are in scope for REPL inputs,
+/// e.g. after running `:load path/to/Parser.roc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplImport {
+    /// The module name from the interface header, e.g. `Parser` or `Parser.Core`
+    pub module_name: String,
+    /// Everything listed in the header's `exposes`
+    pub exposed: Vec<String>,
+    /// The directory the module's name is resolved relative to
+    pub src_dir: PathBuf,
+}
+
 #[derive(Default, Debug)]
 pub struct Problems {
     pub errors: Vec<String>,
@@ -48,12 +111,80 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
     defs: I,
     expr: &str,
+    opt_import: Option<&ReplImport>,
     target_info: TargetInfo,
     palette: Palette,
 ) -> (Option<MonomorphizedModule<'a>>, Problems) {
     let filename = PathBuf::from("");
-    let src_dir = PathBuf::from("fake/test/path");
-    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, defs, expr);
+    let (bytes_before_expr, module_src) = promote_expr_to_module(arena, defs, expr, opt_import);
+
+    load_repl_module(
+        arena,
+        filename,
+        module_src,
+        bytes_before_expr,
+        opt_import,
+        ExecutionMode::Executable,
+        target_info,
+        palette,
+    )
+}
+
+/// Compile the past defs along with a top-level `expect`, so that it can be run
+/// the same way `roc test` runs expects.
+///
+/// The module's source gets written to the given path, because that's where
+/// failed expects are rendered from.
+pub fn compile_expect_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
+    arena: &'a Bump,
+    defs: I,
+    expect_src: &str,
+    path: &Path,
+    opt_import: Option<&ReplImport>,
+    target_info: TargetInfo,
+    palette: Palette,
+) -> (Option<MonomorphizedModule<'a>>, Problems) {
+    let (bytes_before_expect, module_src) =
+        promote_expect_to_module(arena, defs, expect_src, opt_import);
+
+    if let Err(err) = std::fs::write(path, module_src) {
+        return (
+            None,
+            Problems {
+                errors: vec![format!(
+                    "I couldn't write the expect to {}: {}",
+                    path.display(),
+                    err
+                )],
+                warnings: Vec::new(),
+            },
+        );
+    }
+
+    load_repl_module(
+        arena,
+        path.to_path_buf(),
+        module_src,
+        bytes_before_expect,
+        opt_import,
+        ExecutionMode::Test,
+        target_info,
+        palette,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn load_repl_module<'a>(
+    arena: &'a Bump,
+    filename: PathBuf,
+    module_src: &'a str,
+    bytes_before_expr: usize,
+    opt_import: Option<&ReplImport>,
+    exec_mode: ExecutionMode,
+    target_info: TargetInfo,
+    palette: Palette,
+) -> (Option<MonomorphizedModule<'a>>, Problems) {
+    let src_dir = repl_src_dir(opt_import);
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
         filename,
@@ -62,36 +193,37 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
         LoadConfig {
             target_info,
-            render: roc_reporting::report::RenderTarget::ColorTerminal,
+            render: RenderTarget::ColorTerminal,
             palette,
             threading: Threading::Single,
-            exec_mode: ExecutionMode::Executable,
+            exec_mode,
+            typed_holes: false,
         },
     );
 
     let mut loaded = match loaded {
         Ok(v) => v,
-        Err(LoadMonomorphizedError::ErrorModule(m)) => {
-            todo!(
-                "error while loading module: {:?}",
-                (m.can_problems, m.type_problems)
-            );
-        }
-        Err(LoadMonomorphizedError::LoadingProblem(LoadingProblem::FormattedReport(report))) => {
-            return (
-                None,
-                Problems {
-                    errors: vec![report],
-                    warnings: Vec::new(),
-                },
+        Err(LoadMonomorphizedError::ErrorModule(mut module)) => {
+            // This happens when there were errors in a mode which stops at errors, e.g. Test.
+            let problems = report_problems(
+                module.module_id,
+                &module.interns,
+                &module.sources,
+                &mut module.can_problems,
+                &mut module.type_problems,
+                bytes_before_expr,
+                palette,
             );
+
+            return (None, problems);
         }
-        Err(e) => {
-            todo!("error while loading module: {:?}", e)
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return (None, loading_problem_to_problems(problem));
         }
     };
 
     let MonomorphizedModule {
+        module_id,
         interns,
         sources,
         can_problems,
@@ -99,14 +231,117 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         ..
     } = &mut loaded;
 
+    let problems = report_problems(
+        *module_id,
+        interns,
+        sources,
+        can_problems,
+        type_problems,
+        bytes_before_expr,
+        palette,
+    );
+
+    (Some(loaded), problems)
+}
+
+/// Type-check the given expr (along with the past defs) without generating any code,
+/// and return its pretty-printed type. This is what the REPL's `:type` command uses.
+pub fn compile_to_type<'i, I: Iterator<Item = &'i str>>(
+    defs: I,
+    expr: &str,
+    opt_import: Option<&ReplImport>,
+    target_info: TargetInfo,
+    palette: Palette,
+) -> (Option<String>, Problems) {
+    let arena = Bump::new();
+    let filename = PathBuf::from("");
+    let src_dir = repl_src_dir(opt_import);
+    let (bytes_before_expr, module_src) = promote_expr_to_module(&arena, defs, expr, opt_import);
+    let loaded = roc_load::load_and_typecheck_str(
+        &arena,
+        filename,
+        module_src,
+        src_dir,
+        target_info,
+        RenderTarget::ColorTerminal,
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        palette,
+    );
+
+    let LoadedModule {
+        module_id,
+        interns,
+        mut solved,
+        mut can_problems,
+        mut type_problems,
+        exposed_to_host,
+        sources,
+        ..
+    } = match loaded {
+        Ok(v) => v,
+        Err(problem) => {
+            return (None, loading_problem_to_problems(problem));
+        }
+    };
+
+    let problems = report_problems(
+        module_id,
+        &interns,
+        &sources,
+        &mut can_problems,
+        &mut type_problems,
+        bytes_before_expr,
+        palette,
+    );
+
+    // The only thing the REPL module provides is the expr itself.
+    let opt_type = exposed_to_host.values().next().map(|var| {
+        name_and_print_var(
+            *var,
+            solved.inner_mut(),
+            module_id,
+            &interns,
+            DebugPrint::NOTHING,
+        )
+    });
+
+    (opt_type, problems)
+}
+
+/// Loading problems are reported like any other error, so that e.g. a `:load` of a module
+/// with a missing import doesn't bring down the whole REPL.
+fn loading_problem_to_problems(problem: LoadingProblem<'_>) -> Problems {
+    let report = match problem {
+        LoadingProblem::FormattedReport(report) => report,
+        LoadingProblem::FileProblem { filename, error } => {
+            to_file_problem_report_string(&filename, error)
+        }
+        other => format!("Error while loading module: {:?}", other),
+    };
+
+    Problems {
+        errors: vec![report],
+        warnings: Vec::new(),
+    }
+}
+
+fn report_problems(
+    home: ModuleId,
+    interns: &Interns,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    bytes_before_expr: usize,
+    palette: Palette,
+) -> Problems {
     let mut problems = Problems::default();
 
     let errors = &mut problems.errors;
     let warnings = &mut problems.warnings;
 
-    for (home, (module_path, src)) in sources.iter() {
-        let can_probs = can_problems.remove(home).unwrap_or_default();
-        let type_probs = type_problems.remove(home).unwrap_or_default();
+    for (module_id, (module_path, src)) in sources.iter() {
+        let can_probs = can_problems.remove(module_id).unwrap_or_default();
+        let type_probs = type_problems.remove(module_id).unwrap_or_default();
 
         let error_count = can_probs.len() + type_probs.len();
 
@@ -114,18 +349,21 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             continue;
         }
 
-        let line_info = LineInfo::new(module_src);
+        let line_info = LineInfo::new(src);
         let src_lines: Vec<&str> = src.split('\n').collect();
 
         // Report parsing and canonicalization problems
-        let alloc = RocDocAllocator::new(&src_lines, *home, interns);
+        let alloc = RocDocAllocator::new(&src_lines, *module_id, interns);
 
         for problem in can_probs.into_iter() {
             // Filter out all warnings and errors whose regions end before this,
             // because they must be part of the defs (excluding the most renently added def,
             // if that's the one being evaluated) and therefore not things we should show.
             // This filters out things like shadowing warnings and unused def warnings.
-            if problem.region().unwrap_or_default().end().offset as usize >= bytes_before_expr {
+            // Errors in a `:load`ed module are always shown, since they affect every input.
+            let region_end = problem.region().unwrap_or_default().end().offset as usize;
+
+            if *module_id != home || region_end >= bytes_before_expr {
                 let report = can_problem(&alloc, &line_info, module_path.clone(), problem);
                 let severity = report.severity;
                 let mut buf = String::new();
@@ -134,7 +372,10 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
 
                 match severity {
                     Severity::Warning => {
-                        warnings.push(buf);
+                        // Warnings in a `:load`ed module would be repeated after every input.
+                        if *module_id == home {
+                            warnings.push(buf);
+                        }
                     }
                     Severity::Fatal | Severity::RuntimeError => {
                         errors.push(buf);
@@ -152,7 +393,9 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
 
                 match severity {
                     Severity::Warning => {
-                        warnings.push(buf);
+                        if *module_id == home {
+                            warnings.push(buf);
+                        }
                     }
                     Severity::Fatal | Severity::RuntimeError => {
                         errors.push(buf);
@@ -162,19 +405,27 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
         }
     }
 
-    (Some(loaded), problems)
+    problems
+}
+
+fn repl_src_dir(opt_import: Option<&ReplImport>) -> PathBuf {
+    match opt_import {
+        Some(import) => import.src_dir.clone(),
+        None => PathBuf::from("fake/test/path"),
+    }
 }
 
+const REPL_MODULE_MAIN_DEF: &str = "replOutput =\n";
+
 fn promote_expr_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
     arena: &'a Bump,
     defs: I,
     expr: &str,
+    opt_import: Option<&ReplImport>,
 ) -> (usize, &'a str) {
-    const REPL_MODULE_HEADER: &str = "app \"app\" provides [replOutput] to \"./platform\"\n\n";
-    const REPL_MODULE_MAIN_DEF: &str = "replOutput =\n";
     const INDENT: &str = "    ";
 
-    let mut buffer = bumpalo::collections::string::String::from_str_in(REPL_MODULE_HEADER, arena);
+    let mut buffer = repl_module_header(arena, opt_import);
 
     for line in defs {
         // don't indent the defs
@@ -195,3 +446,58 @@ fn promote_expr_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
 
     (bytes_before_expr, buffer.into_bump_str())
 }
+
+fn promote_expect_to_module<'a, 'i, I: Iterator<Item = &'i str>>(
+    arena: &'a Bump,
+    defs: I,
+    expect_src: &str,
+    opt_import: Option<&ReplImport>,
+) -> (usize, &'a str) {
+    let mut buffer = repl_module_header(arena, opt_import);
+
+    for line in defs {
+        buffer.push_str(line);
+        buffer.push_str("\n\n");
+    }
+
+    // The module still has to provide its main def, even though it won't get run.
+    buffer.push_str(REPL_MODULE_MAIN_DEF);
+    buffer.push_str("    {}\n\n");
+
+    let bytes_before_expect = buffer.len();
+
+    // The expect is a top-level def, so it doesn't get indented.
+    buffer.push_str(expect_src.trim_end());
+    buffer.push('\n');
+
+    (bytes_before_expect, buffer.into_bump_str())
+}
+
+fn repl_module_header<'a>(
+    arena: &'a Bump,
+    opt_import: Option<&ReplImport>,
+) -> bumpalo::collections::String<'a> {
+    const REPL_MODULE_HEADER_START: &str = "app \"app\" ";
+    const REPL_MODULE_HEADER_END: &str = "provides [replOutput] to \"./platform\"\n\n";
+
+    let mut buffer =
+        bumpalo::collections::string::String::from_str_in(REPL_MODULE_HEADER_START, arena);
+
+    if let Some(import) = opt_import {
+        // e.g. `imports [Parser.{ parse, Problem }] `
+        buffer.push_str("imports [");
+        buffer.push_str(&import.module_name);
+
+        if !import.exposed.is_empty() {
+            buffer.push_str(".{ ");
+            buffer.push_str(&import.exposed.join(", "));
+            buffer.push_str(" }");
+        }
+
+        buffer.push_str("] ");
+    }
+
+    buffer.push_str(REPL_MODULE_HEADER_END);
+
+    buffer
+}
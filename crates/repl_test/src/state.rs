@@ -101,6 +101,190 @@ fn standalone_annotation() {
     assert_eq!(state.step(&input, None), Ok(String::new()));
 }
 
+#[test]
+fn type_command() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, Ok(("5 : Num *", "x")));
+    output(":type x + 1", &mut state, "x + 1 : Num *");
+    output(":type Str.isEmpty", &mut state, "Str.isEmpty : Str -> Bool");
+
+    // :type doesn't evaluate anything, so it doesn't get an auto-generated name
+    complete("x * 2", &mut state, Ok(("10 : Num *", "val1")));
+}
+
+#[test]
+fn defs_command() {
+    let mut state = ReplState::new();
+
+    output(":defs", &mut state, "No definitions yet.");
+
+    complete("x = 5", &mut state, Ok(("5 : Num *", "x")));
+    complete("\"hi\"", &mut state, Ok(("\"hi\" : Str", "val1")));
+    complete("y = 6.5", &mut state, Ok(("6.5 : Float *", "y")));
+
+    output(":defs", &mut state, "x : Num *\nval1 : Str\ny : Float *");
+}
+
+#[test]
+fn reload_without_load() {
+    output(
+        ":reload",
+        &mut ReplState::new(),
+        "No module has been loaded yet. Use :load path/to/Module.roc to load one.",
+    );
+}
+
+#[test]
+fn load_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_greeting_module(dir.path(), "Hi");
+    let loaded = format!(
+        "Loaded Greeting from {}\n\ngreeting, double",
+        path.display()
+    );
+    let mut state = ReplState::new();
+
+    output(&format!(":load {}", path.display()), &mut state, &loaded);
+
+    complete("greeting", &mut state, Ok(("\"Hi\" : Str", "val1")));
+    complete("double 21", &mut state, Ok(("42 : Num *", "val2")));
+    complete("Greeting.double 2", &mut state, Ok(("4 : Num *", "val3")));
+    output(":type double", &mut state, "double : Num a -> Num a");
+    output(
+        ":defs",
+        &mut state,
+        "val1 : Str\nval2 : Num *\nval3 : Num *\n\nIn scope from Greeting: greeting, double",
+    );
+}
+
+#[test]
+fn reload_command() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_greeting_module(dir.path(), "Hi");
+    let loaded = format!(
+        "Loaded Greeting from {}\n\ngreeting, double",
+        path.display()
+    );
+    let mut state = ReplState::new();
+
+    output(&format!(":load {}", path.display()), &mut state, &loaded);
+    complete("greeting", &mut state, Ok(("\"Hi\" : Str", "val1")));
+
+    write_greeting_module(dir.path(), "Hello");
+
+    output(":reload", &mut state, &loaded);
+    complete("greeting", &mut state, Ok(("\"Hello\" : Str", "val2")));
+}
+
+#[test]
+fn complete_commands() {
+    let state = ReplState::new();
+
+    assert_eq!(
+        state.completions(":re", 3),
+        (0, vec![":reload".to_string()])
+    );
+    assert_eq!(
+        state.completions(":q", 2),
+        (0, vec![":q".to_string(), ":quit".to_string()])
+    );
+}
+
+#[test]
+fn complete_builtin_module_members() {
+    let state = ReplState::new();
+    let (start, names) = state.completions("Str.conc", 8);
+
+    assert_eq!(start, 4);
+    assert_eq!(names, vec!["concat".to_string()]);
+}
+
+#[test]
+fn complete_past_defs_and_record_fields() {
+    let mut state = ReplState::new();
+
+    complete(
+        "user = { name: \"Sam\", address: { city: \"Oslo\" } }",
+        &mut state,
+        Ok((
+            "{ address: { city: \"Oslo\" }, name: \"Sam\" } : { address : { city : Str }, name : Str }",
+            "user",
+        )),
+    );
+
+    assert_eq!(
+        state.completions("1 + us", 6),
+        (4, vec!["user".to_string()])
+    );
+    assert_eq!(
+        state.completions("user.", 5),
+        (5, vec!["address".to_string(), "name".to_string()])
+    );
+    assert_eq!(
+        state.completions("user.address.c", 14),
+        (13, vec!["city".to_string()])
+    );
+}
+
+#[test]
+fn dbg_prints_value() {
+    let mut state = ReplState::new();
+
+    complete("x = 2", &mut state, Ok(("2 : Num *", "x")));
+    output("dbg x + 2", &mut state, "4 : Num *");
+
+    // dbg doesn't give the value a name
+    complete("x + 1", &mut state, Ok(("3 : Num *", "val1")));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn expect_passes() {
+    output(
+        "expect 1 + 1 == 2",
+        &mut ReplState::new(),
+        "Expectation passed.",
+    );
+}
+
+#[cfg(not(windows))]
+#[test]
+fn expect_fails_with_values() {
+    let mut state = ReplState::new();
+
+    complete("x = 2", &mut state, Ok(("2 : Num *", "x")));
+
+    let out = state.step("expect x == 3", None).unwrap();
+    let out = std::string::String::from_utf8(strip_ansi_escapes::strip(out).unwrap()).unwrap();
+
+    assert!(out.contains("EXPECT FAILED"), "{}", out);
+    assert!(out.contains("x = 2"), "{}", out);
+}
+
+/// Write an interface module named Greeting to the given directory, and return its path.
+fn write_greeting_module(dir: &std::path::Path, greeting: &str) -> std::path::PathBuf {
+    let path = dir.join("Greeting.roc");
+    let src = format!(
+        indoc!(
+            r#"
+            interface Greeting
+                exposes [greeting, double]
+                imports []
+
+            greeting = "{}"
+
+            double = \n -> n * 2
+            "#
+        ),
+        greeting
+    );
+
+    std::fs::write(&path, src).unwrap();
+
+    path
+}
+
 /// validate and step the given input, then check the Result vs the output
 /// with ANSI escape codes stripped.
 fn complete(input: &str, state: &mut ReplState, expected_step_result: Result<(&str, &str), i32>) {
@@ -150,3 +334,15 @@ fn error(input: &str, state: &mut ReplState, expected_step_result: String) {
 
     assert_eq!(Ok(expected_step_result), escaped);
 }
+
+/// step the given command, then check the given string vs the output
+/// with ANSI escape codes stripped.
+fn output(input: &str, state: &mut ReplState, expected_output: &str) {
+    assert!(!is_incomplete(input));
+
+    let escaped = state.step(input, None).map(|string| {
+        std::string::String::from_utf8(strip_ansi_escapes::strip(string.trim()).unwrap()).unwrap()
+    });
+
+    assert_eq!(Ok(expected_output.to_string()), escaped);
+}
@@ -186,6 +186,7 @@ pub async fn entrypoint_from_js(src: String) -> Result<String, String> {
         arena,
         std::iter::empty(),
         &src,
+        None,
         target_info,
         DEFAULT_PALETTE_HTML,
     ) {
@@ -2,9 +2,12 @@ use std::path::PathBuf;
 
 use roc_collections::MutMap;
 use roc_module::symbol::{Interns, ModuleId};
+use roc_problem::Levels;
 use roc_region::all::LineInfo;
 use roc_solve_problem::TypeError;
 
+use crate::report::Fix;
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Problems {
     pub fatally_errored: bool,
@@ -57,6 +60,7 @@ pub fn report_problems(
     interns: &Interns,
     can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+    levels: &Levels,
 ) -> Problems {
     use crate::report::{can_problem, type_problem, Report, RocDocAllocator, DEFAULT_PALETTE};
     use roc_problem::Severity::*;
@@ -81,10 +85,16 @@ pub fn report_problems(
         let problems = can_problems.remove(home).unwrap_or_default();
 
         for problem in problems.into_iter() {
-            let report = can_problem(&alloc, &lines, module_path.clone(), problem);
-            let severity = report.severity;
+            let name = problem.name();
+            let mut report = can_problem(&alloc, &lines, module_path.clone(), problem);
+            let severity = match levels.severity(name, report.severity) {
+                Some(severity) => severity,
+                None => continue,
+            };
             let mut buf = String::new();
 
+            report.severity = severity;
+
             report.render_color_terminal(&mut buf, &alloc, &palette);
 
             match severity {
@@ -104,10 +114,17 @@ pub fn report_problems(
         let problems = type_problems.remove(home).unwrap_or_default();
 
         for problem in problems {
-            if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
-                let severity = report.severity;
+            let name = problem.name();
+
+            if let Some(mut report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
+                let severity = match levels.severity(name, report.severity) {
+                    Some(severity) => severity,
+                    None => continue,
+                };
                 let mut buf = String::new();
 
+                report.severity = severity;
+
                 report.render_color_terminal(&mut buf, &alloc, &palette);
 
                 match severity {
@@ -159,3 +176,38 @@ pub fn report_problems(
         warnings: warnings.len(),
     }
 }
+
+/// The fixes for all of these problems that have them, along with the path of the module
+/// that each group of fixes is for. Modules without any fixes are left out.
+pub fn collect_fixes(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+) -> Vec<(ModuleId, PathBuf, Vec<Fix>)> {
+    use crate::error::canonicalize::can_problem_fixes;
+    use crate::error::r#type::type_problem_fixes;
+    use crate::report::RocDocAllocator;
+
+    let mut all_fixes = Vec::new();
+
+    for (home, (module_path, src)) in sources.iter() {
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let alloc = RocDocAllocator::new(&src_lines, *home, interns);
+        let mut fixes = Vec::new();
+
+        for problem in can_problems.remove(home).unwrap_or_default() {
+            fixes.extend(can_problem_fixes(src, &problem));
+        }
+
+        for problem in type_problems.remove(home).unwrap_or_default() {
+            fixes.extend(type_problem_fixes(&alloc, src, &problem));
+        }
+
+        if !fixes.is_empty() {
+            all_fixes.push((*home, module_path.clone(), fixes));
+        }
+    }
+
+    all_fixes
+}
@@ -12,7 +12,10 @@ use roc_types::types::AliasKind;
 use std::path::PathBuf;
 
 use crate::error::r#type::suggest;
-use crate::report::{to_file_problem_report, Annotation, Report, RocDocAllocator, RocDocBuilder};
+use crate::fix;
+use crate::report::{
+    to_file_problem_report, Annotation, Fix, Report, RocDocAllocator, RocDocBuilder,
+};
 use ven_pretty::{text, DocAllocator};
 
 const SYNTAX_PROBLEM: &str = "SYNTAX PROBLEM";
@@ -1098,6 +1101,14 @@ pub fn can_problem<'b>(
             doc = report.doc;
             title = report.title;
         }
+        Problem::SyntaxError {
+            title: syntax_title,
+            rendered,
+            ..
+        } => {
+            doc = alloc.stack(rendered.lines().map(|line| alloc.text(line.to_string())));
+            title = syntax_title;
+        }
     };
 
     Report {
@@ -1108,6 +1119,29 @@ pub fn can_problem<'b>(
     }
 }
 
+/// Edits to `src` that would fix the problem, for the problems where that's clear-cut.
+pub fn can_problem_fixes(src: &str, problem: &Problem) -> Vec<Fix> {
+    match problem {
+        Problem::UnusedImport(_, region) | Problem::UnusedModuleImport(_, region) => {
+            vec![fix::remove_list_item(src, *region)]
+        }
+        Problem::UnusedDef(_, region) => fix::remove_def(src, *region).into_iter().collect(),
+        Problem::RuntimeError(RuntimeError::LookupNotInScope(loc_name, options)) => {
+            let name = loc_name.value.as_inline_str().as_str();
+            let suggestions = suggest::sort(name, options.iter().map(|v| v.as_ref()).collect());
+
+            match suggestions.first() {
+                Some(closest) if fix::is_likely_typo(name, closest) => vec![Fix {
+                    region: loc_name.region,
+                    replacement: closest.to_string(),
+                }],
+                _ => Vec::new(),
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
 fn list_builtin_abilities<'a>(alloc: &'a RocDocAllocator<'a>) -> RocDocBuilder<'a> {
     alloc.intersperse(
         DERIVABLE_ABILITIES
@@ -1726,6 +1760,10 @@ fn pretty_runtime_error<'b>(
         RuntimeError::MalformedClosure(_) => {
             todo!("");
         }
+        RuntimeError::MalformedDef(_) => {
+            // do nothing, reported with SyntaxError
+            unreachable!();
+        }
         RuntimeError::InvalidFloat(sign @ FloatErrorKind::PositiveInfinity, region, _raw_str)
         | RuntimeError::InvalidFloat(sign @ FloatErrorKind::NegativeInfinity, region, _raw_str) => {
             let tip = alloc
@@ -1,7 +1,8 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::error::canonicalize::{to_circular_def_doc, CIRCULAR_DEF};
-use crate::report::{Annotation, Report, RocDocAllocator, RocDocBuilder};
+use crate::fix;
+use crate::report::{Annotation, CiWrite, Fix, Report, RocDocAllocator, RocDocBuilder};
 use itertools::EitherOrBoth;
 use itertools::Itertools;
 use roc_can::expected::{Expected, PExpected};
@@ -29,6 +30,9 @@ use ven_pretty::{text, DocAllocator};
 
 const ADD_ANNOTATIONS: &str = r#"Can more type annotations be added? Type annotations always help me give more specific messages, and I think they could help a lot in this case"#;
 
+/// How many of the values that could fill a typed hole to list
+const MAX_TYPED_HOLE_FITS: usize = 10;
+
 const OPAQUE_NUM_SYMBOLS: &[Symbol] = &[
     Symbol::NUM_NUM,
     Symbol::NUM_INTEGER,
@@ -248,6 +252,59 @@ pub fn type_problem<'b>(
                 severity,
             })
         }
+        TypedHole { region, typ, fits } => {
+            let mut stack = vec![
+                alloc.reflow("This hole needs a value of this type:"),
+                alloc.region(lines.convert_region(region)),
+                alloc.type_block(error_type_to_doc(alloc, typ)),
+            ];
+
+            // Generated names (which don't start with a letter) aren't something the user can
+            // write, so they can't fill the hole.
+            let fits: Vec<_> = fits
+                .into_iter()
+                .filter(|(symbol, _)| {
+                    symbol
+                        .as_str(alloc.interns)
+                        .starts_with(|c: char| c.is_ascii_lowercase())
+                })
+                .collect();
+
+            if !fits.is_empty() {
+                let shown = fits.len().min(MAX_TYPED_HOLE_FITS);
+                let hidden = fits.len() - shown;
+
+                stack.push(alloc.reflow("These values in scope have a type that fits:"));
+                stack.push(
+                    alloc.type_block(alloc.vcat(fits.into_iter().take(shown).map(
+                        |(symbol, typ)| {
+                            let name = if symbol.module_id() == alloc.home {
+                                alloc.symbol_unqualified(symbol)
+                            } else {
+                                alloc.symbol_qualified(symbol)
+                            };
+
+                            alloc.concat([name, alloc.text(" : "), error_type_to_doc(alloc, typ)])
+                        },
+                    ))),
+                );
+
+                if hidden > 0 {
+                    stack.push(alloc.concat([
+                        alloc.reflow("...and "),
+                        alloc.string(hidden.to_string()),
+                        alloc.reflow(" more."),
+                    ]));
+                }
+            }
+
+            Some(Report {
+                title: "TYPED HOLE".to_string(),
+                filename,
+                doc: alloc.stack(stack),
+                severity,
+            })
+        }
     }
 }
 
@@ -5008,6 +5065,55 @@ fn exhaustive_problem<'a>(
     }
 }
 
+/// Edits to `src` that would fix the problem, for the problems where that's clear-cut.
+pub fn type_problem_fixes<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    src: &str,
+    problem: &TypeError,
+) -> Vec<Fix> {
+    use roc_exhaustive::{Context::BadCase, Error::Incomplete};
+
+    match problem {
+        TypeError::Exhaustive(Incomplete(region, BadCase, missing)) => {
+            missing_branch_fixes(alloc, src, *region, missing)
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Add a branch that crashes for each of the `missing` patterns of the `when` whose condition
+/// is in `condition_region`. Patterns that only need a guard removed can't be fixed this way.
+fn missing_branch_fixes<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    src: &str,
+    condition_region: Region,
+    missing: &[roc_exhaustive::Pattern],
+) -> Vec<Fix> {
+    use roc_exhaustive::{Pattern, RenderAs};
+
+    let mut patterns = Vec::with_capacity(missing.len());
+
+    for pattern in missing {
+        if matches!(pattern, Pattern::Ctor(union, _, _) if matches!(union.render_as, RenderAs::Guard))
+        {
+            return Vec::new();
+        }
+
+        let mut buf = String::new();
+        let doc = exhaustive_pattern_to_doc(alloc, pattern.clone());
+
+        if doc.1.render_raw(1000, &mut CiWrite::new(&mut buf)).is_err() {
+            return Vec::new();
+        }
+
+        patterns.push(buf);
+    }
+
+    fix::add_when_branches(src, condition_region, &patterns)
+        .into_iter()
+        .collect()
+}
+
 pub fn unhandled_patterns_to_doc_block<'b>(
     alloc: &'b RocDocAllocator<'b>,
     patterns: Vec<roc_exhaustive::Pattern>,
@@ -0,0 +1,319 @@
+//! Mechanical fixes for problems, which `roc fix` applies to the source.
+//!
+//! These work on the text of the module rather than its AST, so that they leave
+//! everything they don't touch (comments, formatting) exactly as it was.
+use crate::report::Fix;
+use roc_region::all::{Position, Region};
+
+fn span(start: usize, end: usize) -> Region {
+    Region::new(Position::new(start as u32), Position::new(end as u32))
+}
+
+fn line_start(src: &str, offset: usize) -> usize {
+    src[..offset].rfind('\n').map_or(0, |newline| newline + 1)
+}
+
+/// The offset just past the newline that ends the line `offset` is on (or the end of the source).
+fn next_line_start(src: &str, offset: usize) -> usize {
+    src[offset..]
+        .find('\n')
+        .map_or(src.len(), |newline| offset + newline + 1)
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Remove an entry from a comma-separated list (e.g. an unused import), along with its comma.
+pub fn remove_list_item(src: &str, region: Region) -> Fix {
+    let start = region.start().offset as usize;
+    let end = region.end().offset as usize;
+    let after_item = src[end..].trim_start_matches(&[' ', '\t'][..]);
+    let after_item_offset = src.len() - after_item.len();
+
+    let region = if let Some(after_comma) = after_item.strip_prefix(',') {
+        let first_on_line = line_start(src, start);
+        let rest_of_line = &src[after_item_offset + 1..next_line_start(src, after_item_offset)];
+
+        if is_blank(&src[first_on_line..start]) && is_blank(rest_of_line) {
+            // The item has a line to itself, so take the whole line
+            span(first_on_line, next_line_start(src, after_item_offset))
+        } else {
+            let after_spaces = after_comma.trim_start_matches(&[' ', '\t'][..]);
+
+            span(start, src.len() - after_spaces.len())
+        }
+    } else {
+        // This was the last item, so take the comma before it instead (if there is one)
+        let before_item = src[..start].trim_end();
+
+        match before_item.strip_suffix(',') {
+            Some(before_comma) => span(before_comma.len(), end),
+            None => span(start, end),
+        }
+    };
+
+    Fix {
+        region,
+        replacement: String::new(),
+    }
+}
+
+/// Remove the `name = ...` def whose name is in `name_region`, along with its annotation.
+/// Returns `None` for defs this can't safely remove, like destructures.
+pub fn remove_def(src: &str, name_region: Region) -> Option<Fix> {
+    let start = name_region.start().offset as usize;
+    let end = name_region.end().offset as usize;
+    let first_on_line = line_start(src, start);
+
+    if !src[first_on_line..start].bytes().all(|byte| byte == b' ') {
+        return None;
+    }
+
+    let after_name = src[end..].trim_start_matches(' ');
+
+    if !after_name.starts_with('=') || after_name.starts_with("==") {
+        return None;
+    }
+
+    let name = &src[start..end];
+    let indent = start - first_on_line;
+
+    // The def goes on for as long as the lines are indented more than it is
+    let mut def_end = next_line_start(src, start);
+    let mut next_def = def_end;
+
+    while next_def < src.len() {
+        let line_end = next_line_start(src, next_def);
+        let line = &src[next_def..line_end];
+
+        if is_blank(line) {
+            next_def = line_end;
+        } else if indentation(line) > indent {
+            next_def = line_end;
+            def_end = line_end;
+        } else {
+            break;
+        }
+    }
+
+    let def_start = annotation_start(src, first_on_line, indent, name).unwrap_or(first_on_line);
+
+    // Don't leave two blank lines where the def used to be
+    let blank_before = def_start == 0 || src[..def_start].ends_with("\n\n");
+    let removed_end = if blank_before { next_def } else { def_end };
+
+    Some(Fix {
+        region: span(def_start, removed_end),
+        replacement: String::new(),
+    })
+}
+
+/// If the lines right before the def at `def_start` are an annotation for `name`,
+/// returns where the annotation starts.
+fn annotation_start(src: &str, def_start: usize, indent: usize, name: &str) -> Option<usize> {
+    let mut cursor = def_start;
+
+    while cursor > 0 {
+        let previous = line_start(src, cursor - 1);
+        let line = &src[previous..cursor];
+
+        if is_blank(line) || indentation(line) < indent {
+            return None;
+        }
+
+        if indentation(line) == indent {
+            let after_name = line.trim_start_matches(' ').strip_prefix(name)?;
+            let after_spaces = after_name.trim_start_matches(' ');
+
+            return (after_spaces.starts_with(':') && !after_spaces.starts_with(":="))
+                .then_some(previous);
+        }
+
+        // This line is part of a multiline annotation
+        cursor = previous;
+    }
+
+    None
+}
+
+/// Add a `crash` branch for each of the `patterns` to the end of the `when` whose condition is in
+/// `condition_region`.
+pub fn add_when_branches(src: &str, condition_region: Region, patterns: &[String]) -> Option<Fix> {
+    let condition_end = condition_region.end().offset as usize;
+    let mut branch_start = next_line_start(src, condition_end);
+
+    while branch_start < src.len()
+        && is_blank(&src[branch_start..next_line_start(src, branch_start)])
+    {
+        branch_start = next_line_start(src, branch_start);
+    }
+
+    if branch_start >= src.len() {
+        return None;
+    }
+
+    let branch_indent = indentation(&src[branch_start..]);
+    let mut when_end = branch_start;
+    let mut cursor = branch_start;
+
+    while cursor < src.len() {
+        let line_end = next_line_start(src, cursor);
+        let line = &src[cursor..line_end];
+
+        if is_blank(line) {
+            cursor = line_end;
+        } else if indentation(line) >= branch_indent {
+            when_end = cursor + line.trim_end().len();
+            cursor = line_end;
+        } else {
+            break;
+        }
+    }
+
+    let mut replacement = String::new();
+
+    for pattern in patterns {
+        replacement.push('\n');
+        replacement.push_str(&" ".repeat(branch_indent));
+        replacement.push_str(pattern);
+        replacement.push_str(" -> crash \"TODO\"");
+    }
+
+    Some(Fix {
+        region: span(when_end, when_end),
+        replacement,
+    })
+}
+
+/// Whether `suggestion` is close enough to `typo` that it's very likely what was meant.
+pub fn is_likely_typo(typo: &str, suggestion: &str) -> bool {
+    distance::damerau_levenshtein(typo, suggestion) <= (typo.len() / 3).max(1)
+}
+
+/// Apply as many of the `fixes` as we can without any two of them touching the same source.
+/// When fixes overlap, the one that starts first (or is bigger) wins.
+/// Returns the fixed source and the number of fixes that were applied.
+pub fn apply_fixes(src: &str, mut fixes: Vec<Fix>) -> (String, usize) {
+    fixes.sort_by_key(|fix| (fix.region.start(), std::cmp::Reverse(fix.region.end())));
+    fixes.dedup();
+
+    let mut fixed = String::with_capacity(src.len());
+    let mut copied_up_to = 0;
+    let mut applied = 0;
+
+    for fix in fixes {
+        let start = fix.region.start().offset as usize;
+        let end = fix.region.end().offset as usize;
+
+        if start < copied_up_to || end > src.len() {
+            continue;
+        }
+
+        fixed.push_str(&src[copied_up_to..start]);
+        fixed.push_str(&fix.replacement);
+        copied_up_to = end;
+        applied += 1;
+    }
+
+    fixed.push_str(&src[copied_up_to..]);
+
+    (fixed, applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region_of(src: &str, text: &str) -> Region {
+        let start = src.find(text).unwrap();
+
+        span(start, start + text.len())
+    }
+
+    fn fixed(src: &str, fix: Option<Fix>) -> String {
+        apply_fixes(src, fix.into_iter().collect()).0
+    }
+
+    #[test]
+    fn remove_import_in_the_middle() {
+        let src = "imports [pf.Stdout, Json, pf.Task]\n";
+        let fix = remove_list_item(src, region_of(src, "Json"));
+
+        assert_eq!(fixed(src, Some(fix)), "imports [pf.Stdout, pf.Task]\n");
+    }
+
+    #[test]
+    fn remove_last_import() {
+        let src = "imports [pf.Stdout, Json]\n";
+        let fix = remove_list_item(src, region_of(src, "Json"));
+
+        assert_eq!(fixed(src, Some(fix)), "imports [pf.Stdout]\n");
+    }
+
+    #[test]
+    fn remove_import_on_its_own_line() {
+        let src = "imports [\n    pf.Stdout,\n    Json,\n    pf.Task,\n]\n";
+        let fix = remove_list_item(src, region_of(src, "Json"));
+
+        assert_eq!(
+            fixed(src, Some(fix)),
+            "imports [\n    pf.Stdout,\n    pf.Task,\n]\n"
+        );
+    }
+
+    #[test]
+    fn remove_def_with_annotation() {
+        let src = "main = 1\n\nunused : Str\nunused =\n    \"hi\"\n\nother = 2\n";
+        let start = src.find("unused =").unwrap();
+        let fix = remove_def(src, span(start, start + "unused".len()));
+
+        assert_eq!(fixed(src, fix), "main = 1\n\nother = 2\n");
+    }
+
+    #[test]
+    fn remove_nested_def() {
+        let src = "main =\n    x = 1\n    y = 2\n\n    y\n";
+        let fix = remove_def(src, region_of(src, "x"));
+
+        assert_eq!(fixed(src, fix), "main =\n    y = 2\n\n    y\n");
+    }
+
+    #[test]
+    fn dont_remove_destructure() {
+        let src = "main =\n    { x, y } = rec\n\n    y\n";
+
+        assert_eq!(remove_def(src, region_of(src, "x")), None);
+    }
+
+    #[test]
+    fn add_branches() {
+        let src =
+            "f = \\x ->\n    when x is\n        A -> 1\n        B ->\n            2\n\ng = 3\n";
+        let condition = span(src.find("x is").unwrap(), src.find("x is").unwrap() + 1);
+        let fix = add_when_branches(src, condition, &["C".to_string(), "D _".to_string()]);
+
+        assert_eq!(
+            fixed(src, fix),
+            "f = \\x ->\n    when x is\n        A -> 1\n        B ->\n            2\n        C -> crash \"TODO\"\n        D _ -> crash \"TODO\"\n\ng = 3\n"
+        );
+    }
+
+    #[test]
+    fn overlapping_fixes() {
+        let src = "imports [Json.{ toUtf8 }, pf.Task]\n";
+        let fixes = vec![
+            remove_list_item(src, region_of(src, "toUtf8")),
+            remove_list_item(src, region_of(src, "Json.{ toUtf8 }")),
+        ];
+
+        assert_eq!(
+            apply_fixes(src, fixes),
+            ("imports [pf.Task]\n".to_string(), 1)
+        );
+    }
+}
@@ -2,7 +2,7 @@ use roc_module::ident::Ident;
 use roc_module::ident::{Lowercase, ModuleName, TagName, Uppercase};
 use roc_module::symbol::{Interns, ModuleId, ModuleIds, PQModuleName, PackageQualified, Symbol};
 use roc_problem::Severity;
-use roc_region::all::LineColumnRegion;
+use roc_region::all::{LineColumnRegion, Region};
 use std::path::{Path, PathBuf};
 use std::{fmt, io};
 use ven_pretty::{text, BoxAllocator, DocAllocator, DocBuilder, Render, RenderAnnotated};
@@ -114,6 +114,14 @@ pub struct Report<'b> {
     pub severity: Severity,
 }
 
+/// A machine-applicable edit: replace the source in `region` with `replacement`.
+/// All of the fixes for a problem should be applied together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fix {
+    pub region: Region,
+    pub replacement: String,
+}
+
 impl<'b> Report<'b> {
     pub fn render(
         self,
@@ -1095,6 +1103,43 @@ pub fn to_file_problem_report_string(filename: &Path, error: io::ErrorKind) -> S
     buf
 }
 
+/// Renders a syntax error in `src` (the contents of `filename`) as a report.
+pub fn to_parse_problem_report_string<'a>(
+    filename: &Path,
+    src: &'a str,
+    problem: roc_parse::parser::SyntaxError<'a>,
+    render: RenderTarget,
+    palette: &Palette,
+) -> String {
+    use roc_parse::parser::{FileError, SourceError};
+    use roc_region::all::LineInfo;
+
+    let src_lines: Vec<&str> = src.lines().collect();
+
+    let mut module_ids = ModuleIds::default();
+
+    let module_id = module_ids.get_or_insert(&"find module name somehow?".into());
+
+    let interns = Interns::default();
+
+    let alloc = RocDocAllocator::new(&src_lines, module_id, &interns);
+
+    let lines = LineInfo::new(src);
+    let problem = FileError {
+        problem: SourceError {
+            problem,
+            bytes: src.as_bytes(),
+        },
+        filename: filename.to_path_buf(),
+    };
+
+    let mut buf = String::new();
+    let report = parse_problem(&alloc, &lines, filename.to_path_buf(), 0, problem);
+    report.render(render, &mut buf, &alloc, palette);
+
+    buf
+}
+
 pub fn to_file_problem_report<'b>(
     alloc: &'b RocDocAllocator<'b>,
     filename: &Path,
@@ -95,6 +95,7 @@ mod test_reporting {
         subdir: &str,
         arena: &'a Bump,
         src: &'a str,
+        typed_holes: bool,
     ) -> (String, Result<LoadedModule, LoadingProblem<'a>>) {
         use std::fs::File;
         use std::io::Write;
@@ -127,6 +128,7 @@ mod test_reporting {
                 palette: DEFAULT_PALETTE,
                 threading: Threading::Single,
                 exec_mode: ExecutionMode::Check,
+                typed_holes,
             };
             let result = roc_load::load_and_typecheck(
                 arena,
@@ -147,6 +149,7 @@ mod test_reporting {
         subdir: &str,
         arena: &'a Bump,
         expr_src: &'a str,
+        typed_holes: bool,
     ) -> Result<
         (
             String,
@@ -157,7 +160,7 @@ mod test_reporting {
         ),
         LoadingProblem<'a>,
     > {
-        let (module_src, result) = run_load_and_infer(subdir, arena, expr_src);
+        let (module_src, result) = run_load_and_infer(subdir, arena, expr_src, typed_holes);
         let LoadedModule {
             module_id: home,
             mut can_problems,
@@ -172,7 +175,13 @@ mod test_reporting {
         Ok((module_src, type_problems, can_problems, home, interns))
     }
 
-    fn list_reports_new<F>(subdir: &str, arena: &Bump, src: &str, finalize_render: F) -> String
+    fn list_reports_new<F>(
+        subdir: &str,
+        arena: &Bump,
+        src: &str,
+        typed_holes: bool,
+        finalize_render: F,
+    ) -> String
     where
         F: FnOnce(RocDocBuilder<'_>, &mut String),
     {
@@ -182,7 +191,7 @@ mod test_reporting {
 
         let mut buf = String::new();
 
-        match infer_expr_help_new(subdir, arena, src) {
+        match infer_expr_help_new(subdir, arena, src, typed_holes) {
             Err(LoadingProblem::FormattedReport(fail)) => fail,
             Ok((module_src, type_problems, can_problems, home, interns)) => {
                 let lines = LineInfo::new(&module_src);
@@ -420,7 +429,12 @@ mod test_reporting {
     }
 
     /// Do not call this directly! Use the test_report macro below!
-    fn __new_report_problem_as(test_name: &str, src: &str, check_render: impl FnOnce(&str)) {
+    fn __new_report_problem_as(
+        test_name: &str,
+        src: &str,
+        typed_holes: bool,
+        check_render: impl FnOnce(&str),
+    ) {
         let arena = Bump::new();
 
         let finalize_render = |doc: RocDocBuilder<'_>, buf: &mut String| {
@@ -429,7 +443,7 @@ mod test_reporting {
                 .expect("list_reports")
         };
 
-        let buf = list_reports_new(test_name, &arena, src, finalize_render);
+        let buf = list_reports_new(test_name, &arena, src, typed_holes, finalize_render);
 
         check_render(buf.as_str());
     }
@@ -442,7 +456,7 @@ mod test_reporting {
             #[test]
             $(#[$meta])*
             fn $test_name() {
-                __new_report_problem_as(std::stringify!($test_name), $program, $expecting)
+                __new_report_problem_as(std::stringify!($test_name), $program, false, $expecting)
             }
         }
     }
@@ -452,7 +466,7 @@ mod test_reporting {
             #[test]
             $(#[$meta])*
             fn $test_name() {
-                __new_report_problem_as(std::stringify!($test_name), $program, |golden| pretty_assertions::assert_eq!(golden, ""))
+                __new_report_problem_as(std::stringify!($test_name), $program, false, |golden| pretty_assertions::assert_eq!(golden, ""))
             }
         }
     }
@@ -10177,6 +10191,40 @@ I recommend using camelCase. It's the standard style in Roc code!
         )
     );
 
+    #[test]
+    fn typed_hole() {
+        __new_report_problem_as(
+            "typed_hole",
+            indoc!(
+                r#"
+                f : Str, Str -> Str
+                f = \x, y -> Str.concat x y
+
+                greeting = "Hello"
+
+                f greeting _
+                "#
+            ),
+            true,
+            |golden| {
+                insta::assert_snapshot!(golden, @r###"
+                ── TYPED HOLE ──────────────────────────────────────────── /code/proj/Main.roc ─
+
+                This hole needs a value of this type:
+
+                9│      f greeting _
+                                   ^
+
+                    Str
+
+                These values in scope have a type that fits:
+
+                    greeting : Str
+                "###)
+            },
+        )
+    }
+
     // Record Builders
 
     test_report!(
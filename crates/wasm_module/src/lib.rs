@@ -3,6 +3,8 @@ pub mod opcodes;
 pub mod parse;
 pub mod sections;
 pub mod serialize;
+pub mod validate;
+pub mod wat;
 
 use std::iter::repeat;
 
@@ -22,6 +24,7 @@ use self::sections::{
     TableSection, TypeSection,
 };
 pub use self::serialize::{SerialBuffer, Serialize};
+use self::validate::{validate_module, ValidationError};
 
 pub const STACK_POINTER_GLOBAL_ID: u32 = 0;
 pub const FRAME_ALIGNMENT_BYTES: i32 = 16;
@@ -210,6 +213,17 @@ impl<'a> WasmModule<'a> {
         })
     }
 
+    /// Type-check all function bodies and check indices between sections,
+    /// as a Wasm engine would do before instantiating the module.
+    pub fn validate(&self, arena: &'a Bump) -> Result<(), ValidationError> {
+        validate_module(arena, self)
+    }
+
+    /// Print the module in WebAssembly text format, for debugging
+    pub fn to_wat(&self, arena: &'a Bump) -> Result<String, ParseError> {
+        wat::module_to_wat(arena, self)
+    }
+
     pub fn eliminate_dead_code(&mut self, arena: &'a Bump, called_fns: BitVec<usize>) {
         if DEBUG_SETTINGS.skip_dead_code_elim {
             return;
@@ -191,6 +191,206 @@ impl From<u8> for OpCode {
     }
 }
 
+impl TryFrom<u8> for OpCode {
+    type Error = String;
+
+    /// Checked conversion, for bytes that may not be a valid instruction
+    fn try_from(x: u8) -> Result<Self, Self::Error> {
+        match x {
+            0x00..=0x05 | 0x0b..=0x11 | 0x1a..=0x1b | 0x20..=0x24 | 0x28..=0xbf => {
+                Ok(OpCode::from(x))
+            }
+            _ => Err(format!("Unknown Wasm instruction 0x{:02x}", x)),
+        }
+    }
+}
+
+impl OpCode {
+    /// Instruction name in the WebAssembly text format
+    pub fn text_name(self) -> &'static str {
+        use OpCode::*;
+
+        match self {
+            UNREACHABLE => "unreachable",
+            NOP => "nop",
+            BLOCK => "block",
+            LOOP => "loop",
+            IF => "if",
+            ELSE => "else",
+            END => "end",
+            BR => "br",
+            BRIF => "br_if",
+            BRTABLE => "br_table",
+            RETURN => "return",
+            CALL => "call",
+            CALLINDIRECT => "call_indirect",
+            DROP => "drop",
+            SELECT => "select",
+            GETLOCAL => "local.get",
+            SETLOCAL => "local.set",
+            TEELOCAL => "local.tee",
+            GETGLOBAL => "global.get",
+            SETGLOBAL => "global.set",
+            I32LOAD => "i32.load",
+            I64LOAD => "i64.load",
+            F32LOAD => "f32.load",
+            F64LOAD => "f64.load",
+            I32LOAD8S => "i32.load8_s",
+            I32LOAD8U => "i32.load8_u",
+            I32LOAD16S => "i32.load16_s",
+            I32LOAD16U => "i32.load16_u",
+            I64LOAD8S => "i64.load8_s",
+            I64LOAD8U => "i64.load8_u",
+            I64LOAD16S => "i64.load16_s",
+            I64LOAD16U => "i64.load16_u",
+            I64LOAD32S => "i64.load32_s",
+            I64LOAD32U => "i64.load32_u",
+            I32STORE => "i32.store",
+            I64STORE => "i64.store",
+            F32STORE => "f32.store",
+            F64STORE => "f64.store",
+            I32STORE8 => "i32.store8",
+            I32STORE16 => "i32.store16",
+            I64STORE8 => "i64.store8",
+            I64STORE16 => "i64.store16",
+            I64STORE32 => "i64.store32",
+            CURRENTMEMORY => "memory.size",
+            GROWMEMORY => "memory.grow",
+            I32CONST => "i32.const",
+            I64CONST => "i64.const",
+            F32CONST => "f32.const",
+            F64CONST => "f64.const",
+            I32EQZ => "i32.eqz",
+            I32EQ => "i32.eq",
+            I32NE => "i32.ne",
+            I32LTS => "i32.lt_s",
+            I32LTU => "i32.lt_u",
+            I32GTS => "i32.gt_s",
+            I32GTU => "i32.gt_u",
+            I32LES => "i32.le_s",
+            I32LEU => "i32.le_u",
+            I32GES => "i32.ge_s",
+            I32GEU => "i32.ge_u",
+            I64EQZ => "i64.eqz",
+            I64EQ => "i64.eq",
+            I64NE => "i64.ne",
+            I64LTS => "i64.lt_s",
+            I64LTU => "i64.lt_u",
+            I64GTS => "i64.gt_s",
+            I64GTU => "i64.gt_u",
+            I64LES => "i64.le_s",
+            I64LEU => "i64.le_u",
+            I64GES => "i64.ge_s",
+            I64GEU => "i64.ge_u",
+            F32EQ => "f32.eq",
+            F32NE => "f32.ne",
+            F32LT => "f32.lt",
+            F32GT => "f32.gt",
+            F32LE => "f32.le",
+            F32GE => "f32.ge",
+            F64EQ => "f64.eq",
+            F64NE => "f64.ne",
+            F64LT => "f64.lt",
+            F64GT => "f64.gt",
+            F64LE => "f64.le",
+            F64GE => "f64.ge",
+            I32CLZ => "i32.clz",
+            I32CTZ => "i32.ctz",
+            I32POPCNT => "i32.popcnt",
+            I32ADD => "i32.add",
+            I32SUB => "i32.sub",
+            I32MUL => "i32.mul",
+            I32DIVS => "i32.div_s",
+            I32DIVU => "i32.div_u",
+            I32REMS => "i32.rem_s",
+            I32REMU => "i32.rem_u",
+            I32AND => "i32.and",
+            I32OR => "i32.or",
+            I32XOR => "i32.xor",
+            I32SHL => "i32.shl",
+            I32SHRS => "i32.shr_s",
+            I32SHRU => "i32.shr_u",
+            I32ROTL => "i32.rotl",
+            I32ROTR => "i32.rotr",
+            I64CLZ => "i64.clz",
+            I64CTZ => "i64.ctz",
+            I64POPCNT => "i64.popcnt",
+            I64ADD => "i64.add",
+            I64SUB => "i64.sub",
+            I64MUL => "i64.mul",
+            I64DIVS => "i64.div_s",
+            I64DIVU => "i64.div_u",
+            I64REMS => "i64.rem_s",
+            I64REMU => "i64.rem_u",
+            I64AND => "i64.and",
+            I64OR => "i64.or",
+            I64XOR => "i64.xor",
+            I64SHL => "i64.shl",
+            I64SHRS => "i64.shr_s",
+            I64SHRU => "i64.shr_u",
+            I64ROTL => "i64.rotl",
+            I64ROTR => "i64.rotr",
+            F32ABS => "f32.abs",
+            F32NEG => "f32.neg",
+            F32CEIL => "f32.ceil",
+            F32FLOOR => "f32.floor",
+            F32TRUNC => "f32.trunc",
+            F32NEAREST => "f32.nearest",
+            F32SQRT => "f32.sqrt",
+            F32ADD => "f32.add",
+            F32SUB => "f32.sub",
+            F32MUL => "f32.mul",
+            F32DIV => "f32.div",
+            F32MIN => "f32.min",
+            F32MAX => "f32.max",
+            F32COPYSIGN => "f32.copysign",
+            F64ABS => "f64.abs",
+            F64NEG => "f64.neg",
+            F64CEIL => "f64.ceil",
+            F64FLOOR => "f64.floor",
+            F64TRUNC => "f64.trunc",
+            F64NEAREST => "f64.nearest",
+            F64SQRT => "f64.sqrt",
+            F64ADD => "f64.add",
+            F64SUB => "f64.sub",
+            F64MUL => "f64.mul",
+            F64DIV => "f64.div",
+            F64MIN => "f64.min",
+            F64MAX => "f64.max",
+            F64COPYSIGN => "f64.copysign",
+            I32WRAPI64 => "i32.wrap_i64",
+            I32TRUNCSF32 => "i32.trunc_f32_s",
+            I32TRUNCUF32 => "i32.trunc_f32_u",
+            I32TRUNCSF64 => "i32.trunc_f64_s",
+            I32TRUNCUF64 => "i32.trunc_f64_u",
+            I64EXTENDSI32 => "i64.extend_i32_s",
+            I64EXTENDUI32 => "i64.extend_i32_u",
+            I64TRUNCSF32 => "i64.trunc_f32_s",
+            I64TRUNCUF32 => "i64.trunc_f32_u",
+            I64TRUNCSF64 => "i64.trunc_f64_s",
+            I64TRUNCUF64 => "i64.trunc_f64_u",
+            F32CONVERTSI32 => "f32.convert_i32_s",
+            F32CONVERTUI32 => "f32.convert_i32_u",
+            F32CONVERTSI64 => "f32.convert_i64_s",
+            F32CONVERTUI64 => "f32.convert_i64_u",
+            F32DEMOTEF64 => "f32.demote_f64",
+            F64CONVERTSI32 => "f64.convert_i32_s",
+            F64CONVERTUI32 => "f64.convert_i32_u",
+            F64CONVERTSI64 => "f64.convert_i64_s",
+            F64CONVERTUI64 => "f64.convert_i64_u",
+            F64PROMOTEF32 => "f64.promote_f32",
+            I32REINTERPRETF32 => "i32.reinterpret_f32",
+            I64REINTERPRETF64 => "i64.reinterpret_f64",
+            F32REINTERPRETI32 => "f32.reinterpret_i32",
+            F64REINTERPRETI64 => "f64.reinterpret_i64",
+
+            // Catch-all in case of an invalid cast from u8 to OpCode while parsing binary
+            #[allow(unreachable_patterns)]
+            _ => "<unknown>",
+        }
+    }
+}
+
 /// The format of the *immediate* operands of an operator
 /// Immediates appear directly in the byte stream after the opcode,
 /// rather than being popped off the value stack. These are the possible forms.
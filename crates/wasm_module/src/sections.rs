@@ -298,6 +298,11 @@ impl<'a> TypeSection<'a> {
         self.bytes.is_empty()
     }
 
+    /// Number of signatures in the section
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
     pub fn look_up(&'a self, sig_index: u32) -> (SignatureParamsIter<'a>, Option<ValueType>) {
         let mut offset = self.offsets[sig_index as usize];
         offset += 1; // separator
@@ -935,6 +940,15 @@ impl ConstExpr {
         }
     }
 
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            ConstExpr::I32(_) => ValueType::I32,
+            ConstExpr::I64(_) => ValueType::I64,
+            ConstExpr::F32(_) => ValueType::F32,
+            ConstExpr::F64(_) => ValueType::F64,
+        }
+    }
+
     // ConstExpr and Value are separate types in case we ever need to support
     // arbitrary constant expressions, rather than just i32.const and friends.
     fn as_value(&self) -> Value {
@@ -964,11 +978,13 @@ impl Parse<()> for ConstExpr {
             OpCode::F32CONST => {
                 let mut b = [0; 4];
                 b.copy_from_slice(&bytes[*cursor..][..4]);
+                *cursor += 4;
                 Ok(ConstExpr::F32(f32::from_le_bytes(b)))
             }
             OpCode::F64CONST => {
                 let mut b = [0; 8];
                 b.copy_from_slice(&bytes[*cursor..][..8]);
+                *cursor += 8;
                 Ok(ConstExpr::F64(f64::from_le_bytes(b)))
             }
             _ => Err(ParseError {
@@ -1067,6 +1083,18 @@ impl<'a> GlobalSection<'a> {
         self.count += 1;
     }
 
+    /// Parse the type and initial value of every global in the section
+    pub fn parse_globals<'b>(&self, arena: &'b Bump) -> Result<Vec<'b, Global>, ParseError> {
+        let mut cursor = 0;
+        let mut globals = Vec::with_capacity_in(self.count as usize, arena);
+        for _ in 0..self.count {
+            let ty = GlobalType::parse((), &self.bytes, &mut cursor)?;
+            let init = ConstExpr::parse((), &self.bytes, &mut cursor)?;
+            globals.push(Global { ty, init });
+        }
+        Ok(globals)
+    }
+
     pub fn initial_values<'b>(&self, arena: &'b Bump) -> Vec<'b, Value> {
         let mut cursor = 0;
         let iter = (0..self.count)
@@ -1570,6 +1598,28 @@ impl<'a> DataSection<'a> {
         index
     }
 
+    /// Parse the mode of each segment, along with a reference to its bytes
+    pub fn segments<'b>(
+        &'b self,
+        arena: &'b Bump,
+    ) -> Result<Vec<'b, (DataMode, &'b [u8])>, ParseError> {
+        let mut cursor = 0;
+        let mut segments = Vec::with_capacity_in(self.count as usize, arena);
+        for _ in 0..self.count {
+            let mode = DataMode::parse((), &self.bytes, &mut cursor)?;
+            let len = u32::parse((), &self.bytes, &mut cursor)? as usize;
+            if cursor + len > self.bytes.len() {
+                return Err(ParseError {
+                    offset: cursor,
+                    message: format!("Data segment of {} bytes overruns the Data section", len),
+                });
+            }
+            segments.push((mode, &self.bytes[cursor..][..len]));
+            cursor += len;
+        }
+        Ok(segments)
+    }
+
     pub fn load_into(&self, memory: &mut [u8]) -> Result<(), String> {
         let mut cursor = 0;
         for _ in 0..self.count {
@@ -0,0 +1,1097 @@
+use std::fmt::{self, Display, Formatter};
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+
+use crate::opcodes::OpCode;
+use crate::parse::{Parse, ParseError};
+use crate::sections::{
+    ConstExpr, DataMode, ExportType, GlobalType, ImportDesc, Limits, MemorySection,
+};
+use crate::{Align, ValueType, WasmModule};
+
+/// An error found while validating a module.
+/// Errors inside a function body carry the function index, its debug name (if known),
+/// and the byte offset of the offending instruction within the Code section.
+#[derive(Debug)]
+pub struct ValidationError {
+    pub fn_index: Option<u32>,
+    pub fn_name: Option<String>,
+    pub offset: Option<usize>,
+    pub message: String,
+}
+
+impl ValidationError {
+    fn module_error(message: String) -> Self {
+        ValidationError {
+            fn_index: None,
+            fn_name: None,
+            offset: None,
+            message,
+        }
+    }
+}
+
+impl From<ParseError> for ValidationError {
+    fn from(e: ParseError) -> Self {
+        ValidationError {
+            fn_index: None,
+            fn_name: None,
+            offset: Some(e.offset),
+            message: e.message,
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid Wasm module")?;
+        match (self.fn_index, &self.fn_name) {
+            (Some(index), Some(name)) => write!(f, " in function #{} `{}`", index, name)?,
+            (Some(index), None) => write!(f, " in function #{}", index)?,
+            _ => {}
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " at code offset {:#x}", offset)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Index spaces and types that instructions can refer to
+struct ModuleContext<'a> {
+    /// Signature index of every function, imports first
+    fn_signatures: Vec<'a, u32>,
+    globals: Vec<'a, GlobalType>,
+    type_count: usize,
+    has_memory: bool,
+    has_table: bool,
+}
+
+/// Check that the module would pass validation in a Wasm engine.
+/// https://webassembly.github.io/spec/core/valid/index.html
+///
+/// Only the Wasm MVP instructions supported by this crate are accepted.
+pub fn validate_module<'a>(
+    arena: &'a Bump,
+    module: &WasmModule<'a>,
+) -> Result<(), ValidationError> {
+    let ctx = build_context(arena, module)?;
+
+    validate_exports(arena, module, &ctx)?;
+    validate_elements(module, &ctx)?;
+    validate_data(arena, module, &ctx)?;
+
+    let import_fn_count = module.import.function_count();
+    let dummy_count = module.code.dead_import_dummy_count as usize;
+    for (i, &fn_offset) in module.code.function_offsets.iter().enumerate() {
+        let fn_index = (import_fn_count + dummy_count + i) as u32;
+        let signature_index = ctx.fn_signatures[fn_index as usize];
+        let end = match module.code.function_offsets.get(i + 1) {
+            Some(next) => *next as usize,
+            None => module.code.bytes.len(),
+        };
+
+        let mut validator = FunctionValidator::new(
+            arena,
+            module,
+            &ctx,
+            fn_offset as usize,
+            end,
+            signature_index,
+        );
+        validator.validate().map_err(|message| {
+            let fn_name = module
+                .names
+                .function_names
+                .iter()
+                .find(|(index, _)| *index == fn_index)
+                .map(|(_, name)| name.to_string());
+            ValidationError {
+                fn_index: Some(fn_index),
+                fn_name,
+                offset: Some(validator.op_offset),
+                message,
+            }
+        })?;
+    }
+
+    Ok(())
+}
+
+fn build_context<'a>(
+    arena: &'a Bump,
+    module: &WasmModule<'a>,
+) -> Result<ModuleContext<'a>, ValidationError> {
+    let type_count = module.types.len();
+    let mut fn_signatures = Vec::with_capacity_in(
+        module.import.imports.len() + module.function.signatures.len(),
+        arena,
+    );
+    let mut globals = Vec::new_in(arena);
+    let mut has_memory = module.memory.count > 0;
+    let mut has_table = !module.element.is_empty();
+
+    for import in module.import.imports.iter() {
+        match import.description {
+            ImportDesc::Func { signature_index } => fn_signatures.push(signature_index),
+            ImportDesc::Global { ty } => globals.push(ty),
+            ImportDesc::Mem { .. } => has_memory = true,
+            ImportDesc::Table { .. } => has_table = true,
+        }
+    }
+    fn_signatures.extend(module.function.signatures.iter().copied());
+
+    for (fn_index, signature_index) in fn_signatures.iter().enumerate() {
+        if *signature_index as usize >= type_count {
+            return Err(ValidationError::module_error(format!(
+                "Function #{} has signature index {} but there are only {} signatures",
+                fn_index, signature_index, type_count
+            )));
+        }
+    }
+
+    let code_fn_count =
+        module.code.dead_import_dummy_count as usize + module.code.function_offsets.len();
+    if module.function.signatures.len() != code_fn_count {
+        return Err(ValidationError::module_error(format!(
+            "Function section declares {} functions but Code section has {}",
+            module.function.signatures.len(),
+            code_fn_count
+        )));
+    }
+
+    for global in module.global.parse_globals(arena)? {
+        let init_type = global.init.value_type();
+        if init_type != global.ty.value_type {
+            return Err(ValidationError::module_error(format!(
+                "Global #{} has type {:?} but its initial value is {:?}",
+                globals.len(),
+                global.ty.value_type,
+                init_type
+            )));
+        }
+        globals.push(global.ty);
+    }
+
+    Ok(ModuleContext {
+        fn_signatures,
+        globals,
+        type_count,
+        has_memory,
+        has_table,
+    })
+}
+
+fn validate_exports<'a>(
+    arena: &'a Bump,
+    module: &WasmModule<'a>,
+    ctx: &ModuleContext<'a>,
+) -> Result<(), ValidationError> {
+    let mut names: Vec<'a, &str> = Vec::with_capacity_in(module.export.exports.len(), arena);
+    for export in module.export.exports.iter() {
+        if names.contains(&export.name) {
+            return Err(ValidationError::module_error(format!(
+                "Duplicate export name `{}`",
+                export.name
+            )));
+        }
+        names.push(export.name);
+
+        let is_valid = match export.ty {
+            ExportType::Func => (export.index as usize) < ctx.fn_signatures.len(),
+            ExportType::Global => (export.index as usize) < ctx.globals.len(),
+            ExportType::Mem => export.index == 0 && ctx.has_memory,
+            ExportType::Table => export.index == 0 && ctx.has_table,
+        };
+        if !is_valid {
+            return Err(ValidationError::module_error(format!(
+                "Export `{}` refers to {:?} #{}, which does not exist",
+                export.name, export.ty, export.index
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate_elements(module: &WasmModule, ctx: &ModuleContext) -> Result<(), ValidationError> {
+    if module.element.is_empty() {
+        return Ok(());
+    }
+    let table_size = match module.table.function_table.limits {
+        Limits::Min(min) | Limits::MinMax(min, _) => min,
+    };
+    for segment in module.element.segments.iter() {
+        let start = match segment.offset {
+            ConstExpr::I32(x) => x as u32,
+            _ => {
+                return Err(ValidationError::module_error(
+                    "Element segment offset must be an i32 constant".into(),
+                ))
+            }
+        };
+        let end = start + segment.fn_indices.len() as u32;
+        if end > table_size {
+            return Err(ValidationError::module_error(format!(
+                "Element segment covers table indices {}..{} but the table only has {} entries",
+                start, end, table_size
+            )));
+        }
+        for fn_index in segment.fn_indices.iter() {
+            if *fn_index as usize >= ctx.fn_signatures.len() {
+                return Err(ValidationError::module_error(format!(
+                    "Element segment refers to function #{}, which does not exist",
+                    fn_index
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_data<'a>(
+    arena: &'a Bump,
+    module: &WasmModule<'a>,
+    ctx: &ModuleContext<'a>,
+) -> Result<(), ValidationError> {
+    let segments = module.data.segments(arena)?;
+    if segments.is_empty() {
+        return Ok(());
+    }
+    if !ctx.has_memory {
+        return Err(ValidationError::module_error(
+            "Data section is not empty, but the module has no memory".into(),
+        ));
+    }
+
+    // Memory may be imported instead of defined, in which case only the import knows its size
+    let memory_bytes = if module.memory.count > 0 {
+        module.memory.min_bytes()?
+    } else {
+        module
+            .import
+            .imports
+            .iter()
+            .find_map(|import| match import.description {
+                ImportDesc::Mem {
+                    limits: Limits::Min(min) | Limits::MinMax(min, _),
+                } => Some(min * MemorySection::PAGE_SIZE),
+                _ => None,
+            })
+            .unwrap_or(0)
+    };
+
+    for (i, (mode, init)) in segments.iter().enumerate() {
+        match mode {
+            DataMode::Active {
+                offset: ConstExpr::I32(addr),
+            } => {
+                let end = *addr as u64 + init.len() as u64;
+                if end > memory_bytes as u64 {
+                    return Err(ValidationError::module_error(format!(
+                        "Data segment #{} covers addresses {:#x}..{:#x} but the memory is only {:#x} bytes",
+                        i, addr, end, memory_bytes
+                    )));
+                }
+            }
+            DataMode::Active { .. } => {
+                return Err(ValidationError::module_error(format!(
+                    "Data segment #{} offset must be an i32 constant",
+                    i
+                )));
+            }
+            DataMode::Passive => {}
+        }
+    }
+    Ok(())
+}
+
+/*******************************************************************
+ *
+ * Function bodies
+ * https://webassembly.github.io/spec/core/appendix/algorithm.html
+ *
+ *******************************************************************/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BlockKind {
+    Function,
+    Block,
+    Loop,
+    If,
+    Else,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ControlFrame {
+    kind: BlockKind,
+    result: Option<ValueType>,
+    /// Height of the value stack when the block was entered
+    height: usize,
+    /// Set after an unconditional branch. The rest of the block is dead code,
+    /// so its value stack is polymorphic.
+    unreachable: bool,
+}
+
+impl ControlFrame {
+    /// The type that a branch to this block must provide
+    fn label_type(&self) -> Option<ValueType> {
+        match self.kind {
+            BlockKind::Loop => None,
+            _ => self.result,
+        }
+    }
+}
+
+struct FunctionValidator<'a, 'm> {
+    arena: &'a Bump,
+    module: &'m WasmModule<'a>,
+    ctx: &'m ModuleContext<'a>,
+    bytes: &'m [u8],
+    cursor: usize,
+    end: usize,
+    signature_index: u32,
+    /// Offset of the current instruction, for error messages
+    op_offset: usize,
+    locals: Vec<'a, ValueType>,
+    /// `None` represents a value of unknown type, only possible in unreachable code
+    values: Vec<'a, Option<ValueType>>,
+    blocks: Vec<'a, ControlFrame>,
+}
+
+impl<'a, 'm> FunctionValidator<'a, 'm> {
+    fn new(
+        arena: &'a Bump,
+        module: &'m WasmModule<'a>,
+        ctx: &'m ModuleContext<'a>,
+        start: usize,
+        end: usize,
+        signature_index: u32,
+    ) -> Self {
+        FunctionValidator {
+            arena,
+            module,
+            ctx,
+            bytes: &module.code.bytes,
+            cursor: start,
+            end,
+            signature_index,
+            op_offset: start,
+            locals: Vec::new_in(arena),
+            values: Vec::with_capacity_in(16, arena),
+            blocks: Vec::with_capacity_in(8, arena),
+        }
+    }
+
+    fn validate(&mut self) -> Result<(), String> {
+        let body_size = self.imm_u32()? as usize;
+        if self.cursor + body_size != self.end {
+            return Err(format!(
+                "Function body size is {} bytes but the next function starts {} bytes later",
+                body_size,
+                self.end - self.cursor
+            ));
+        }
+
+        let module = self.module;
+        let (params, ret_type) = module.types.look_up(self.signature_index);
+        self.locals.extend(params);
+
+        let local_group_count = self.imm_u32()?;
+        for _ in 0..local_group_count {
+            let count = self.imm_u32()?;
+            let type_byte = self.imm_byte()?;
+            let ty = self.value_type(type_byte)?;
+            self.locals
+                .extend(std::iter::repeat(ty).take(count as usize));
+        }
+
+        self.blocks.push(ControlFrame {
+            kind: BlockKind::Function,
+            result: ret_type,
+            height: 0,
+            unreachable: false,
+        });
+
+        while !self.blocks.is_empty() {
+            if self.cursor >= self.end {
+                return Err("Function body ended without a final END instruction".into());
+            }
+            self.op_offset = self.cursor;
+            let op = OpCode::try_from(self.imm_byte()?)?;
+            self.instruction(op)?;
+        }
+
+        if self.cursor != self.end {
+            return Err(format!(
+                "Found {} bytes after the final END instruction",
+                self.end - self.cursor
+            ));
+        }
+        Ok(())
+    }
+
+    fn instruction(&mut self, op: OpCode) -> Result<(), String> {
+        use OpCode::*;
+        use ValueType::*;
+
+        match op {
+            UNREACHABLE => self.set_unreachable(),
+            NOP => {}
+            BLOCK => self.enter_block(BlockKind::Block)?,
+            LOOP => self.enter_block(BlockKind::Loop)?,
+            IF => {
+                self.pop_expected(I32)?;
+                self.enter_block(BlockKind::If)?;
+            }
+            ELSE => {
+                let frame = self.end_block()?;
+                if frame.kind != BlockKind::If {
+                    return Err("Found ELSE without a matching IF".into());
+                }
+                self.blocks.push(ControlFrame {
+                    kind: BlockKind::Else,
+                    result: frame.result,
+                    height: self.values.len(),
+                    unreachable: false,
+                });
+            }
+            END => {
+                let frame = self.end_block()?;
+                if let (BlockKind::If, Some(ty)) = (frame.kind, frame.result) {
+                    return Err(format!("IF block returns {:?} but has no ELSE branch", ty));
+                }
+                if frame.kind != BlockKind::Function {
+                    self.push_opt(frame.result);
+                }
+            }
+            BR => {
+                let depth = self.imm_u32()?;
+                self.pop_label(depth)?;
+                self.set_unreachable();
+            }
+            BRIF => {
+                let depth = self.imm_u32()?;
+                self.pop_expected(I32)?;
+                let ty = self.pop_label(depth)?;
+                self.push_opt(ty);
+            }
+            BRTABLE => {
+                let count = self.imm_u32()?;
+                let mut depths = Vec::with_capacity_in(count as usize, self.arena);
+                for _ in 0..count {
+                    depths.push(self.imm_u32()?);
+                }
+                let default_depth = self.imm_u32()?;
+                self.pop_expected(I32)?;
+                let default_type = self.label(default_depth)?.label_type();
+                for depth in depths {
+                    let ty = self.label(depth)?.label_type();
+                    if ty != default_type {
+                        return Err(format!(
+                            "br_table targets have different types: {:?} and {:?}",
+                            ty, default_type
+                        ));
+                    }
+                }
+                self.pop_label(default_depth)?;
+                self.set_unreachable();
+            }
+            RETURN => {
+                if let Some(ty) = self.blocks[0].result {
+                    self.pop_expected(ty)?;
+                }
+                self.set_unreachable();
+            }
+            CALL => {
+                let fn_index = self.imm_u32()? as usize;
+                let signature_index = match self.ctx.fn_signatures.get(fn_index) {
+                    Some(sig) => *sig,
+                    None => {
+                        return Err(format!(
+                            "Call to function #{}, but there are only {} functions",
+                            fn_index,
+                            self.ctx.fn_signatures.len()
+                        ))
+                    }
+                };
+                self.call(signature_index)?;
+            }
+            CALLINDIRECT => {
+                let signature_index = self.imm_u32()?;
+                let table_index = self.imm_u32()?;
+                if signature_index as usize >= self.ctx.type_count {
+                    return Err(format!(
+                        "Indirect call with signature index {}, but there are only {} signatures",
+                        signature_index, self.ctx.type_count
+                    ));
+                }
+                if table_index != 0 || !self.ctx.has_table {
+                    return Err(format!(
+                        "Indirect call uses table #{}, which does not exist",
+                        table_index
+                    ));
+                }
+                self.pop_expected(I32)?;
+                self.call(signature_index)?;
+            }
+            DROP => {
+                self.pop()?;
+            }
+            SELECT => {
+                self.pop_expected(I32)?;
+                let second = self.pop()?;
+                let first = self.pop()?;
+                match (first, second) {
+                    (Some(t1), Some(t2)) if t1 != t2 => {
+                        return Err(format!(
+                            "SELECT operands have different types: {:?} and {:?}",
+                            t1, t2
+                        ))
+                    }
+                    _ => self.push_opt(first.or(second)),
+                }
+            }
+            GETLOCAL => {
+                let index = self.imm_u32()?;
+                let ty = self.local(index)?;
+                self.push(ty);
+            }
+            SETLOCAL => {
+                let index = self.imm_u32()?;
+                let ty = self.local(index)?;
+                self.pop_expected(ty)?;
+            }
+            TEELOCAL => {
+                let index = self.imm_u32()?;
+                let ty = self.local(index)?;
+                self.pop_expected(ty)?;
+                self.push(ty);
+            }
+            GETGLOBAL => {
+                let index = self.imm_u32()?;
+                let global = self.global(index)?;
+                self.push(global.value_type);
+            }
+            SETGLOBAL => {
+                let index = self.imm_u32()?;
+                let global = self.global(index)?;
+                if !global.is_mutable {
+                    return Err(format!("Global #{} is immutable", index));
+                }
+                self.pop_expected(global.value_type)?;
+            }
+
+            I32LOAD => self.load(I32, Align::Bytes4)?,
+            I64LOAD => self.load(I64, Align::Bytes8)?,
+            F32LOAD => self.load(F32, Align::Bytes4)?,
+            F64LOAD => self.load(F64, Align::Bytes8)?,
+            I32LOAD8S | I32LOAD8U => self.load(I32, Align::Bytes1)?,
+            I32LOAD16S | I32LOAD16U => self.load(I32, Align::Bytes2)?,
+            I64LOAD8S | I64LOAD8U => self.load(I64, Align::Bytes1)?,
+            I64LOAD16S | I64LOAD16U => self.load(I64, Align::Bytes2)?,
+            I64LOAD32S | I64LOAD32U => self.load(I64, Align::Bytes4)?,
+            I32STORE => self.store(I32, Align::Bytes4)?,
+            I64STORE => self.store(I64, Align::Bytes8)?,
+            F32STORE => self.store(F32, Align::Bytes4)?,
+            F64STORE => self.store(F64, Align::Bytes8)?,
+            I32STORE8 => self.store(I32, Align::Bytes1)?,
+            I32STORE16 => self.store(I32, Align::Bytes2)?,
+            I64STORE8 => self.store(I64, Align::Bytes1)?,
+            I64STORE16 => self.store(I64, Align::Bytes2)?,
+            I64STORE32 => self.store(I64, Align::Bytes4)?,
+
+            CURRENTMEMORY => {
+                self.memory_index()?;
+                self.push(I32);
+            }
+            GROWMEMORY => {
+                self.memory_index()?;
+                self.pop_expected(I32)?;
+                self.push(I32);
+            }
+
+            I32CONST => {
+                self.check_not_at_end()?;
+                i32::parse((), self.bytes, &mut self.cursor).map_err(|e| e.message)?;
+                self.check_not_past_end()?;
+                self.push(I32);
+            }
+            I64CONST => {
+                self.check_not_at_end()?;
+                i64::parse((), self.bytes, &mut self.cursor).map_err(|e| e.message)?;
+                self.check_not_past_end()?;
+                self.push(I64);
+            }
+            F32CONST => {
+                self.skip(4)?;
+                self.push(F32);
+            }
+            F64CONST => {
+                self.skip(8)?;
+                self.push(F64);
+            }
+
+            I32EQZ => self.test_op(I32)?,
+            I64EQZ => self.test_op(I64)?,
+
+            I32EQ | I32NE | I32LTS | I32LTU | I32GTS | I32GTU | I32LES | I32LEU | I32GES
+            | I32GEU => self.compare_op(I32)?,
+            I64EQ | I64NE | I64LTS | I64LTU | I64GTS | I64GTU | I64LES | I64LEU | I64GES
+            | I64GEU => self.compare_op(I64)?,
+            F32EQ | F32NE | F32LT | F32GT | F32LE | F32GE => self.compare_op(F32)?,
+            F64EQ | F64NE | F64LT | F64GT | F64LE | F64GE => self.compare_op(F64)?,
+
+            I32CLZ | I32CTZ | I32POPCNT => self.unary_op(I32)?,
+            I64CLZ | I64CTZ | I64POPCNT => self.unary_op(I64)?,
+            F32ABS | F32NEG | F32CEIL | F32FLOOR | F32TRUNC | F32NEAREST | F32SQRT => {
+                self.unary_op(F32)?
+            }
+            F64ABS | F64NEG | F64CEIL | F64FLOOR | F64TRUNC | F64NEAREST | F64SQRT => {
+                self.unary_op(F64)?
+            }
+
+            I32ADD | I32SUB | I32MUL | I32DIVS | I32DIVU | I32REMS | I32REMU | I32AND | I32OR
+            | I32XOR | I32SHL | I32SHRS | I32SHRU | I32ROTL | I32ROTR => self.binary_op(I32)?,
+            I64ADD | I64SUB | I64MUL | I64DIVS | I64DIVU | I64REMS | I64REMU | I64AND | I64OR
+            | I64XOR | I64SHL | I64SHRS | I64SHRU | I64ROTL | I64ROTR => self.binary_op(I64)?,
+            F32ADD | F32SUB | F32MUL | F32DIV | F32MIN | F32MAX | F32COPYSIGN => {
+                self.binary_op(F32)?
+            }
+            F64ADD | F64SUB | F64MUL | F64DIV | F64MIN | F64MAX | F64COPYSIGN => {
+                self.binary_op(F64)?
+            }
+
+            I32WRAPI64 => self.convert_op(I64, I32)?,
+            I32TRUNCSF32 | I32TRUNCUF32 | I32REINTERPRETF32 => self.convert_op(F32, I32)?,
+            I32TRUNCSF64 | I32TRUNCUF64 => self.convert_op(F64, I32)?,
+            I64EXTENDSI32 | I64EXTENDUI32 => self.convert_op(I32, I64)?,
+            I64TRUNCSF32 | I64TRUNCUF32 => self.convert_op(F32, I64)?,
+            I64TRUNCSF64 | I64TRUNCUF64 | I64REINTERPRETF64 => self.convert_op(F64, I64)?,
+            F32CONVERTSI32 | F32CONVERTUI32 | F32REINTERPRETI32 => self.convert_op(I32, F32)?,
+            F32CONVERTSI64 | F32CONVERTUI64 => self.convert_op(I64, F32)?,
+            F32DEMOTEF64 => self.convert_op(F64, F32)?,
+            F64CONVERTSI32 | F64CONVERTUI32 => self.convert_op(I32, F64)?,
+            F64CONVERTSI64 | F64CONVERTUI64 | F64REINTERPRETI64 => self.convert_op(I64, F64)?,
+            F64PROMOTEF32 => self.convert_op(F32, F64)?,
+        }
+
+        Ok(())
+    }
+
+    /*
+     * Immediates
+     */
+
+    fn check_not_at_end(&self) -> Result<(), String> {
+        if self.cursor >= self.end {
+            Err("Unexpected end of function body".into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_not_past_end(&self) -> Result<(), String> {
+        if self.cursor > self.end {
+            Err("Unexpected end of function body".into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn imm_byte(&mut self) -> Result<u8, String> {
+        self.check_not_at_end()?;
+        let byte = self.bytes[self.cursor];
+        self.cursor += 1;
+        Ok(byte)
+    }
+
+    fn imm_u32(&mut self) -> Result<u32, String> {
+        self.check_not_at_end()?;
+        let value = u32::parse((), self.bytes, &mut self.cursor).map_err(|e| e.message)?;
+        self.check_not_past_end()?;
+        Ok(value)
+    }
+
+    fn skip(&mut self, n: usize) -> Result<(), String> {
+        self.cursor += n;
+        self.check_not_past_end()
+    }
+
+    fn value_type(&self, byte: u8) -> Result<ValueType, String> {
+        match byte {
+            0x7c..=0x7f => Ok(ValueType::from(byte)),
+            _ => Err(format!("Invalid value type 0x{:02x}", byte)),
+        }
+    }
+
+    fn local(&self, index: u32) -> Result<ValueType, String> {
+        self.locals.get(index as usize).copied().ok_or_else(|| {
+            format!(
+                "Local #{} does not exist. This function has {} locals (including arguments)",
+                index,
+                self.locals.len()
+            )
+        })
+    }
+
+    fn global(&self, index: u32) -> Result<GlobalType, String> {
+        self.ctx
+            .globals
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "Global #{} does not exist. This module has {} globals",
+                    index,
+                    self.ctx.globals.len()
+                )
+            })
+    }
+
+    fn memory_index(&mut self) -> Result<(), String> {
+        let index = self.imm_byte()?;
+        if index != 0 || !self.ctx.has_memory {
+            return Err(format!("Memory #{} does not exist", index));
+        }
+        Ok(())
+    }
+
+    fn memarg(&mut self, natural_alignment: Align) -> Result<(), String> {
+        let alignment = self.imm_u32()?;
+        self.imm_u32()?; // offset
+        if !self.ctx.has_memory {
+            return Err("Memory access in a module with no memory".into());
+        }
+        if alignment > natural_alignment as u32 {
+            return Err(format!(
+                "Alignment of 2^{} bytes is larger than the natural alignment of {:?}",
+                alignment, natural_alignment
+            ));
+        }
+        Ok(())
+    }
+
+    /*
+     * Value stack
+     */
+
+    fn push(&mut self, ty: ValueType) {
+        self.values.push(Some(ty));
+    }
+
+    fn push_opt(&mut self, ty: Option<ValueType>) {
+        if let Some(t) = ty {
+            self.push(t);
+        }
+    }
+
+    fn pop(&mut self) -> Result<Option<ValueType>, String> {
+        let frame = self.blocks.last().unwrap();
+        if self.values.len() > frame.height {
+            Ok(self.values.pop().unwrap())
+        } else if frame.unreachable {
+            Ok(None)
+        } else {
+            Err("Tried to pop a value from the stack, but it was empty".into())
+        }
+    }
+
+    fn pop_expected(&mut self, expected: ValueType) -> Result<(), String> {
+        match self.pop()? {
+            Some(actual) if actual != expected => Err(format!(
+                "Type mismatch. Expected {:?}, but found {:?}",
+                expected, actual
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn unary_op(&mut self, ty: ValueType) -> Result<(), String> {
+        self.pop_expected(ty)?;
+        self.push(ty);
+        Ok(())
+    }
+
+    fn binary_op(&mut self, ty: ValueType) -> Result<(), String> {
+        self.pop_expected(ty)?;
+        self.pop_expected(ty)?;
+        self.push(ty);
+        Ok(())
+    }
+
+    fn test_op(&mut self, ty: ValueType) -> Result<(), String> {
+        self.pop_expected(ty)?;
+        self.push(ValueType::I32);
+        Ok(())
+    }
+
+    fn compare_op(&mut self, ty: ValueType) -> Result<(), String> {
+        self.pop_expected(ty)?;
+        self.pop_expected(ty)?;
+        self.push(ValueType::I32);
+        Ok(())
+    }
+
+    fn convert_op(&mut self, from: ValueType, to: ValueType) -> Result<(), String> {
+        self.pop_expected(from)?;
+        self.push(to);
+        Ok(())
+    }
+
+    fn load(&mut self, ty: ValueType, natural_alignment: Align) -> Result<(), String> {
+        self.memarg(natural_alignment)?;
+        self.pop_expected(ValueType::I32)?;
+        self.push(ty);
+        Ok(())
+    }
+
+    fn store(&mut self, ty: ValueType, natural_alignment: Align) -> Result<(), String> {
+        self.memarg(natural_alignment)?;
+        self.pop_expected(ty)?;
+        self.pop_expected(ValueType::I32)?;
+        Ok(())
+    }
+
+    fn call(&mut self, signature_index: u32) -> Result<(), String> {
+        let module = self.module;
+        let (params, ret_type) = module.types.look_up(signature_index);
+        for ty in params.rev() {
+            self.pop_expected(ty)?;
+        }
+        self.push_opt(ret_type);
+        Ok(())
+    }
+
+    /*
+     * Control flow
+     */
+
+    fn enter_block(&mut self, kind: BlockKind) -> Result<(), String> {
+        let block_type = self.imm_byte()?;
+        let result = if block_type == ValueType::VOID {
+            None
+        } else {
+            Some(self.value_type(block_type)?)
+        };
+        self.blocks.push(ControlFrame {
+            kind,
+            result,
+            height: self.values.len(),
+            unreachable: false,
+        });
+        Ok(())
+    }
+
+    fn end_block(&mut self) -> Result<ControlFrame, String> {
+        let frame = *self.blocks.last().unwrap();
+        if let Some(ty) = frame.result {
+            self.pop_expected(ty)?;
+        }
+        if self.values.len() != frame.height {
+            return Err(format!(
+                "{:?} block ended with {} extra values on the stack",
+                frame.kind,
+                self.values.len() - frame.height
+            ));
+        }
+        self.blocks.pop();
+        Ok(frame)
+    }
+
+    fn label(&self, depth: u32) -> Result<&ControlFrame, String> {
+        let len = self.blocks.len();
+        if (depth as usize) < len {
+            Ok(&self.blocks[len - 1 - depth as usize])
+        } else {
+            Err(format!(
+                "Branch to block depth {}, but only {} blocks are open",
+                depth, len
+            ))
+        }
+    }
+
+    fn pop_label(&mut self, depth: u32) -> Result<Option<ValueType>, String> {
+        let ty = self.label(depth)?.label_type();
+        if let Some(t) = ty {
+            self.pop_expected(t)?;
+        }
+        Ok(ty)
+    }
+
+    fn set_unreachable(&mut self) {
+        let frame = self.blocks.last_mut().unwrap();
+        self.values.truncate(frame.height);
+        frame.unreachable = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sections::Signature;
+    use crate::SerialBuffer;
+
+    fn module_with_function<'a>(
+        arena: &'a Bump,
+        param_types: &[ValueType],
+        ret_type: Option<ValueType>,
+        body: &[u8],
+    ) -> WasmModule<'a> {
+        let mut module = WasmModule::new(arena);
+        module.add_function_signature(Signature {
+            param_types: Vec::from_iter_in(param_types.iter().copied(), arena),
+            ret_type,
+        });
+
+        // Code section bytes start with the function count
+        module.code.bytes.encode_u32(1);
+        module
+            .code
+            .function_offsets
+            .push(module.code.bytes.len() as u32);
+        module.code.function_count = 1;
+
+        let no_locals = 0;
+        module.code.bytes.encode_u32(body.len() as u32 + 1);
+        module.code.bytes.append_u8(no_locals);
+        module.code.bytes.append_slice(body);
+
+        module.names.append_function(0, "my_function");
+        module
+    }
+
+    #[test]
+    fn valid_add() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let body = [
+            GETLOCAL as u8,
+            0,
+            GETLOCAL as u8,
+            1,
+            I32ADD as u8,
+            END as u8,
+        ];
+        let module = module_with_function(
+            arena,
+            &[ValueType::I32, ValueType::I32],
+            Some(ValueType::I32),
+            &body,
+        );
+        validate_module(arena, &module).unwrap();
+    }
+
+    #[test]
+    fn valid_unreachable_code() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let body = [UNREACHABLE as u8, I64ADD as u8, DROP as u8, END as u8];
+        let module = module_with_function(arena, &[], Some(ValueType::F64), &body);
+        validate_module(arena, &module).unwrap();
+    }
+
+    #[test]
+    fn valid_if_else() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let body = [
+            GETLOCAL as u8,
+            0,
+            IF as u8,
+            ValueType::I64 as u8,
+            I64CONST as u8,
+            1,
+            ELSE as u8,
+            I64CONST as u8,
+            2,
+            END as u8,
+            END as u8,
+        ];
+        let module = module_with_function(arena, &[ValueType::I32], Some(ValueType::I64), &body);
+        validate_module(arena, &module).unwrap();
+    }
+
+    #[test]
+    fn type_mismatch() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let body = [
+            GETLOCAL as u8,
+            0,
+            I64CONST as u8,
+            1,
+            I32ADD as u8,
+            END as u8,
+        ];
+        let module = module_with_function(arena, &[ValueType::I32], Some(ValueType::I32), &body);
+        let err = validate_module(arena, &module).unwrap_err();
+        assert_eq!(err.fn_index, Some(0));
+        assert_eq!(err.fn_name.as_deref(), Some("my_function"));
+        assert_eq!(err.message, "Type mismatch. Expected I32, but found I64");
+    }
+
+    #[test]
+    fn missing_return_value() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let body = [NOP as u8, END as u8];
+        let module = module_with_function(arena, &[], Some(ValueType::I32), &body);
+        assert!(validate_module(arena, &module).is_err());
+    }
+
+    #[test]
+    fn extra_value_at_end_of_block() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let body = [
+            BLOCK as u8,
+            ValueType::VOID,
+            I32CONST as u8,
+            1,
+            END as u8,
+            END as u8,
+        ];
+        let module = module_with_function(arena, &[], None, &body);
+        assert!(validate_module(arena, &module).is_err());
+    }
+
+    #[test]
+    fn invalid_local_index() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let body = [GETLOCAL as u8, 1, DROP as u8, END as u8];
+        let module = module_with_function(arena, &[ValueType::I32], None, &body);
+        let err = validate_module(arena, &module).unwrap_err();
+        assert!(err.message.starts_with("Local #1 does not exist"));
+    }
+
+    #[test]
+    fn invalid_call_index() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let body = [CALL as u8, 5, END as u8];
+        let module = module_with_function(arena, &[], None, &body);
+        assert!(validate_module(arena, &module).is_err());
+    }
+
+    #[test]
+    fn unknown_opcode() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let undefined_opcode = 0x06;
+        let body = [NOP as u8, undefined_opcode, END as u8];
+        let module = module_with_function(arena, &[], None, &body);
+        let err = validate_module(arena, &module).unwrap_err();
+        assert_eq!(err.message, "Unknown Wasm instruction 0x06");
+    }
+
+    #[test]
+    fn invalid_branch_depth() {
+        use OpCode::*;
+        let arena = &Bump::new();
+        let body = [BR as u8, 1, END as u8];
+        let module = module_with_function(arena, &[], None, &body);
+        assert!(validate_module(arena, &module).is_err());
+    }
+}
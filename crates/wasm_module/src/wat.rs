@@ -0,0 +1,711 @@
+use std::fmt::Write;
+
+use bumpalo::Bump;
+
+use crate::linking::{SymInfo, WasmObjectSymbol};
+use crate::opcodes::OpCode;
+use crate::parse::{Parse, ParseError};
+use crate::sections::{ConstExpr, DataMode, ImportDesc, Limits, MemorySection, TableType};
+use crate::{ExportType, ValueType, WasmModule};
+
+/// Print a module in the WebAssembly text format (.wat)
+/// https://webassembly.github.io/spec/core/text/index.html
+///
+/// Functions and globals are labelled with names from the Name section or the linking data,
+/// so that the output is readable without any external tools.
+pub fn module_to_wat<'a>(arena: &'a Bump, module: &WasmModule<'a>) -> Result<String, ParseError> {
+    let mut printer = WatPrinter::new(module);
+    printer.print_module(arena)?;
+    Ok(printer.out)
+}
+
+struct WatPrinter<'a, 'm> {
+    module: &'m WasmModule<'a>,
+    out: String,
+    /// Unique WAT identifier for each function (imports first), if it has a name
+    fn_names: std::vec::Vec<Option<String>>,
+    /// Unique WAT identifier for each global (imports first), if it has a name
+    global_names: std::vec::Vec<Option<String>>,
+}
+
+impl<'a, 'm> WatPrinter<'a, 'm> {
+    fn new(module: &'m WasmModule<'a>) -> Self {
+        let import_fn_count = module.import.function_count();
+        let fn_count = import_fn_count + module.function.signatures.len();
+        let import_global_count = module
+            .import
+            .imports
+            .iter()
+            .filter(|imp| matches!(imp.description, ImportDesc::Global { .. }))
+            .count();
+        let global_count = import_global_count + module.global.count as usize;
+
+        let mut raw_fn_names: std::vec::Vec<Option<&str>> = vec![None; fn_count];
+        let mut raw_global_names: std::vec::Vec<Option<&str>> = vec![None; global_count];
+
+        // Lowest priority: the names of imports
+        let mut import_fn_index = 0;
+        let mut import_global_index = 0;
+        for import in module.import.imports.iter() {
+            match import.description {
+                ImportDesc::Func { .. } => {
+                    raw_fn_names[import_fn_index] = Some(import.name);
+                    import_fn_index += 1;
+                }
+                ImportDesc::Global { .. } => {
+                    raw_global_names[import_global_index] = Some(import.name);
+                    import_global_index += 1;
+                }
+                _ => {}
+            }
+        }
+
+        // Then linker symbols
+        for sym in module.linking.symbol_table.iter() {
+            match sym {
+                SymInfo::Function(WasmObjectSymbol::ExplicitlyNamed { index, name, .. }) => {
+                    if let Some(slot) = raw_fn_names.get_mut(*index as usize) {
+                        *slot = Some(*name);
+                    }
+                }
+                SymInfo::Global(WasmObjectSymbol::ExplicitlyNamed { index, name, .. }) => {
+                    if let Some(slot) = raw_global_names.get_mut(*index as usize) {
+                        *slot = Some(*name);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Highest priority: debug names
+        for (index, name) in module.names.function_names.iter() {
+            if let Some(slot) = raw_fn_names.get_mut(*index as usize) {
+                *slot = Some(*name);
+            }
+        }
+
+        WatPrinter {
+            module,
+            out: String::with_capacity(module.code.bytes.len() * 4),
+            fn_names: unique_identifiers(&raw_fn_names),
+            global_names: unique_identifiers(&raw_global_names),
+        }
+    }
+
+    fn print_module(&mut self, arena: &'a Bump) -> Result<(), ParseError> {
+        self.out.push_str("(module\n");
+        self.print_types();
+        self.print_imports();
+        self.print_functions()?;
+        self.print_table();
+        self.print_memory()?;
+        self.print_globals(arena)?;
+        self.print_exports();
+        self.print_elements();
+        self.print_data(arena)?;
+        self.out.push_str(")\n");
+        Ok(())
+    }
+
+    fn print_types(&mut self) {
+        for sig_index in 0..self.module.types.len() {
+            write!(self.out, "  (type (;{};) (func", sig_index).unwrap();
+            self.print_signature(sig_index as u32);
+            self.out.push_str("))\n");
+        }
+    }
+
+    fn print_imports(&mut self) {
+        let module = self.module;
+        let mut fn_index = 0;
+        let mut global_index = 0;
+        for import in module.import.imports.iter() {
+            self.out.push_str("  (import ");
+            print_string(&mut self.out, import.module.as_bytes());
+            self.out.push(' ');
+            print_string(&mut self.out, import.name.as_bytes());
+            self.out.push(' ');
+            match &import.description {
+                ImportDesc::Func { signature_index } => {
+                    self.out.push_str("(func");
+                    self.print_fn_label(fn_index);
+                    write!(self.out, " (type {})", signature_index).unwrap();
+                    self.print_signature(*signature_index);
+                    self.out.push(')');
+                    fn_index += 1;
+                }
+                ImportDesc::Global { ty } => {
+                    self.out.push_str("(global");
+                    self.print_global_label(global_index);
+                    self.out.push(' ');
+                    self.print_global_type(ty.value_type, ty.is_mutable);
+                    self.out.push(')');
+                    global_index += 1;
+                }
+                ImportDesc::Mem { limits } => {
+                    self.out.push_str("(memory (;0;)");
+                    print_limits(&mut self.out, limits);
+                    self.out.push(')');
+                }
+                ImportDesc::Table { ty } => {
+                    self.out.push_str("(table (;0;)");
+                    print_table_type(&mut self.out, ty);
+                    self.out.push(')');
+                }
+            }
+            self.out.push_str(")\n");
+        }
+    }
+
+    fn print_functions(&mut self) -> Result<(), ParseError> {
+        let module = self.module;
+        let import_fn_count = module.import.function_count();
+        let dummy_count = module.code.dead_import_dummy_count as usize;
+
+        for i in 0..dummy_count {
+            self.print_fn_header(import_fn_count + i);
+            self.out.push_str("\n    unreachable)\n");
+        }
+
+        for (i, &fn_offset) in module.code.function_offsets.iter().enumerate() {
+            let fn_index = import_fn_count + dummy_count + i;
+            self.print_fn_header(fn_index);
+            self.out.push('\n');
+
+            let mut cursor = fn_offset as usize;
+            let bytes = &module.code.bytes;
+            u32::parse((), bytes, &mut cursor)?; // function size
+
+            let local_group_count = u32::parse((), bytes, &mut cursor)?;
+            if local_group_count > 0 {
+                self.out.push_str("    (local");
+                for _ in 0..local_group_count {
+                    let (count, ty) = <(u32, ValueType)>::parse((), bytes, &mut cursor)?;
+                    for _ in 0..count {
+                        write!(self.out, " {}", value_type_name(ty)).unwrap();
+                    }
+                }
+                self.out.push_str(")\n");
+            }
+
+            self.print_body(&mut cursor)?;
+        }
+        Ok(())
+    }
+
+    fn print_fn_header(&mut self, fn_index: usize) {
+        let import_fn_count = self.module.import.function_count();
+        let signature_index = self.module.function.signatures[fn_index - import_fn_count];
+        self.out.push_str("  (func");
+        self.print_fn_label(fn_index);
+        write!(self.out, " (type {})", signature_index).unwrap();
+        self.print_signature(signature_index);
+    }
+
+    /// Print instructions up to and including the `end` of the function body
+    fn print_body(&mut self, cursor: &mut usize) -> Result<(), ParseError> {
+        use OpCode::*;
+
+        let module = self.module;
+        let bytes: &[u8] = &module.code.bytes;
+        let mut depth = 1;
+
+        while depth > 0 {
+            if *cursor >= bytes.len() {
+                return Err(ParseError {
+                    offset: *cursor,
+                    message: "Function body ended without a final END instruction".into(),
+                });
+            }
+            let op_offset = *cursor;
+            let op = OpCode::try_from(bytes[*cursor]).map_err(|message| ParseError {
+                offset: op_offset,
+                message,
+            })?;
+            *cursor += 1;
+
+            match op {
+                ELSE => self.indent(depth - 1),
+                END => {
+                    depth -= 1;
+                    if depth == 0 {
+                        // The function's own `end` closes the s-expression
+                        self.out.truncate(self.out.trim_end().len());
+                        self.out.push_str(")\n");
+                        break;
+                    }
+                    self.indent(depth);
+                }
+                _ => self.indent(depth),
+            }
+            self.out.push_str(op.text_name());
+
+            match op {
+                BLOCK | LOOP | IF => {
+                    let block_type = bytes[*cursor];
+                    *cursor += 1;
+                    if block_type != ValueType::VOID {
+                        let ty = ValueType::from(block_type);
+                        write!(self.out, " (result {})", value_type_name(ty)).unwrap();
+                    }
+                    depth += 1;
+                }
+                BR | BRIF => {
+                    let label = u32::parse((), bytes, cursor)?;
+                    write!(self.out, " {}", label).unwrap();
+                }
+                BRTABLE => {
+                    let count = u32::parse((), bytes, cursor)?;
+                    for _ in 0..=count {
+                        let label = u32::parse((), bytes, cursor)?;
+                        write!(self.out, " {}", label).unwrap();
+                    }
+                }
+                CALL => {
+                    let fn_index = u32::parse((), bytes, cursor)?;
+                    self.print_fn_reference(fn_index);
+                }
+                CALLINDIRECT => {
+                    let signature_index = u32::parse((), bytes, cursor)?;
+                    let _table_index = u32::parse((), bytes, cursor)?;
+                    write!(self.out, " (type {})", signature_index).unwrap();
+                }
+                GETLOCAL | SETLOCAL | TEELOCAL => {
+                    let local_index = u32::parse((), bytes, cursor)?;
+                    write!(self.out, " {}", local_index).unwrap();
+                }
+                GETGLOBAL | SETGLOBAL => {
+                    let global_index = u32::parse((), bytes, cursor)?;
+                    self.print_global_reference(global_index);
+                }
+                I32LOAD | F32LOAD | I64LOAD32S | I64LOAD32U | I32STORE | F32STORE | I64STORE32 => {
+                    self.print_memarg(bytes, cursor, 4)?
+                }
+                I64LOAD | F64LOAD | I64STORE | F64STORE => self.print_memarg(bytes, cursor, 8)?,
+                I32LOAD16S | I32LOAD16U | I64LOAD16S | I64LOAD16U | I32STORE16 | I64STORE16 => {
+                    self.print_memarg(bytes, cursor, 2)?
+                }
+                I32LOAD8S | I32LOAD8U | I64LOAD8S | I64LOAD8U | I32STORE8 | I64STORE8 => {
+                    self.print_memarg(bytes, cursor, 1)?
+                }
+                CURRENTMEMORY | GROWMEMORY => {
+                    *cursor += 1; // memory index (always zero in the MVP)
+                }
+                I32CONST => {
+                    let x = i32::parse((), bytes, cursor)?;
+                    write!(self.out, " {}", x).unwrap();
+                }
+                I64CONST => {
+                    let x = i64::parse((), bytes, cursor)?;
+                    write!(self.out, " {}", x).unwrap();
+                }
+                F32CONST => {
+                    let mut b = [0; 4];
+                    b.copy_from_slice(&bytes[*cursor..][..4]);
+                    *cursor += 4;
+                    self.print_f32(f32::from_le_bytes(b));
+                }
+                F64CONST => {
+                    let mut b = [0; 8];
+                    b.copy_from_slice(&bytes[*cursor..][..8]);
+                    *cursor += 8;
+                    self.print_f64(f64::from_le_bytes(b));
+                }
+                _ => {
+                    if op.text_name() == "<unknown>" {
+                        return Err(ParseError {
+                            offset: op_offset,
+                            message: format!("Unknown Wasm instruction 0x{:02x}", op as u8),
+                        });
+                    }
+                }
+            }
+            self.out.push('\n');
+        }
+        Ok(())
+    }
+
+    fn print_memarg(
+        &mut self,
+        bytes: &[u8],
+        cursor: &mut usize,
+        natural_alignment_bytes: u32,
+    ) -> Result<(), ParseError> {
+        let alignment_bytes = 1 << u32::parse((), bytes, cursor)?;
+        let offset = u32::parse((), bytes, cursor)?;
+        if offset != 0 {
+            write!(self.out, " offset={}", offset).unwrap();
+        }
+        if alignment_bytes != natural_alignment_bytes {
+            write!(self.out, " align={}", alignment_bytes).unwrap();
+        }
+        Ok(())
+    }
+
+    fn print_f32(&mut self, x: f32) {
+        if x.is_nan() {
+            let sign = if x.is_sign_negative() { "-" } else { "" };
+            let payload = x.to_bits() & 0x007f_ffff;
+            write!(self.out, " {}nan:0x{:x}", sign, payload).unwrap();
+        } else if x.is_infinite() {
+            let sign = if x.is_sign_negative() { "-" } else { "" };
+            write!(self.out, " {}inf", sign).unwrap();
+        } else {
+            write!(self.out, " {}", x).unwrap();
+        }
+    }
+
+    fn print_f64(&mut self, x: f64) {
+        if x.is_nan() {
+            let sign = if x.is_sign_negative() { "-" } else { "" };
+            let payload = x.to_bits() & 0x000f_ffff_ffff_ffff;
+            write!(self.out, " {}nan:0x{:x}", sign, payload).unwrap();
+        } else if x.is_infinite() {
+            let sign = if x.is_sign_negative() { "-" } else { "" };
+            write!(self.out, " {}inf", sign).unwrap();
+        } else {
+            write!(self.out, " {}", x).unwrap();
+        }
+    }
+
+    fn print_table(&mut self) {
+        // The table is only serialized if it has elements
+        if self.module.element.is_empty() {
+            return;
+        }
+        self.out.push_str("  (table (;0;)");
+        print_table_type(&mut self.out, &self.module.table.function_table);
+        self.out.push_str(")\n");
+    }
+
+    fn print_memory(&mut self) -> Result<(), ParseError> {
+        let module = self.module;
+        let memory = &module.memory;
+        if memory.count == 0 {
+            return Ok(());
+        }
+        let min_pages = memory.min_bytes()? / MemorySection::PAGE_SIZE;
+        write!(self.out, "  (memory (;0;) {}", min_pages).unwrap();
+        if let Some(max_bytes) = memory.max_bytes()? {
+            let max_pages = max_bytes / MemorySection::PAGE_SIZE;
+            write!(self.out, " {}", max_pages).unwrap();
+        }
+        self.out.push_str(")\n");
+        Ok(())
+    }
+
+    fn print_globals(&mut self, arena: &'a Bump) -> Result<(), ParseError> {
+        let module = self.module;
+        let import_global_count = self.global_names.len() - module.global.count as usize;
+        for (i, global) in module.global.parse_globals(arena)?.iter().enumerate() {
+            self.out.push_str("  (global");
+            self.print_global_label(import_global_count + i);
+            self.out.push(' ');
+            self.print_global_type(global.ty.value_type, global.ty.is_mutable);
+            self.out.push_str(" (");
+            self.print_const_expr(&global.init);
+            self.out.push_str("))\n");
+        }
+        Ok(())
+    }
+
+    fn print_exports(&mut self) {
+        let module = self.module;
+        for export in module.export.exports.iter() {
+            self.out.push_str("  (export ");
+            print_string(&mut self.out, export.name.as_bytes());
+            self.out.push(' ');
+            match export.ty {
+                ExportType::Func => {
+                    self.out.push_str("(func");
+                    self.print_fn_reference(export.index);
+                }
+                ExportType::Global => {
+                    self.out.push_str("(global");
+                    self.print_global_reference(export.index);
+                }
+                ExportType::Mem => {
+                    write!(self.out, "(memory {}", export.index).unwrap();
+                }
+                ExportType::Table => {
+                    write!(self.out, "(table {}", export.index).unwrap();
+                }
+            }
+            self.out.push_str("))\n");
+        }
+    }
+
+    fn print_elements(&mut self) {
+        let module = self.module;
+        if module.element.is_empty() {
+            return;
+        }
+        for (i, segment) in module.element.segments.iter().enumerate() {
+            write!(self.out, "  (elem (;{};) (", i).unwrap();
+            self.print_const_expr(&segment.offset);
+            self.out.push_str(") func");
+            for fn_index in segment.fn_indices.iter() {
+                self.print_fn_reference(*fn_index);
+            }
+            self.out.push_str(")\n");
+        }
+    }
+
+    fn print_data(&mut self, arena: &'a Bump) -> Result<(), ParseError> {
+        let module = self.module;
+        for (i, (mode, init)) in module.data.segments(arena)?.iter().enumerate() {
+            write!(self.out, "  (data (;{};) ", i).unwrap();
+            if let DataMode::Active { offset } = mode {
+                self.out.push('(');
+                self.print_const_expr(offset);
+                self.out.push_str(") ");
+            }
+            print_string(&mut self.out, init);
+            self.out.push_str(")\n");
+        }
+        Ok(())
+    }
+
+    /*
+     * Helpers
+     */
+
+    fn indent(&mut self, depth: usize) {
+        for _ in 0..=depth {
+            self.out.push_str("  ");
+        }
+    }
+
+    fn print_signature(&mut self, signature_index: u32) {
+        let module = self.module;
+        let (params, ret_type) = module.types.look_up(signature_index);
+        let mut params = params.peekable();
+        if params.peek().is_some() {
+            self.out.push_str(" (param");
+            for ty in params {
+                write!(self.out, " {}", value_type_name(ty)).unwrap();
+            }
+            self.out.push(')');
+        }
+        if let Some(ty) = ret_type {
+            write!(self.out, " (result {})", value_type_name(ty)).unwrap();
+        }
+    }
+
+    fn print_fn_label(&mut self, fn_index: usize) {
+        if let Some(Some(name)) = self.fn_names.get(fn_index) {
+            write!(self.out, " ${}", name).unwrap();
+        }
+        write!(self.out, " (;{};)", fn_index).unwrap();
+    }
+
+    fn print_global_label(&mut self, global_index: usize) {
+        if let Some(Some(name)) = self.global_names.get(global_index) {
+            write!(self.out, " ${}", name).unwrap();
+        }
+        write!(self.out, " (;{};)", global_index).unwrap();
+    }
+
+    fn print_fn_reference(&mut self, fn_index: u32) {
+        match self.fn_names.get(fn_index as usize) {
+            Some(Some(name)) => write!(self.out, " ${}", name).unwrap(),
+            _ => write!(self.out, " {}", fn_index).unwrap(),
+        }
+    }
+
+    fn print_global_reference(&mut self, global_index: u32) {
+        match self.global_names.get(global_index as usize) {
+            Some(Some(name)) => write!(self.out, " ${}", name).unwrap(),
+            _ => write!(self.out, " {}", global_index).unwrap(),
+        }
+    }
+
+    fn print_global_type(&mut self, ty: ValueType, is_mutable: bool) {
+        if is_mutable {
+            write!(self.out, "(mut {})", value_type_name(ty)).unwrap();
+        } else {
+            self.out.push_str(value_type_name(ty));
+        }
+    }
+
+    fn print_const_expr(&mut self, expr: &ConstExpr) {
+        match expr {
+            ConstExpr::I32(x) => write!(self.out, "i32.const {}", x).unwrap(),
+            ConstExpr::I64(x) => write!(self.out, "i64.const {}", x).unwrap(),
+            ConstExpr::F32(x) => {
+                self.out.push_str("f32.const");
+                self.print_f32(*x);
+            }
+            ConstExpr::F64(x) => {
+                self.out.push_str("f64.const");
+                self.print_f64(*x);
+            }
+        }
+    }
+}
+
+fn value_type_name(ty: ValueType) -> &'static str {
+    match ty {
+        ValueType::I32 => "i32",
+        ValueType::I64 => "i64",
+        ValueType::F32 => "f32",
+        ValueType::F64 => "f64",
+    }
+}
+
+fn print_limits(out: &mut String, limits: &Limits) {
+    match limits {
+        Limits::Min(min) => write!(out, " {}", min).unwrap(),
+        Limits::MinMax(min, max) => write!(out, " {} {}", min, max).unwrap(),
+    }
+}
+
+fn print_table_type(out: &mut String, table_type: &TableType) {
+    print_limits(out, &table_type.limits);
+    out.push_str(" funcref");
+}
+
+/// Print bytes as a WAT string literal, escaping quotes, backslashes and non-printable bytes
+fn print_string(out: &mut String, bytes: &[u8]) {
+    out.push('"');
+    for byte in bytes.iter() {
+        match byte {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            0x20..=0x7e => out.push(*byte as char),
+            _ => write!(out, "\\{:02x}", byte).unwrap(),
+        }
+    }
+    out.push('"');
+}
+
+/// Convert names to valid WAT identifiers, adding a suffix to any duplicates
+fn unique_identifiers(names: &[Option<&str>]) -> std::vec::Vec<Option<String>> {
+    let mut used = std::collections::HashSet::with_capacity(names.len());
+    names
+        .iter()
+        .enumerate()
+        .map(|(index, maybe_name)| {
+            maybe_name.map(|name| {
+                let mut id: String = name
+                    .chars()
+                    .map(|c| if is_wat_id_char(c) { c } else { '_' })
+                    .collect();
+                if id.is_empty() || used.contains(&id) {
+                    write!(id, ".{}", index).unwrap();
+                }
+                used.insert(id.clone());
+                id
+            })
+        })
+        .collect()
+}
+
+/// https://webassembly.github.io/spec/core/text/values.html#text-id
+fn is_wat_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-./:<=>?@\\^_`|~".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sections::Signature;
+    use crate::SerialBuffer;
+
+    #[test]
+    fn print_function() {
+        use OpCode::*;
+        use ValueType::*;
+
+        let arena = &Bump::new();
+        let mut module = WasmModule::new(arena);
+        module.add_function_signature(Signature {
+            param_types: bumpalo::vec![in arena; I32],
+            ret_type: Some(I32),
+        });
+
+        let body = [
+            1, // one group of locals
+            1,
+            I64 as u8,
+            GETLOCAL as u8,
+            0,
+            IF as u8,
+            I32 as u8,
+            I32CONST as u8,
+            1,
+            ELSE as u8,
+            GETLOCAL as u8,
+            0,
+            CALL as u8,
+            0,
+            END as u8,
+            END as u8,
+        ];
+        let code = &mut module.code;
+        code.bytes.encode_u32(1);
+        code.function_offsets.push(code.bytes.len() as u32);
+        code.function_count = 1;
+        code.bytes.encode_u32(body.len() as u32);
+        code.bytes.append_slice(&body);
+        module.names.append_function(0, "my function");
+
+        let wat = module_to_wat(arena, &module).unwrap();
+        let expected = [
+            "(module",
+            "  (type (;0;) (func (param i32) (result i32)))",
+            "  (func $my_function (;0;) (type 0) (param i32) (result i32)",
+            "    (local i64)",
+            "    local.get 0",
+            "    if (result i32)",
+            "      i32.const 1",
+            "    else",
+            "      local.get 0",
+            "      call $my_function",
+            "    end)",
+            ")",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(wat, expected);
+    }
+
+    #[test]
+    fn escaped_strings() {
+        let mut out = String::new();
+        print_string(&mut out, b"a \"b\" c\\d\n\x7f");
+        assert_eq!(out, r#""a \"b\" c\\d\0a\7f""#);
+    }
+
+    #[test]
+    fn unknown_opcode() {
+        let arena = &Bump::new();
+        let mut module = WasmModule::new(arena);
+        module.add_function_signature(Signature {
+            param_types: bumpalo::vec![in arena],
+            ret_type: None,
+        });
+
+        let undefined_opcode = 0x06;
+        let body = [0, undefined_opcode, OpCode::END as u8];
+        let code = &mut module.code;
+        code.bytes.encode_u32(1);
+        code.function_offsets.push(code.bytes.len() as u32);
+        code.function_count = 1;
+        code.bytes.encode_u32(body.len() as u32);
+        code.bytes.append_slice(&body);
+
+        let err = module_to_wat(arena, &module).unwrap_err();
+        assert_eq!(err.message, "Unknown Wasm instruction 0x06");
+    }
+
+    #[test]
+    fn identifiers() {
+        let names = [Some("a b"), None, Some("a_b"), Some("#UserApp_main_1")];
+        let ids = unique_identifiers(&names);
+        assert_eq!(ids[0].as_deref(), Some("a_b"));
+        assert_eq!(ids[1], None);
+        assert_eq!(ids[2].as_deref(), Some("a_b.2"));
+        assert_eq!(ids[3].as_deref(), Some("#UserApp_main_1"));
+    }
+}
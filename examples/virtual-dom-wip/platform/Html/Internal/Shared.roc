@@ -23,7 +23,7 @@ App state initData : {
     wasmUrl : Str,
 }
 
-DecodingResult a : Result a [Leftover (List U8), TooShort]
+DecodingResult a : Result a [Leftover (List U8)]DecodeError
 
 Html state : [
     None,
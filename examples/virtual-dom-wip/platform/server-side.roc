@@ -22,6 +22,12 @@ main = \initJson, hostJavaScript ->
             TooShort ->
                 "JSON initialization data is too short!"
 
+            Expected shape ->
+                "JSON initialization data should be a \(shape)"
+
+            At path _ ->
+                "JSON initialization data is invalid at \(Decode.pathToStr path)"
+
             Leftover _ ->
                 "JSON initialization data is too long!"
 